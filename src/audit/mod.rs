@@ -0,0 +1,452 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::prelude::*;
+use actix_web::{HttpMessage, HttpRequest};
+use bean_factory::{bean, Inject};
+
+use self::model::{AuditLogDo, AuditLogDto, AuditLogQueryParam};
+use crate::common::appdata::AppShareData;
+use crate::common::constant::AUDIT_LOG_TREE_NAME;
+use crate::common::model::{TokenSession, UserSession};
+use crate::config::core::ConfigKey;
+use crate::grpc::RequestMeta;
+use crate::now_millis_i64;
+use crate::raft::cluster::model::RouteAddr;
+use crate::raft::cluster::route::RaftAddrRouter;
+use crate::raft::db::{
+    route::TableRoute,
+    table::{
+        TableFilterQueryParam, TableManager, TableManagerInnerReq, TableManagerQueryReq,
+        TableManagerReq, TableManagerResult,
+    },
+};
+
+pub mod model;
+
+/// 过期审计日志清理间隔
+const AUDIT_LOG_PRUNE_INTERVAL: Duration = Duration::from_secs(600);
+/// 每批清理的最大条数
+const AUDIT_LOG_PRUNE_BATCH: usize = 1000;
+
+///
+/// 审计日志管理
+/// 日志只追加不修改,通过raft表T_AUDIT_LOG在集群内同步;超出保留天数或条数的日志由主节点定时清理
+#[bean(inject)]
+#[derive(Default)]
+pub struct AuditManager {
+    raft_table_route: Option<Arc<TableRoute>>,
+    table_manager: Option<Addr<TableManager>>,
+    raft_addr_route: Option<Arc<RaftAddrRouter>>,
+    keep_days: u32,
+    max_count: usize,
+}
+
+impl AuditManager {
+    pub fn new(keep_days: u32, max_count: usize) -> Self {
+        Self {
+            keep_days,
+            max_count,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// key由操作时间与uuid组成,按key排序即按时间排序
+    fn build_id(op_time: i64) -> String {
+        format!("{:020}_{}", op_time, uuid::Uuid::new_v4().simple())
+    }
+
+    ///
+    /// 操作时间对应的key边界,小于该值的key操作时间都早于op_time
+    fn build_time_key(op_time: i64) -> Vec<u8> {
+        format!("{:020}", op_time.max(0)).into_bytes()
+    }
+
+    async fn add_log(
+        raft_table_route: Option<Arc<TableRoute>>,
+        mut log: AuditLogDto,
+    ) -> anyhow::Result<AuditManagerResult> {
+        if log.op_time == 0 {
+            log.op_time = now_millis_i64();
+        }
+        if log.id.is_empty() {
+            log.id = Arc::new(Self::build_id(log.op_time));
+        }
+        let value = AuditLogDo::from(&log).to_bytes();
+        let req = TableManagerReq::Set {
+            table_name: AUDIT_LOG_TREE_NAME.clone(),
+            key: log.id.as_bytes().to_owned(),
+            value,
+            last_seq_id: None,
+        };
+        if let Some(raft_table_route) = raft_table_route {
+            raft_table_route.request(req).await?;
+        }
+        Ok(AuditManagerResult::None)
+    }
+
+    ///
+    /// 时间范围转换为key范围,其它条件在表内过滤,只返回当前页数据
+    async fn query_page(
+        table_manager: Option<Addr<TableManager>>,
+        param: Box<AuditLogQueryParam>,
+    ) -> anyhow::Result<AuditManagerResult> {
+        let table_manager = match table_manager {
+            Some(v) => v,
+            None => return Ok(AuditManagerResult::PageResult(0, vec![])),
+        };
+        let query_param = TableFilterQueryParam {
+            table_name: AUDIT_LOG_TREE_NAME.clone(),
+            start_key: param.start_time.map(Self::build_time_key),
+            end_key: param.end_time.map(Self::build_time_key),
+            offset: param.offset,
+            limit: param.limit,
+            is_rev: true,
+            filter: Box::new(move |v| match AuditLogDo::from_bytes(v) {
+                Ok(item) => param.match_item(&item),
+                Err(err) => {
+                    log::warn!("decode audit log error,{}", err);
+                    false
+                }
+            }),
+        };
+        let query_req = TableManagerInnerReq::FilterPageList(Box::new(query_param));
+        let (total, list) = match table_manager.send(query_req).await?? {
+            TableManagerResult::PageListResult(total, list) => (total, list),
+            _ => (0, vec![]),
+        };
+        let mut rlist = Vec::with_capacity(list.len());
+        for (_, v) in list {
+            rlist.push(AuditLogDo::from_bytes(&v)?.into());
+        }
+        Ok(AuditManagerResult::PageResult(total, rlist))
+    }
+
+    ///
+    /// 需要清理的最早日志数量;keys为按时间正序的最早一批key
+    fn prune_count(
+        keys: &[Vec<u8>],
+        total: usize,
+        expire_key: Option<&Vec<u8>>,
+        max_count: usize,
+    ) -> usize {
+        let expired_count = expire_key
+            .map(|expire_key| keys.iter().take_while(|k| *k < expire_key).count())
+            .unwrap_or_default();
+        let overflow_count = if max_count > 0 {
+            total.saturating_sub(max_count)
+        } else {
+            0
+        };
+        expired_count.max(overflow_count).min(keys.len())
+    }
+
+    async fn prune_logs(
+        raft_addr_route: Option<Arc<RaftAddrRouter>>,
+        raft_table_route: Option<Arc<TableRoute>>,
+        table_manager: Option<Addr<TableManager>>,
+        keep_days: u32,
+        max_count: usize,
+    ) -> anyhow::Result<()> {
+        let (raft_addr_route, raft_table_route, table_manager) =
+            match (raft_addr_route, raft_table_route, table_manager) {
+                (Some(a), Some(b), Some(c)) => (a, b, c),
+                _ => return Ok(()),
+            };
+        if !matches!(raft_addr_route.get_route_addr().await?, RouteAddr::Local) {
+            return Ok(());
+        }
+        let expire_key = if keep_days > 0 {
+            Some(Self::build_time_key(
+                now_millis_i64() - keep_days as i64 * 24 * 3600 * 1000,
+            ))
+        } else {
+            None
+        };
+        loop {
+            let query_req = TableManagerQueryReq::QueryPageList {
+                table_name: AUDIT_LOG_TREE_NAME.clone(),
+                like_key: None,
+                offset: None,
+                limit: Some(AUDIT_LOG_PRUNE_BATCH as i64),
+                is_rev: false,
+            };
+            let (total, list) = match table_manager.send(query_req).await?? {
+                TableManagerResult::PageListResult(total, list) => (total, list),
+                _ => return Ok(()),
+            };
+            let keys: Vec<Vec<u8>> = list.into_iter().map(|(k, _)| k).collect();
+            let count = Self::prune_count(&keys, total, expire_key.as_ref(), max_count);
+            for key in keys.into_iter().take(count) {
+                let req = TableManagerReq::Remove {
+                    table_name: AUDIT_LOG_TREE_NAME.clone(),
+                    key,
+                };
+                raft_table_route.request(req).await?;
+            }
+            if count < AUDIT_LOG_PRUNE_BATCH {
+                return Ok(());
+            }
+        }
+    }
+
+    fn prune_timer(&self, ctx: &mut Context<Self>) {
+        ctx.run_later(AUDIT_LOG_PRUNE_INTERVAL, |act, ctx| {
+            let raft_addr_route = act.raft_addr_route.clone();
+            let raft_table_route = act.raft_table_route.clone();
+            let table_manager = act.table_manager.clone();
+            let (keep_days, max_count) = (act.keep_days, act.max_count);
+            async move {
+                Self::prune_logs(
+                    raft_addr_route,
+                    raft_table_route,
+                    table_manager,
+                    keep_days,
+                    max_count,
+                )
+                .await
+            }
+            .into_actor(act)
+            .map(|r, act, ctx| {
+                if let Err(err) = r {
+                    log::warn!("prune audit log error,{}", err);
+                }
+                act.prune_timer(ctx);
+            })
+            .spawn(ctx);
+        });
+    }
+}
+
+impl Actor for AuditManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("AuditManager started")
+    }
+}
+
+impl Inject for AuditManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        ctx: &mut Self::Context,
+    ) {
+        self.raft_table_route = factory_data.get_bean();
+        self.table_manager = factory_data.get_actor();
+        self.raft_addr_route = factory_data.get_bean();
+        if self.keep_days > 0 || self.max_count > 0 {
+            self.prune_timer(ctx);
+        }
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<AuditManagerResult>")]
+pub enum AuditManagerReq {
+    Add(Box<AuditLogDto>),
+    QueryPage(Box<AuditLogQueryParam>),
+}
+
+pub enum AuditManagerResult {
+    None,
+    PageResult(usize, Vec<AuditLogDto>),
+}
+
+impl Handler<AuditManagerReq> for AuditManager {
+    type Result = ResponseActFuture<Self, anyhow::Result<AuditManagerResult>>;
+
+    fn handle(&mut self, msg: AuditManagerReq, _ctx: &mut Self::Context) -> Self::Result {
+        let raft_table_route = self.raft_table_route.clone();
+        let table_manager = self.table_manager.clone();
+        let fut = async move {
+            match msg {
+                AuditManagerReq::Add(log) => Self::add_log(raft_table_route, *log).await,
+                AuditManagerReq::QueryPage(param) => Self::query_page(table_manager, param).await,
+            }
+        }
+        .into_actor(self)
+        .map(|r, _act, _ctx| r);
+        Box::pin(fut)
+    }
+}
+
+pub struct AuditLogUtils;
+
+impl AuditLogUtils {
+    pub fn config_resource(key: &ConfigKey) -> String {
+        format!("{}/{}/{}", &key.tenant, &key.group, &key.data_id)
+    }
+
+    pub fn service_resource(namespace_id: &str, group_name: &str, service_name: &str) -> String {
+        format!("{}/{}/{}", namespace_id, group_name, service_name)
+    }
+
+    ///
+    /// 控制台登录会话或openapi token会话中的用户名,未登录时为空
+    pub fn get_op_user(req: &HttpRequest) -> Arc<String> {
+        let extensions = req.extensions();
+        if let Some(session) = extensions.get::<Arc<UserSession>>() {
+            return session.username.clone();
+        }
+        extensions
+            .get::<Arc<TokenSession>>()
+            .map(|session| session.username.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn get_op_user_option(req: &HttpRequest) -> Option<Arc<String>> {
+        Some(Self::get_op_user(req)).filter(|v| !v.is_empty())
+    }
+
+    ///
+    /// 取连接的对端地址,不使用可被伪造的X-Forwarded-For等请求头
+    pub fn get_client_ip(req: &HttpRequest) -> String {
+        req.peer_addr()
+            .map(|addr| addr.ip().to_string())
+            .unwrap_or_default()
+    }
+
+    ///
+    /// 操作人,未登录(如未开启openapi鉴权)时使用客户端ip
+    pub fn get_operator(req: &HttpRequest) -> String {
+        let op_user = Self::get_op_user(req);
+        if op_user.is_empty() {
+            Self::get_client_ip(req)
+        } else {
+            op_user.as_ref().to_owned()
+        }
+    }
+
+    ///
+    /// 按http请求构建审计日志,操作人取自登录会话
+    pub fn build_by_request(
+        req: &HttpRequest,
+        module: &str,
+        action: &str,
+        resource: String,
+    ) -> AuditLogDto {
+        AuditLogDto {
+            op_user: Self::get_op_user(req),
+            client_ip: Arc::new(Self::get_client_ip(req)),
+            api: Arc::new(format!("{} {}", req.method(), req.path())),
+            module: Arc::new(module.to_owned()),
+            action: Arc::new(action.to_owned()),
+            resource: Arc::new(resource),
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// 记录http接口的操作;审计日志写入失败不影响接口结果
+    pub fn record(
+        app: &AppShareData,
+        req: &HttpRequest,
+        module: &str,
+        action: &str,
+        resource: String,
+        success: bool,
+    ) {
+        Self::record_detail(app, req, module, action, resource, success, None);
+    }
+
+    pub fn record_detail(
+        app: &AppShareData,
+        req: &HttpRequest,
+        module: &str,
+        action: &str,
+        resource: String,
+        success: bool,
+        detail: Option<String>,
+    ) {
+        let mut log = Self::build_by_request(req, module, action, resource);
+        log.success = success;
+        log.detail = detail.map(Arc::new);
+        app.audit_manager
+            .do_send(AuditManagerReq::Add(Box::new(log)));
+    }
+
+    ///
+    /// 记录grpc接口的操作,操作人取自token会话;api记录为请求类型,如: GRPC ConfigPublishRequest
+    pub fn record_grpc(
+        app: &AppShareData,
+        request_meta: &RequestMeta,
+        request_type: &str,
+        module: &str,
+        action: &str,
+        resource: String,
+        success: bool,
+    ) {
+        let log = AuditLogDto {
+            op_user: request_meta
+                .token_session
+                .as_ref()
+                .map(|session| session.username.clone())
+                .unwrap_or_default(),
+            client_ip: Arc::new(request_meta.client_ip.clone()),
+            api: Arc::new(format!("GRPC {}", request_type)),
+            module: Arc::new(module.to_owned()),
+            action: Arc::new(action.to_owned()),
+            resource: Arc::new(resource),
+            success,
+            ..Default::default()
+        };
+        app.audit_manager
+            .do_send(AuditManagerReq::Add(Box::new(log)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audit_log_page_and_prune() {
+        let mut table_manager = TableManager::new();
+        let mut keys = vec![];
+        for i in 1..=5 {
+            let log = AuditLogDo {
+                id: AuditManager::build_id(i * 1000),
+                op_time: i * 1000,
+                op_user: if i % 2 == 0 { "admin" } else { "dev" }.to_owned(),
+                ..Default::default()
+            };
+            keys.push(log.id.as_bytes().to_owned());
+            table_manager.insert(
+                AUDIT_LOG_TREE_NAME.clone(),
+                log.id.as_bytes().to_owned(),
+                log.to_bytes(),
+                None,
+            );
+        }
+        let param = AuditLogQueryParam {
+            op_user: Some("dev".to_owned()),
+            start_time: Some(1000),
+            end_time: Some(5000),
+            ..Default::default()
+        };
+        let query_param = TableFilterQueryParam {
+            table_name: AUDIT_LOG_TREE_NAME.clone(),
+            start_key: param.start_time.map(AuditManager::build_time_key),
+            end_key: param.end_time.map(AuditManager::build_time_key),
+            offset: 0,
+            limit: 1,
+            is_rev: true,
+            filter: Box::new(move |v| param.match_item(&AuditLogDo::from_bytes(v).unwrap())),
+        };
+        let (total, list) = table_manager.query_filter_page_list(&query_param);
+        // 5000的记录不在时间范围内,命中1000与3000,按时间倒序分页
+        assert_eq!(total, 2);
+        assert_eq!(list.len(), 1);
+        assert_eq!(AuditLogDo::from_bytes(&list[0].1).unwrap().op_time, 3000);
+
+        let expire_key = AuditManager::build_time_key(3000);
+        assert_eq!(AuditManager::prune_count(&keys, 5, Some(&expire_key), 0), 2);
+        assert_eq!(AuditManager::prune_count(&keys, 5, Some(&expire_key), 1), 4);
+        assert_eq!(AuditManager::prune_count(&keys, 5, None, 10), 0);
+    }
+}
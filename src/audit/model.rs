@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::string_utils::StringUtils;
+
+pub const AUDIT_MODULE_CONFIG: &str = "CONFIG";
+pub const AUDIT_MODULE_NAMESPACE: &str = "NAMESPACE";
+pub const AUDIT_MODULE_USER: &str = "USER";
+pub const AUDIT_MODULE_NAMING: &str = "NAMING";
+
+pub const AUDIT_ACTION_ADD: &str = "ADD";
+pub const AUDIT_ACTION_PUBLISH: &str = "PUBLISH";
+pub const AUDIT_ACTION_UPDATE: &str = "UPDATE";
+pub const AUDIT_ACTION_DELETE: &str = "DELETE";
+pub const AUDIT_ACTION_ROLLBACK: &str = "ROLLBACK";
+pub const AUDIT_ACTION_IMPORT: &str = "IMPORT";
+pub const AUDIT_ACTION_GRAY_PUBLISH: &str = "GRAY_PUBLISH";
+pub const AUDIT_ACTION_GRAY_PROMOTE: &str = "GRAY_PROMOTE";
+pub const AUDIT_ACTION_GRAY_REMOVE: &str = "GRAY_REMOVE";
+
+#[derive(Clone, prost::Message, Serialize, Deserialize)]
+pub struct AuditLogDo {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(int64, tag = "2")]
+    pub op_time: i64,
+    #[prost(string, tag = "3")]
+    pub op_user: String,
+    #[prost(string, tag = "4")]
+    pub client_ip: String,
+    #[prost(string, tag = "5")]
+    pub api: String,
+    #[prost(string, tag = "6")]
+    pub module: String,
+    #[prost(string, tag = "7")]
+    pub action: String,
+    #[prost(string, tag = "8")]
+    pub resource: String,
+    #[prost(bool, tag = "9")]
+    pub success: bool,
+    #[prost(string, optional, tag = "10")]
+    pub detail: Option<String>,
+}
+
+impl AuditLogDo {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::new();
+        prost::Message::encode(self, &mut v).unwrap_or_default();
+        v
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(prost::Message::decode(v)?)
+    }
+}
+
+///
+/// 审计日志
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogDto {
+    pub id: Arc<String>,
+    /// 时间戳,单位毫秒
+    pub op_time: i64,
+    pub op_user: Arc<String>,
+    pub client_ip: Arc<String>,
+    /// 请求方法及路径,如: POST /rnacos/api/console/v2/config/update
+    pub api: Arc<String>,
+    pub module: Arc<String>,
+    pub action: Arc<String>,
+    /// 被操作的对象,如配置key、命名空间id、用户名、服务key
+    pub resource: Arc<String>,
+    pub success: bool,
+    pub detail: Option<Arc<String>>,
+}
+
+impl From<AuditLogDo> for AuditLogDto {
+    fn from(v: AuditLogDo) -> Self {
+        Self {
+            id: Arc::new(v.id),
+            op_time: v.op_time,
+            op_user: Arc::new(v.op_user),
+            client_ip: Arc::new(v.client_ip),
+            api: Arc::new(v.api),
+            module: Arc::new(v.module),
+            action: Arc::new(v.action),
+            resource: Arc::new(v.resource),
+            success: v.success,
+            detail: v.detail.map(Arc::new),
+        }
+    }
+}
+
+impl From<&AuditLogDto> for AuditLogDo {
+    fn from(v: &AuditLogDto) -> Self {
+        Self {
+            id: v.id.as_ref().to_owned(),
+            op_time: v.op_time,
+            op_user: v.op_user.as_ref().to_owned(),
+            client_ip: v.client_ip.as_ref().to_owned(),
+            api: v.api.as_ref().to_owned(),
+            module: v.module.as_ref().to_owned(),
+            action: v.action.as_ref().to_owned(),
+            resource: v.resource.as_ref().to_owned(),
+            success: v.success,
+            detail: v.detail.as_ref().map(|e| e.as_ref().to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogQueryParam {
+    pub module: Option<String>,
+    pub action: Option<String>,
+    pub op_user: Option<String>,
+    pub client_ip: Option<String>,
+    /// 模糊匹配
+    pub resource: Option<String>,
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl AuditLogQueryParam {
+    pub fn match_item(&self, item: &AuditLogDo) -> bool {
+        Self::match_eq(&self.module, &item.module)
+            && Self::match_eq(&self.action, &item.action)
+            && Self::match_eq(&self.op_user, &item.op_user)
+            && Self::match_eq(&self.client_ip, &item.client_ip)
+            && self
+                .resource
+                .as_ref()
+                .map(|e| StringUtils::like(&item.resource, e).is_some())
+                .unwrap_or(true)
+            && self.start_time.map(|e| item.op_time >= e).unwrap_or(true)
+            && self.end_time.map(|e| item.op_time < e).unwrap_or(true)
+    }
+
+    fn match_eq(condition: &Option<String>, value: &str) -> bool {
+        condition.as_ref().map(|e| e == value).unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audit_log_query_match() {
+        let item = AuditLogDo {
+            id: "1".to_owned(),
+            op_time: 1000,
+            op_user: "admin".to_owned(),
+            client_ip: "127.0.0.1".to_owned(),
+            api: "POST /rnacos/api/console/v2/config/update".to_owned(),
+            module: AUDIT_MODULE_CONFIG.to_owned(),
+            action: AUDIT_ACTION_PUBLISH.to_owned(),
+            resource: "dev/DEFAULT_GROUP/app.yaml".to_owned(),
+            success: true,
+            detail: None,
+        };
+        let item = AuditLogDo::from_bytes(&item.to_bytes()).unwrap();
+        let mut param = AuditLogQueryParam {
+            module: Some(AUDIT_MODULE_CONFIG.to_owned()),
+            op_user: Some("admin".to_owned()),
+            resource: Some("app".to_owned()),
+            start_time: Some(1000),
+            end_time: Some(1001),
+            ..Default::default()
+        };
+        assert!(param.match_item(&item));
+        param.action = Some(AUDIT_ACTION_DELETE.to_owned());
+        assert!(!param.match_item(&item));
+        param.action = None;
+        param.end_time = Some(1000);
+        assert!(!param.match_item(&item));
+    }
+}
@@ -1,5 +1,7 @@
+use crate::audit::AuditManager;
 use crate::common::AppSysConfig;
 use crate::config::core::ConfigActor;
+use crate::config::webhook::ConfigWebhookManager;
 use crate::grpc::bistream_manage::BiStreamManage;
 use crate::health::core::HealthManager;
 use crate::ldap::core::LdapManager;
@@ -8,6 +10,8 @@ use crate::namespace::NamespaceActor;
 use crate::naming::cluster::node_manage::{InnerNodeManage, NodeManage};
 use crate::naming::cluster::route::NamingRoute;
 use crate::naming::core::NamingActor;
+use crate::naming::health_check::NamingHealthCheckManager;
+use crate::oidc::core::OidcManager;
 use crate::raft::cache::route::CacheRoute;
 use crate::raft::cache::CacheManager;
 use crate::raft::cluster::route::{ConfigRoute, RaftRequestRoute};
@@ -18,6 +22,8 @@ use crate::raft::network::factory::RaftClusterRequestSender;
 use crate::raft::NacosRaft;
 use crate::transfer::reader::TransferImportManager;
 use crate::transfer::writer::TransferWriterManager;
+use crate::user::access_key::AccessKeyManager;
+use crate::user::role::RoleManager;
 use crate::user::UserManager;
 use actix::Addr;
 use bean_factory::FactoryData;
@@ -50,4 +56,10 @@ pub struct AppShareData {
     pub transfer_import_manager: Addr<TransferImportManager>,
     pub health_manager: Addr<HealthManager>,
     pub ldap_manager: Addr<LdapManager>,
+    pub oidc_manager: Addr<OidcManager>,
+    pub audit_manager: Addr<AuditManager>,
+    pub config_webhook_manager: Addr<ConfigWebhookManager>,
+    pub naming_health_check_manager: Addr<NamingHealthCheckManager>,
+    pub role_manager: Addr<RoleManager>,
+    pub access_key_manager: Addr<AccessKeyManager>,
 }
@@ -13,6 +13,12 @@ pub const SEQ_KEY_CONFIG: &str = "SEQ_CONFIG";
 
 pub const AUTHORIZATION_HEADER: &str = "Authorization";
 pub const ACCESS_TOKEN_HEADER: &str = "accessToken";
+pub const ACCESS_KEY_HEADER: &str = "ak";
+pub const SPAS_ACCESS_KEY_HEADER: &str = "Spas-AccessKey";
+pub const SIGNATURE_HEADER: &str = "signature";
+pub const SPAS_SIGNATURE_HEADER: &str = "Spas-Signature";
+pub const SIGNATURE_DATA_HEADER: &str = "data";
+pub const TIMESTAMP_HEADER: &str = "Timestamp";
 
 pub const GRPC_HEAD_KEY_CLUSTER_ID: &str = "cluster_id";
 pub const GRPC_HEAD_KEY_TRACE_ID: &str = "trace_id";
@@ -25,6 +31,15 @@ lazy_static::lazy_static! {
     pub static ref USER_TREE_NAME: Arc<String> =  Arc::new("T_USER".to_string());
     pub static ref CACHE_TREE_NAME: Arc<String> =  Arc::new("T_CACHE".to_string());
     pub static ref NAMESPACE_TREE_NAME: Arc<String> =  Arc::new("T_NAMESPACE".to_string());
+    pub static ref AUDIT_LOG_TREE_NAME: Arc<String> =  Arc::new("T_AUDIT_LOG".to_string());
+    pub static ref CONFIG_WEBHOOK_TREE_NAME: Arc<String> =  Arc::new("T_CONFIG_WEBHOOK".to_string());
+    pub static ref NAMING_PERSISTENT_INSTANCE_TREE_NAME: Arc<String> =  Arc::new("T_NAMING_PERSISTENT_INSTANCE".to_string());
+    pub static ref NAMING_HEALTH_CHECK_TREE_NAME: Arc<String> =  Arc::new("T_NAMING_HEALTH_CHECK".to_string());
+    pub static ref NAMING_SERVICE_TREE_NAME: Arc<String> =  Arc::new("T_NAMING_SERVICE".to_string());
+    pub static ref NAMING_SERVICE_CLUSTER_TREE_NAME: Arc<String> =  Arc::new("T_NAMING_SERVICE_CLUSTER".to_string());
+    pub static ref NAMING_INSTANCE_DRAIN_TREE_NAME: Arc<String> =  Arc::new("T_NAMING_INSTANCE_DRAIN".to_string());
+    pub static ref ROLE_TREE_NAME: Arc<String> =  Arc::new("T_ROLE".to_string());
+    pub static ref ACCESS_KEY_TREE_NAME: Arc<String> =  Arc::new("T_ACCESS_KEY".to_string());
     pub static ref EMPTY_ARC_STRING: Arc<String> = Arc::new("".to_string());
     pub static ref DEFAULT_NAMESPACE_ARC_STRING: Arc<String> = Arc::new("".to_string());
     pub static ref EMPTY_CLIENT_VERSION: Arc<ClientVersion> = Arc::new(ClientVersion::default());
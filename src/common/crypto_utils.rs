@@ -50,3 +50,28 @@ pub fn decrypt_aes128(key: &str, iv: &str, cipher: &[u8]) -> anyhow::Result<Vec<
         Err(e) => Err(anyhow::anyhow!("decrypt error,{}", &e)),
     }
 }
+
+type Aes256CbcEnc = cbc::Encryptor<aes::Aes256>;
+type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
+
+/// 加密
+/// key长度32字节,iv长度16字节
+pub fn encrypt_aes256(key: &[u8], iv: &[u8], plain: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut buf = vec![0u8; plain.len() + 16];
+    match Aes256CbcEnc::new(key.into(), iv.into()).encrypt_padded_b2b_mut::<Pkcs7>(plain, &mut buf)
+    {
+        Ok(ct) => Ok(ct.to_vec()),
+        Err(e) => Err(anyhow::anyhow!("encrypt error,{}", &e)),
+    }
+}
+
+/// 解密
+/// key长度32字节,iv长度16字节
+pub fn decrypt_aes256(key: &[u8], iv: &[u8], cipher: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut buf = vec![0u8; cipher.len()];
+    match Aes256CbcDec::new(key.into(), iv.into()).decrypt_padded_b2b_mut::<Pkcs7>(cipher, &mut buf)
+    {
+        Ok(pt) => Ok(pt.to_vec()),
+        Err(e) => Err(anyhow::anyhow!("decrypt error,{}", &e)),
+    }
+}
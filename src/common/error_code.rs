@@ -2,3 +2,6 @@
 pub const NO_PERMISSION: &str = "NO_PERMISSION";
 
 pub const NO_NAMESPACE_PERMISSION: &str = "NO_NAMESPACE_PERMISSION";
+pub const NO_GROUP_PERMISSION: &str = "NO_GROUP_PERMISSION";
+
+pub const CONFIG_CONTENT_INVALID: &str = "CONFIG_CONTENT_INVALID";
@@ -54,6 +54,46 @@ macro_rules! user_namespace_privilege {
     }};
 }
 
+///
+/// 获取当前请求用户的配置分组数据权限,兼容控制台与openapi会话
+#[macro_export]
+macro_rules! user_config_group_privilege {
+    ($req:expr) => {{
+        let extensions = $req.extensions();
+        if let Some(session) =
+            extensions.get::<std::sync::Arc<$crate::common::model::UserSession>>()
+        {
+            session.get_config_group_privilege()
+        } else if let Some(session) =
+            extensions.get::<std::sync::Arc<$crate::common::model::TokenSession>>()
+        {
+            session.get_config_group_privilege()
+        } else {
+            $crate::common::model::privilege::GroupPrivilegeGroup::default()
+        }
+    }};
+}
+
+///
+/// 获取当前请求用户的服务分组数据权限,兼容控制台与openapi会话
+#[macro_export]
+macro_rules! user_service_group_privilege {
+    ($req:expr) => {{
+        let extensions = $req.extensions();
+        if let Some(session) =
+            extensions.get::<std::sync::Arc<$crate::common::model::UserSession>>()
+        {
+            session.get_service_group_privilege()
+        } else if let Some(session) =
+            extensions.get::<std::sync::Arc<$crate::common::model::TokenSession>>()
+        {
+            session.get_service_group_privilege()
+        } else {
+            $crate::common::model::privilege::GroupPrivilegeGroup::default()
+        }
+    }};
+}
+
 #[macro_export]
 macro_rules! user_no_namespace_permission {
     ($param:expr) => {{
@@ -63,3 +103,13 @@ macro_rules! user_no_namespace_permission {
         ));
     }};
 }
+
+#[macro_export]
+macro_rules! user_no_group_permission {
+    ($param:expr) => {{
+        return actix_web::HttpResponse::Ok().json($crate::common::model::ApiResult::<()>::error(
+            $crate::common::error_code::NO_GROUP_PERMISSION.to_string(),
+            Some(format!("user no such group permission: {:?}", $param)),
+        ));
+    }};
+}
@@ -1,5 +1,10 @@
 use crate::common::string_utils::StringUtils;
+use crate::common::tls_utils::{TlsClientAuth, DEFAULT_TLS_RELOAD_INTERVAL_SECOND};
 use crate::ldap::model::LdapConfig;
+use crate::naming::event_history::{DEFAULT_EVENT_HISTORY_SIZE, DEFAULT_EVENT_LOG_MAX_SIZE_MB};
+use crate::naming::order::{InstanceOrderConfig, DEFAULT_ZONE_HEALTHY_THRESHOLD};
+use crate::oidc::model::OidcConfig;
+use crate::user::password_policy::PasswordPolicy;
 use crate::user::permission;
 use crate::user::permission::UserRoleHelper;
 use std::collections::HashSet;
@@ -29,6 +34,7 @@ pub mod sled_utils;
 pub mod sqlx_utils;
 pub mod string_utils;
 pub mod tempfile;
+pub mod tls_utils;
 pub mod web_utils;
 /*
 use lazy_static::lazy_static;
@@ -48,6 +54,7 @@ pub struct NamingSysConfig {
     pub instance_metadata_time_out_millis: u64,
     pub instance_health_timeout_millis: i64,
     pub instance_timeout_millis: i64,
+    pub instance_order: InstanceOrderConfig,
 }
 
 impl NamingSysConfig {
@@ -58,6 +65,7 @@ impl NamingSysConfig {
             instance_metadata_time_out_millis: 60000,
             instance_health_timeout_millis: 18000,
             instance_timeout_millis: 33000,
+            instance_order: InstanceOrderConfig::default(),
         }
     }
 }
@@ -95,6 +103,12 @@ pub struct AppSysConfig {
     pub run_in_docker: bool,
     pub naming_health_timeout: u64,
     pub naming_instance_timeout: u64,
+    pub naming_instance_order: Arc<String>,
+    pub naming_zone_cidr: Arc<String>,
+    pub naming_zone_healthy_threshold: f32,
+    pub naming_event_history_size: usize,
+    pub naming_event_log_file: Arc<String>,
+    pub naming_event_log_max_size: u64,
     pub ldap_enable: bool,
     pub ldap_url: Arc<String>,
     pub ldap_user_base_dn: Arc<String>,
@@ -102,6 +116,35 @@ pub struct AppSysConfig {
     pub ldap_user_developer_groups: Arc<HashSet<String>>,
     pub ldap_user_admin_groups: Arc<HashSet<String>>,
     pub ldap_user_default_role: Arc<String>,
+    pub oidc_enable: bool,
+    pub oidc_issuer: Arc<String>,
+    pub oidc_client_id: Arc<String>,
+    pub oidc_client_secret: Arc<String>,
+    pub oidc_redirect_url: Arc<String>,
+    pub oidc_scopes: Arc<String>,
+    pub oidc_username_claim: Arc<String>,
+    pub oidc_groups_claim: Arc<String>,
+    pub oidc_user_developer_groups: Arc<HashSet<String>>,
+    pub oidc_user_admin_groups: Arc<HashSet<String>>,
+    pub oidc_user_default_role: Arc<String>,
+    pub password_min_length: usize,
+    pub password_min_char_types: usize,
+    pub password_expire_days: u32,
+    pub password_history_count: usize,
+    pub password_init_admin_force_change: bool,
+    pub login_lock_failed_times: u32,
+    pub login_lock_second: u32,
+    pub audit_log_keep_days: u32,
+    pub audit_log_max_count: usize,
+    pub tls_cert_file: Arc<String>,
+    pub tls_key_file: Arc<String>,
+    pub tls_ca_file: Arc<String>,
+    pub http_tls_enable: bool,
+    pub console_tls_enable: bool,
+    pub grpc_tls_enable: bool,
+    pub grpc_tls_client_auth: TlsClientAuth,
+    pub cluster_tls_server_name: Arc<String>,
+    pub tls_reload_interval_second: u64,
 }
 
 impl AppSysConfig {
@@ -235,6 +278,27 @@ impl AppSysConfig {
             //如果配置不合理，则默认使过期时间大于心跳时间15秒
             naming_instance_timeout = naming_health_timeout + 15 * 1000;
         }
+        let naming_instance_order = std::env::var("RNACOS_NAMING_INSTANCE_ORDER")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let naming_zone_cidr = std::env::var("RNACOS_NAMING_ZONE_CIDR")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let naming_zone_healthy_threshold = std::env::var("RNACOS_NAMING_ZONE_HEALTHY_THRESHOLD")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(DEFAULT_ZONE_HEALTHY_THRESHOLD);
+        let naming_event_history_size = std::env::var("RNACOS_NAMING_EVENT_HISTORY_SIZE")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(DEFAULT_EVENT_HISTORY_SIZE);
+        let naming_event_log_file = std::env::var("RNACOS_NAMING_EVENT_LOG_FILE")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let naming_event_log_max_size = std::env::var("RNACOS_NAMING_EVENT_LOG_MAX_SIZE")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(DEFAULT_EVENT_LOG_MAX_SIZE_MB);
         let ldap_enable = std::env::var("RNACOS_LDAP_ENABLE")
             .unwrap_or("false".to_owned())
             .parse()
@@ -260,6 +324,115 @@ impl AppSysConfig {
                 UserRoleHelper::get_role_by_name(&upper, permission::USER_ROLE_VISITOR.clone())
             })
             .unwrap_or(permission::USER_ROLE_VISITOR.clone());
+        let oidc_enable = std::env::var("RNACOS_OIDC_ENABLE")
+            .unwrap_or("false".to_owned())
+            .parse()
+            .unwrap_or(false);
+        let oidc_issuer = std::env::var("RNACOS_OIDC_ISSUER")
+            .map(|v| Arc::new(v.trim_end_matches('/').to_owned()))
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let oidc_client_id = std::env::var("RNACOS_OIDC_CLIENT_ID")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let oidc_client_secret = std::env::var("RNACOS_OIDC_CLIENT_SECRET")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let oidc_redirect_url = std::env::var("RNACOS_OIDC_REDIRECT_URL")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let oidc_scopes = std::env::var("RNACOS_OIDC_SCOPES")
+            .map(Arc::new)
+            .unwrap_or(Arc::new("openid profile email".to_owned()));
+        let oidc_username_claim = std::env::var("RNACOS_OIDC_USERNAME_CLAIM")
+            .map(Arc::new)
+            .unwrap_or(Arc::new("preferred_username".to_owned()));
+        let oidc_groups_claim = std::env::var("RNACOS_OIDC_GROUPS_CLAIM")
+            .map(Arc::new)
+            .unwrap_or(Arc::new("groups".to_owned()));
+        let oidc_user_developer_groups = Arc::new(StringUtils::split_to_hashset(
+            &std::env::var("RNACOS_OIDC_USER_DEVELOPER_GROUP").unwrap_or_default(),
+        ));
+        let oidc_user_admin_groups = Arc::new(StringUtils::split_to_hashset(
+            &std::env::var("RNACOS_OIDC_USER_ADMIN_GROUP").unwrap_or_default(),
+        ));
+        let oidc_user_default_role = std::env::var("RNACOS_OIDC_USER_DEFAULT_ROLE")
+            .map(|v| {
+                let upper = v.to_uppercase();
+                UserRoleHelper::get_role_by_name(&upper, permission::USER_ROLE_VISITOR.clone())
+            })
+            .unwrap_or(permission::USER_ROLE_VISITOR.clone());
+        let password_min_length = std::env::var("RNACOS_PASSWORD_MIN_LENGTH")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(0);
+        let password_min_char_types = std::env::var("RNACOS_PASSWORD_MIN_CHAR_TYPES")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(0);
+        let password_expire_days = std::env::var("RNACOS_PASSWORD_EXPIRE_DAYS")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(0);
+        let password_history_count = std::env::var("RNACOS_PASSWORD_HISTORY_COUNT")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(0);
+        let password_init_admin_force_change =
+            std::env::var("RNACOS_PASSWORD_INIT_ADMIN_FORCE_CHANGE")
+                .unwrap_or("false".to_owned())
+                .parse()
+                .unwrap_or(false);
+        let login_lock_failed_times = std::env::var("RNACOS_LOGIN_LOCK_FAILED_TIMES")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(0);
+        let login_lock_second = std::env::var("RNACOS_LOGIN_LOCK_SECOND")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(1800);
+        let audit_log_keep_days = std::env::var("RNACOS_AUDIT_LOG_KEEP_DAYS")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(90);
+        let audit_log_max_count = std::env::var("RNACOS_AUDIT_LOG_MAX_COUNT")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(100000);
+        let tls_cert_file = std::env::var("RNACOS_TLS_CERT_FILE")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let tls_key_file = std::env::var("RNACOS_TLS_KEY_FILE")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let tls_ca_file = std::env::var("RNACOS_TLS_CA_FILE")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let http_tls_enable = std::env::var("RNACOS_HTTP_TLS_ENABLE")
+            .unwrap_or("false".to_owned())
+            .parse()
+            .unwrap_or(false);
+        let console_tls_enable = std::env::var("RNACOS_CONSOLE_TLS_ENABLE")
+            .unwrap_or("false".to_owned())
+            .parse()
+            .unwrap_or(false);
+        let grpc_tls_enable = std::env::var("RNACOS_GRPC_TLS_ENABLE")
+            .unwrap_or("false".to_owned())
+            .parse()
+            .unwrap_or(false);
+        let grpc_tls_client_auth = std::env::var("RNACOS_GRPC_TLS_CLIENT_AUTH")
+            .map(|v| TlsClientAuth::from(v.as_str()))
+            .unwrap_or(if tls_ca_file.is_empty() {
+                TlsClientAuth::None
+            } else {
+                TlsClientAuth::Optional
+            });
+        let cluster_tls_server_name = std::env::var("RNACOS_CLUSTER_TLS_SERVER_NAME")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let tls_reload_interval_second = std::env::var("RNACOS_TLS_RELOAD_INTERVAL_SECOND")
+            .unwrap_or_default()
+            .parse()
+            .unwrap_or(DEFAULT_TLS_RELOAD_INTERVAL_SECOND);
         Self {
             local_db_dir,
             config_db_file,
@@ -292,6 +465,12 @@ impl AppSysConfig {
             run_in_docker,
             naming_health_timeout,
             naming_instance_timeout,
+            naming_instance_order,
+            naming_zone_cidr,
+            naming_zone_healthy_threshold,
+            naming_event_history_size,
+            naming_event_log_file,
+            naming_event_log_max_size,
             ldap_enable,
             ldap_url,
             ldap_user_base_dn,
@@ -299,6 +478,35 @@ impl AppSysConfig {
             ldap_user_developer_groups,
             ldap_user_admin_groups,
             ldap_user_default_role,
+            oidc_enable,
+            oidc_issuer,
+            oidc_client_id,
+            oidc_client_secret,
+            oidc_redirect_url,
+            oidc_scopes,
+            oidc_username_claim,
+            oidc_groups_claim,
+            oidc_user_developer_groups,
+            oidc_user_admin_groups,
+            oidc_user_default_role,
+            password_min_length,
+            password_min_char_types,
+            password_expire_days,
+            password_history_count,
+            password_init_admin_force_change,
+            login_lock_failed_times,
+            login_lock_second,
+            audit_log_keep_days,
+            audit_log_max_count,
+            tls_cert_file,
+            tls_key_file,
+            tls_ca_file,
+            http_tls_enable,
+            console_tls_enable,
+            grpc_tls_enable,
+            grpc_tls_client_auth,
+            cluster_tls_server_name,
+            tls_reload_interval_second,
         }
     }
 
@@ -346,6 +554,33 @@ impl AppSysConfig {
             ldap_user_default_role: self.ldap_user_default_role.clone(),
         })
     }
+
+    pub fn get_oidc_config(&self) -> Arc<OidcConfig> {
+        Arc::new(OidcConfig {
+            issuer: self.oidc_issuer.clone(),
+            client_id: self.oidc_client_id.clone(),
+            client_secret: self.oidc_client_secret.clone(),
+            redirect_url: self.oidc_redirect_url.clone(),
+            scopes: self.oidc_scopes.clone(),
+            username_claim: self.oidc_username_claim.clone(),
+            groups_claim: self.oidc_groups_claim.clone(),
+            user_developer_groups: self.oidc_user_developer_groups.clone(),
+            user_admin_groups: self.oidc_user_admin_groups.clone(),
+            user_default_role: self.oidc_user_default_role.clone(),
+        })
+    }
+
+    pub fn get_password_policy(&self) -> Arc<PasswordPolicy> {
+        Arc::new(PasswordPolicy {
+            min_length: self.password_min_length,
+            min_char_types: self.password_min_char_types,
+            expire_days: self.password_expire_days,
+            history_count: self.password_history_count,
+            init_admin_force_change: self.password_init_admin_force_change,
+            lock_failed_times: self.login_lock_failed_times,
+            lock_second: self.login_lock_second,
+        })
+    }
 }
 
 /**
@@ -4,7 +4,9 @@ pub mod privilege;
 use std::{collections::HashMap, sync::Arc};
 
 pub use crate::common::model::client_version::ClientVersion;
-use crate::common::model::privilege::PrivilegeGroup;
+use crate::common::model::privilege::{
+    GroupPrivilegeGroup, NamespacePrivilegeGroup, PrivilegeGroup,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -94,9 +96,32 @@ pub struct UserSession {
     pub nickname: Option<String>,
     pub roles: Vec<Arc<String>>,
     pub namespace_privilege: Option<PrivilegeGroup<Arc<String>>>,
+    #[serde(default)]
+    pub config_group_privilege: Option<PrivilegeGroup<Arc<String>>>,
+    #[serde(default)]
+    pub service_group_privilege: Option<PrivilegeGroup<Arc<String>>>,
     pub extend_infos: HashMap<String, String>,
     /// 时间戳，单位秒
     pub refresh_time: u32,
+    /// 需要先修改密码才能使用其它功能
+    #[serde(default)]
+    pub need_change_password: bool,
+}
+
+impl UserSession {
+    pub fn get_config_group_privilege(&self) -> GroupPrivilegeGroup {
+        self.config_group_privilege
+            .clone()
+            .map(GroupPrivilegeGroup::new)
+            .unwrap_or_default()
+    }
+
+    pub fn get_service_group_privilege(&self) -> GroupPrivilegeGroup {
+        self.service_group_privilege
+            .clone()
+            .map(GroupPrivilegeGroup::new)
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
@@ -104,4 +129,40 @@ pub struct TokenSession {
     pub username: Arc<String>,
     pub roles: Vec<Arc<String>>,
     pub extend_infos: HashMap<String, String>,
+    #[serde(default)]
+    pub config_group_privilege: Option<PrivilegeGroup<Arc<String>>>,
+    #[serde(default)]
+    pub service_group_privilege: Option<PrivilegeGroup<Arc<String>>>,
+    /// 访问密钥会话的命名空间范围,为空时不限制
+    #[serde(default)]
+    pub namespace_privilege: Option<PrivilegeGroup<Arc<String>>>,
+    /// 只读访问密钥会话
+    #[serde(default)]
+    pub read_only: bool,
+    /// 访问密钥会话对应的key id,使用时校验密钥是否仍然有效
+    #[serde(default)]
+    pub access_key_id: Option<Arc<String>>,
+}
+
+impl TokenSession {
+    pub fn get_config_group_privilege(&self) -> GroupPrivilegeGroup {
+        self.config_group_privilege
+            .clone()
+            .map(GroupPrivilegeGroup::new)
+            .unwrap_or_default()
+    }
+
+    pub fn get_service_group_privilege(&self) -> GroupPrivilegeGroup {
+        self.service_group_privilege
+            .clone()
+            .map(GroupPrivilegeGroup::new)
+            .unwrap_or_default()
+    }
+
+    pub fn check_namespace_permission(&self, namespace: &Arc<String>) -> bool {
+        match &self.namespace_privilege {
+            Some(v) => NamespacePrivilegeGroup::new(v.clone()).check_permission(namespace),
+            None => true,
+        }
+    }
 }
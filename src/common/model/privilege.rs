@@ -1,4 +1,5 @@
 use crate::common::constant::DEFAULT_NAMESPACE_ARC_STRING;
+use crate::config::config_fuzzy::ConfigFuzzyPattern;
 use crate::namespace::is_default_namespace;
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
@@ -40,6 +41,24 @@ where
             && self.blacklist_is_all.is_none()
             && self.blacklist.is_none()
     }
+
+    ///
+    /// 将设置的值覆盖到权限组,未设置的值保持不变
+    pub fn apply_to(self, group: &mut PrivilegeGroup<T>) {
+        group.enabled = true;
+        if let Some(v) = self.whitelist_is_all {
+            group.whitelist_is_all = v;
+        }
+        if let Some(v) = self.whitelist {
+            group.whitelist = Some(v);
+        }
+        if let Some(v) = self.blacklist_is_all {
+            group.blacklist_is_all = v;
+        }
+        if let Some(v) = self.blacklist {
+            group.blacklist = Some(v);
+        }
+    }
 }
 
 ///
@@ -210,3 +229,82 @@ impl NamespacePrivilegeGroup {
         self.0.is_all()
     }
 }
+
+///
+/// 分组数据权限组
+/// 黑白名单中的值支持`*`通配符
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupPrivilegeGroup(pub PrivilegeGroup<Arc<String>>);
+
+impl GroupPrivilegeGroup {
+    pub fn new(inner: PrivilegeGroup<Arc<String>>) -> Self {
+        Self(inner)
+    }
+
+    pub fn check_permission(&self, group: &str) -> bool {
+        self.at_whitelist(group) && !self.at_blacklist(group)
+    }
+
+    pub fn check_option_value_permission(&self, group: &Option<Arc<String>>) -> bool {
+        if let Some(group) = group {
+            self.check_permission(group)
+        } else {
+            self.is_all()
+        }
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.0.is_all()
+    }
+
+    fn at_whitelist(&self, group: &str) -> bool {
+        if self.0.whitelist_is_all {
+            return true;
+        }
+        Self::match_list(&self.0.whitelist, group)
+    }
+
+    fn at_blacklist(&self, group: &str) -> bool {
+        if self.0.blacklist_is_all {
+            return true;
+        }
+        Self::match_list(&self.0.blacklist, group)
+    }
+
+    fn match_list(list: &Option<Arc<HashSet<Arc<String>>>>, group: &str) -> bool {
+        if let Some(list) = list {
+            list.iter()
+                .any(|pattern| ConfigFuzzyPattern::wildcard_match(pattern, group))
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_privilege_wildcard() {
+        let whitelist: HashSet<Arc<String>> = ["team-a-*", "SHARED"]
+            .iter()
+            .map(|v| Arc::new(v.to_string()))
+            .collect();
+        let blacklist: HashSet<Arc<String>> = ["team-a-secret"]
+            .iter()
+            .map(|v| Arc::new(v.to_string()))
+            .collect();
+        let group = GroupPrivilegeGroup::new(PrivilegeGroup::new(
+            PrivilegeGroupFlags::ENABLE.bits(),
+            Some(Arc::new(whitelist)),
+            Some(Arc::new(blacklist)),
+        ));
+        assert!(group.check_permission("team-a-order"));
+        assert!(group.check_permission("SHARED"));
+        assert!(!group.check_permission("team-a-secret"));
+        assert!(!group.check_permission("team-b-order"));
+        assert!(!group.check_option_value_permission(&None));
+        assert!(GroupPrivilegeGroup::default().check_option_value_permission(&None));
+    }
+}
@@ -0,0 +1,703 @@
+use std::convert::TryFrom;
+use std::fs::File;
+use std::future::Future;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+use actix_http::{body::MessageBody, HttpService, Request, Response};
+use actix_service::{
+    fn_service, map_config, IntoServiceFactory, ServiceFactory, ServiceFactoryExt,
+};
+use actix_web::dev::AppConfig;
+use actix_web::Error;
+use rustls::server::{
+    AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient, NoClientAuth,
+};
+use rustls::{Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig, ServerName};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::{server::TlsStream, TlsAcceptor, TlsConnector};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::body::BoxBody;
+use tonic::codegen::http;
+use tonic::transport::server::Connected;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tower::{Service, ServiceExt};
+
+use super::AppSysConfig;
+
+pub const DEFAULT_TLS_RELOAD_INTERVAL_SECOND: u64 = 30;
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+///
+/// grpc服务端对客户端证书的校验方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TlsClientAuth {
+    #[default]
+    None,
+    /// 客户端提供证书时校验,不提供也允许连接
+    Optional,
+    /// 客户端必须提供CA签发的证书
+    Required,
+}
+
+impl TlsClientAuth {
+    ///
+    /// 开启客户端证书校验时,集群节点间请求必须携带CA校验通过的证书;
+    /// sdk客户端与集群节点共用grpc端口,Optional模式下只对集群请求强制要求证书
+    pub fn allow_cluster_request(&self, peer_cert_verified: bool) -> bool {
+        matches!(self, TlsClientAuth::None) || peer_cert_verified
+    }
+}
+
+impl From<&str> for TlsClientAuth {
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "optional" => Self::Optional,
+            "required" => Self::Required,
+            _ => Self::None,
+        }
+    }
+}
+
+///
+/// 证书相关文件,cert文件与key文件为pem格式
+#[derive(Debug, Clone, Default)]
+pub struct TlsFileConfig {
+    pub cert_file: Arc<String>,
+    pub key_file: Arc<String>,
+    pub ca_file: Arc<String>,
+}
+
+impl TlsFileConfig {
+    pub fn new(sys_config: &AppSysConfig) -> Self {
+        Self {
+            cert_file: sys_config.tls_cert_file.clone(),
+            key_file: sys_config.tls_key_file.clone(),
+            ca_file: sys_config.tls_ca_file.clone(),
+        }
+    }
+
+    fn modified_times(&self) -> Vec<Option<SystemTime>> {
+        [&self.cert_file, &self.key_file, &self.ca_file]
+            .iter()
+            .map(|path| {
+                if path.is_empty() {
+                    None
+                } else {
+                    std::fs::metadata(path.as_str())
+                        .and_then(|e| e.modified())
+                        .ok()
+                }
+            })
+            .collect()
+    }
+
+    pub fn build_server_config(
+        &self,
+        client_auth: TlsClientAuth,
+        alpn_protocols: Vec<Vec<u8>>,
+    ) -> anyhow::Result<ServerConfig> {
+        let certs = TlsUtils::load_certs(&self.cert_file)?;
+        let key = TlsUtils::load_private_key(&self.key_file)?;
+        let verifier = match client_auth {
+            TlsClientAuth::None => NoClientAuth::boxed(),
+            _ if self.ca_file.is_empty() => {
+                return Err(anyhow::anyhow!("tls client auth require ca file"));
+            }
+            TlsClientAuth::Optional => AllowAnyAnonymousOrAuthenticatedClient::new(
+                TlsUtils::load_root_store(&self.ca_file)?,
+            )
+            .boxed(),
+            TlsClientAuth::Required => {
+                AllowAnyAuthenticatedClient::new(TlsUtils::load_root_store(&self.ca_file)?).boxed()
+            }
+        };
+        let mut config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certs, key)?;
+        config.alpn_protocols = alpn_protocols;
+        Ok(config)
+    }
+
+    ///
+    /// 集群节点间的客户端配置,使用ca校验对端证书,并以本节点证书做客户端认证
+    pub fn build_client_config(&self) -> anyhow::Result<ClientConfig> {
+        if self.ca_file.is_empty() {
+            return Err(anyhow::anyhow!("tls client require ca file"));
+        }
+        let builder = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(TlsUtils::load_root_store(&self.ca_file)?);
+        let mut config = if self.cert_file.is_empty() || self.key_file.is_empty() {
+            builder.with_no_client_auth()
+        } else {
+            builder.with_client_auth_cert(
+                TlsUtils::load_certs(&self.cert_file)?,
+                TlsUtils::load_private_key(&self.key_file)?,
+            )?
+        };
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        Ok(config)
+    }
+}
+
+type TlsConfigBuilder<T> = Box<dyn Fn(&TlsFileConfig) -> anyhow::Result<T> + Send + Sync>;
+
+///
+/// 支持热更新的tls配置,证书文件修改后重新加载;新建连接使用最新配置,已建立的连接不受影响
+pub struct ReloadableTlsConfig<T> {
+    files: TlsFileConfig,
+    builder: TlsConfigBuilder<T>,
+    current: RwLock<Arc<T>>,
+    modified_times: Mutex<Vec<Option<SystemTime>>>,
+}
+
+pub type ServerTlsConfig = ReloadableTlsConfig<ServerConfig>;
+pub type ClientTlsConfig = ReloadableTlsConfig<ClientConfig>;
+
+impl<T> ReloadableTlsConfig<T>
+where
+    T: Send + Sync + 'static,
+{
+    pub fn new(files: TlsFileConfig, builder: TlsConfigBuilder<T>) -> anyhow::Result<Arc<Self>> {
+        let modified_times = files.modified_times();
+        let current = builder(&files)?;
+        Ok(Arc::new(Self {
+            files,
+            builder,
+            current: RwLock::new(Arc::new(current)),
+            modified_times: Mutex::new(modified_times),
+        }))
+    }
+
+    pub fn get(&self) -> Arc<T> {
+        self.current.read().unwrap().clone()
+    }
+
+    ///
+    /// 文件修改时间变化时重新加载,加载失败继续使用原配置
+    pub fn reload_if_changed(&self) -> bool {
+        let modified_times = self.files.modified_times();
+        {
+            let mut last_times = self.modified_times.lock().unwrap();
+            if *last_times == modified_times {
+                return false;
+            }
+            *last_times = modified_times;
+        }
+        match (self.builder)(&self.files) {
+            Ok(config) => {
+                *self.current.write().unwrap() = Arc::new(config);
+                log::info!("tls config reloaded,cert file:{}", &self.files.cert_file);
+                true
+            }
+            Err(err) => {
+                log::warn!("tls config reload error,{}", err);
+                false
+            }
+        }
+    }
+
+    pub fn spawn_reload_task(self: &Arc<Self>, interval_second: u64) {
+        if interval_second == 0 {
+            return;
+        }
+        let config = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_second));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                config.reload_if_changed();
+            }
+        });
+    }
+}
+
+pub struct TlsUtils;
+
+impl TlsUtils {
+    pub fn load_certs(path: &str) -> anyhow::Result<Vec<Certificate>> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let certs: Vec<Certificate> = rustls_pemfile::certs(&mut reader)?
+            .into_iter()
+            .map(Certificate)
+            .collect();
+        if certs.is_empty() {
+            return Err(anyhow::anyhow!("no certificate found in {}", path));
+        }
+        Ok(certs)
+    }
+
+    pub fn load_private_key(path: &str) -> anyhow::Result<PrivateKey> {
+        let mut reader = BufReader::new(File::open(path)?);
+        for item in rustls_pemfile::read_all(&mut reader)? {
+            match item {
+                rustls_pemfile::Item::PKCS8Key(key)
+                | rustls_pemfile::Item::RSAKey(key)
+                | rustls_pemfile::Item::ECKey(key) => return Ok(PrivateKey(key)),
+                _ => {}
+            }
+        }
+        Err(anyhow::anyhow!("no private key found in {}", path))
+    }
+
+    pub fn load_root_store(path: &str) -> anyhow::Result<RootCertStore> {
+        let mut store = RootCertStore::empty();
+        for cert in Self::load_certs(path)? {
+            store.add(&cert)?;
+        }
+        Ok(store)
+    }
+
+    ///
+    /// http openapi与控制台使用的服务端配置,不校验客户端证书
+    pub fn build_http_tls_config(
+        sys_config: &AppSysConfig,
+    ) -> anyhow::Result<Arc<ServerTlsConfig>> {
+        let config = ReloadableTlsConfig::new(
+            TlsFileConfig::new(sys_config),
+            Box::new(|files| files.build_server_config(TlsClientAuth::None, vec![])),
+        )?;
+        config.spawn_reload_task(sys_config.tls_reload_interval_second);
+        Ok(config)
+    }
+
+    pub fn build_grpc_tls_config(
+        sys_config: &AppSysConfig,
+    ) -> anyhow::Result<Arc<ServerTlsConfig>> {
+        let client_auth = sys_config.grpc_tls_client_auth;
+        let config = ReloadableTlsConfig::new(
+            TlsFileConfig::new(sys_config),
+            Box::new(move |files| files.build_server_config(client_auth, vec![b"h2".to_vec()])),
+        )?;
+        config.spawn_reload_task(sys_config.tls_reload_interval_second);
+        Ok(config)
+    }
+
+    ///
+    /// grpc开启tls时,集群节点间请求同样使用tls
+    pub fn build_cluster_tls_connector(
+        sys_config: &AppSysConfig,
+    ) -> anyhow::Result<Option<Arc<ClusterTlsConnector>>> {
+        if !sys_config.grpc_tls_enable {
+            return Ok(None);
+        }
+        let config = ReloadableTlsConfig::new(
+            TlsFileConfig::new(sys_config),
+            Box::new(|files| files.build_client_config()),
+        )?;
+        config.spawn_reload_task(sys_config.tls_reload_interval_second);
+        Ok(Some(Arc::new(ClusterTlsConnector::new(
+            config,
+            sys_config.cluster_tls_server_name.clone(),
+        ))))
+    }
+
+    ///
+    /// grpc服务的tls连接流,握手在独立任务中完成,避免慢连接阻塞accept
+    pub fn grpc_tls_incoming(
+        listener: TcpListener,
+        tls_config: Arc<ServerTlsConfig>,
+    ) -> ReceiverStream<std::io::Result<TlsServerStream>> {
+        let (tx, rx) = tokio::sync::mpsc::channel(128);
+        tokio::spawn(async move {
+            while !tx.is_closed() {
+                let (stream, remote_addr) = match listener.accept().await {
+                    Ok(v) => v,
+                    Err(err) => {
+                        log::warn!("grpc tls accept error,{}", err);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+                stream.set_nodelay(true).ok();
+                let acceptor = TlsAcceptor::from(tls_config.get());
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await
+                    {
+                        Ok(Ok(inner)) => {
+                            tx.send(Ok(TlsServerStream { inner, remote_addr }))
+                                .await
+                                .ok();
+                        }
+                        Ok(Err(err)) => {
+                            log::warn!("grpc tls handshake error,{},{}", &remote_addr, err)
+                        }
+                        Err(_) => log::warn!("grpc tls handshake timeout,{}", &remote_addr),
+                    }
+                });
+            }
+        });
+        ReceiverStream::new(rx)
+    }
+
+    ///
+    /// 构建https服务,使用方式同HttpServer::new
+    pub fn build_https_server<F, I, S, B>(
+        addr: &str,
+        workers: Option<usize>,
+        tls_config: Arc<ServerTlsConfig>,
+        factory: F,
+    ) -> std::io::Result<actix_server::Server>
+    where
+        F: Fn() -> I + Send + Clone + 'static,
+        I: IntoServiceFactory<S, Request>,
+        S: ServiceFactory<Request, Config = AppConfig> + 'static,
+        S::Error: Into<Error> + 'static,
+        S::InitError: std::fmt::Debug,
+        S::Response: Into<Response<B>> + 'static,
+        <S::Service as actix_service::Service<Request>>::Future: 'static,
+        S::Service: 'static,
+        B: MessageBody + 'static,
+    {
+        let listener = std::net::TcpListener::bind(addr)?;
+        let name = format!("rnacos-https-{}", addr);
+        let mut builder = actix_server::Server::build();
+        if let Some(num) = workers {
+            builder = builder.workers(num);
+        }
+        Ok(builder
+            .listen(name, listener, move || {
+                let tls_config = tls_config.clone();
+                let app = factory()
+                    .into_factory()
+                    .map_err(|err| err.into().error_response());
+                fn_service(move |io: TcpStream| {
+                    let acceptor = TlsAcceptor::from(tls_config.get());
+                    async move {
+                        let peer_addr = io.peer_addr().ok();
+                        let stream =
+                            tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(io))
+                                .await
+                                .map_err(|_| {
+                                    std::io::Error::new(
+                                        std::io::ErrorKind::TimedOut,
+                                        "tls handshake timeout",
+                                    )
+                                })
+                                .and_then(|e| e)
+                                .map_err(actix_http::error::DispatchError::Io)?;
+                        Ok((stream, actix_http::Protocol::Http1, peer_addr))
+                    }
+                })
+                .and_then(HttpService::build().finish(map_config(app, |_| AppConfig::default())))
+            })?
+            .run())
+    }
+}
+
+///
+/// 已完成tls握手的grpc连接
+pub struct TlsServerStream {
+    inner: TlsStream<TcpStream>,
+    remote_addr: SocketAddr,
+}
+
+impl Connected for TlsServerStream {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        Some(self.remote_addr)
+    }
+
+    ///
+    /// 客户端提供的证书在握手时已通过CA校验
+    fn peer_certs(&self) -> Option<Vec<tonic::transport::Certificate>> {
+        let (_, session) = self.inner.get_ref();
+        session.peer_certificates().map(|certs| {
+            certs
+                .iter()
+                .map(|e| tonic::transport::Certificate::from_pem(e.0.clone()))
+                .collect()
+        })
+    }
+}
+
+impl AsyncRead for TlsServerStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for TlsServerStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+///
+/// 集群节点间grpc的tls连接器
+pub struct ClusterTlsConnector {
+    tls_config: Arc<ClientTlsConfig>,
+    /// 校验对端证书使用的名称,为空时使用节点地址中的host
+    server_name: Arc<String>,
+}
+
+impl ClusterTlsConnector {
+    pub fn new(tls_config: Arc<ClientTlsConfig>, server_name: Arc<String>) -> Self {
+        Self {
+            tls_config,
+            server_name,
+        }
+    }
+
+    ///
+    /// 构建延迟连接的通道,与非tls的`connect_lazy`一致,对端节点未启动时不影响启动与证书重载
+    pub fn connect_lazy(self: &Arc<Self>, addr: Arc<String>) -> LazyTlsChannel {
+        LazyTlsChannel {
+            connector: self.clone(),
+            addr,
+            channel: Default::default(),
+        }
+    }
+
+    pub async fn connect(&self, addr: &str) -> anyhow::Result<Channel> {
+        let endpoint = Endpoint::from_shared(format!("https://{}", addr))?;
+        let server_name = if self.server_name.is_empty() {
+            endpoint.uri().host().unwrap_or_default().to_owned()
+        } else {
+            self.server_name.as_str().to_owned()
+        };
+        let server_name =
+            ServerName::try_from(server_name.trim_start_matches('[').trim_end_matches(']'))?;
+        let connector = TlsConnector::from(self.tls_config.get());
+        let service = tower::service_fn(move |uri: Uri| {
+            let connector = connector.clone();
+            let server_name = server_name.clone();
+            async move {
+                let addr = uri.authority().map(|e| e.as_str()).unwrap_or_default();
+                let stream = TcpStream::connect(addr).await?;
+                stream.set_nodelay(true)?;
+                connector.connect(server_name, stream).await
+            }
+        });
+        Ok(endpoint.connect_with_connector(service).await?)
+    }
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+type ChannelResponse = <Channel as Service<http::Request<BoxBody>>>::Response;
+
+///
+/// 集群节点间的tls通道,首次请求时才建立连接;连接失败时下次请求重新连接
+#[derive(Clone)]
+pub struct LazyTlsChannel {
+    connector: Arc<ClusterTlsConnector>,
+    addr: Arc<String>,
+    channel: Arc<tokio::sync::Mutex<Option<Channel>>>,
+}
+
+impl LazyTlsChannel {
+    async fn get_channel(&self) -> anyhow::Result<Channel> {
+        let mut channel = self.channel.lock().await;
+        if let Some(channel) = channel.as_ref() {
+            return Ok(channel.clone());
+        }
+        let new_channel = self.connector.connect(&self.addr).await?;
+        *channel = Some(new_channel.clone());
+        Ok(new_channel)
+    }
+}
+
+impl Service<http::Request<BoxBody>> for LazyTlsChannel {
+    type Response = ChannelResponse;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<BoxBody>) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let mut channel = this.get_channel().await?;
+            let resp = channel.ready().await?.call(req).await?;
+            Ok(resp)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::nacos_proto::request_client::RequestClient;
+    use crate::grpc::nacos_proto::request_server::{self, RequestServer};
+    use crate::grpc::nacos_proto::Payload;
+
+    const TESTDATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/common/testdata/tls");
+
+    fn testdata_file(name: &str) -> Arc<String> {
+        Arc::new(format!("{}/{}", TESTDATA_DIR, name))
+    }
+
+    fn node_files(node: &str) -> TlsFileConfig {
+        TlsFileConfig {
+            cert_file: testdata_file(&format!("{}.pem", node)),
+            key_file: testdata_file(&format!("{}.key", node)),
+            ca_file: testdata_file("ca.pem"),
+        }
+    }
+
+    fn build_connector(files: TlsFileConfig) -> Arc<ClusterTlsConnector> {
+        let config =
+            ReloadableTlsConfig::new(files, Box::new(|files| files.build_client_config())).unwrap();
+        Arc::new(ClusterTlsConnector::new(
+            config,
+            Arc::new("localhost".to_owned()),
+        ))
+    }
+
+    ///
+    /// 模拟集群请求处理:只接受携带校验通过证书的请求
+    struct ClusterRequestServer;
+
+    #[tonic::async_trait]
+    impl request_server::Request for ClusterRequestServer {
+        async fn request(
+            &self,
+            request: tonic::Request<Payload>,
+        ) -> Result<tonic::Response<Payload>, tonic::Status> {
+            let peer_cert_verified = request
+                .peer_certs()
+                .map(|certs| !certs.is_empty())
+                .unwrap_or(false);
+            if TlsClientAuth::Optional.allow_cluster_request(peer_cert_verified) {
+                Ok(tonic::Response::new(Payload::default()))
+            } else {
+                Err(tonic::Status::permission_denied("no client certificate"))
+            }
+        }
+    }
+
+    #[test]
+    fn tls_client_auth_parse() {
+        assert_eq!(TlsClientAuth::from("Required"), TlsClientAuth::Required);
+        assert_eq!(TlsClientAuth::from(" optional "), TlsClientAuth::Optional);
+        assert_eq!(TlsClientAuth::from(""), TlsClientAuth::None);
+        let files = TlsFileConfig {
+            cert_file: Arc::new("/not/exists/cert.pem".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(files.modified_times(), vec![None, None, None]);
+        assert!(files.build_client_config().is_err());
+    }
+
+    #[test]
+    fn allow_cluster_request() {
+        assert!(TlsClientAuth::None.allow_cluster_request(false));
+        assert!(!TlsClientAuth::Optional.allow_cluster_request(false));
+        assert!(TlsClientAuth::Optional.allow_cluster_request(true));
+        assert!(!TlsClientAuth::Required.allow_cluster_request(false));
+    }
+
+    #[actix_rt::test]
+    async fn grpc_tls_handshake() {
+        let server_config = ReloadableTlsConfig::new(
+            node_files("node1"),
+            Box::new(|files| {
+                files.build_server_config(TlsClientAuth::Optional, vec![b"h2".to_vec()])
+            }),
+        )
+        .unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = Arc::new(listener.local_addr().unwrap().to_string());
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(RequestServer::new(ClusterRequestServer))
+                .serve_with_incoming(TlsUtils::grpc_tls_incoming(listener, server_config)),
+        );
+
+        //携带CA签发证书的节点可以访问集群请求
+        let channel = build_connector(node_files("node2")).connect_lazy(addr.clone());
+        let mut client = RequestClient::new(channel);
+        assert!(client.request(Payload::default()).await.is_ok());
+
+        //未提供客户端证书时握手成功,但集群请求被拒绝
+        let files = TlsFileConfig {
+            ca_file: testdata_file("ca.pem"),
+            ..Default::default()
+        };
+        let channel = build_connector(files).connect_lazy(addr);
+        let mut client = RequestClient::new(channel);
+        let status = client.request(Payload::default()).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        //延迟连接,对端未启动时构建通道不报错,请求时才失败
+        let unused = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let unused_addr = Arc::new(unused.local_addr().unwrap().to_string());
+        drop(unused);
+        let channel = build_connector(node_files("node2")).connect_lazy(unused_addr);
+        let mut client = RequestClient::new(channel);
+        assert!(client.request(Payload::default()).await.is_err());
+    }
+
+    #[test]
+    fn tls_config_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let copy_file = |src: &str, dst: &str| -> Arc<String> {
+            let path = dir.path().join(dst).to_string_lossy().to_string();
+            std::fs::copy(testdata_file(src).as_str(), &path).unwrap();
+            Arc::new(path)
+        };
+        let files = TlsFileConfig {
+            cert_file: copy_file("node1.pem", "cert.pem"),
+            key_file: copy_file("node1.key", "cert.key"),
+            ca_file: copy_file("ca.pem", "ca.pem"),
+        };
+        let config = ReloadableTlsConfig::new(
+            files.clone(),
+            Box::new(|files| TlsUtils::load_certs(&files.cert_file)),
+        )
+        .unwrap();
+        let first_cert = config.get()[0].clone();
+        assert!(!config.reload_if_changed());
+
+        copy_file("node2.pem", "cert.pem");
+        copy_file("node2.key", "cert.key");
+        //避免文件系统时间精度导致修改时间未变化
+        let modified = SystemTime::now() + Duration::from_secs(5);
+        File::options()
+            .write(true)
+            .open(files.cert_file.as_str())
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        assert!(config.reload_if_changed());
+        assert_ne!(config.get()[0], first_cert);
+
+        //文件内容无效时继续使用原配置
+        std::fs::write(files.cert_file.as_str(), "invalid").unwrap();
+        File::options()
+            .write(true)
+            .open(files.cert_file.as_str())
+            .unwrap()
+            .set_modified(modified + Duration::from_secs(5))
+            .unwrap();
+        let reloaded_cert = config.get()[0].clone();
+        assert!(!config.reload_if_changed());
+        assert_eq!(config.get()[0], reloaded_cert);
+    }
+}
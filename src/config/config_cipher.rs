@@ -0,0 +1,271 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+
+use crate::common::crypto_utils::{decode_base64, decrypt_aes256, encode_base64, encrypt_aes256};
+use crate::config::core::{ConfigKey, ConfigValue};
+use crate::utils::get_md5;
+
+/// 需要加密存储的配置dataId前缀(与nacos一致)
+pub const CIPHER_DATA_ID_PREFIX: &str = "cipher-";
+
+/// 加密后内容的前缀,用于区分已加密内容
+const CIPHER_CONTENT_PREFIX: &str = "rnacos-cipher:";
+
+const IV_LEN: usize = 16;
+
+lazy_static::lazy_static! {
+    static ref CONFIG_CIPHER: Option<ConfigCipher> = ConfigCipher::init_from_env();
+}
+
+///
+/// 配置加密密钥来源
+pub trait ConfigCipherKeyProvider {
+    fn load_key(&self) -> anyhow::Result<Option<String>>;
+}
+
+///
+/// 从环境变量直接读取密钥
+pub struct EnvKeyProvider {
+    pub name: String,
+}
+
+impl ConfigCipherKeyProvider for EnvKeyProvider {
+    fn load_key(&self) -> anyhow::Result<Option<String>> {
+        Ok(std::env::var(&self.name).ok().filter(|v| !v.is_empty()))
+    }
+}
+
+///
+/// 从本地密钥文件读取密钥,忽略首尾空白
+pub struct FileKeyProvider {
+    pub path: PathBuf,
+}
+
+impl ConfigCipherKeyProvider for FileKeyProvider {
+    fn load_key(&self) -> anyhow::Result<Option<String>> {
+        let v = std::fs::read_to_string(&self.path)?;
+        let v = v.trim();
+        if v.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(v.to_owned()))
+        }
+    }
+}
+
+///
+/// 配置内容加解密,算法为AES-256-CBC,密钥为原始密钥的sha256值
+pub struct ConfigCipher {
+    key: [u8; 32],
+}
+
+impl ConfigCipher {
+    pub fn new(secret: &str) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.input_str(secret);
+        let mut key = [0u8; 32];
+        hasher.result(&mut key);
+        Self { key }
+    }
+
+    pub fn from_provider(provider: &dyn ConfigCipherKeyProvider) -> anyhow::Result<Option<Self>> {
+        Ok(provider.load_key()?.map(|v| Self::new(&v)))
+    }
+
+    ///
+    /// RNACOS_CONFIG_CIPHER_KEY_FILE优先,未设置时使用RNACOS_CONFIG_CIPHER_KEY
+    fn init_from_env() -> Option<Self> {
+        let key_file = std::env::var("RNACOS_CONFIG_CIPHER_KEY_FILE").unwrap_or_default();
+        let r = if key_file.is_empty() {
+            Self::from_provider(&EnvKeyProvider {
+                name: "RNACOS_CONFIG_CIPHER_KEY".to_owned(),
+            })
+        } else {
+            Self::from_provider(&FileKeyProvider {
+                path: key_file.into(),
+            })
+        };
+        match r {
+            Ok(v) => v,
+            Err(err) => {
+                log::error!("load config cipher key error,{}", err);
+                None
+            }
+        }
+    }
+
+    pub fn is_encrypted(content: &str) -> bool {
+        content.starts_with(CIPHER_CONTENT_PREFIX)
+    }
+
+    pub fn encrypt(&self, content: &str) -> anyhow::Result<String> {
+        if Self::is_encrypted(content) {
+            return Ok(content.to_owned());
+        }
+        let iv = *uuid::Uuid::new_v4().as_bytes();
+        let mut data = iv.to_vec();
+        data.extend(encrypt_aes256(&self.key, &iv, content.as_bytes())?);
+        Ok(format!("{}{}", CIPHER_CONTENT_PREFIX, encode_base64(&data)))
+    }
+
+    pub fn decrypt(&self, content: &str) -> anyhow::Result<String> {
+        let data = match content.strip_prefix(CIPHER_CONTENT_PREFIX) {
+            Some(v) => decode_base64(v)?,
+            None => return Ok(content.to_owned()),
+        };
+        if data.len() < IV_LEN {
+            return Err(anyhow::anyhow!("cipher content is too short"));
+        }
+        let (iv, cipher) = data.split_at(IV_LEN);
+        Ok(String::from_utf8(decrypt_aes256(&self.key, iv, cipher)?)?)
+    }
+}
+
+///
+/// 配置落盘(raft日志、镜像、迁移文件)前加密,加载到内存时解密;
+/// 内存中保存明文,md5按明文计算,与客户端保持一致
+pub struct ConfigCipherUtils;
+
+impl ConfigCipherUtils {
+    pub fn is_cipher_key(key: &ConfigKey) -> bool {
+        key.data_id.starts_with(CIPHER_DATA_ID_PREFIX)
+    }
+
+    ///
+    /// 发布加密配置前校验是否已配置密钥
+    pub fn check_enable(key: &ConfigKey) -> anyhow::Result<()> {
+        if Self::is_cipher_key(key) && CONFIG_CIPHER.is_none() {
+            return Err(anyhow::anyhow!(
+                "the config cipher key is not configured, can't publish config: {}",
+                &key.data_id
+            ));
+        }
+        Ok(())
+    }
+
+    ///
+    /// 写入raft日志前加密,未配置密钥时报错
+    pub fn encrypt_content(key: &ConfigKey, content: Arc<String>) -> anyhow::Result<Arc<String>> {
+        if !Self::is_cipher_key(key) || ConfigCipher::is_encrypted(&content) {
+            return Ok(content);
+        }
+        match CONFIG_CIPHER.as_ref() {
+            Some(cipher) => Ok(Arc::new(cipher.encrypt(&content)?)),
+            None => Err(anyhow::anyhow!(
+                "the config cipher key is not configured, config: {}",
+                &key.data_id
+            )),
+        }
+    }
+
+    ///
+    /// 解密失败时保留原内容
+    pub fn decrypt_content(key: &ConfigKey, content: Arc<String>) -> Arc<String> {
+        if !Self::is_cipher_key(key) || !ConfigCipher::is_encrypted(&content) {
+            return content;
+        }
+        match CONFIG_CIPHER
+            .as_ref()
+            .map(|cipher| cipher.decrypt(&content))
+        {
+            Some(Ok(v)) => Arc::new(v),
+            Some(Err(err)) => {
+                log::error!("decrypt config error, key:{}, {}", key.build_key(), err);
+                content
+            }
+            None => {
+                log::warn!(
+                    "the config cipher key is not configured, can't decrypt config: {}",
+                    key.build_key()
+                );
+                content
+            }
+        }
+    }
+
+    ///
+    /// 写入镜像、迁移文件前加密配置内容、历史记录及灰度内容;未配置密钥或加密失败时报错,不以明文写入
+    pub fn encrypt_value(key: &ConfigKey, mut value: ConfigValue) -> anyhow::Result<ConfigValue> {
+        if !Self::is_cipher_key(key) {
+            return Ok(value);
+        }
+        let cipher = match CONFIG_CIPHER.as_ref() {
+            Some(cipher) => cipher,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "the config cipher key is not configured, config: {}",
+                    key.build_key()
+                ))
+            }
+        };
+        let encrypt = |content: &Arc<String>| -> anyhow::Result<Arc<String>> {
+            cipher.encrypt(content).map(Arc::new).map_err(|err| {
+                anyhow::anyhow!("encrypt config error, key:{}, {}", key.build_key(), err)
+            })
+        };
+        value.content = encrypt(&value.content)?;
+        for item in &mut value.histories {
+            item.content = encrypt(&item.content)?;
+        }
+        if let Some(gray) = &mut value.gray {
+            gray.content = encrypt(&gray.content)?;
+        }
+        Ok(value)
+    }
+
+    ///
+    /// 写入镜像、迁移文件时使用;单个配置加密失败(如未配置密钥时已存在的加密dataId)不能中断整体写入,
+    /// 此时记录错误并按当前存储内容写入
+    pub fn encrypt_value_or_keep(key: &ConfigKey, value: ConfigValue) -> ConfigValue {
+        if !Self::is_cipher_key(key) {
+            return value;
+        }
+        match Self::encrypt_value(key, value.clone()) {
+            Ok(v) => v,
+            Err(err) => {
+                log::error!("{}, write the config as stored", err);
+                value
+            }
+        }
+    }
+
+    ///
+    /// 从镜像、迁移数据加载时解密,并按明文重新计算md5
+    pub fn decrypt_value(key: &ConfigKey, mut value: ConfigValue) -> ConfigValue {
+        if !Self::is_cipher_key(key) {
+            return value;
+        }
+        value.content = Self::decrypt_content(key, value.content);
+        value.md5 = Arc::new(get_md5(&value.content));
+        for item in &mut value.histories {
+            item.content = Self::decrypt_content(key, item.content.clone());
+        }
+        if let Some(gray) = &mut value.gray {
+            gray.content = Self::decrypt_content(key, gray.content.clone());
+            gray.md5 = Arc::new(get_md5(&gray.content));
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigCipher;
+
+    #[test]
+    fn encrypt_and_decrypt() {
+        let cipher = ConfigCipher::new("test-secret");
+        let content = "db.password=123456";
+        let v = cipher.encrypt(content).unwrap();
+        assert!(ConfigCipher::is_encrypted(&v));
+        assert!(!v.contains("123456"));
+        assert_ne!(v, cipher.encrypt(content).unwrap());
+        assert_eq!(cipher.encrypt(&v).unwrap(), v);
+        assert_eq!(cipher.decrypt(&v).unwrap(), content);
+        assert_eq!(cipher.decrypt(content).unwrap(), content);
+        assert!(ConfigCipher::new("other-secret").decrypt(&v).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+
+use crate::config::core::ConfigKey;
+
+///
+/// 对比的配置内容来源
+#[derive(Debug, Clone)]
+pub enum ConfigDiffSource {
+    /// 当前正式配置
+    Current(ConfigKey),
+    /// 指定历史记录
+    History(ConfigKey, u64),
+}
+
+impl ConfigDiffSource {
+    pub fn new(key: ConfigKey, history_id: Option<u64>) -> Self {
+        match history_id {
+            Some(id) => Self::History(key, id),
+            None => Self::Current(key),
+        }
+    }
+
+    pub fn get_key(&self) -> &ConfigKey {
+        match self {
+            ConfigDiffSource::Current(key) => key,
+            ConfigDiffSource::History(key, _) => key,
+        }
+    }
+
+    ///
+    /// 作为diff文件头的名称,格式: tenant/group/dataId@current 或 tenant/group/dataId@history-{id}
+    pub fn get_label(&self) -> String {
+        let key = self.get_key();
+        let version = match self {
+            ConfigDiffSource::Current(_) => "current".to_owned(),
+            ConfigDiffSource::History(_, id) => format!("history-{}", id),
+        };
+        format!(
+            "{}/{}/{}@{}",
+            &key.tenant, &key.group, &key.data_id, version
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiffDto {
+    pub source: String,
+    pub target: String,
+    pub changed: bool,
+    /// unified diff格式的差异内容,内容一致时为空
+    pub diff: String,
+}
+
+impl ConfigDiffDto {
+    pub fn build(
+        source: &ConfigDiffSource,
+        source_content: &Arc<String>,
+        target: &ConfigDiffSource,
+        target_content: &Arc<String>,
+    ) -> Self {
+        let source_label = source.get_label();
+        let target_label = target.get_label();
+        let changed = source_content != target_content;
+        let diff = if changed {
+            TextDiff::from_lines(source_content.as_str(), target_content.as_str())
+                .unified_diff()
+                .context_radius(3)
+                .header(&source_label, &target_label)
+                .to_string()
+        } else {
+            String::new()
+        };
+        Self {
+            source: source_label,
+            target: target_label,
+            changed,
+            diff,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigDiffDto, ConfigDiffSource};
+    use crate::config::core::ConfigKey;
+    use std::sync::Arc;
+
+    #[test]
+    fn build_unified_diff() {
+        let source =
+            ConfigDiffSource::new(ConfigKey::new("app.yaml", "DEFAULT_GROUP", "dev"), Some(3));
+        let target =
+            ConfigDiffSource::new(ConfigKey::new("app.yaml", "DEFAULT_GROUP", "prod"), None);
+        let old = Arc::new("a: 1\nb: 2\nc: 3\n".to_owned());
+        let new = Arc::new("a: 1\nb: 20\nc: 3\n".to_owned());
+        let dto = ConfigDiffDto::build(&source, &old, &target, &new);
+        assert!(dto.changed);
+        assert_eq!(dto.source, "dev/DEFAULT_GROUP/app.yaml@history-3");
+        assert_eq!(dto.target, "prod/DEFAULT_GROUP/app.yaml@current");
+        assert!(dto.diff.starts_with(
+            "--- dev/DEFAULT_GROUP/app.yaml@history-3\n+++ prod/DEFAULT_GROUP/app.yaml@current\n"
+        ));
+        assert!(dto.diff.contains("-b: 2\n+b: 20\n"));
+
+        let dto = ConfigDiffDto::build(&source, &old, &target, &old);
+        assert!(!dto.changed);
+        assert!(dto.diff.is_empty());
+    }
+}
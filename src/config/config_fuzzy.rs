@@ -0,0 +1,328 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::model::privilege::GroupPrivilegeGroup;
+use crate::config::core::{ConfigKey, ListenerItem};
+
+pub const FUZZY_WATCH: &str = "WATCH";
+pub const FUZZY_CANCEL_WATCH: &str = "CANCEL_WATCH";
+
+///
+/// 配置模糊订阅规则
+/// tenant需要精确匹配;group与dataId支持`*`通配符,如`route-*`
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct ConfigFuzzyPattern {
+    pub tenant: Arc<String>,
+    pub group: Arc<String>,
+    pub data_id: Arc<String>,
+}
+
+impl ConfigFuzzyPattern {
+    pub fn new(data_id: &str, group: &str, tenant: &str) -> Self {
+        Self {
+            tenant: Arc::new(tenant.to_owned()),
+            group: Arc::new(Self::fill_pattern(group)),
+            data_id: Arc::new(Self::fill_pattern(data_id)),
+        }
+    }
+
+    fn fill_pattern(pattern: &str) -> String {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            "*".to_owned()
+        } else {
+            pattern.to_owned()
+        }
+    }
+
+    pub fn match_key(&self, key: &ConfigKey) -> bool {
+        self.tenant.as_str() == key.tenant.as_str()
+            && Self::wildcard_match(&self.group, &key.group)
+            && Self::wildcard_match(&self.data_id, &key.data_id)
+    }
+
+    ///
+    /// `*`匹配任意长度字符(包括空字符)
+    pub fn wildcard_match(pattern: &str, value: &str) -> bool {
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        if !value.starts_with(first) {
+            return false;
+        }
+        let mut rest = &value[first.len()..];
+        let mut parts: Vec<&str> = parts.collect();
+        let last = match parts.pop() {
+            Some(v) => v,
+            //无通配符,需要完全相等
+            None => return rest.is_empty(),
+        };
+        for part in parts {
+            match rest.find(part) {
+                Some(i) => rest = &rest[i + part.len()..],
+                None => return false,
+            }
+        }
+        rest.len() >= last.len() && rest.ends_with(last)
+    }
+}
+
+///
+/// 模糊订阅规则及订阅者的配置分组数据权限,只匹配有权限的分组
+#[derive(Debug, Clone, Default)]
+pub struct ConfigFuzzyWatcher {
+    pub pattern: ConfigFuzzyPattern,
+    pub group_privilege: GroupPrivilegeGroup,
+}
+
+impl ConfigFuzzyWatcher {
+    pub fn new(pattern: ConfigFuzzyPattern, group_privilege: GroupPrivilegeGroup) -> Self {
+        Self {
+            pattern,
+            group_privilege,
+        }
+    }
+
+    ///
+    /// 分组规则不含通配符时,需要有该分组的权限才能订阅
+    pub fn check_group_permission(&self) -> bool {
+        self.pattern.group.contains('*')
+            || self.group_privilege.check_permission(&self.pattern.group)
+    }
+
+    pub fn match_key(&self, key: &ConfigKey) -> bool {
+        self.pattern.match_key(key) && self.group_privilege.check_permission(&key.group)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigChangeType {
+    Add,
+    Update,
+    Delete,
+}
+
+impl ConfigChangeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigChangeType::Add => "ADD_CONFIG",
+            ConfigChangeType::Update => "CONFIG_CHANGED",
+            ConfigChangeType::Delete => "DELETE_CONFIG",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigFuzzyEvent {
+    pub key: ConfigKey,
+    pub change_type: ConfigChangeType,
+}
+
+impl ConfigFuzzyEvent {
+    pub fn new(key: ConfigKey, change_type: ConfigChangeType) -> Self {
+        Self { key, change_type }
+    }
+
+    pub fn to_dto(&self) -> ConfigFuzzyChangeDto {
+        ConfigFuzzyChangeDto {
+            tenant: self.key.tenant.clone(),
+            group: self.key.group.clone(),
+            data_id: self.key.data_id.clone(),
+            change_type: self.change_type.as_str().to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFuzzyChangeDto {
+    pub tenant: Arc<String>,
+    pub group: Arc<String>,
+    pub data_id: Arc<String>,
+    pub change_type: String,
+}
+
+///
+/// 根据客户端已知的配置md5,计算模糊订阅范围内的新增、变更、删除配置
+pub(crate) fn build_fuzzy_changes<'a>(
+    watcher: &ConfigFuzzyWatcher,
+    known_items: &[ListenerItem],
+    current: impl Iterator<Item = (&'a ConfigKey, &'a Arc<String>)>,
+) -> Vec<ConfigFuzzyEvent> {
+    let mut known: HashMap<&ConfigKey, &Arc<String>> = known_items
+        .iter()
+        .filter(|e| watcher.match_key(&e.key))
+        .map(|e| (&e.key, &e.md5))
+        .collect();
+    let mut changes = vec![];
+    for (key, md5) in current.filter(|(key, _)| watcher.match_key(key)) {
+        match known.remove(key) {
+            Some(known_md5) => {
+                if known_md5 != md5 {
+                    changes.push(ConfigFuzzyEvent::new(key.clone(), ConfigChangeType::Update));
+                }
+            }
+            None => changes.push(ConfigFuzzyEvent::new(key.clone(), ConfigChangeType::Add)),
+        }
+    }
+    for key in known.into_keys() {
+        changes.push(ConfigFuzzyEvent::new(key.clone(), ConfigChangeType::Delete));
+    }
+    changes
+}
+
+///
+/// 长链接客户端的模糊订阅关系
+#[derive(Default)]
+pub struct ConfigFuzzySubscriber {
+    listener: HashMap<ConfigFuzzyPattern, HashSet<Arc<String>>>,
+    client_patterns: HashMap<Arc<String>, HashSet<ConfigFuzzyPattern>>,
+    /// 客户端的配置分组数据权限,推送时过滤无权限的分组
+    client_privilege: HashMap<Arc<String>, GroupPrivilegeGroup>,
+}
+
+impl ConfigFuzzySubscriber {
+    pub fn add_subscribe(&mut self, client_id: Arc<String>, watcher: ConfigFuzzyWatcher) {
+        let pattern = watcher.pattern;
+        self.client_privilege
+            .insert(client_id.clone(), watcher.group_privilege);
+        self.listener
+            .entry(pattern.clone())
+            .or_default()
+            .insert(client_id.clone());
+        self.client_patterns
+            .entry(client_id)
+            .or_default()
+            .insert(pattern);
+    }
+
+    pub fn remove_subscribe(&mut self, client_id: &Arc<String>, pattern: &ConfigFuzzyPattern) {
+        if let Some(set) = self.listener.get_mut(pattern) {
+            set.remove(client_id);
+            if set.is_empty() {
+                self.listener.remove(pattern);
+            }
+        }
+        if let Some(set) = self.client_patterns.get_mut(client_id) {
+            set.remove(pattern);
+            if set.is_empty() {
+                self.client_patterns.remove(client_id);
+                self.client_privilege.remove(client_id);
+            }
+        }
+    }
+
+    pub fn remove_client_subscribe(&mut self, client_id: &Arc<String>) {
+        self.client_privilege.remove(client_id);
+        if let Some(patterns) = self.client_patterns.remove(client_id) {
+            for pattern in patterns {
+                if let Some(set) = self.listener.get_mut(&pattern) {
+                    set.remove(client_id);
+                    if set.is_empty() {
+                        self.listener.remove(&pattern);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn match_clients(&self, key: &ConfigKey) -> HashSet<Arc<String>> {
+        let mut clients = HashSet::new();
+        for (pattern, set) in &self.listener {
+            if pattern.match_key(key) {
+                clients.extend(set.iter().cloned());
+            }
+        }
+        clients.retain(|client_id| {
+            self.client_privilege
+                .get(client_id)
+                .map(|e| e.check_permission(&key.group))
+                .unwrap_or(true)
+        });
+        clients
+    }
+
+    pub fn get_pattern_size(&self) -> usize {
+        self.listener.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::model::privilege::{PrivilegeGroup, PrivilegeGroupFlags};
+
+    #[test]
+    fn fuzzy_pattern_match() {
+        assert!(ConfigFuzzyPattern::wildcard_match("route-*", "route-a"));
+        assert!(ConfigFuzzyPattern::wildcard_match("route-*", "route-"));
+        assert!(!ConfigFuzzyPattern::wildcard_match("route-*", "xroute-a"));
+        assert!(ConfigFuzzyPattern::wildcard_match("*-gw-*", "a-gw-b"));
+        assert!(ConfigFuzzyPattern::wildcard_match("a*a", "aa"));
+        assert!(!ConfigFuzzyPattern::wildcard_match("a*a", "a"));
+        assert!(ConfigFuzzyPattern::wildcard_match("app", "app"));
+        assert!(!ConfigFuzzyPattern::wildcard_match("app", "app1"));
+
+        let pattern = ConfigFuzzyPattern::new("route-*", "GATEWAY", "");
+        assert!(pattern.match_key(&ConfigKey::new("route-order", "GATEWAY", "")));
+        assert!(!pattern.match_key(&ConfigKey::new("route-order", "DEFAULT", "")));
+        assert!(!pattern.match_key(&ConfigKey::new("route-order", "GATEWAY", "dev")));
+
+        let watcher = ConfigFuzzyWatcher::new(pattern.clone(), GroupPrivilegeGroup::default());
+        let md5 = Arc::new("1".to_owned());
+        let new_md5 = Arc::new("2".to_owned());
+        let current = vec![
+            (ConfigKey::new("route-a", "GATEWAY", ""), md5.clone()),
+            (ConfigKey::new("route-b", "GATEWAY", ""), new_md5),
+            (ConfigKey::new("other", "GATEWAY", ""), md5.clone()),
+        ];
+        let known = vec![
+            ListenerItem::new(ConfigKey::new("route-b", "GATEWAY", ""), md5.clone()),
+            ListenerItem::new(ConfigKey::new("route-c", "GATEWAY", ""), md5),
+        ];
+        let mut changes: Vec<(String, &str)> =
+            build_fuzzy_changes(&watcher, &known, current.iter().map(|(k, v)| (k, v)))
+                .into_iter()
+                .map(|e| (e.key.data_id.as_ref().to_owned(), e.change_type.as_str()))
+                .collect();
+        changes.sort();
+        assert_eq!(
+            changes,
+            vec![
+                ("route-a".to_owned(), "ADD_CONFIG"),
+                ("route-b".to_owned(), "CONFIG_CHANGED"),
+                ("route-c".to_owned(), "DELETE_CONFIG"),
+            ]
+        );
+
+        //只匹配、推送有权限的分组
+        let whitelist: HashSet<Arc<String>> = [Arc::new("GATEWAY".to_owned())].into();
+        let group_privilege = GroupPrivilegeGroup::new(PrivilegeGroup::new(
+            PrivilegeGroupFlags::ENABLE.bits(),
+            Some(Arc::new(whitelist)),
+            None,
+        ));
+        let watcher = ConfigFuzzyWatcher::new(
+            ConfigFuzzyPattern::new("*", "*", ""),
+            group_privilege.clone(),
+        );
+        assert!(watcher.check_group_permission());
+        assert!(watcher.match_key(&ConfigKey::new("route-a", "GATEWAY", "")));
+        assert!(!watcher.match_key(&ConfigKey::new("db", "SECRET", "")));
+        assert!(!ConfigFuzzyWatcher::new(
+            ConfigFuzzyPattern::new("*", "SECRET", ""),
+            group_privilege
+        )
+        .check_group_permission());
+        let client_id = Arc::new("c1".to_owned());
+        let mut subscriber = ConfigFuzzySubscriber::default();
+        subscriber.add_subscribe(client_id.clone(), watcher);
+        assert!(subscriber
+            .match_clients(&ConfigKey::new("route-a", "GATEWAY", ""))
+            .contains(&client_id));
+        assert!(subscriber
+            .match_clients(&ConfigKey::new("db", "SECRET", ""))
+            .is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::core::ConfigKey;
+use crate::utils::get_md5;
+
+///
+/// 灰度发布规则
+/// 客户端ip命中列表,或客户端标签包含规则中的全部标签时,读取灰度配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigGrayRule {
+    pub client_ips: Vec<String>,
+    pub labels: HashMap<String, String>,
+}
+
+impl ConfigGrayRule {
+    ///
+    /// 按页面参数构建规则
+    /// ips格式: ip1,ip2
+    /// labels格式: k1=v1,k2=v2
+    pub fn from_web_param(ips: Option<&str>, labels: Option<&str>) -> Self {
+        let client_ips = ips
+            .unwrap_or_default()
+            .split(',')
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+            .map(|e| e.to_owned())
+            .collect();
+        let mut label_map = HashMap::new();
+        for item in labels.unwrap_or_default().split(',') {
+            if let Some((k, v)) = item.split_once('=') {
+                let k = k.trim();
+                if !k.is_empty() {
+                    label_map.insert(k.to_owned(), v.trim().to_owned());
+                }
+            }
+        }
+        Self {
+            client_ips,
+            labels: label_map,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.client_ips.is_empty() && self.labels.is_empty()
+    }
+
+    pub fn match_client(&self, client: &ConfigClient) -> bool {
+        if self.client_ips.iter().any(|e| e == client.ip.as_str()) {
+            return true;
+        }
+        if self.labels.is_empty() {
+            return false;
+        }
+        self.labels
+            .iter()
+            .all(|(k, v)| client.labels.get(k).map(|e| e == v).unwrap_or(false))
+    }
+}
+
+///
+/// 读取配置的客户端信息,用于匹配灰度规则
+#[derive(Debug, Clone, Default)]
+pub struct ConfigClient {
+    pub ip: Arc<String>,
+    pub labels: Arc<HashMap<String, String>>,
+}
+
+impl ConfigClient {
+    pub fn new(ip: Arc<String>, labels: Arc<HashMap<String, String>>) -> Self {
+        Self { ip, labels }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigGrayValue {
+    pub(crate) content: Arc<String>,
+    pub(crate) md5: Arc<String>,
+    pub(crate) rule: ConfigGrayRule,
+    pub(crate) last_modified: i64,
+    pub(crate) op_user: Option<Arc<String>>,
+}
+
+impl ConfigGrayValue {
+    pub fn new(
+        content: Arc<String>,
+        rule: ConfigGrayRule,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+    ) -> Self {
+        let md5 = Arc::new(get_md5(&content));
+        Self {
+            content,
+            md5,
+            rule,
+            last_modified: op_time,
+            op_user,
+        }
+    }
+
+    pub(crate) fn to_dto(&self, key: &ConfigKey) -> ConfigGrayInfoDto {
+        ConfigGrayInfoDto {
+            tenant: key.tenant.clone(),
+            group: key.group.clone(),
+            data_id: key.data_id.clone(),
+            content: self.content.clone(),
+            md5: self.md5.clone(),
+            client_ips: self.rule.client_ips.clone(),
+            labels: self.rule.labels.clone(),
+            last_modified: self.last_modified,
+            op_user: self.op_user.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigGrayInfoDto {
+    pub tenant: Arc<String>,
+    pub group: Arc<String>,
+    pub data_id: Arc<String>,
+    pub content: Arc<String>,
+    pub md5: Arc<String>,
+    pub client_ips: Vec<String>,
+    pub labels: HashMap<String, String>,
+    pub last_modified: i64,
+    pub op_user: Option<Arc<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigClient, ConfigGrayRule};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn match_client_by_ip_or_labels() {
+        let rule = ConfigGrayRule::from_web_param(
+            Some("10.0.0.1, 10.0.0.2,"),
+            Some("AppName=order,zone = z1"),
+        );
+        assert_eq!(rule.client_ips, vec!["10.0.0.1", "10.0.0.2"]);
+        assert_eq!(rule.labels.len(), 2);
+
+        let client = ConfigClient::new(Arc::new("10.0.0.2".to_owned()), Default::default());
+        assert!(rule.match_client(&client));
+
+        let mut labels = HashMap::new();
+        labels.insert("AppName".to_owned(), "order".to_owned());
+        let client = ConfigClient::new(Arc::new("10.0.0.9".to_owned()), Arc::new(labels.clone()));
+        assert!(!rule.match_client(&client));
+
+        labels.insert("zone".to_owned(), "z1".to_owned());
+        let client = ConfigClient::new(Arc::new("10.0.0.9".to_owned()), Arc::new(labels));
+        assert!(rule.match_client(&client));
+
+        let empty_rule = ConfigGrayRule::from_web_param(None, None);
+        assert!(empty_rule.is_empty());
+        assert!(!empty_rule.match_client(&client));
+    }
+}
@@ -1,6 +1,7 @@
-use crate::common::model::privilege::NamespacePrivilegeGroup;
+use crate::common::model::privilege::{GroupPrivilegeGroup, NamespacePrivilegeGroup};
 use crate::common::string_utils::StringUtils;
 use crate::config::core::ConfigKey;
+use crate::config::ConfigUtils;
 use crate::namespace::model::{NamespaceActorReq, WeakNamespaceFromType, WeakNamespaceParam};
 use crate::namespace::NamespaceActor;
 use actix::Addr;
@@ -18,7 +19,10 @@ pub struct ConfigQueryParam {
     pub data_id: Option<Arc<String>>,
     pub like_group: Option<String>,
     pub like_data_id: Option<String>,
+    /// 标签过滤,配置包含其中任一标签即命中
+    pub tags: Option<Vec<String>>,
     pub namespace_privilege: NamespacePrivilegeGroup,
+    pub group_privilege: GroupPrivilegeGroup,
     pub query_context: bool,
     pub offset: usize,
     pub limit: usize,
@@ -26,6 +30,9 @@ pub struct ConfigQueryParam {
 
 impl ConfigQueryParam {
     pub fn match_group(&self, g: &Arc<String>) -> bool {
+        if !self.group_privilege.check_permission(g) {
+            return false;
+        }
         if let Some(group) = &self.group {
             group.is_empty() || StringUtils::eq(g, group)
         } else if let Some(like_group) = &self.like_group {
@@ -43,6 +50,17 @@ impl ConfigQueryParam {
             true
         }
     }
+    pub fn match_tags(&self, config_tags: Option<&Arc<String>>) -> bool {
+        match &self.tags {
+            Some(tags) if !tags.is_empty() => config_tags
+                .map(|v| ConfigUtils::split_tags(v).any(|t| tags.iter().any(|e| e == t)))
+                .unwrap_or(false),
+            _ => true,
+        }
+    }
+    pub fn has_tags(&self) -> bool {
+        self.tags.as_ref().map(|v| !v.is_empty()).unwrap_or(false)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -86,12 +104,16 @@ impl ConfigIndex {
         (b, self.group_data.len())
     }
 
-    pub(crate) fn query_config_page(
+    pub(crate) fn query_config_page<F>(
         &self,
         tenant: &Arc<String>,
         limit: usize,
         param: &ConfigQueryParam,
-    ) -> (usize, Vec<ConfigKey>) {
+        filter: &F,
+    ) -> (usize, Vec<ConfigKey>)
+    where
+        F: Fn(&ConfigKey) -> bool,
+    {
         let mut rlist = vec![];
         let end_index = param.offset + limit;
         let mut index = 0;
@@ -99,8 +121,11 @@ impl ConfigIndex {
             if param.match_group(g) {
                 for s in set {
                     if param.match_data_id(s) {
+                        let key = ConfigKey::new_by_arc(s.clone(), g.clone(), tenant.clone());
+                        if !filter(&key) {
+                            continue;
+                        }
                         if index >= param.offset && index < end_index {
-                            let key = ConfigKey::new_by_arc(s.clone(), g.clone(), tenant.clone());
                             rlist.push(key);
                         }
                         index += 1;
@@ -212,20 +237,33 @@ impl TenantIndex {
     }
 
     pub fn query_config_page(&self, param: &ConfigQueryParam) -> (usize, Vec<ConfigKey>) {
+        self.query_config_page_by_filter(param, &|_| true)
+    }
+
+    ///
+    /// 分页查询配置,filter用于按索引外的属性(如标签)过滤
+    pub fn query_config_page_by_filter<F>(
+        &self,
+        param: &ConfigQueryParam,
+        filter: &F,
+    ) -> (usize, Vec<ConfigKey>)
+    where
+        F: Fn(&ConfigKey) -> bool,
+    {
         let mut rlist = vec![];
         let mut size = 0;
         let mut limit = param.limit;
         if let Some(tenant) = &param.tenant {
             if param.namespace_privilege.check_permission(tenant) {
                 if let Some(index) = self.tenant_group.get(tenant) {
-                    return index.query_config_page(tenant, limit, param);
+                    return index.query_config_page(tenant, limit, param, filter);
                 }
             }
         } else {
             for (tenant, service_index) in &self.tenant_group {
                 if param.namespace_privilege.check_permission(tenant) {
                     let (sub_size, mut sub_list) =
-                        service_index.query_config_page(tenant, limit, param);
+                        service_index.query_config_page(tenant, limit, param, filter);
                     size += sub_size;
                     limit -= sub_list.len();
                     rlist.append(&mut sub_list);
@@ -315,3 +353,35 @@ fn query_service() {
     assert!(size == 0);
     assert!(list.is_empty());
 }
+
+#[test]
+fn query_by_tags() {
+    let mut index = TenantIndex::new();
+    let key1 = ConfigKey::new("1", "1", "1");
+    let key2 = ConfigKey::new("2", "1", "1");
+    let key3 = ConfigKey::new("3", "1", "1");
+    index.insert_config(key1.clone());
+    index.insert_config(key2.clone());
+    index.insert_config(key3.clone());
+    let tags = |key: &ConfigKey| -> Option<Arc<String>> {
+        if key == &key1 {
+            Some(ConfigUtils::format_tags(" team-a, sys-x ,team-a"))
+        } else if key == &key2 {
+            Some(ConfigUtils::format_tags("team-b"))
+        } else {
+            None
+        }
+    };
+    assert_eq!(tags(&key1).unwrap().as_str(), "team-a,sys-x");
+
+    let param = ConfigQueryParam {
+        tenant: Some(Arc::new("1".to_owned())),
+        tags: Some(vec!["sys-x".to_owned(), "team-b".to_owned()]),
+        limit: 0xffff_ffff,
+        ..ConfigQueryParam::default()
+    };
+    let (size, list) =
+        index.query_config_page_by_filter(&param, &|key| param.match_tags(tags(key).as_ref()));
+    assert_eq!(size, 2);
+    assert_eq!(list, vec![key1.clone(), key2.clone()]);
+}
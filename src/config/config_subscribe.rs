@@ -3,6 +3,9 @@ use std::{
     sync::Arc,
 };
 
+use super::config_fuzzy::{
+    ConfigChangeType, ConfigFuzzyPattern, ConfigFuzzySubscriber, ConfigFuzzyWatcher,
+};
 use super::core::{ConfigKey, ListenerItem};
 use crate::grpc::bistream_manage::{BiStreamManage, BiStreamManageCmd};
 use actix::prelude::*;
@@ -11,6 +14,7 @@ use actix::prelude::*;
 pub struct Subscriber {
     listener: HashMap<ConfigKey, HashSet<Arc<String>>>,
     client_keys: HashMap<Arc<String>, HashSet<ConfigKey>>,
+    fuzzy_subscriber: ConfigFuzzySubscriber,
     conn_manage: Option<Addr<BiStreamManage>>,
 }
 
@@ -19,6 +23,7 @@ impl Subscriber {
         Self {
             listener: Default::default(),
             client_keys: Default::default(),
+            fuzzy_subscriber: Default::default(),
             conn_manage: Default::default(),
         }
     }
@@ -84,7 +89,16 @@ impl Subscriber {
         }
     }
 
+    pub fn add_fuzzy_subscribe(&mut self, client_id: Arc<String>, watcher: ConfigFuzzyWatcher) {
+        self.fuzzy_subscriber.add_subscribe(client_id, watcher);
+    }
+
+    pub fn remove_fuzzy_subscribe(&mut self, client_id: Arc<String>, pattern: ConfigFuzzyPattern) {
+        self.fuzzy_subscriber.remove_subscribe(&client_id, &pattern);
+    }
+
     pub fn remove_client_subscribe(&mut self, client_id: Arc<String>) {
+        self.fuzzy_subscriber.remove_client_subscribe(&client_id);
         if let Some(set) = self.client_keys.remove(&client_id) {
             let mut remove_keys = vec![];
             for key in set {
@@ -126,6 +140,25 @@ impl Subscriber {
         }
     }
 
+    ///
+    /// 通知模糊订阅的客户端;新增、变更、删除配置都会通知
+    pub fn notify_fuzzy(&self, key: &ConfigKey, change_type: ConfigChangeType) {
+        if let Some(conn_manage) = &self.conn_manage {
+            let set = self.fuzzy_subscriber.match_clients(key);
+            if !set.is_empty() {
+                conn_manage.do_send(BiStreamManageCmd::NotifyConfigFuzzy(
+                    key.clone(),
+                    change_type,
+                    set,
+                ));
+            }
+        }
+    }
+
+    pub fn get_fuzzy_pattern_size(&self) -> usize {
+        self.fuzzy_subscriber.get_pattern_size()
+    }
+
     pub fn get_listener_key_size(&self) -> usize {
         self.listener.len()
     }
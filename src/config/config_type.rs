@@ -1,5 +1,9 @@
 use std::sync::Arc;
 
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use thiserror::Error;
+
 lazy_static::lazy_static! {
     pub(crate) static ref CONFIG_TYPE_TEXT: Arc<String> =  Arc::new("text".to_string());
     pub(crate) static ref CONFIG_TYPE_JSON: Arc<String> =  Arc::new("json".to_string());
@@ -16,6 +20,15 @@ pub(crate) const MEDIA_TYPE_TEXT_HTML: &str = "text/html;charset=UTF-8";
 pub(crate) const MEDIA_TYPE_APPLICATION_JSON: &str = "application/json;charset=UTF-8";
 pub(crate) const MEDIA_TYPE_APPLICATION_XML: &str = "application/xml;charset=UTF-8";
 
+///
+/// 配置内容与声明的类型不匹配
+#[derive(Debug, Clone, Error)]
+#[error("config content is not valid {config_type}: {msg}")]
+pub struct ConfigContentInvalidError {
+    pub config_type: Arc<String>,
+    pub msg: String,
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum ConfigType {
     Text,
@@ -78,4 +91,130 @@ impl ConfigType {
             ConfigType::Toml => MEDIA_TYPE_TEXT_PLAIN,
         }
     }
+
+    ///
+    /// 按类型校验配置内容;text与html不做校验
+    ///
+    pub fn check_content(&self, content: &str) -> Result<(), ConfigContentInvalidError> {
+        let r = match self {
+            ConfigType::Text | ConfigType::Html => Ok(()),
+            ConfigType::Json => serde_json::from_str::<serde_json::Value>(content)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            ConfigType::Yaml => serde_yaml::from_str::<serde_yaml::Value>(content)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            ConfigType::Toml => toml::from_str::<toml::Table>(content)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            ConfigType::Xml => Self::check_xml(content),
+            ConfigType::Properties => Self::check_properties(content),
+        };
+        r.map_err(|msg| ConfigContentInvalidError {
+            config_type: self.get_value(),
+            msg,
+        })
+    }
+
+    fn check_xml(content: &str) -> Result<(), String> {
+        let mut reader = Reader::from_str(content);
+        let mut depth = 0usize;
+        let mut has_root = false;
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(_)) => {
+                    if depth == 0 && has_root {
+                        return Err("multiple root elements".to_owned());
+                    }
+                    depth += 1;
+                    has_root = true;
+                }
+                Ok(Event::Empty(_)) => {
+                    if depth == 0 && has_root {
+                        return Err("multiple root elements".to_owned());
+                    }
+                    has_root = true;
+                }
+                Ok(Event::End(_)) => {
+                    if depth == 0 {
+                        return Err("unexpected end element".to_owned());
+                    }
+                    depth -= 1;
+                }
+                Ok(Event::Text(e)) => {
+                    if depth == 0 && !e.iter().all(|c| c.is_ascii_whitespace()) {
+                        return Err(format!(
+                            "text outside of root element at position {}",
+                            reader.buffer_position()
+                        ));
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    return Err(format!(
+                        "error at position {}: {}",
+                        reader.buffer_position(),
+                        e
+                    ))
+                }
+            }
+        }
+        if depth > 0 {
+            return Err("unclosed element".to_owned());
+        }
+        if !has_root {
+            return Err("missing root element".to_owned());
+        }
+        Ok(())
+    }
+
+    ///
+    /// properties格式宽松,只校验转义字符是否合法(与java Properties.load一致)
+    fn check_properties(content: &str) -> Result<(), String> {
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim_start();
+            if line.starts_with('#') || line.starts_with('!') {
+                continue;
+            }
+            let mut chars = line.chars();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    continue;
+                }
+                if let Some('u') = chars.next() {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    if hex.len() != 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                        return Err(format!("malformed \\uxxxx encoding at line {}", i + 1));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigType;
+
+    #[test]
+    fn check_content_by_type() {
+        assert!(ConfigType::Text.check_content("a: [").is_ok());
+        assert!(ConfigType::Json.check_content(r#"{"a":1}"#).is_ok());
+        assert!(ConfigType::Json.check_content(r#"{"a":1"#).is_err());
+        assert!(ConfigType::Yaml.check_content("a:\n  b: 1\n").is_ok());
+        assert!(ConfigType::Yaml.check_content("a: [1, 2\nb: 1").is_err());
+        assert!(ConfigType::Toml.check_content("[a]\nb = 1").is_ok());
+        assert!(ConfigType::Toml.check_content("[a\nb = 1").is_err());
+        assert!(ConfigType::Xml
+            .check_content("<?xml version=\"1.0\"?><a><b/></a>")
+            .is_ok());
+        assert!(ConfigType::Xml.check_content("<a><b></a>").is_err());
+        assert!(ConfigType::Xml.check_content("<a>").is_err());
+        assert!(ConfigType::Properties
+            .check_content("a=1\nb=\\u4e2d\n# \\u bad in comment")
+            .is_ok());
+        assert!(ConfigType::Properties.check_content("a=\\u4e").is_err());
+    }
 }
@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+use std::io::{self, Read, Seek, Write};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use zip::write::FileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::config::core::{ConfigInfoDto, ConfigKey};
+
+/// nacos 1.x 导出的元数据文件,为`{group}.{dataId}.app={appName}`格式的properties;
+/// 早期版本的rnacos也以yaml格式导出到该文件
+pub const META_FILE_NAME: &str = ".meta.yml";
+/// nacos 2.x 导出的元数据文件,导出时使用该格式
+pub const NACOS_META_FILE_NAME: &str = ".metadata.yml";
+
+pub const IMPORT_STATUS_SUCCESS: &str = "SUCCESS";
+pub const IMPORT_STATUS_SKIP: &str = "SKIP";
+pub const IMPORT_STATUS_FAIL: &str = "FAIL";
+
+///
+/// 导入时配置已存在的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigImportPolicy {
+    /// 存在冲突时整体放弃导入
+    Abort,
+    /// 跳过已存在的配置
+    Skip,
+    /// 覆盖已存在的配置
+    Overwrite,
+}
+
+impl ConfigImportPolicy {
+    pub fn new(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_uppercase().as_str() {
+            "ABORT" => Ok(Self::Abort),
+            "SKIP" => Ok(Self::Skip),
+            //兼容旧接口,不设置时覆盖
+            "OVERWRITE" | "" => Ok(Self::Overwrite),
+            _ => Err(anyhow::anyhow!("unknown import policy: {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigZipMetaItem {
+    pub data_id: String,
+    pub group: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub config_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigZipMetadata {
+    #[serde(default)]
+    pub metadata: Vec<ConfigZipMetaItem>,
+}
+
+///
+/// zip包中的一个配置文件
+#[derive(Debug, Clone, Default)]
+pub struct ConfigZipItem {
+    pub group: Arc<String>,
+    pub data_id: Arc<String>,
+    pub content: Arc<String>,
+    pub config_type: Option<Arc<String>>,
+    pub desc: Option<Arc<String>>,
+    /// 文件无法解析时的错误信息
+    pub error: Option<String>,
+}
+
+impl ConfigZipItem {
+    pub fn to_key(&self, tenant: Arc<String>) -> ConfigKey {
+        ConfigKey::new_by_arc(self.data_id.clone(), self.group.clone(), tenant)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigImportItemResult {
+    pub group: Arc<String>,
+    pub data_id: Arc<String>,
+    pub status: &'static str,
+    pub message: Option<String>,
+}
+
+///
+/// 导入结果报告
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigImportResult {
+    pub success_count: usize,
+    pub skip_count: usize,
+    pub fail_count: usize,
+    pub items: Vec<ConfigImportItemResult>,
+}
+
+impl ConfigImportResult {
+    pub fn push(&mut self, item: &ConfigZipItem, status: &'static str, message: Option<String>) {
+        match status {
+            IMPORT_STATUS_SUCCESS => self.success_count += 1,
+            IMPORT_STATUS_SKIP => self.skip_count += 1,
+            _ => self.fail_count += 1,
+        }
+        self.items.push(ConfigImportItemResult {
+            group: item.group.clone(),
+            data_id: item.data_id.clone(),
+            status,
+            message,
+        });
+    }
+}
+
+pub struct ConfigZipUtils;
+
+impl ConfigZipUtils {
+    ///
+    /// 按nacos 2.x格式导出: {group}/{dataId},元数据写入.metadata.yml
+    pub fn write_zip<W: Write + Seek>(writer: W, list: &[ConfigInfoDto]) -> anyhow::Result<()> {
+        let mut zip = ZipWriter::new(writer);
+        let options = FileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .unix_permissions(0o755);
+        if list.is_empty() {
+            zip.start_file(".ignore", options)?;
+            zip.write_all("empty config".as_bytes())?;
+            zip.finish()?;
+            return Ok(());
+        }
+        let mut metadata = ConfigZipMetadata::default();
+        for item in list {
+            zip.add_directory(item.group.as_str(), Default::default())
+                .ok();
+            zip.start_file(
+                format!("{}/{}", &item.group.as_str(), &item.data_id.as_str()),
+                options,
+            )?;
+            if let Some(content) = &item.content {
+                zip.write_all(content.as_bytes())?;
+            }
+            metadata.metadata.push(ConfigZipMetaItem {
+                data_id: item.data_id.as_ref().to_owned(),
+                group: item.group.as_ref().to_owned(),
+                config_type: item.config_type.as_ref().map(|e| e.as_ref().to_owned()),
+                desc: item.desc.as_ref().map(|e| e.as_ref().to_owned()),
+                app_name: None,
+            });
+        }
+        zip.start_file(NACOS_META_FILE_NAME, options)?;
+        zip.write_all(serde_yaml::to_string(&metadata)?.as_bytes())?;
+        zip.finish()?;
+        Ok(())
+    }
+
+    ///
+    /// .metadata.yml为yaml格式;.meta.yml按nacos 1.x的properties格式解析,兼容早期rnacos导出的yaml格式
+    fn parse_metadata(filename: &str, content: &str) -> anyhow::Result<ConfigZipMetadata> {
+        if filename == NACOS_META_FILE_NAME {
+            return Ok(serde_yaml::from_str(content)?);
+        }
+        match Self::parse_v1_metadata(content) {
+            Ok(v) => Ok(v),
+            Err(err) => serde_yaml::from_str(content).map_err(|_| err),
+        }
+    }
+
+    ///
+    /// nacos 1.x的元数据只包含应用名,key中dataId的`.`被替换为`~`
+    fn parse_v1_metadata(content: &str) -> anyhow::Result<ConfigZipMetadata> {
+        let mut metadata = ConfigZipMetadata::default();
+        for line in content.lines().map(|e| e.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (group, data_id, app_name) = line
+                .split_once('=')
+                .and_then(|(key, app_name)| {
+                    let (group, data_id) = key.strip_suffix(".app")?.rsplit_once('.')?;
+                    Some((group, data_id, app_name))
+                })
+                .filter(|(group, data_id, _)| !group.is_empty() && !data_id.is_empty())
+                .ok_or_else(|| anyhow::anyhow!("invalid metadata line: {}", line))?;
+            metadata.metadata.push(ConfigZipMetaItem {
+                data_id: data_id.replace('~', "."),
+                group: group.to_owned(),
+                app_name: Some(app_name.to_owned()).filter(|e| !e.is_empty()),
+                ..Default::default()
+            });
+        }
+        Ok(metadata)
+    }
+
+    ///
+    /// 读取nacos格式的zip包;不符合{group}/{dataId}格式的文件及无法解析的元数据文件带上错误信息返回
+    pub fn read_zip<R: Read + Seek>(reader: R) -> anyhow::Result<Vec<ConfigZipItem>> {
+        let mut archive = ZipArchive::new(reader)?;
+        let mut items = vec![];
+        let mut metadata: Option<ConfigZipMetadata> = None;
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            let filename = file.name().to_owned();
+            if filename.ends_with('/') {
+                continue;
+            }
+            if filename == META_FILE_NAME || filename == NACOS_META_FILE_NAME {
+                let content = io::read_to_string(&mut file)?;
+                match Self::parse_metadata(&filename, &content) {
+                    Ok(v) => metadata = Some(v),
+                    Err(err) => items.push(ConfigZipItem {
+                        data_id: Arc::new(filename),
+                        error: Some(format!("invalid metadata file, {}", err)),
+                        ..Default::default()
+                    }),
+                }
+                continue;
+            }
+            //忽略.ignore等隐藏文件
+            if filename.starts_with('.') {
+                continue;
+            }
+            let parts = filename.split('/').collect::<Vec<_>>();
+            let mut item = ConfigZipItem::default();
+            if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
+                item.data_id = Arc::new(filename);
+                item.error = Some("invalid config path, need {group}/{dataId}".to_owned());
+                items.push(item);
+                continue;
+            }
+            item.group = Arc::new(parts[0].to_owned());
+            item.data_id = Arc::new(parts[1].to_owned());
+            match io::read_to_string(&mut file) {
+                Ok(v) => item.content = Arc::new(v),
+                Err(err) => item.error = Some(err.to_string()),
+            }
+            items.push(item);
+        }
+        if let Some(metadata) = metadata {
+            let meta_map: HashMap<(String, String), ConfigZipMetaItem> = metadata
+                .metadata
+                .into_iter()
+                .map(|e| ((e.group.clone(), e.data_id.clone()), e))
+                .collect();
+            for item in items.iter_mut() {
+                let key = (
+                    item.group.as_ref().to_owned(),
+                    item.data_id.as_ref().to_owned(),
+                );
+                if let Some(meta) = meta_map.get(&key) {
+                    item.config_type = meta
+                        .config_type
+                        .as_ref()
+                        .filter(|e| !e.is_empty())
+                        .map(|e| Arc::new(e.to_owned()));
+                    item.desc = meta
+                        .desc
+                        .as_ref()
+                        .filter(|e| !e.is_empty())
+                        .map(|e| Arc::new(e.to_owned()));
+                }
+            }
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn config_zip_round_trip() {
+        let list = vec![
+            ConfigInfoDto {
+                group: Arc::new("DEFAULT_GROUP".to_owned()),
+                data_id: Arc::new("app.yaml".to_owned()),
+                content: Some(Arc::new("a: 1".to_owned())),
+                config_type: Some(Arc::new("yaml".to_owned())),
+                desc: Some(Arc::new("app config".to_owned())),
+                ..Default::default()
+            },
+            ConfigInfoDto {
+                group: Arc::new("DEFAULT_GROUP".to_owned()),
+                data_id: Arc::new("app.txt".to_owned()),
+                content: Some(Arc::new("hello".to_owned())),
+                ..Default::default()
+            },
+        ];
+        let mut buf = Cursor::new(vec![]);
+        ConfigZipUtils::write_zip(&mut buf, &list).unwrap();
+        buf.set_position(0);
+        let mut items = ConfigZipUtils::read_zip(buf).unwrap();
+        items.sort_by(|a, b| a.data_id.cmp(&b.data_id));
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].data_id.as_str(), "app.txt");
+        assert!(items[0].config_type.is_none());
+        assert_eq!(items[1].content.as_str(), "a: 1");
+        assert_eq!(items[1].config_type.as_ref().unwrap().as_str(), "yaml");
+        assert_eq!(items[1].desc.as_ref().unwrap().as_str(), "app config");
+
+        assert_eq!(
+            ConfigImportPolicy::new("skip").unwrap(),
+            ConfigImportPolicy::Skip
+        );
+        assert_eq!(
+            ConfigImportPolicy::new("").unwrap(),
+            ConfigImportPolicy::Overwrite
+        );
+        assert!(ConfigImportPolicy::new("merge").is_err());
+    }
+
+    fn build_nacos_zip(meta_file_name: &str, meta_content: &str) -> Cursor<Vec<u8>> {
+        let mut buf = Cursor::new(vec![]);
+        let mut zip = ZipWriter::new(&mut buf);
+        let options = FileOptions::default();
+        for (name, content) in [
+            ("DEFAULT_GROUP/app.yaml", "a: 1"),
+            ("DEFAULT_GROUP/app.txt", "hello"),
+            (meta_file_name, meta_content),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        drop(zip);
+        buf.set_position(0);
+        buf
+    }
+
+    fn read_sorted(buf: Cursor<Vec<u8>>) -> Vec<ConfigZipItem> {
+        let mut items = ConfigZipUtils::read_zip(buf).unwrap();
+        items.sort_by(|a, b| a.data_id.cmp(&b.data_id));
+        items
+    }
+
+    #[test]
+    fn read_nacos_zip_metadata() {
+        //nacos 2.x导出的.metadata.yml
+        let buf = build_nacos_zip(
+            NACOS_META_FILE_NAME,
+            include_str!("testdata/nacos_v2.metadata.yml"),
+        );
+        let items = read_sorted(buf);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].config_type.as_ref().unwrap().as_str(), "text");
+        assert_eq!(items[1].config_type.as_ref().unwrap().as_str(), "yaml");
+        assert_eq!(items[1].desc.as_ref().unwrap().as_str(), "app config");
+
+        //nacos 1.x导出的.meta.yml,只包含应用名,配置正常导入
+        let buf = build_nacos_zip(META_FILE_NAME, include_str!("testdata/nacos_v1.meta.yml"));
+        let items = read_sorted(buf);
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|e| e.error.is_none()));
+        assert_eq!(items[1].data_id.as_str(), "app.yaml");
+        assert_eq!(items[1].content.as_str(), "a: 1");
+        assert!(items[1].config_type.is_none());
+        let metadata = ConfigZipUtils::parse_metadata(
+            META_FILE_NAME,
+            include_str!("testdata/nacos_v1.meta.yml"),
+        )
+        .unwrap();
+        assert_eq!(metadata.metadata[0].data_id, "app.yaml");
+        assert_eq!(metadata.metadata[0].app_name.as_deref(), Some("demo"));
+
+        //早期rnacos导出到.meta.yml的yaml格式
+        let buf = build_nacos_zip(
+            META_FILE_NAME,
+            include_str!("testdata/nacos_v2.metadata.yml"),
+        );
+        let items = read_sorted(buf);
+        assert_eq!(items[1].config_type.as_ref().unwrap().as_str(), "yaml");
+
+        //无法解析的元数据文件在导入结果中报错
+        let buf = build_nacos_zip(NACOS_META_FILE_NAME, "metadata: [");
+        let items = read_sorted(buf);
+        assert_eq!(items.len(), 3);
+        let err_item = items
+            .iter()
+            .find(|e| e.data_id.as_str() == NACOS_META_FILE_NAME)
+            .unwrap();
+        assert!(err_item.error.is_some());
+        assert!(items.iter().all(|e| e.config_type.is_none()));
+    }
+}
@@ -4,6 +4,7 @@ use bean_factory::Inject;
 use chrono::Local;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::hash::Hash;
 use std::sync::Arc;
 use std::sync::Weak;
@@ -15,18 +16,29 @@ use crate::utils::get_md5;
 use serde::{Deserialize, Serialize};
 
 use crate::common::byte_utils::id_to_bin;
-use crate::common::constant::{CONFIG_TREE_NAME, SEQUENCE_TREE_NAME, SEQ_KEY_CONFIG};
+use crate::common::constant::{
+    CONFIG_TREE_NAME, EMPTY_ARC_STRING, SEQUENCE_TREE_NAME, SEQ_KEY_CONFIG,
+};
 use crate::common::sequence_utils::SimpleSequence;
 use actix::prelude::*;
 
 use super::config_subscribe::Subscriber;
 use super::dal::ConfigHistoryParam;
+use crate::config::config_cipher::ConfigCipherUtils;
+use crate::config::config_diff::{ConfigDiffDto, ConfigDiffSource};
+use crate::config::config_fuzzy::{
+    build_fuzzy_changes, ConfigChangeType, ConfigFuzzyEvent, ConfigFuzzyPattern, ConfigFuzzyWatcher,
+};
+use crate::config::config_gray::{
+    ConfigClient, ConfigGrayInfoDto, ConfigGrayRule, ConfigGrayValue,
+};
 use crate::config::config_index::{ConfigQueryParam, TenantIndex};
 use crate::config::config_type::ConfigType;
 use crate::config::model::{
     ConfigRaftCmd, ConfigRaftResult, ConfigValueDO, HistoryItem, SetConfigParam,
 };
 use crate::config::utils::param_utils;
+use crate::config::webhook::{ConfigWebhookManager, ConfigWebhookReq};
 use crate::namespace::NamespaceActor;
 use crate::now_millis_i64;
 use crate::raft::filestore::model::SnapshotRecordDto;
@@ -116,6 +128,8 @@ pub struct ConfigValue {
     pub(crate) histories: Vec<HistoryItem>,
     pub(crate) config_type: Option<Arc<String>>,
     pub(crate) desc: Option<Arc<String>>,
+    pub(crate) tags: Option<Arc<String>>,
+    pub(crate) gray: Option<ConfigGrayValue>,
     pub(crate) last_modified: i64,
 }
 
@@ -129,6 +143,8 @@ impl ConfigValue {
             histories: vec![],
             config_type: None,
             desc: None,
+            tags: None,
+            gray: None,
             last_modified: now_millis_i64(),
         }
     }
@@ -154,9 +170,13 @@ impl ConfigValue {
                 content,
                 modified_time: op_time,
                 op_user,
+                config_type: None,
+                desc: None,
             }],
             config_type: None,
             desc: None,
+            tags: None,
+            gray: None,
             last_modified: op_time,
         }
     }
@@ -182,6 +202,8 @@ impl ConfigValue {
             content,
             modified_time: op_time,
             op_user,
+            config_type: self.config_type.clone(),
+            desc: self.desc.clone(),
         };
         if self.histories.len() >= 100 {
             self.histories.remove(0);
@@ -189,6 +211,24 @@ impl ConfigValue {
         self.last_modified = op_time;
         self.histories.push(item);
     }
+
+    ///
+    /// 获取客户端命中的灰度配置,未命中返回None
+    pub fn get_gray_by_client(&self, client: &ConfigClient) -> Option<&ConfigGrayValue> {
+        self.gray
+            .as_ref()
+            .filter(|gray| gray.rule.match_client(client))
+    }
+
+    ///
+    /// 客户端实际读取到的配置md5
+    pub fn get_md5_by_client(&self, client: &ConfigClient) -> &Arc<String> {
+        if let Some(gray) = self.get_gray_by_client(client) {
+            &gray.md5
+        } else {
+            &self.md5
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -199,7 +239,9 @@ pub struct ConfigInfoDto {
     pub data_id: Arc<String>,
     pub content: Option<Arc<String>>,
     pub md5: Option<Arc<String>>,
+    pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    pub tags: Option<Arc<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -212,6 +254,8 @@ pub struct ConfigHistoryInfoDto {
     pub content: Option<String>,
     pub modified_time: Option<i64>, //给历史记录使用
     pub op_user: Option<String>,
+    pub config_type: Option<String>,
+    pub desc: Option<String>,
 }
 
 #[derive(Debug)]
@@ -302,6 +346,7 @@ struct OnceListener {
 pub enum ListenerResult {
     NULL,
     DATA(Vec<ConfigKey>),
+    FuzzyData(Vec<ConfigFuzzyEvent>),
 }
 
 type ListenerSenderType = tokio::sync::oneshot::Sender<ListenerResult>;
@@ -312,6 +357,7 @@ pub(crate) struct ConfigListener {
     listener: HashMap<ConfigKey, Vec<u64>>,
     time_listener: BTreeMap<i64, Vec<OnceListener>>,
     sender_map: HashMap<u64, ListenerSenderType>,
+    fuzzy_listener: HashMap<u64, ConfigFuzzyWatcher>,
 }
 
 impl ConfigListener {
@@ -321,6 +367,7 @@ impl ConfigListener {
             listener: Default::default(),
             time_listener: Default::default(),
             sender_map: Default::default(),
+            fuzzy_listener: Default::default(),
         }
     }
 
@@ -338,11 +385,11 @@ impl ConfigListener {
             };
         }
         self.sender_map.insert(self.version, sender);
-        let once_listener = OnceListener {
-            version: self.version,
-            //time,
-            //list: items,
-        };
+        self.add_time_listener(self.version, time);
+    }
+
+    fn add_time_listener(&mut self, version: u64, time: i64) {
+        let once_listener = OnceListener { version };
         match self.time_listener.get_mut(&time) {
             Some(list) => {
                 list.push(once_listener);
@@ -353,6 +400,13 @@ impl ConfigListener {
         }
     }
 
+    fn add_fuzzy(&mut self, watcher: ConfigFuzzyWatcher, sender: ListenerSenderType, time: i64) {
+        self.version += 1;
+        self.fuzzy_listener.insert(self.version, watcher);
+        self.sender_map.insert(self.version, sender);
+        self.add_time_listener(self.version, time);
+    }
+
     fn notify(&mut self, key: ConfigKey) {
         if let Some(list) = self.listener.remove(&key) {
             for v in list {
@@ -363,6 +417,25 @@ impl ConfigListener {
         }
     }
 
+    fn notify_fuzzy(&mut self, key: &ConfigKey, change_type: ConfigChangeType) {
+        if self.fuzzy_listener.is_empty() {
+            return;
+        }
+        let versions: Vec<u64> = self
+            .fuzzy_listener
+            .iter()
+            .filter(|(_, watcher)| watcher.match_key(key))
+            .map(|(v, _)| *v)
+            .collect();
+        for v in versions {
+            self.fuzzy_listener.remove(&v);
+            if let Some(sender) = self.sender_map.remove(&v) {
+                let event = ConfigFuzzyEvent::new(key.clone(), change_type);
+                sender.send(ListenerResult::FuzzyData(vec![event])).ok();
+            }
+        }
+    }
+
     fn timeout(&mut self) {
         let current_time = Local::now().timestamp_millis();
         let mut keys: Vec<i64> = Vec::new();
@@ -371,6 +444,7 @@ impl ConfigListener {
                 keys.push(*key);
                 for item in list {
                     let v = item.version;
+                    self.fuzzy_listener.remove(&v);
                     if let Some(sender) = self.sender_map.remove(&v) {
                         sender.send(ListenerResult::NULL).ok();
                     }
@@ -401,9 +475,19 @@ pub struct ConfigActor {
     pub(crate) tenant_index: TenantIndex,
     raft: Option<Weak<NacosRaft>>,
     namespace_actor: Option<Addr<NamespaceActor>>,
+    webhook_manager: Option<Addr<ConfigWebhookManager>>,
+    webhook_pending: HashMap<ConfigKey, WebhookPendingChange>,
     sequence: SimpleSequence,
 }
 
+///
+/// 等待写入提交的webhook变更;变更类型在raft apply时按实际状态记录,提交成功后按顺序取出通知
+#[derive(Default)]
+struct WebhookPendingChange {
+    writer_count: usize,
+    changes: VecDeque<ConfigChangeType>,
+}
+
 impl Inject for ConfigActor {
     type Context = Context<Self>;
 
@@ -417,6 +501,7 @@ impl Inject for ConfigActor {
         self.raft = raft.map(|e| Arc::downgrade(&e));
         self.namespace_actor = factory_data.get_actor();
         self.tenant_index.namespace_actor = self.namespace_actor.clone();
+        self.webhook_manager = factory_data.get_actor();
         if let Some(conn_manage) = factory_data.get_actor() {
             self.subscriber.set_conn_manage(conn_manage);
         }
@@ -439,6 +524,8 @@ impl ConfigActor {
             tenant_index: TenantIndex::new(),
             raft: None,
             namespace_actor: None,
+            webhook_manager: None,
+            webhook_pending: HashMap::new(),
             sequence: SimpleSequence::new(0, 100),
         }
     }
@@ -455,6 +542,50 @@ impl ConfigActor {
         }
     }
 
+    ///
+    /// 提交raft前登记等待中的webhook变更,只有本节点发起的写入才会在apply时记录变更类型
+    fn begin_webhook_change(&mut self, msg: &ConfigAsyncCmd) -> Option<ConfigKey> {
+        self.webhook_manager.as_ref()?;
+        let key = match msg {
+            ConfigAsyncCmd::Add { key, .. }
+            | ConfigAsyncCmd::Delete(key)
+            | ConfigAsyncCmd::GrayPromote { key, .. } => key.clone(),
+            _ => return None,
+        };
+        self.webhook_pending
+            .entry(key.clone())
+            .or_default()
+            .writer_count += 1;
+        Some(key)
+    }
+
+    fn record_webhook_change(&mut self, key: &ConfigKey, change_type: ConfigChangeType) {
+        if let Some(pending) = self.webhook_pending.get_mut(key) {
+            pending.changes.push_back(change_type);
+        }
+    }
+
+    ///
+    /// 提交成功时取出apply记录的变更类型通知webhook;内容未变化时没有记录,不通知
+    fn finish_webhook_change(&mut self, key: ConfigKey, committed: bool) {
+        let pending = match self.webhook_pending.get_mut(&key) {
+            Some(v) => v,
+            None => return,
+        };
+        pending.writer_count -= 1;
+        let change_type = if committed {
+            pending.changes.pop_front()
+        } else {
+            None
+        };
+        if pending.writer_count == 0 {
+            self.webhook_pending.remove(&key);
+        }
+        if let (Some(change_type), Some(webhook_manager)) = (change_type, &self.webhook_manager) {
+            webhook_manager.do_send(ConfigWebhookReq::Notify(key, change_type));
+        }
+    }
+
     fn inner_set_config(&mut self, key: ConfigKey, value: ConfigValue) {
         self.tenant_index.insert_config(key.clone());
         self.cache.insert(key, value);
@@ -464,7 +595,9 @@ impl ConfigActor {
         if let Some(history_table_id) = param.history_table_id {
             self.sequence.set_valid_last_id(history_table_id);
         }
+        let change_type;
         if let Some(v) = self.cache.get_mut(&param.key) {
+            change_type = ConfigChangeType::Update;
             let md5 = get_md5(param.value.as_str());
             if let Some(s) = param.config_type {
                 v.config_type = Some(s);
@@ -472,6 +605,9 @@ impl ConfigActor {
             if let Some(s) = param.desc {
                 v.desc = Some(s);
             }
+            if let Some(s) = param.tags {
+                v.tags = Some(s).filter(|e| !e.is_empty());
+            }
             if !v.tmp && v.md5.as_str() == md5 {
                 return Ok(ConfigResult::NULL);
             }
@@ -486,6 +622,7 @@ impl ConfigActor {
                 param.op_user,
             );
         } else {
+            change_type = ConfigChangeType::Add;
             let mut v = ConfigValue::init(
                 param.value,
                 param.history_id,
@@ -495,16 +632,168 @@ impl ConfigActor {
             );
             v.config_type = param.config_type;
             v.desc = param.desc;
+            v.tags = param.tags.filter(|e| !e.is_empty());
+            if let Some(item) = v.histories.last_mut() {
+                item.config_type = v.config_type.clone();
+                item.desc = v.desc.clone();
+            }
             self.cache.insert(param.key.clone(), v);
             self.tenant_index.insert_config(param.key.clone());
         }
+        self.record_webhook_change(&param.key, change_type);
+        self.listener.notify_fuzzy(&param.key, change_type);
+        self.subscriber.notify_fuzzy(&param.key, change_type);
         self.listener.notify(param.key.clone());
         self.subscriber.notify(param.key);
         Ok(ConfigResult::NULL)
     }
 
+    fn set_gray_config(
+        &mut self,
+        key: ConfigKey,
+        content: Arc<String>,
+        rule: ConfigGrayRule,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+    ) -> anyhow::Result<()> {
+        if let Some(v) = self.cache.get_mut(&key) {
+            v.gray = Some(ConfigGrayValue::new(content, rule, op_time, op_user));
+            self.listener.notify(key.clone());
+            self.subscriber.notify(key);
+        }
+        Ok(())
+    }
+
+    fn remove_gray_config(&mut self, key: ConfigKey) -> anyhow::Result<()> {
+        if let Some(v) = self.cache.get_mut(&key) {
+            if v.gray.take().is_some() {
+                self.listener.notify(key.clone());
+                self.subscriber.notify(key);
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// 把灰度配置发布为正式配置
+    fn promote_gray_config(
+        &mut self,
+        key: ConfigKey,
+        history_id: u64,
+        history_table_id: Option<u64>,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+    ) -> anyhow::Result<()> {
+        let gray = match self.cache.get_mut(&key).and_then(|v| v.gray.take()) {
+            Some(gray) => gray,
+            None => return Ok(()),
+        };
+        let param = SetConfigParam {
+            key,
+            value: gray.content,
+            config_type: None,
+            desc: None,
+            tags: None,
+            history_id,
+            history_table_id,
+            op_time,
+            op_user,
+        };
+        self.set_config(param)?;
+        Ok(())
+    }
+
+    fn get_config_data(&self, key: &ConfigKey, client: Option<&ConfigClient>) -> ConfigResult {
+        if let Some(v) = self.cache.get(key) {
+            if let Some(gray) = client.and_then(|client| v.get_gray_by_client(client)) {
+                return ConfigResult::Data {
+                    value: gray.content.clone(),
+                    md5: gray.md5.clone(),
+                    config_type: v.config_type.clone(),
+                    desc: v.desc.clone(),
+                    tags: v.tags.clone(),
+                    last_modified: gray.last_modified,
+                    gray: true,
+                };
+            }
+            return ConfigResult::Data {
+                value: v.content.clone(),
+                md5: v.md5.clone(),
+                config_type: v.config_type.clone(),
+                desc: v.desc.clone(),
+                tags: v.tags.clone(),
+                last_modified: v.last_modified,
+                gray: false,
+            };
+        }
+        ConfigResult::NULL
+    }
+
+    ///
+    /// 获取对比用的配置内容;正式配置不存在时按空内容对比,历史记录不存在时报错
+    fn get_diff_content(&self, source: &ConfigDiffSource) -> anyhow::Result<Arc<String>> {
+        match source {
+            ConfigDiffSource::Current(key) => Ok(self
+                .cache
+                .get(key)
+                .map(|v| v.content.clone())
+                .unwrap_or_else(|| EMPTY_ARC_STRING.clone())),
+            ConfigDiffSource::History(key, history_id) => self
+                .cache
+                .get(key)
+                .and_then(|v| v.histories.iter().find(|e| e.id == *history_id))
+                .map(|e| e.content.clone())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("the config history is not exist, id: {}", history_id)
+                }),
+        }
+    }
+
+    fn get_change_keys(&self, items: &[ListenerItem], client: &ConfigClient) -> Vec<ConfigKey> {
+        let mut changes = vec![];
+        for item in items {
+            if let Some(v) = self.cache.get(&item.key) {
+                if v.get_md5_by_client(client) != &item.md5 {
+                    changes.push(item.key.clone());
+                }
+            } else if !item.md5.is_empty() {
+                changes.push(item.key.clone());
+            }
+        }
+        changes
+    }
+
+    ///
+    /// 模糊订阅范围内,客户端已知配置与当前配置的差异
+    fn get_fuzzy_changes(
+        &self,
+        watcher: &ConfigFuzzyWatcher,
+        items: &[ListenerItem],
+    ) -> Vec<ConfigFuzzyEvent> {
+        build_fuzzy_changes(
+            watcher,
+            items,
+            self.cache
+                .iter()
+                .filter(|(_, v)| !v.tmp)
+                .map(|(k, v)| (k, &v.md5)),
+        )
+    }
+
+    fn get_fuzzy_keys(&self, watcher: &ConfigFuzzyWatcher) -> Vec<ConfigKey> {
+        self.cache
+            .iter()
+            .filter(|(k, v)| !v.tmp && watcher.match_key(k))
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+
     fn del_config(&mut self, key: ConfigKey) -> anyhow::Result<()> {
-        self.cache.remove(&key);
+        if self.cache.remove(&key).is_some() {
+            self.record_webhook_change(&key, ConfigChangeType::Delete);
+            self.listener.notify_fuzzy(&key, ConfigChangeType::Delete);
+            self.subscriber.notify_fuzzy(&key, ConfigChangeType::Delete);
+        }
         //self.config_db.del_config(&key).ok();
         self.tenant_index.remove_config(&key);
         self.listener.notify(key.clone());
@@ -543,7 +832,14 @@ impl ConfigActor {
     }
 
     pub fn get_config_info_page(&self, param: &ConfigQueryParam) -> (usize, Vec<ConfigInfoDto>) {
-        let (size, list) = self.tenant_index.query_config_page(param);
+        let (size, list) = if param.has_tags() {
+            self.tenant_index
+                .query_config_page_by_filter(param, &|key| {
+                    param.match_tags(self.cache.get(key).and_then(|v| v.tags.as_ref()))
+                })
+        } else {
+            self.tenant_index.query_config_page(param)
+        };
 
         if size == 0 {
             return (size, Vec::new());
@@ -556,7 +852,9 @@ impl ConfigActor {
                     tenant: item.tenant.clone(),
                     group: item.group.clone(),
                     data_id: item.data_id.clone(),
+                    config_type: value.config_type.clone(),
                     desc: value.desc.clone(),
+                    tags: value.tags.clone(),
                     //md5:Some(value.md5.clone()),
                     //content:Some(value.content.clone()),
                     ..Default::default()
@@ -586,7 +884,9 @@ impl ConfigActor {
                     tenant: key.tenant.clone(),
                     group: key.group.clone(),
                     data_id: key.data_id.clone(),
+                    config_type: value.config_type.clone(),
                     desc: value.desc.clone(),
+                    tags: value.tags.clone(),
                     content: Some(value.content.clone()),
                     md5: Some(value.md5.clone()),
                 };
@@ -653,7 +953,8 @@ impl ConfigActor {
     ///
     fn build_snapshot(&self, writer: Addr<SnapshotWriterActor>) -> anyhow::Result<()> {
         for (key, value) in &self.cache {
-            let value_db: ConfigValueDO = value.clone().into();
+            let value_db: ConfigValueDO =
+                ConfigCipherUtils::encrypt_value_or_keep(key, value.clone()).into();
             let record = SnapshotRecordDto {
                 tree: CONFIG_TREE_NAME.clone(),
                 key: key.build_key().as_bytes().to_vec(),
@@ -676,7 +977,8 @@ impl ConfigActor {
     /// 迁移数据备件
     fn transfer_backup(&self, writer: Addr<TransferWriterActor>) -> anyhow::Result<()> {
         for (key, value) in &self.cache {
-            let value_db: ConfigValueDO = value.clone().into();
+            let value_db: ConfigValueDO =
+                ConfigCipherUtils::encrypt_value_or_keep(key, value.clone()).into();
             let record = TransferRecordDto {
                 table_name: Some(CONFIG_TREE_NAME.clone()),
                 key: key.build_key().as_bytes().to_vec(),
@@ -697,6 +999,25 @@ impl ConfigActor {
         Ok(())
     }
 
+    ///
+    /// 灰度操作在提交raft前校验当前状态
+    fn check_async_cmd(&self, msg: &ConfigAsyncCmd) -> anyhow::Result<()> {
+        match msg {
+            ConfigAsyncCmd::GraySet { rule, .. } if rule.is_empty() => {
+                Err(anyhow::anyhow!("the gray rule is empty"))
+            }
+            ConfigAsyncCmd::GraySet { key, .. } if !self.cache.contains_key(key) => Err(
+                anyhow::anyhow!("the config is not exist, publish the formal config first"),
+            ),
+            ConfigAsyncCmd::GrayPromote { key, .. }
+                if self.cache.get(key).and_then(|v| v.gray.as_ref()).is_none() =>
+            {
+                Err(anyhow::anyhow!("the gray config is not exist"))
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn hb(&self, ctx: &mut actix::Context<Self>) {
         ctx.run_later(Duration::from_millis(500), |act, ctx| {
             act.listener.timeout();
@@ -714,12 +1035,29 @@ pub enum ConfigCmd {
     SetFullValue(ConfigKey, ConfigValue),
     InnerSetLastId(u64),
     GET(ConfigKey),
+    GetByClient(ConfigKey, Arc<ConfigClient>),
+    GetGray(ConfigKey),
     QueryPageInfo(Box<ConfigQueryParam>),
     QueryInfoByKeys(Box<Vec<ConfigKey>>),
     QueryHistoryPageInfo(Box<ConfigHistoryParam>),
-    LISTENER(Vec<ListenerItem>, ListenerSenderType, i64),
-    Subscribe(Vec<ListenerItem>, Arc<String>),
+    GetHistory(ConfigKey, u64),
+    Diff(ConfigDiffSource, ConfigDiffSource),
+    LISTENER(
+        Vec<ListenerItem>,
+        ListenerSenderType,
+        i64,
+        Arc<ConfigClient>,
+    ),
+    FuzzyListener(
+        Box<ConfigFuzzyWatcher>,
+        Vec<ListenerItem>,
+        ListenerSenderType,
+        i64,
+    ),
+    Subscribe(Vec<ListenerItem>, Arc<String>, Arc<ConfigClient>),
     RemoveSubscribe(Vec<ListenerItem>, Arc<String>),
+    FuzzySubscribe(Box<ConfigFuzzyWatcher>, Arc<String>),
+    RemoveFuzzySubscribe(Box<ConfigFuzzyPattern>, Arc<String>),
     RemoveSubscribeClient(Arc<String>),
     BuildSnapshot(Addr<SnapshotWriterActor>),
     GetSequenceSection(u64),
@@ -734,8 +1072,20 @@ pub enum ConfigAsyncCmd {
         op_user: Option<Arc<String>>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        tags: Option<Arc<String>>,
     },
     Delete(ConfigKey),
+    GraySet {
+        key: ConfigKey,
+        value: Arc<String>,
+        rule: ConfigGrayRule,
+        op_user: Option<Arc<String>>,
+    },
+    GrayRemove(ConfigKey),
+    GrayPromote {
+        key: ConfigKey,
+        op_user: Option<Arc<String>>,
+    },
 }
 
 pub enum ConfigResult {
@@ -744,12 +1094,17 @@ pub enum ConfigResult {
         md5: Arc<String>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        tags: Option<Arc<String>>,
         last_modified: i64,
+        gray: bool,
     },
     NULL,
+    GrayInfo(Box<ConfigGrayInfoDto>),
     ChangeKey(Vec<ConfigKey>),
     ConfigInfoPage(usize, Vec<ConfigInfoDto>),
     ConfigHistoryInfoPage(usize, Vec<ConfigHistoryInfoDto>),
+    ConfigHistoryInfo(Box<ConfigHistoryInfoDto>),
+    Diff(Box<ConfigDiffDto>),
     SequenceSection {
         //id包含start值
         start: u64,
@@ -782,33 +1137,25 @@ impl Handler<ConfigCmd> for ConfigActor {
                 self.set_tmp_config(key, value);
             }
             ConfigCmd::SetFullValue(key, value) => {
+                let value = ConfigCipherUtils::decrypt_value(&key, value);
                 self.inner_set_config(key, value);
             }
             ConfigCmd::InnerSetLastId(last_id) => {
                 self.sequence.set_last_id(last_id);
             }
             ConfigCmd::GET(key) => {
-                if let Some(v) = self.cache.get(&key) {
-                    return Ok(ConfigResult::Data {
-                        value: v.content.clone(),
-                        md5: v.md5.clone(),
-                        config_type: v.config_type.clone(),
-                        desc: v.desc.clone(),
-                        last_modified: v.last_modified,
-                    });
-                }
+                return Ok(self.get_config_data(&key, None));
             }
-            ConfigCmd::LISTENER(items, sender, time) => {
-                let mut changes = vec![];
-                for item in &items {
-                    if let Some(v) = self.cache.get(&item.key) {
-                        if v.md5 != item.md5 {
-                            changes.push(item.key.clone());
-                        }
-                    } else if !item.md5.is_empty() {
-                        changes.push(item.key.clone());
-                    }
+            ConfigCmd::GetByClient(key, client) => {
+                return Ok(self.get_config_data(&key, Some(client.as_ref())));
+            }
+            ConfigCmd::GetGray(key) => {
+                if let Some(gray) = self.cache.get(&key).and_then(|v| v.gray.as_ref()) {
+                    return Ok(ConfigResult::GrayInfo(Box::new(gray.to_dto(&key))));
                 }
+            }
+            ConfigCmd::LISTENER(items, sender, time, client) => {
+                let changes = self.get_change_keys(&items, &client);
                 if !changes.is_empty() || time <= 0 {
                     sender.send(ListenerResult::DATA(changes)).ok();
                     return Ok(ConfigResult::NULL);
@@ -817,17 +1164,24 @@ impl Handler<ConfigCmd> for ConfigActor {
                     return Ok(ConfigResult::NULL);
                 }
             }
-            ConfigCmd::Subscribe(items, client_id) => {
-                let mut changes = vec![];
-                for item in &items {
-                    if let Some(v) = self.cache.get(&item.key) {
-                        if v.md5 != item.md5 {
-                            changes.push(item.key.clone());
-                        }
-                    } else if !item.md5.is_empty() {
-                        changes.push(item.key.clone());
-                    }
+            ConfigCmd::FuzzyListener(watcher, items, sender, time) => {
+                let changes = self.get_fuzzy_changes(&watcher, &items);
+                if !changes.is_empty() || time <= 0 {
+                    sender.send(ListenerResult::FuzzyData(changes)).ok();
+                } else {
+                    self.listener.add_fuzzy(*watcher, sender, time);
                 }
+            }
+            ConfigCmd::FuzzySubscribe(watcher, client_id) => {
+                let keys = self.get_fuzzy_keys(&watcher);
+                self.subscriber.add_fuzzy_subscribe(client_id, *watcher);
+                return Ok(ConfigResult::ChangeKey(keys));
+            }
+            ConfigCmd::RemoveFuzzySubscribe(pattern, client_id) => {
+                self.subscriber.remove_fuzzy_subscribe(client_id, *pattern);
+            }
+            ConfigCmd::Subscribe(items, client_id, client) => {
+                let changes = self.get_change_keys(&items, &client);
                 self.subscriber.add_subscribe(client_id, items);
                 if !changes.is_empty() {
                     return Ok(ConfigResult::ChangeKey(changes));
@@ -851,8 +1205,27 @@ impl Handler<ConfigCmd> for ConfigActor {
                 let (size, list) = self.get_history_info_page(query_param.as_ref());
                 return Ok(ConfigResult::ConfigHistoryInfoPage(size, list));
             }
+            ConfigCmd::GetHistory(key, history_id) => {
+                if let Some(item) = self
+                    .cache
+                    .get(&key)
+                    .and_then(|v| v.histories.iter().find(|e| e.id == history_id))
+                {
+                    return Ok(ConfigResult::ConfigHistoryInfo(Box::new(item.to_dto(&key))));
+                }
+            }
+            ConfigCmd::Diff(source, target) => {
+                let source_content = self.get_diff_content(&source)?;
+                let target_content = self.get_diff_content(&target)?;
+                return Ok(ConfigResult::Diff(Box::new(ConfigDiffDto::build(
+                    &source,
+                    &source_content,
+                    &target,
+                    &target_content,
+                ))));
+            }
             ConfigCmd::BuildSnapshot(writer) => {
-                self.build_snapshot(writer).ok();
+                self.build_snapshot(writer)?;
             }
             ConfigCmd::GetSequenceSection(size) => {
                 let (start, end) = self.sequence.next_section(size)?;
@@ -868,12 +1241,19 @@ impl Handler<ConfigAsyncCmd> for ConfigActor {
 
     fn handle(&mut self, msg: ConfigAsyncCmd, _ctx: &mut Context<Self>) -> Self::Result {
         let raft = self.raft.clone();
-        let history_info = if let ConfigAsyncCmd::Add { .. } = &msg {
-            self.sequence.next_state().ok()
-        } else {
-            None
+        if let Err(err) = self.check_async_cmd(&msg) {
+            return Box::pin(async move { Err(err) }.into_actor(self));
+        }
+        let history_info = match &msg {
+            ConfigAsyncCmd::Add { .. } | ConfigAsyncCmd::GrayPromote { .. } => {
+                self.sequence.next_state().ok()
+            }
+            _ => None,
         };
+        //webhook只在写入提交后由主节点通知,避免raft apply重放日志时重复投递
+        let webhook_key = self.begin_webhook_change(&msg);
         let fut = async move {
+            let mut committed = false;
             match msg {
                 ConfigAsyncCmd::Add {
                     key,
@@ -881,32 +1261,75 @@ impl Handler<ConfigAsyncCmd> for ConfigActor {
                     op_user,
                     config_type,
                     desc,
+                    tags,
                 } => {
                     if let Some((history_id, history_table_id)) = history_info {
+                        let value = ConfigCipherUtils::encrypt_content(&key, value)?;
                         let req = ClientRequest::ConfigSet {
                             key: key.build_key(),
                             value,
                             config_type,
                             desc,
+                            tags,
                             history_id,
                             history_table_id,
                             op_time: now_millis_i64(),
                             op_user,
                         };
-                        Self::send_raft_request(&raft, req).await.ok();
+                        committed = Self::send_raft_request(&raft, req).await.is_ok();
                     }
                 }
                 ConfigAsyncCmd::Delete(key) => {
                     let req = ClientRequest::ConfigRemove {
                         key: key.build_key(),
                     };
-                    Self::send_raft_request(&raft, req).await.ok();
+                    committed = Self::send_raft_request(&raft, req).await.is_ok();
+                }
+                ConfigAsyncCmd::GraySet {
+                    key,
+                    value,
+                    rule,
+                    op_user,
+                } => {
+                    let value = ConfigCipherUtils::encrypt_content(&key, value)?;
+                    let req = ClientRequest::ConfigGraySet {
+                        key: key.build_key(),
+                        value,
+                        rule,
+                        op_time: now_millis_i64(),
+                        op_user,
+                    };
+                    Self::send_raft_request(&raft, req).await?;
+                }
+                ConfigAsyncCmd::GrayRemove(key) => {
+                    let req = ClientRequest::ConfigGrayRemove {
+                        key: key.build_key(),
+                    };
+                    Self::send_raft_request(&raft, req).await?;
+                }
+                ConfigAsyncCmd::GrayPromote { key, op_user } => {
+                    let (history_id, history_table_id) = history_info
+                        .ok_or_else(|| anyhow::anyhow!("config history sequence is unavailable"))?;
+                    let req = ClientRequest::ConfigGrayPromote {
+                        key: key.build_key(),
+                        history_id,
+                        history_table_id,
+                        op_time: now_millis_i64(),
+                        op_user,
+                    };
+                    Self::send_raft_request(&raft, req).await?;
+                    committed = true;
                 }
             }
-            Ok(ConfigResult::NULL)
+            Ok(committed)
         }
         .into_actor(self)
-        .map(|r, _act, _ctx| r);
+        .map(move |r: anyhow::Result<bool>, act, _ctx| {
+            if let Some(key) = webhook_key {
+                act.finish_webhook_change(key, matches!(r, Ok(true)));
+            }
+            r.map(|_| ConfigResult::NULL)
+        });
         Box::pin(fut)
     }
 }
@@ -921,18 +1344,21 @@ impl Handler<ConfigRaftCmd> for ConfigActor {
                 value,
                 config_type,
                 desc,
+                tags,
                 history_id,
                 history_table_id,
                 op_time,
                 op_user,
             } => {
                 let key: ConfigKey = (&key as &str).into();
+                let value = ConfigCipherUtils::decrypt_content(&key, value);
                 let param = SetConfigParam {
                     key,
                     value,
                     config_type: config_type
                         .map(|v| ConfigType::new_by_value(v.as_ref()).get_value()),
                     desc,
+                    tags,
                     history_id,
                     history_table_id,
                     op_time,
@@ -945,6 +1371,7 @@ impl Handler<ConfigRaftCmd> for ConfigActor {
                 value,
                 last_id,
             } => {
+                let value = ConfigCipherUtils::decrypt_value(&key, value);
                 self.inner_set_config(key, value);
                 if let Some(last_id) = last_id {
                     self.sequence.set_valid_last_id(last_id);
@@ -954,6 +1381,39 @@ impl Handler<ConfigRaftCmd> for ConfigActor {
                 let config_key: ConfigKey = (&key as &str).into();
                 self.del_config(config_key).ok();
             }
+            ConfigRaftCmd::GraySet {
+                key,
+                value,
+                rule,
+                op_time,
+                op_user,
+            } => {
+                let config_key: ConfigKey = (&key as &str).into();
+                let value = ConfigCipherUtils::decrypt_content(&config_key, value);
+                self.set_gray_config(config_key, value, rule, op_time, op_user)
+                    .ok();
+            }
+            ConfigRaftCmd::GrayRemove { key } => {
+                let config_key: ConfigKey = (&key as &str).into();
+                self.remove_gray_config(config_key).ok();
+            }
+            ConfigRaftCmd::GrayPromote {
+                key,
+                history_id,
+                history_table_id,
+                op_time,
+                op_user,
+            } => {
+                let config_key: ConfigKey = (&key as &str).into();
+                self.promote_gray_config(
+                    config_key,
+                    history_id,
+                    history_table_id,
+                    op_time,
+                    op_user,
+                )
+                .ok();
+            }
         }
         Ok(ConfigRaftResult::None)
     }
@@ -973,3 +1433,61 @@ impl Handler<TransferDataRequest> for ConfigActor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::model::ConfigValueDO;
+    use crate::raft::filestore::model::SnapshotHeaderDto;
+    use crate::raft::filestore::raftsnapshot::SnapshotReader;
+
+    #[actix_rt::test]
+    async fn build_snapshot_with_cipher_key_without_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = Arc::new(dir.path().join("snapshot").to_string_lossy().to_string());
+        let header = SnapshotHeaderDto {
+            last_index: 1,
+            last_term: 1,
+            member: vec![1],
+            member_after_consensus: vec![],
+            node_addrs: HashMap::new(),
+        };
+        let mut actor = ConfigActor::new();
+        for i in 0..5 {
+            let key = ConfigKey::new(&format!("data{}", i), "DEFAULT_GROUP", "");
+            actor.inner_set_config(key, ConfigValue::new(Arc::new(format!("v{}", i))));
+        }
+        //未配置密钥时,已存在的加密dataId按存储内容写入,不能中断镜像
+        let cipher_key = ConfigKey::new("cipher-db", "DEFAULT_GROUP", "");
+        actor.inner_set_config(
+            cipher_key.clone(),
+            ConfigValue::new(Arc::new("pwd".to_owned())),
+        );
+
+        let writer = SnapshotWriterActor::new(path.clone(), header).start();
+        actor.build_snapshot(writer.clone()).unwrap();
+        writer
+            .send(SnapshotWriterRequest::Flush)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut reader = SnapshotReader::init(&path).await.unwrap();
+        let mut config_count = 0;
+        let mut has_seq = false;
+        while let Some(record) = reader.read_record().await.unwrap() {
+            if record.tree.as_str() == CONFIG_TREE_NAME.as_str() {
+                config_count += 1;
+                if record.key == cipher_key.build_key().as_bytes() {
+                    let value: ConfigValue =
+                        ConfigValueDO::from_bytes(&record.value).unwrap().into();
+                    assert_eq!(value.content.as_str(), "pwd");
+                }
+            } else if record.tree.as_str() == SEQUENCE_TREE_NAME.as_str() {
+                has_seq = true;
+            }
+        }
+        assert_eq!(config_count, 6);
+        assert!(has_seq);
+    }
+}
@@ -1,16 +1,22 @@
 use crate::common::constant::EMPTY_ARC_STRING;
 use std::sync::Arc;
 
+pub mod config_cipher;
 pub mod config_db;
+pub mod config_diff;
+pub mod config_fuzzy;
+pub mod config_gray;
 pub mod config_index;
 pub mod config_sled;
 pub mod config_subscribe;
 pub mod config_type;
+pub mod config_zip;
 pub mod core;
 pub mod dal;
 pub mod metrics;
 pub mod model;
 pub mod utils;
+pub mod webhook;
 
 pub struct ConfigUtils;
 
@@ -36,4 +42,20 @@ impl ConfigUtils {
     pub fn is_default_tenant(val: &str) -> bool {
         val == DEFAULT_TENANT
     }
+
+    ///
+    /// 标签格式: tag1,tag2 ;去除空白与重复项
+    pub fn format_tags(val: &str) -> Arc<String> {
+        let mut tags: Vec<&str> = vec![];
+        for tag in Self::split_tags(val) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        Arc::new(tags.join(","))
+    }
+
+    pub fn split_tags(val: &str) -> impl Iterator<Item = &str> {
+        val.split(',').map(|e| e.trim()).filter(|e| !e.is_empty())
+    }
 }
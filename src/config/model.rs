@@ -1,8 +1,10 @@
+use crate::config::config_gray::{ConfigGrayRule, ConfigGrayValue};
 use crate::config::config_type::ConfigType;
 use crate::config::core::{ConfigHistoryInfoDto, ConfigKey, ConfigValue};
 use crate::utils::get_md5;
 use actix::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Message)]
@@ -13,6 +15,7 @@ pub enum ConfigRaftCmd {
         value: Arc<String>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        tags: Option<Arc<String>>,
         history_id: u64,
         history_table_id: Option<u64>,
         op_time: i64,
@@ -26,6 +29,23 @@ pub enum ConfigRaftCmd {
         value: ConfigValue,
         last_id: Option<u64>,
     },
+    GraySet {
+        key: String,
+        value: Arc<String>,
+        rule: ConfigGrayRule,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+    },
+    GrayRemove {
+        key: String,
+    },
+    GrayPromote {
+        key: String,
+        history_id: u64,
+        history_table_id: Option<u64>,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+    },
 }
 
 #[derive(Debug)]
@@ -34,6 +54,7 @@ pub struct SetConfigParam {
     pub value: Arc<String>,
     pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    pub tags: Option<Arc<String>>,
     pub history_id: u64,
     pub history_table_id: Option<u64>,
     pub op_time: i64,
@@ -54,6 +75,8 @@ pub struct HistoryItem {
     pub content: Arc<String>,
     pub modified_time: i64,
     pub op_user: Option<Arc<String>>,
+    pub config_type: Option<Arc<String>>,
+    pub desc: Option<Arc<String>>,
 }
 
 impl HistoryItem {
@@ -66,6 +89,8 @@ impl HistoryItem {
             content: Some(self.content.to_string()),
             modified_time: Some(self.modified_time),
             op_user: self.op_user.as_ref().map(|e| e.to_string()),
+            config_type: self.config_type.as_ref().map(|e| e.to_string()),
+            desc: self.desc.as_ref().map(|e| e.to_string()),
         }
     }
 }
@@ -80,6 +105,10 @@ pub struct ConfigHistoryItemDO {
     pub last_time: Option<i64>,
     #[prost(string, optional, tag = "4")]
     pub op_user: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub config_type: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    pub desc: Option<String>,
 }
 
 impl From<HistoryItem> for ConfigHistoryItemDO {
@@ -89,6 +118,8 @@ impl From<HistoryItem> for ConfigHistoryItemDO {
             content: Some(value.content.as_ref().to_string()),
             last_time: Some(value.modified_time),
             op_user: value.op_user.map(|e| e.as_ref().to_string()),
+            config_type: value.config_type.map(|e| e.as_ref().to_string()),
+            desc: value.desc.map(|e| e.as_ref().to_string()),
         }
     }
 }
@@ -100,6 +131,10 @@ impl From<ConfigHistoryItemDO> for HistoryItem {
             content: Arc::new(value.content.unwrap_or_default()),
             modified_time: value.last_time.unwrap_or_default(),
             op_user: value.op_user.map(Arc::new),
+            config_type: value
+                .config_type
+                .map(|v| ConfigType::new_by_value(&v).get_value()),
+            desc: value.desc.map(Arc::new),
         }
     }
 }
@@ -113,6 +148,51 @@ pub struct ConfigValueDO {
     pub config_type: Option<String>,
     #[prost(string, optional, tag = "4")]
     pub desc: Option<String>,
+    #[prost(message, optional, tag = "5")]
+    pub gray: Option<ConfigGrayValueDO>,
+    #[prost(string, optional, tag = "6")]
+    pub tags: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost_derive::Message, Deserialize, Serialize)]
+pub struct ConfigGrayValueDO {
+    #[prost(string, optional, tag = "1")]
+    pub content: Option<String>,
+    #[prost(string, repeated, tag = "2")]
+    pub client_ips: Vec<String>,
+    #[prost(map = "string, string", tag = "3")]
+    pub labels: HashMap<String, String>,
+    #[prost(int64, optional, tag = "4")]
+    pub last_time: Option<i64>,
+    #[prost(string, optional, tag = "5")]
+    pub op_user: Option<String>,
+}
+
+impl From<ConfigGrayValue> for ConfigGrayValueDO {
+    fn from(value: ConfigGrayValue) -> Self {
+        Self {
+            content: Some(value.content.as_ref().to_owned()),
+            client_ips: value.rule.client_ips,
+            labels: value.rule.labels,
+            last_time: Some(value.last_modified),
+            op_user: value.op_user.map(|e| e.as_ref().to_owned()),
+        }
+    }
+}
+
+impl From<ConfigGrayValueDO> for ConfigGrayValue {
+    fn from(value: ConfigGrayValueDO) -> Self {
+        let rule = ConfigGrayRule {
+            client_ips: value.client_ips,
+            labels: value.labels,
+        };
+        ConfigGrayValue::new(
+            Arc::new(value.content.unwrap_or_default()),
+            rule,
+            value.last_time.unwrap_or_default(),
+            value.op_user.map(Arc::new),
+        )
+    }
 }
 
 impl ConfigValueDO {
@@ -137,6 +217,8 @@ impl From<ConfigValue> for ConfigValueDO {
             histories: value.histories.into_iter().map(|e| e.into()).collect(),
             config_type: value.config_type.map(|e| e.as_ref().to_owned()),
             desc: value.desc.map(|e| e.as_ref().to_owned()),
+            gray: value.gray.map(|e| e.into()),
+            tags: value.tags.map(|e| e.as_ref().to_owned()),
         }
     }
 }
@@ -160,6 +242,8 @@ impl From<ConfigValueDO> for ConfigValue {
                 .config_type
                 .map(|v| ConfigType::new_by_value(&v).get_value()),
             desc: value.desc.map(Arc::new),
+            tags: value.tags.map(Arc::new),
+            gray: value.gray.map(|e| e.into()),
             last_modified,
         }
     }
@@ -0,0 +1,590 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::prelude::*;
+use bean_factory::{bean, Inject};
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::Sha256;
+
+use self::model::{
+    ConfigWebhookDo, ConfigWebhookDto, ConfigWebhookEvent, WebhookDeliveryDto,
+    WebhookDeliveryQueryParam, DELIVERY_STATUS_FAILED, DELIVERY_STATUS_PENDING,
+    DELIVERY_STATUS_SUCCESS,
+};
+use crate::common::constant::CONFIG_WEBHOOK_TREE_NAME;
+use crate::config::config_fuzzy::ConfigChangeType;
+use crate::config::core::ConfigKey;
+use crate::now_millis_i64;
+use crate::raft::db::{
+    route::TableRoute,
+    table::{TableManager, TableManagerQueryReq, TableManagerReq, TableManagerResult},
+};
+
+pub mod model;
+
+pub const SIGNATURE_HEADER: &str = "X-Rnacos-Signature";
+pub const EVENT_HEADER: &str = "X-Rnacos-Event";
+pub const DELIVERY_HEADER: &str = "X-Rnacos-Delivery";
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const MAX_RETRIES_LIMIT: u32 = 10;
+const MAX_DELIVERY_RECORDS: usize = 1000;
+
+///
+/// 配置变更webhook
+/// webhook定义通过raft表T_CONFIG_WEBHOOK在集群内同步;
+/// 只由主节点在配置变更写入raft提交后投递(raft apply及日志重放时不触发);
+/// 投递记录只保存在主节点内存中,控制台查询会转发到主节点,节点重启或切换主节点后记录清空
+#[bean(inject)]
+pub struct ConfigWebhookManager {
+    raft_table_route: Option<Arc<TableRoute>>,
+    table_manager: Option<Addr<TableManager>>,
+    client: reqwest::Client,
+    deliveries: VecDeque<WebhookDeliveryDto>,
+    delivery_id: u64,
+    /// 首次重试间隔,之后每次翻倍
+    retry_interval: Duration,
+}
+
+impl Default for ConfigWebhookManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigWebhookManager {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap_or_default();
+        Self {
+            raft_table_route: None,
+            table_manager: None,
+            client,
+            deliveries: VecDeque::new(),
+            delivery_id: 0,
+            retry_interval: Duration::from_secs(1),
+        }
+    }
+
+    pub fn set_retry_interval(&mut self, retry_interval: Duration) {
+        self.retry_interval = retry_interval;
+    }
+
+    ///
+    /// 签名内容为请求体,值格式: sha256=<hex>
+    pub fn sign(secret: &str, body: &str) -> String {
+        let mut hmac = Hmac::new(Sha256::new(), secret.as_bytes());
+        hmac.input(body.as_bytes());
+        let code = hmac.result();
+        let hex: String = code.code().iter().map(|b| format!("{:02x}", b)).collect();
+        format!("sha256={}", hex)
+    }
+
+    async fn query_list(
+        table_manager: &Option<Addr<TableManager>>,
+    ) -> anyhow::Result<Vec<ConfigWebhookDo>> {
+        let table_manager = match table_manager {
+            Some(v) => v,
+            None => return Ok(vec![]),
+        };
+        let query_req = TableManagerQueryReq::QueryPageList {
+            table_name: CONFIG_WEBHOOK_TREE_NAME.clone(),
+            like_key: None,
+            offset: None,
+            limit: None,
+            is_rev: false,
+        };
+        let list = match table_manager.send(query_req).await?? {
+            TableManagerResult::PageListResult(_, list) => list,
+            _ => vec![],
+        };
+        let mut rlist = Vec::with_capacity(list.len());
+        for (_, v) in list {
+            match ConfigWebhookDo::from_bytes(&v) {
+                Ok(v) => rlist.push(v),
+                Err(err) => log::warn!("decode config webhook error,{}", err),
+            }
+        }
+        Ok(rlist)
+    }
+
+    async fn get_webhook(
+        table_manager: &Option<Addr<TableManager>>,
+        id: &str,
+    ) -> anyhow::Result<Option<ConfigWebhookDo>> {
+        let table_manager = match table_manager {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        let query_req = TableManagerQueryReq::Get {
+            table_name: CONFIG_WEBHOOK_TREE_NAME.clone(),
+            key: id.to_owned(),
+        };
+        match table_manager.send(query_req).await?? {
+            TableManagerResult::Value(v) => Ok(Some(ConfigWebhookDo::from_bytes(&v)?)),
+            _ => Ok(None),
+        }
+    }
+
+    async fn set_webhook(
+        raft_table_route: Option<Arc<TableRoute>>,
+        table_manager: Option<Addr<TableManager>>,
+        dto: ConfigWebhookDto,
+    ) -> anyhow::Result<ConfigWebhookResult> {
+        let now = now_millis_i64();
+        let mut value = match &dto.id {
+            Some(id) => match Self::get_webhook(&table_manager, id).await? {
+                Some(v) => v,
+                None => return Err(anyhow::anyhow!("webhook {} is not exist", id)),
+            },
+            None => ConfigWebhookDo {
+                id: uuid::Uuid::new_v4().simple().to_string(),
+                enable: true,
+                max_retries: DEFAULT_MAX_RETRIES,
+                gmt_create: now,
+                ..Default::default()
+            },
+        };
+        if let Some(v) = dto.name {
+            value.name = v;
+        }
+        if let Some(v) = dto.url {
+            value.url = v.trim().to_owned();
+        }
+        if let Some(v) = dto.tenant {
+            value.tenant = v;
+        }
+        if let Some(v) = dto.group_pattern {
+            value.group_pattern = v;
+        }
+        if let Some(v) = dto.data_id_pattern {
+            value.data_id_pattern = v;
+        }
+        //secret传空字符串表示去除签名
+        if let Some(v) = dto.secret {
+            value.secret = Some(v).filter(|e| !e.is_empty());
+        }
+        if let Some(v) = dto.enable {
+            value.enable = v;
+        }
+        if let Some(v) = dto.max_retries {
+            value.max_retries = v.min(MAX_RETRIES_LIMIT);
+        }
+        value.gmt_modified = now;
+        if !value.url.starts_with("http://") && !value.url.starts_with("https://") {
+            return Err(anyhow::anyhow!("webhook url is invalid: {}", &value.url));
+        }
+        let req = TableManagerReq::Set {
+            table_name: CONFIG_WEBHOOK_TREE_NAME.clone(),
+            key: value.id.as_bytes().to_owned(),
+            value: value.to_bytes(),
+            last_seq_id: None,
+        };
+        if let Some(raft_table_route) = raft_table_route {
+            raft_table_route.request(req).await?;
+        }
+        Ok(ConfigWebhookResult::Webhook(value.into()))
+    }
+
+    async fn remove_webhook(
+        raft_table_route: Option<Arc<TableRoute>>,
+        id: Arc<String>,
+    ) -> anyhow::Result<ConfigWebhookResult> {
+        let req = TableManagerReq::Remove {
+            table_name: CONFIG_WEBHOOK_TREE_NAME.clone(),
+            key: id.as_bytes().to_owned(),
+        };
+        if let Some(raft_table_route) = raft_table_route {
+            raft_table_route.request(req).await?;
+        }
+        Ok(ConfigWebhookResult::None)
+    }
+
+    async fn post_event(
+        client: &reqwest::Client,
+        url: &str,
+        secret: &Option<String>,
+        event: &ConfigWebhookEvent,
+        body: &str,
+    ) -> anyhow::Result<u16> {
+        let mut builder = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header(EVENT_HEADER, &event.change_type)
+            .header(DELIVERY_HEADER, event.delivery_id.to_string());
+        if let Some(secret) = secret {
+            builder = builder.header(SIGNATURE_HEADER, Self::sign(secret, body));
+        }
+        let resp = builder.body(body.to_owned()).send().await?;
+        Ok(resp.status().as_u16())
+    }
+
+    ///
+    /// 投递单个事件,失败后按指数退避重试
+    async fn deliver(
+        addr: Addr<Self>,
+        client: reqwest::Client,
+        webhook: ConfigWebhookDo,
+        event: ConfigWebhookEvent,
+        retry_interval: Duration,
+    ) {
+        let body = serde_json::to_string(&event).unwrap_or_default();
+        let mut interval = retry_interval;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let (status_code, error) =
+                match Self::post_event(&client, &webhook.url, &webhook.secret, &event, &body).await
+                {
+                    Ok(code) if (200..300).contains(&code) => (Some(code), None),
+                    Ok(code) => (Some(code), Some(format!("response status code: {}", code))),
+                    Err(err) => (None, Some(err.to_string())),
+                };
+            let finished = error.is_none() || attempts > webhook.max_retries;
+            let status = if error.is_none() {
+                DELIVERY_STATUS_SUCCESS
+            } else if finished {
+                log::warn!(
+                    "config webhook delivery failed,url:{},error:{:?}",
+                    &webhook.url,
+                    &error
+                );
+                DELIVERY_STATUS_FAILED
+            } else {
+                DELIVERY_STATUS_PENDING
+            };
+            addr.do_send(ConfigWebhookReq::UpdateDelivery {
+                id: event.delivery_id,
+                status,
+                attempts,
+                status_code,
+                error,
+            });
+            if finished {
+                break;
+            }
+            tokio::time::sleep(interval).await;
+            interval *= 2;
+        }
+    }
+
+    fn start_deliveries(
+        &mut self,
+        ctx: &mut Context<Self>,
+        key: ConfigKey,
+        change_type: ConfigChangeType,
+        event_time: i64,
+        webhooks: Vec<ConfigWebhookDo>,
+    ) {
+        for webhook in webhooks {
+            self.delivery_id += 1;
+            let webhook_id = Arc::new(webhook.id.clone());
+            let event = ConfigWebhookEvent::new(
+                webhook_id.clone(),
+                self.delivery_id,
+                &key,
+                change_type,
+                event_time,
+            );
+            self.deliveries.push_back(WebhookDeliveryDto {
+                id: self.delivery_id,
+                webhook_id,
+                url: Arc::new(webhook.url.clone()),
+                tenant: key.tenant.clone(),
+                group: key.group.clone(),
+                data_id: key.data_id.clone(),
+                change_type: change_type.as_str().to_owned(),
+                status: DELIVERY_STATUS_PENDING.to_owned(),
+                create_time: event_time,
+                update_time: event_time,
+                ..Default::default()
+            });
+            while self.deliveries.len() > MAX_DELIVERY_RECORDS {
+                self.deliveries.pop_front();
+            }
+            actix::spawn(Self::deliver(
+                ctx.address(),
+                self.client.clone(),
+                webhook,
+                event,
+                self.retry_interval,
+            ));
+        }
+    }
+
+    fn update_delivery(
+        &mut self,
+        id: u64,
+        status: &str,
+        attempts: u32,
+        status_code: Option<u16>,
+        error: Option<String>,
+    ) {
+        //投递记录按id递增,被淘汰的记录直接忽略
+        let first_id = match self.deliveries.front() {
+            Some(v) => v.id,
+            None => return,
+        };
+        if id < first_id {
+            return;
+        }
+        if let Some(item) = self.deliveries.get_mut((id - first_id) as usize) {
+            item.status = status.to_owned();
+            item.attempts = attempts;
+            item.status_code = status_code;
+            item.error = error;
+            item.update_time = now_millis_i64();
+        }
+    }
+
+    fn query_deliveries(&self, param: &WebhookDeliveryQueryParam) -> ConfigWebhookResult {
+        let mut total = 0;
+        let mut rlist = vec![];
+        let end_index = param.offset + param.limit;
+        for item in self.deliveries.iter().rev() {
+            if !param.match_item(item) {
+                continue;
+            }
+            if total >= param.offset && total < end_index {
+                rlist.push(item.clone());
+            }
+            total += 1;
+        }
+        ConfigWebhookResult::DeliveryPageResult(total, rlist)
+    }
+}
+
+impl Actor for ConfigWebhookManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("ConfigWebhookManager started")
+    }
+}
+
+impl Inject for ConfigWebhookManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        _ctx: &mut Self::Context,
+    ) {
+        self.raft_table_route = factory_data.get_bean();
+        self.table_manager = factory_data.get_actor();
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<ConfigWebhookResult>")]
+pub enum ConfigWebhookReq {
+    Notify(ConfigKey, ConfigChangeType),
+    UpdateDelivery {
+        id: u64,
+        status: &'static str,
+        attempts: u32,
+        status_code: Option<u16>,
+        error: Option<String>,
+    },
+    Set(Box<ConfigWebhookDto>),
+    Remove(Arc<String>),
+    QueryList,
+    QueryDeliveries(Box<WebhookDeliveryQueryParam>),
+}
+
+pub enum ConfigWebhookResult {
+    None,
+    Webhook(ConfigWebhookDto),
+    WebhookList(Vec<ConfigWebhookDto>),
+    DeliveryPageResult(usize, Vec<WebhookDeliveryDto>),
+}
+
+impl Handler<ConfigWebhookReq> for ConfigWebhookManager {
+    type Result = ResponseActFuture<Self, anyhow::Result<ConfigWebhookResult>>;
+
+    fn handle(&mut self, msg: ConfigWebhookReq, _ctx: &mut Self::Context) -> Self::Result {
+        let raft_table_route = self.raft_table_route.clone();
+        let table_manager = self.table_manager.clone();
+        match msg {
+            ConfigWebhookReq::Notify(key, change_type) => {
+                let event_time = now_millis_i64();
+                let match_key = key.clone();
+                let fut = async move {
+                    let list = Self::query_list(&table_manager).await?;
+                    Ok(list
+                        .into_iter()
+                        .filter(|e| e.match_key(&match_key))
+                        .collect())
+                }
+                .into_actor(self)
+                .map(move |r: anyhow::Result<Vec<ConfigWebhookDo>>, act, ctx| {
+                    let webhooks = r?;
+                    if !webhooks.is_empty() {
+                        act.start_deliveries(ctx, key, change_type, event_time, webhooks);
+                    }
+                    Ok(ConfigWebhookResult::None)
+                });
+                Box::pin(fut)
+            }
+            ConfigWebhookReq::UpdateDelivery {
+                id,
+                status,
+                attempts,
+                status_code,
+                error,
+            } => {
+                self.update_delivery(id, status, attempts, status_code, error);
+                Box::pin(actix::fut::ready(Ok(ConfigWebhookResult::None)))
+            }
+            ConfigWebhookReq::QueryDeliveries(param) => {
+                let r = self.query_deliveries(&param);
+                Box::pin(actix::fut::ready(Ok(r)))
+            }
+            ConfigWebhookReq::Set(dto) => {
+                Box::pin(Self::set_webhook(raft_table_route, table_manager, *dto).into_actor(self))
+            }
+            ConfigWebhookReq::Remove(id) => {
+                Box::pin(Self::remove_webhook(raft_table_route, id).into_actor(self))
+            }
+            ConfigWebhookReq::QueryList => Box::pin(
+                async move {
+                    let list = Self::query_list(&table_manager).await?;
+                    Ok(ConfigWebhookResult::WebhookList(
+                        list.into_iter().map(|e| e.into()).collect(),
+                    ))
+                }
+                .into_actor(self),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    ///
+    /// 本地http服务,记录收到的请求头与请求体;前fail_times次返回500
+    async fn start_stub_server(
+        fail_times: usize,
+    ) -> (
+        String,
+        tokio::sync::mpsc::UnboundedReceiver<(HashMap<String, String>, String)>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut count = 0;
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = vec![];
+                let mut tmp = [0u8; 1024];
+                let (headers, body) = loop {
+                    let n = stream.read(&mut tmp).await.unwrap();
+                    buf.extend_from_slice(&tmp[..n]);
+                    let text = String::from_utf8_lossy(&buf).to_string();
+                    if let Some(i) = text.find("\r\n\r\n") {
+                        let headers: HashMap<String, String> = text[..i]
+                            .lines()
+                            .skip(1)
+                            .filter_map(|e| e.split_once(':'))
+                            .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_owned()))
+                            .collect();
+                        let len: usize = headers
+                            .get("content-length")
+                            .and_then(|e| e.parse().ok())
+                            .unwrap_or_default();
+                        if buf.len() >= i + 4 + len {
+                            break (headers, text[i + 4..i + 4 + len].to_owned());
+                        }
+                    }
+                };
+                count += 1;
+                let resp = if count <= fail_times {
+                    "HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                } else {
+                    "HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                };
+                stream.write_all(resp.as_bytes()).await.unwrap();
+                tx.send((headers, body)).ok();
+            }
+        });
+        (url, rx)
+    }
+
+    #[actix_rt::test]
+    async fn webhook_delivery_with_retry() {
+        let (url, mut rx) = start_stub_server(1).await;
+        let mut manager = ConfigWebhookManager::new();
+        manager.set_retry_interval(Duration::from_millis(10));
+        let addr = manager.start();
+        let webhook = ConfigWebhookDo {
+            id: "w1".to_owned(),
+            url,
+            tenant: "dev".to_owned(),
+            group_pattern: "GATEWAY".to_owned(),
+            data_id_pattern: "route-*".to_owned(),
+            secret: Some("abc".to_owned()),
+            enable: true,
+            max_retries: 2,
+            ..Default::default()
+        };
+        assert!(!webhook.match_key(&ConfigKey::new("app", "GATEWAY", "dev")));
+        let key = ConfigKey::new("route-a", "GATEWAY", "dev");
+        assert!(webhook.match_key(&key));
+        addr.send(TestStartDelivery(key, webhook)).await.unwrap();
+
+        let (_, first_body) = rx.recv().await.unwrap();
+        let (headers, body) = rx.recv().await.unwrap();
+        assert_eq!(first_body, body);
+        assert_eq!(
+            headers.get(&SIGNATURE_HEADER.to_lowercase()).unwrap(),
+            &ConfigWebhookManager::sign("abc", &body)
+        );
+        let event: ConfigWebhookEvent = serde_json::from_str(&body).unwrap();
+        assert_eq!(event.data_id.as_str(), "route-a");
+        assert_eq!(&event.change_type, "CONFIG_CHANGED");
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let param = WebhookDeliveryQueryParam {
+            limit: 10,
+            ..Default::default()
+        };
+        match addr
+            .send(ConfigWebhookReq::QueryDeliveries(Box::new(param)))
+            .await
+            .unwrap()
+            .unwrap()
+        {
+            ConfigWebhookResult::DeliveryPageResult(total, list) => {
+                assert_eq!(total, 1);
+                assert_eq!(&list[0].status, DELIVERY_STATUS_SUCCESS);
+                assert_eq!(list[0].attempts, 2);
+                assert_eq!(list[0].status_code, Some(200));
+            }
+            _ => panic!("unexpected result"),
+        }
+    }
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct TestStartDelivery(ConfigKey, ConfigWebhookDo);
+
+    impl Handler<TestStartDelivery> for ConfigWebhookManager {
+        type Result = ();
+
+        fn handle(&mut self, msg: TestStartDelivery, ctx: &mut Self::Context) -> Self::Result {
+            self.start_deliveries(ctx, msg.0, ConfigChangeType::Update, 1, vec![msg.1]);
+        }
+    }
+}
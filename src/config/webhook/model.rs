@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::config_fuzzy::{ConfigChangeType, ConfigFuzzyPattern};
+use crate::config::core::ConfigKey;
+
+pub const DELIVERY_STATUS_PENDING: &str = "PENDING";
+pub const DELIVERY_STATUS_SUCCESS: &str = "SUCCESS";
+pub const DELIVERY_STATUS_FAILED: &str = "FAILED";
+
+#[derive(Clone, prost::Message, Serialize, Deserialize)]
+pub struct ConfigWebhookDo {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub url: String,
+    #[prost(string, tag = "4")]
+    pub tenant: String,
+    #[prost(string, tag = "5")]
+    pub group_pattern: String,
+    #[prost(string, tag = "6")]
+    pub data_id_pattern: String,
+    #[prost(string, optional, tag = "7")]
+    pub secret: Option<String>,
+    #[prost(bool, tag = "8")]
+    pub enable: bool,
+    #[prost(uint32, tag = "9")]
+    pub max_retries: u32,
+    #[prost(int64, tag = "10")]
+    pub gmt_create: i64,
+    #[prost(int64, tag = "11")]
+    pub gmt_modified: i64,
+}
+
+impl ConfigWebhookDo {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::new();
+        prost::Message::encode(self, &mut v).unwrap_or_default();
+        v
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(prost::Message::decode(v)?)
+    }
+
+    pub fn to_pattern(&self) -> ConfigFuzzyPattern {
+        ConfigFuzzyPattern::new(&self.data_id_pattern, &self.group_pattern, &self.tenant)
+    }
+
+    pub fn match_key(&self, key: &ConfigKey) -> bool {
+        self.enable && self.to_pattern().match_key(key)
+    }
+}
+
+///
+/// 配置变更webhook
+/// tenant精确匹配,group与dataId支持`*`通配符;设置secret后请求头携带HMAC-SHA256签名
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWebhookDto {
+    pub id: Option<Arc<String>>,
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub tenant: Option<String>,
+    pub group_pattern: Option<String>,
+    pub data_id_pattern: Option<String>,
+    pub secret: Option<String>,
+    pub enable: Option<bool>,
+    pub max_retries: Option<u32>,
+    pub gmt_create: Option<i64>,
+    pub gmt_modified: Option<i64>,
+}
+
+impl From<ConfigWebhookDo> for ConfigWebhookDto {
+    fn from(v: ConfigWebhookDo) -> Self {
+        Self {
+            id: Some(Arc::new(v.id)),
+            name: Some(v.name),
+            url: Some(v.url),
+            tenant: Some(v.tenant),
+            group_pattern: Some(v.group_pattern),
+            data_id_pattern: Some(v.data_id_pattern),
+            //不对外返回secret
+            secret: v.secret.map(|_| "******".to_owned()),
+            enable: Some(v.enable),
+            max_retries: Some(v.max_retries),
+            gmt_create: Some(v.gmt_create),
+            gmt_modified: Some(v.gmt_modified),
+        }
+    }
+}
+
+///
+/// webhook请求体
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWebhookEvent {
+    pub webhook_id: Arc<String>,
+    pub delivery_id: u64,
+    pub tenant: Arc<String>,
+    pub group: Arc<String>,
+    pub data_id: Arc<String>,
+    pub change_type: String,
+    pub event_time: i64,
+}
+
+impl ConfigWebhookEvent {
+    pub fn new(
+        webhook_id: Arc<String>,
+        delivery_id: u64,
+        key: &ConfigKey,
+        change_type: ConfigChangeType,
+        event_time: i64,
+    ) -> Self {
+        Self {
+            webhook_id,
+            delivery_id,
+            tenant: key.tenant.clone(),
+            group: key.group.clone(),
+            data_id: key.data_id.clone(),
+            change_type: change_type.as_str().to_owned(),
+            event_time,
+        }
+    }
+}
+
+///
+/// webhook投递记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryDto {
+    pub id: u64,
+    pub webhook_id: Arc<String>,
+    pub url: Arc<String>,
+    pub tenant: Arc<String>,
+    pub group: Arc<String>,
+    pub data_id: Arc<String>,
+    pub change_type: String,
+    pub status: String,
+    pub attempts: u32,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub create_time: i64,
+    pub update_time: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebhookDeliveryQueryParam {
+    pub webhook_id: Option<String>,
+    pub status: Option<String>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl WebhookDeliveryQueryParam {
+    pub fn match_item(&self, item: &WebhookDeliveryDto) -> bool {
+        self.webhook_id
+            .as_ref()
+            .map(|e| e == item.webhook_id.as_str())
+            .unwrap_or(true)
+            && self
+                .status
+                .as_ref()
+                .map(|e| e == &item.status)
+                .unwrap_or(true)
+    }
+}
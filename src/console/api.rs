@@ -224,6 +224,18 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
                 web::resource("/login/captcha").route(web::get().to(v2::login_api::gen_captcha)),
             )
             .service(web::resource("/login/logout").route(web::post().to(v2::login_api::logout)))
+            .service(
+                web::resource("/login/oidc/config")
+                    .route(web::get().to(v2::login_api::oidc_config)),
+            )
+            .service(
+                web::resource("/login/oidc/authorize")
+                    .route(web::get().to(v2::login_api::oidc_authorize)),
+            )
+            .service(
+                web::resource("/login/oidc/callback")
+                    .route(web::get().to(v2::login_api::oidc_callback)),
+            )
             .service(web::resource("/user/info").route(web::get().to(v2::user_api::get_user_info)))
             .service(
                 web::resource("/user/list").route(web::get().to(v2::user_api::get_user_page_list)),
@@ -231,6 +243,7 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
             .service(web::resource("/user/add").route(web::post().to(v2::user_api::add_user)))
             .service(web::resource("/user/update").route(web::post().to(v2::user_api::update_user)))
             .service(web::resource("/user/remove").route(web::post().to(v2::user_api::remove_user)))
+            .service(web::resource("/user/unlock").route(web::post().to(v2::user_api::unlock_user)))
             .service(
                 web::resource("/user/web_resources")
                     .route(web::get().to(v2::user_api::get_user_web_resources)),
@@ -239,6 +252,36 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
                 web::resource("/user/reset_password")
                     .route(web::post().to(v2::user_api::reset_password)),
             )
+            .service(
+                web::resource("/role/list").route(web::get().to(v2::role_api::query_role_list)),
+            )
+            .service(
+                web::resource("/role/modules")
+                    .route(web::get().to(v2::role_api::query_module_list)),
+            )
+            .service(web::resource("/role/add").route(web::post().to(v2::role_api::add_role)))
+            .service(web::resource("/role/update").route(web::post().to(v2::role_api::update_role)))
+            .service(web::resource("/role/remove").route(web::post().to(v2::role_api::remove_role)))
+            .service(
+                web::resource("/access_key/list")
+                    .route(web::get().to(v2::access_key_api::query_access_key_list)),
+            )
+            .service(
+                web::resource("/access_key/add")
+                    .route(web::post().to(v2::access_key_api::add_access_key)),
+            )
+            .service(
+                web::resource("/access_key/update")
+                    .route(web::post().to(v2::access_key_api::update_access_key)),
+            )
+            .service(
+                web::resource("/access_key/remove")
+                    .route(web::post().to(v2::access_key_api::remove_access_key)),
+            )
+            .service(
+                web::resource("/audit/list")
+                    .route(web::get().to(v2::audit_api::query_audit_log_page)),
+            )
             .service(
                 web::resource("/namespaces/list")
                     .route(web::get().to(v2::namespace_api::query_namespace_list)),
@@ -284,6 +327,49 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
                 web::resource("/config/history")
                     .route(web::get().to(v2::config_api::query_history_config_page)),
             )
+            .service(
+                web::resource("/config/history/rollback")
+                    .route(web::post().to(v2::config_api::rollback_config)),
+            )
+            .service(
+                web::resource("/config/diff").route(web::get().to(v2::config_api::diff_config)),
+            )
+            .service(
+                web::resource("/config/gray/info")
+                    .route(web::get().to(v2::config_api::get_gray_config)),
+            )
+            .service(
+                web::resource("/config/gray/publish")
+                    .route(web::post().to(v2::config_api::publish_gray_config)),
+            )
+            .service(
+                web::resource("/config/gray/promote")
+                    .route(web::post().to(v2::config_api::promote_gray_config)),
+            )
+            .service(
+                web::resource("/config/gray/abort")
+                    .route(web::post().to(v2::config_api::abort_gray_config)),
+            )
+            .service(
+                web::resource("/config/webhook/list")
+                    .route(web::get().to(v2::config_webhook_api::query_webhook_list)),
+            )
+            .service(
+                web::resource("/config/webhook/add")
+                    .route(web::post().to(v2::config_webhook_api::set_webhook)),
+            )
+            .service(
+                web::resource("/config/webhook/update")
+                    .route(web::post().to(v2::config_webhook_api::set_webhook)),
+            )
+            .service(
+                web::resource("/config/webhook/remove")
+                    .route(web::post().to(v2::config_webhook_api::remove_webhook)),
+            )
+            .service(
+                web::resource("/config/webhook/delivery/list")
+                    .route(web::get().to(v2::config_webhook_api::query_delivery_page)),
+            )
             .service(
                 web::resource("/service/list")
                     .route(web::get().to(v2::naming_api::query_service_list)),
@@ -302,10 +388,59 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
                 web::resource("/service/remove")
                     .route(web::post().to(v2::naming_api::remove_service)),
             )
+            .service(
+                web::resource("/service/healthcheck/list")
+                    .route(web::get().to(v2::naming_health_check_api::query_health_check_list)),
+            )
+            .service(
+                web::resource("/service/healthcheck/update")
+                    .route(web::post().to(v2::naming_health_check_api::set_health_check)),
+            )
+            .service(
+                web::resource("/service/healthcheck/remove")
+                    .route(web::post().to(v2::naming_health_check_api::remove_health_check)),
+            )
+            .service(
+                web::resource("/service/info")
+                    .route(web::get().to(v2::naming_service_cluster_api::query_service_info)),
+            )
+            .service(
+                web::resource("/service/cluster/list").route(
+                    web::get().to(v2::naming_service_cluster_api::query_service_cluster_list),
+                ),
+            )
+            .service(
+                web::resource("/service/cluster/update")
+                    .route(web::post().to(v2::naming_service_cluster_api::update_service_cluster)),
+            )
+            .service(
+                web::resource("/service/cluster/remove")
+                    .route(web::post().to(v2::naming_service_cluster_api::remove_service_cluster)),
+            )
+            .service(
+                web::resource("/service/subscriber/push/list")
+                    .route(web::get().to(v2::naming_subscriber_api::query_subscriber_push_list)),
+            )
+            .service(
+                web::resource("/service/event/list")
+                    .route(web::get().to(v2::naming_event_api::query_service_event_list)),
+            )
             .service(
                 web::resource("/instance/list")
                     .route(web::get().to(v2::naming_api::query_instances_list)),
             )
+            .service(
+                web::resource("/instance/drain/list")
+                    .route(web::get().to(v2::naming_instance_drain_api::query_drain_list)),
+            )
+            .service(
+                web::resource("/instance/drain/add")
+                    .route(web::post().to(v2::naming_instance_drain_api::drain_instance)),
+            )
+            .service(
+                web::resource("/instance/drain/remove")
+                    .route(web::post().to(v2::naming_instance_drain_api::restore_instance)),
+            )
             .service(
                 web::resource("/instance/info").route(web::get().to(v2::naming_api::get_instance)),
             )
@@ -1,5 +1,6 @@
 #![allow(unused_imports)]
 
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::sync::Arc;
@@ -8,20 +9,26 @@ use actix_multipart::form::tempfile::TempFile;
 use actix_multipart::form::text::Text;
 use actix_multipart::form::MultipartForm;
 use actix_web::{http::header, web, Error, HttpMessage, HttpRequest, HttpResponse, Responder};
-use zip::write::FileOptions;
 
 use super::model::PageResult;
+use crate::audit::model::{AUDIT_ACTION_IMPORT, AUDIT_MODULE_CONFIG};
+use crate::audit::AuditLogUtils;
 use crate::common::appdata::AppShareData;
+use crate::common::model::ApiResult;
+use crate::config::config_zip::{
+    ConfigImportPolicy, ConfigImportResult, ConfigZipItem, ConfigZipUtils, IMPORT_STATUS_FAIL,
+    IMPORT_STATUS_SKIP, IMPORT_STATUS_SUCCESS,
+};
 use crate::config::core::{ConfigActor, ConfigCmd, ConfigInfoDto, ConfigKey, ConfigResult};
 use crate::config::ConfigUtils;
 use crate::console::model::config_model::{
     ConfigParams, OpsConfigOptQueryListResponse, OpsConfigQueryListRequest,
 };
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
 use crate::raft::cluster::model::SetConfigReq;
-use crate::{now_millis, user_namespace_privilege};
+use crate::{now_millis, user_config_group_privilege, user_namespace_privilege};
 use actix::prelude::Addr;
 use tokio_stream::StreamExt;
-use zip::ZipWriter;
 
 pub async fn query_config_list(
     req: HttpRequest,
@@ -61,6 +68,7 @@ pub async fn query_config_list(
 }
 
 pub async fn query_history_config_page(
+    req: HttpRequest,
     request: web::Query<OpsConfigQueryListRequest>,
     config_addr: web::Data<Addr<ConfigActor>>,
 ) -> impl Responder {
@@ -70,6 +78,20 @@ pub async fn query_history_config_page(
             return HttpResponse::InternalServerError().body(err.to_string());
         }
     };
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege
+        .check_option_value_permission(&(param.tenant.clone().map(Arc::new)), false)
+    {
+        return HttpResponse::Unauthorized().body(format!(
+            "user no such namespace permission: {:?}",
+            &param.tenant
+        ));
+    }
+    let group = param.group.as_deref().unwrap_or_default();
+    if !user_config_group_privilege!(req).check_permission(group) {
+        return HttpResponse::Unauthorized()
+            .body(format!("user no such group permission: {:?}", &param.group));
+    }
     let cmd = ConfigCmd::QueryHistoryPageInfo(Box::new(param));
     match config_addr.send(cmd).await {
         Ok(res) => {
@@ -96,10 +118,42 @@ pub async fn query_history_config_page(
 pub struct UploadForm {
     #[multipart(rename = "tenant")]
     pub tenant: Option<Text<String>>,
+    /// 配置已存在时的处理策略: ABORT,SKIP,OVERWRITE;默认OVERWRITE
+    #[multipart(rename = "policy")]
+    pub policy: Option<Text<String>>,
     #[multipart(rename = "file")]
     pub files: Vec<TempFile>,
 }
 
+fn import_error(msg: String) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        ERROR_CODE_SYSTEM_ERROR.to_string(),
+        Some(msg),
+    ))
+}
+
+///
+/// 查询导入配置中已存在的配置
+async fn query_exist_keys(
+    app: &AppShareData,
+    tenant: &Arc<String>,
+    items: &[ConfigZipItem],
+) -> anyhow::Result<HashSet<ConfigKey>> {
+    let keys: Vec<ConfigKey> = items
+        .iter()
+        .filter(|e| e.error.is_none())
+        .map(|e| e.to_key(tenant.clone()))
+        .collect();
+    let cmd = ConfigCmd::QueryInfoByKeys(Box::new(keys));
+    match app.config_addr.send(cmd).await?? {
+        ConfigResult::ConfigInfoPage(_, list) => Ok(list
+            .into_iter()
+            .map(|e| ConfigKey::new_by_arc(e.data_id, e.group, e.tenant))
+            .collect()),
+        _ => Ok(HashSet::new()),
+    }
+}
+
 pub async fn import_config(
     req: HttpRequest,
     MultipartForm(form): MultipartForm<UploadForm>,
@@ -118,72 +172,88 @@ pub async fn import_config(
             tenant.as_str()
         )));
     }
-    //let tenant = Arc::new(ConfigUtils::default_tenant(config_info.0.tenant.unwrap_or_default()));
+    let policy_value = match (&form.policy, req.headers().get("policy")) {
+        (Some(v), _) => v.0.clone(),
+        (None, Some(v)) => String::from_utf8_lossy(v.as_bytes()).to_string(),
+        (None, None) => "".to_owned(),
+    };
+    let policy = match ConfigImportPolicy::new(&policy_value) {
+        Ok(v) => v,
+        Err(err) => return Ok(import_error(err.to_string())),
+    };
+    let mut items = vec![];
     for f in form.files {
-        match zip::ZipArchive::new(f.file) {
-            Ok(mut archive) => {
-                for i in 0..archive.len() {
-                    let mut file = archive.by_index(i).unwrap();
-                    /*
-                    let filepath = match file.enclosed_name() {
-                        Some(path) => path,
-                        None => continue,
-                    };
-                    */
-                    let filename = file.name();
-                    if !(*filename).ends_with('/') {
-                        let parts = filename.split('/').collect::<Vec<_>>();
-                        if parts.len() != 2 {
-                            continue;
-                        }
-                        assert!(parts.len() == 2);
-                        let config_key = ConfigKey::new_by_arc(
-                            Arc::new(parts[1].to_owned()),
-                            Arc::new(parts[0].to_owned()),
-                            tenant.clone(),
-                        );
-                        let value = match io::read_to_string(&mut file) {
-                            Ok(v) => v,
-                            Err(_) => continue,
-                        };
-                        //println!("update load, {:?}:{}",&config_key,&value);
-                        //config_addr.do_send(ConfigAsyncCmd::Add(config_key, Arc::new(value)));
-                        let mut req = SetConfigReq::new(config_key.clone(), Arc::new(value));
-                        let data_id_clone = config_key.data_id.clone();
-                        req.config_type = SetConfigReq::detect_config_type(data_id_clone);
-
-                        app.config_route.set_config(req).await.ok();
-                    }
-                }
-            }
-            Err(_) => todo!(),
+        match ConfigZipUtils::read_zip(f.file) {
+            Ok(v) => items.extend(v),
+            Err(err) => return Ok(import_error(format!("read zip file error,{}", err))),
         }
     }
-    Ok(HttpResponse::Ok().finish())
-}
-
-fn zip_file(mut zip: ZipWriter<&mut File>, list: Vec<ConfigInfoDto>) -> anyhow::Result<()> {
-    if list.is_empty() {
-        let options = FileOptions::default()
-            .compression_method(zip::CompressionMethod::Stored)
-            .unix_permissions(0o755);
-        zip.start_file(".ignore", options)?;
-        zip.write_all("empty config".as_bytes())?;
+    let group_privilege = user_config_group_privilege!(req);
+    for item in items.iter_mut() {
+        if item.error.is_none() && !group_privilege.check_permission(&item.group) {
+            item.error = Some(format!("user no such group permission: {}", &item.group));
+        }
     }
-    for item in &list {
-        zip.add_directory(item.group.as_str(), Default::default())
-            .ok();
-        let options = FileOptions::default()
-            .compression_method(zip::CompressionMethod::Stored)
-            .unix_permissions(0o755);
-        zip.start_file(
-            format!("{}/{}", &item.group.as_str(), &item.data_id.as_str()),
-            options,
-        )?;
-        zip.write_all(item.content.as_ref().unwrap().as_bytes())?;
+    let exist_keys = match query_exist_keys(&app, &tenant, &items).await {
+        Ok(v) => v,
+        Err(err) => return Ok(import_error(err.to_string())),
+    };
+    let mut result = ConfigImportResult::default();
+    let has_conflict = items
+        .iter()
+        .any(|e| e.error.is_none() && exist_keys.contains(&e.to_key(tenant.clone())));
+    for item in &items {
+        if let Some(err) = &item.error {
+            result.push(item, IMPORT_STATUS_FAIL, Some(err.clone()));
+            continue;
+        }
+        let config_key = item.to_key(tenant.clone());
+        let exist = exist_keys.contains(&config_key);
+        if policy == ConfigImportPolicy::Abort && has_conflict {
+            if exist {
+                result.push(
+                    item,
+                    IMPORT_STATUS_FAIL,
+                    Some("config already exists".to_owned()),
+                );
+            } else {
+                result.push(item, IMPORT_STATUS_SKIP, Some("import aborted".to_owned()));
+            }
+            continue;
+        }
+        if exist && policy == ConfigImportPolicy::Skip {
+            result.push(
+                item,
+                IMPORT_STATUS_SKIP,
+                Some("config already exists".to_owned()),
+            );
+            continue;
+        }
+        let mut set_req = SetConfigReq::new(config_key, item.content.clone());
+        set_req.config_type = item
+            .config_type
+            .clone()
+            .or_else(|| SetConfigReq::detect_config_type(item.data_id.clone()));
+        set_req.desc = item.desc.clone();
+        set_req.force = true;
+        match app.config_route.set_config(set_req).await {
+            Ok(_) => result.push(item, IMPORT_STATUS_SUCCESS, None),
+            Err(err) => result.push(item, IMPORT_STATUS_FAIL, Some(err.to_string())),
+        }
     }
-    zip.finish()?;
-    Ok(())
+    AuditLogUtils::record_detail(
+        &app,
+        &req,
+        AUDIT_MODULE_CONFIG,
+        AUDIT_ACTION_IMPORT,
+        format!("{}/*", &tenant),
+        result.fail_count == 0,
+        Some(format!(
+            "import policy: {}, success: {}, skip: {}, fail: {}",
+            policy_value, result.success_count, result.skip_count, result.fail_count
+        )),
+    );
+    Ok(HttpResponse::Ok().json(ApiResult::success(Some(result))))
 }
 
 ///
@@ -205,8 +275,7 @@ pub async fn download_config(
                     let mut tmpfile: File = tempfile::tempfile().unwrap();
                     {
                         let write = std::io::Write::by_ref(&mut tmpfile);
-                        let zip = ZipWriter::new(write);
-                        zip_file(zip, list).ok();
+                        ConfigZipUtils::write_zip(write, &list).ok();
                     }
                     // Seek to start
                     tmpfile.seek(SeekFrom::Start(0)).unwrap();
@@ -228,6 +297,7 @@ pub async fn download_config(
 
 /// 按 key 导出配置
 pub async fn download_config_by_keys(
+    req: HttpRequest,
     request: web::Json<Vec<ConfigParams>>,
     config_addr: web::Data<Addr<ConfigActor>>,
 ) -> impl Responder {
@@ -236,6 +306,7 @@ pub async fn download_config_by_keys(
         return HttpResponse::BadRequest().body("keys cannot be empty");
     }
 
+    let group_privilege = user_config_group_privilege!(req);
     let keys = params
         .into_iter()
         .map(|k| {
@@ -245,6 +316,7 @@ pub async fn download_config_by_keys(
                 ..k
             }
         })
+        .filter(|k| group_privilege.check_permission(&k.group))
         .collect();
 
     let cmd = ConfigCmd::QueryInfoByKeys(Box::new(keys));
@@ -256,8 +328,7 @@ pub async fn download_config_by_keys(
                     let mut tmpfile: File = tempfile::tempfile().unwrap();
                     {
                         let write = std::io::Write::by_ref(&mut tmpfile);
-                        let zip = ZipWriter::new(write);
-                        zip_file(zip, list).ok();
+                        ConfigZipUtils::write_zip(write, &list).ok();
                     }
                     tmpfile.seek(SeekFrom::Start(0)).unwrap();
                     let mut buf = vec![];
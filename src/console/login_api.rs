@@ -4,6 +4,9 @@ use std::sync::Arc;
 use super::model::login_model::{LoginParam, LoginToken};
 use crate::ldap::model::actor_model::{LdapMsgReq, LdapMsgResult};
 use crate::ldap::model::LdapUserParam;
+use crate::oidc::model::{
+    OidcAuthorizeParam, OidcCallbackParam, OidcLoginState, OidcReq, OidcResult,
+};
 use crate::{
     common::{
         appdata::AppShareData,
@@ -19,7 +22,7 @@ use crate::{
 };
 use actix_web::http::header;
 use actix_web::{
-    cookie::Cookie,
+    cookie::{time::Duration, Cookie, SameSite},
     web::{self, Data},
     HttpRequest, HttpResponse, Responder,
 };
@@ -71,6 +74,12 @@ pub async fn login(
     let mut error_code = "USER_CHECK_ERROR".to_owned();
     if let Ok(Ok(res)) = app.user_manager.send(msg).await {
         if let UserManagerResult::CheckUserResult(valid, user) = res {
+            if !valid && user.locked.unwrap_or_default() {
+                return HttpResponse::Ok().json(ApiResult::<()>::error(
+                    "USER_LOCKED".to_owned(),
+                    user.lock_until.map(|v| format!("locked until {}", v)),
+                ));
+            }
             if valid {
                 session = Some(Arc::new(UserSession {
                     username: user.username,
@@ -78,7 +87,10 @@ pub async fn login(
                     roles: user.roles.unwrap_or_default(),
                     extend_infos: user.extend_info.unwrap_or_default(),
                     namespace_privilege: user.namespace_privilege,
+                    config_group_privilege: user.config_group_privilege,
+                    service_group_privilege: user.service_group_privilege,
                     refresh_time: now_second_i32() as u32,
+                    need_change_password: user.need_change_password.unwrap_or_default(),
                 }));
             }
         }
@@ -107,11 +119,7 @@ pub async fn login(
     HttpResponse::Ok().json(ApiResult::<()>::error(error_code, None))
 }
 
-fn apply_session(
-    app: Data<Arc<AppShareData>>,
-    limit_key: Arc<String>,
-    session: Arc<UserSession>,
-) -> Option<HttpResponse> {
+fn cache_session(app: &Data<Arc<AppShareData>>, session: Arc<UserSession>) -> Arc<String> {
     //增加长度避免遍历
     let token = Arc::new(
         uuid::Uuid::new_v4().to_string().replace('-', "")
@@ -123,11 +131,22 @@ fn apply_session(
         ttl: app.sys_config.console_login_timeout,
     };
     app.cache_manager.do_send(cache_req);
+    token
+}
+
+fn apply_session(
+    app: Data<Arc<AppShareData>>,
+    limit_key: Arc<String>,
+    session: Arc<UserSession>,
+) -> Option<HttpResponse> {
+    let need_change_password = session.need_change_password;
+    let token = cache_session(&app, session);
     //登录成功后清除登陆限流计数
     let clear_limit_req = CacheManagerReq::Remove(CacheKey::new(CacheType::String, limit_key));
     app.cache_manager.do_send(clear_limit_req);
     let login_token = LoginToken {
         token: token.to_string(),
+        need_change_password,
     };
     Some(
         HttpResponse::Ok()
@@ -167,8 +186,11 @@ async fn ldap_login(
             nickname: Some(meta.user_name),
             roles: vec![meta.role],
             namespace_privilege: meta.namespace_privilege,
+            config_group_privilege: None,
+            service_group_privilege: None,
             extend_infos: HashMap::default(),
             refresh_time: now_second_i32() as u32,
+            need_change_password: false,
         }))
     } else {
         None
@@ -176,6 +198,150 @@ async fn ldap_login(
     Ok(v)
 }
 
+const OIDC_DEFAULT_REDIRECT_URL: &str = "/rnacos/";
+const OIDC_STATE_COOKIE: &str = "oidc_state";
+const OIDC_STATE_TTL: i32 = 300;
+
+fn oidc_state_key(state: &str) -> CacheKey {
+    CacheKey::new(CacheType::String, Arc::new(format!("OidcState_{}", state)))
+}
+
+///
+/// state同时写入发起登录的浏览器cookie,回调时比对,防止登录CSRF
+fn oidc_state_cookie(state: &str, ttl: i32) -> Cookie<'_> {
+    Cookie::build(OIDC_STATE_COOKIE, state)
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .max_age(Duration::seconds(ttl as i64))
+        .finish()
+}
+
+fn oidc_error(message: String) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        "OIDC_LOGIN_ERROR".to_owned(),
+        Some(message),
+    ))
+}
+
+///
+/// 控制台是否开启OIDC登录,供登录页展示单点登录入口
+pub async fn oidc_config(app: Data<Arc<AppShareData>>) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::success(Some(app.sys_config.oidc_enable)))
+}
+
+///
+/// 跳转到身份提供方授权页;登录完成后只允许跳回本站相对路径
+pub async fn oidc_authorize(
+    app: Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<OidcAuthorizeParam>,
+) -> HttpResponse {
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    let redirect_url = param
+        .redirect_url
+        .filter(|v| v.starts_with('/') && !v.starts_with("//"))
+        .unwrap_or_else(|| OIDC_DEFAULT_REDIRECT_URL.to_owned());
+    let msg = OidcReq::AuthorizeUrl {
+        state: state.clone(),
+        nonce: nonce.clone(),
+    };
+    let url = match app.oidc_manager.send(msg).await {
+        Ok(Ok(OidcResult::AuthorizeUrl(url))) => url,
+        Ok(Err(e)) => return oidc_error(e.to_string()),
+        Err(e) => return oidc_error(e.to_string()),
+        _ => return oidc_error("unexpected result".to_owned()),
+    };
+    let login_state = OidcLoginState {
+        nonce,
+        redirect_url,
+    };
+    let cache_req = CacheManagerReq::Set {
+        key: oidc_state_key(&state),
+        value: CacheValue::String(Arc::new(
+            serde_json::to_string(&login_state).unwrap_or_default(),
+        )),
+        ttl: OIDC_STATE_TTL,
+    };
+    app.cache_manager.send(cache_req).await.ok();
+    HttpResponse::Found()
+        .cookie(oidc_state_cookie(&state, OIDC_STATE_TTL))
+        .insert_header((header::LOCATION, url))
+        .finish()
+}
+
+///
+/// 身份提供方授权后的回调,校验state后用授权码换取用户信息并登录
+pub async fn oidc_callback(
+    request: HttpRequest,
+    app: Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<OidcCallbackParam>,
+) -> HttpResponse {
+    if let Some(error) = param.error {
+        return oidc_error(format!(
+            "{},{}",
+            error,
+            param.error_description.unwrap_or_default()
+        ));
+    }
+    let state = param.state.unwrap_or_default();
+    let cookie_state = request
+        .cookie(OIDC_STATE_COOKIE)
+        .map(|v| v.value().to_owned())
+        .unwrap_or_default();
+    if state.is_empty() || cookie_state != state {
+        return oidc_error("invalid oidc state".to_owned());
+    }
+    let cache_req = CacheManagerReq::Get(oidc_state_key(&state));
+    let login_state = match app.cache_manager.send(cache_req).await {
+        Ok(Ok(CacheManagerResult::Value(CacheValue::String(v)))) => {
+            serde_json::from_str::<OidcLoginState>(&v).ok()
+        }
+        _ => None,
+    };
+    let login_state = match login_state {
+        Some(v) => v,
+        None => return oidc_error("invalid oidc state".to_owned()),
+    };
+    app.cache_manager
+        .do_send(CacheManagerReq::Remove(oidc_state_key(&state)));
+    let msg = OidcReq::Login {
+        code: param.code.unwrap_or_default(),
+        nonce: login_state.nonce,
+    };
+    let meta = match app.oidc_manager.send(msg).await {
+        Ok(Ok(OidcResult::UserMeta(meta))) => meta,
+        Ok(Err(e)) => {
+            log::warn!("oidc login error:{}", e);
+            return oidc_error(e.to_string());
+        }
+        Err(e) => return oidc_error(e.to_string()),
+        _ => return oidc_error("unexpected result".to_owned()),
+    };
+    let session = Arc::new(UserSession {
+        username: Arc::new(meta.user_name),
+        nickname: Some(meta.nickname),
+        roles: vec![meta.role],
+        namespace_privilege: meta.namespace_privilege,
+        config_group_privilege: meta.config_group_privilege,
+        service_group_privilege: meta.service_group_privilege,
+        extend_infos: HashMap::default(),
+        refresh_time: now_second_i32() as u32,
+        need_change_password: false,
+    });
+    let token = cache_session(&app, session);
+    HttpResponse::Found()
+        .cookie(
+            Cookie::build("token", token.as_str())
+                .path("/")
+                .http_only(true)
+                .finish(),
+        )
+        .cookie(oidc_state_cookie("", 0))
+        .insert_header((header::LOCATION, login_state.redirect_url))
+        .finish()
+}
+
 async fn login_limit(
     app: &Data<Arc<AppShareData>>,
     limit_key: &Arc<String>,
@@ -18,6 +18,7 @@ use crate::raft::cache::model::{CacheKey, CacheType, CacheValue};
 use crate::raft::cache::{CacheManagerReq, CacheManagerResult, CacheUserChangeReq};
 use crate::user::model::UserDto;
 use crate::user::permission::UserRole;
+use crate::user::role::match_url_by_custom_roles;
 use crate::user::{UserManagerReq, UserManagerResult};
 
 lazy_static::lazy_static! {
@@ -25,6 +26,16 @@ lazy_static::lazy_static! {
         "/rnacos/p/login", "/rnacos/404",
         "/rnacos/api/console/login/login", "/rnacos/api/console/login/captcha",
         "/rnacos/api/console/v2/login/login", "/rnacos/api/console/v2/login/captcha",
+        "/rnacos/api/console/v2/login/oidc/config", "/rnacos/api/console/v2/login/oidc/authorize",
+        "/rnacos/api/console/v2/login/oidc/callback",
+    ];
+    ///
+    /// 需要修改密码时仍允许访问的接口
+    pub static ref NEED_CHANGE_PASSWORD_ALLOW_PATH: Vec<&'static str> = vec![
+        "/rnacos/api/console/login/logout", "/rnacos/api/console/user/info",
+        "/rnacos/api/console/user/web_resources", "/rnacos/api/console/user/reset_password",
+        "/rnacos/api/console/v2/login/logout", "/rnacos/api/console/v2/user/info",
+        "/rnacos/api/console/v2/user/web_resources", "/rnacos/api/console/v2/user/reset_password",
     ];
     pub static ref STATIC_FILE_PATH: Regex= Regex::new(r"(?i).*\.(js|css|png|jpg|jpeg|bmp|svg)").unwrap();
     pub static ref API_PATH: Regex = Regex::new(r"(?i)/(api|nacos)/.*").unwrap();
@@ -101,6 +112,7 @@ where
         Box::pin(async move {
             let mut is_login = true;
             let mut user_has_permission = true;
+            let mut need_change_password = false;
             let path = request.path();
             let method = request.method().as_str();
             if is_check_path {
@@ -110,7 +122,17 @@ where
                     get_user_session(&app_share_data, token.clone()).await
                 {
                     user_has_permission =
-                        UserRole::match_url_by_roles(&session.roles, path, method);
+                        UserRole::match_url_by_roles(&session.roles, path, method)
+                            || match_url_by_custom_roles(
+                                &app_share_data.role_manager,
+                                &session.roles,
+                                path,
+                                method,
+                            )
+                            .await;
+                    need_change_password = session.need_change_password
+                        && !is_page
+                        && !NEED_CHANGE_PASSWORD_ALLOW_PATH.contains(&path);
                     request.extensions_mut().insert(session);
                     true
                 } else {
@@ -119,7 +141,18 @@ where
             }
             //log::info!("token: {}|{}|{}|{}|{}|{}",&token,is_page,is_check_path,is_login,request.path(),request.query_string());
             if is_login {
-                if user_has_permission {
+                if need_change_password {
+                    let response = HttpResponse::Ok()
+                        .insert_header(("Need-Change-Password", "1"))
+                        .json(ApiResultOld::<()>::error(
+                            "PASSWORD_NEED_CHANGE".to_owned(),
+                            None,
+                        ))
+                        .map_into_right_body();
+                    let (http_request, _pl) = request.into_parts();
+                    let res = ServiceResponse::new(http_request, response);
+                    Ok(res)
+                } else if user_has_permission {
                     let res = service.call(request);
                     // forwarded responses map to "left" body
                     res.await.map(ServiceResponse::map_into_left_body)
@@ -221,7 +254,10 @@ fn build_user_session(user: UserDto) -> Arc<UserSession> {
         nickname: user.nickname,
         roles: user.roles.unwrap_or_default(),
         namespace_privilege: user.namespace_privilege,
+        config_group_privilege: user.config_group_privilege,
+        service_group_privilege: user.service_group_privilege,
         extend_infos: user.extend_info.unwrap_or_default(),
         refresh_time: now_second_i32() as u32,
+        need_change_password: user.need_change_password.unwrap_or_default(),
     })
 }
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use crate::audit::model::AuditLogQueryParam;
+use crate::common::string_utils::StringUtils;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogQueryRequest {
+    pub page_no: Option<usize>,
+    pub page_size: Option<usize>,
+    pub module: Option<String>,
+    pub action: Option<String>,
+    pub op_user: Option<String>,
+    pub client_ip: Option<String>,
+    pub resource: Option<String>,
+    /// 开始时间,时间戳毫秒
+    pub start_time: Option<i64>,
+    /// 结束时间,时间戳毫秒
+    pub end_time: Option<i64>,
+}
+
+impl AuditLogQueryRequest {
+    pub fn to_param(self) -> AuditLogQueryParam {
+        let limit = self.page_size.unwrap_or(20);
+        let offset = (self.page_no.unwrap_or(1).max(1) - 1) * limit;
+        AuditLogQueryParam {
+            module: StringUtils::map_not_empty(self.module),
+            action: StringUtils::map_not_empty(self.action),
+            op_user: StringUtils::map_not_empty(self.op_user),
+            client_ip: StringUtils::map_not_empty(self.client_ip),
+            resource: StringUtils::map_not_empty(self.resource),
+            start_time: self.start_time,
+            end_time: self.end_time,
+            offset,
+            limit,
+        }
+    }
+}
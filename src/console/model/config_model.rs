@@ -1,11 +1,16 @@
+use crate::common::string_utils::StringUtils;
+use crate::config::config_diff::ConfigDiffSource;
+use crate::config::config_gray::ConfigGrayRule;
 use crate::config::config_index::ConfigQueryParam;
 use crate::config::core::{ConfigInfoDto, ConfigKey};
 use crate::config::dal::ConfigHistoryParam;
+use crate::config::webhook::model::WebhookDeliveryQueryParam;
 use crate::config::ConfigUtils;
-use crate::user_namespace_privilege;
+use crate::{user_config_group_privilege, user_namespace_privilege};
 use actix_http::HttpMessage;
 use actix_web::HttpRequest;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -24,6 +29,7 @@ pub struct OpsConfigQueryListRequest {
     pub data_param: Option<String>,
     pub group: Option<String>,
     pub data_id: Option<String>,
+    pub tags: Option<String>,
 }
 
 impl OpsConfigQueryListRequest {
@@ -31,12 +37,17 @@ impl OpsConfigQueryListRequest {
         let limit = self.page_size.unwrap_or(0xffff_ffff);
         let offset = (self.page_no.unwrap_or(1) - 1) * limit;
         let namespace_privilege = user_namespace_privilege!(req);
+        let group_privilege = user_config_group_privilege!(req);
         let mut param = ConfigQueryParam {
             limit,
             offset,
             like_group: self.group_param,
             like_data_id: self.data_param,
+            tags: self
+                .tags
+                .map(|v| ConfigUtils::split_tags(&v).map(|e| e.to_owned()).collect()),
             namespace_privilege,
+            group_privilege,
             ..Default::default()
         };
         if let Some(tenant) = self.tenant {
@@ -91,6 +102,10 @@ pub struct ConfigParams {
     pub content: Option<Arc<String>>,
     pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    /// 标签,多个标签用逗号分隔
+    pub tags: Option<String>,
+    /// 为true时跳过按配置类型的内容校验
+    pub force: Option<bool>,
 }
 
 impl ConfigParams {
@@ -104,6 +119,97 @@ impl ConfigParams {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigRollbackParams {
+    pub data_id: Arc<String>,
+    pub group: Option<Arc<String>>,
+    pub tenant: Option<String>,
+    pub history_id: u64,
+}
+
+impl ConfigRollbackParams {
+    pub fn to_key(&self) -> ConfigKey {
+        let group = self
+            .group
+            .clone()
+            .unwrap_or(Arc::new("DEFAULT_GROUP".to_owned()));
+        let tenant = ConfigUtils::default_tenant(self.tenant.clone().unwrap_or_default());
+        ConfigKey::new_by_arc(self.data_id.clone(), group, Arc::new(tenant))
+    }
+}
+
+///
+/// 配置对比参数
+/// historyId为空时取当前正式配置;targetTenant为空时与源配置同一命名空间
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiffParams {
+    pub data_id: Arc<String>,
+    pub group: Option<Arc<String>>,
+    pub tenant: Option<String>,
+    pub history_id: Option<u64>,
+    pub target_tenant: Option<String>,
+    pub target_history_id: Option<u64>,
+}
+
+impl ConfigDiffParams {
+    pub fn to_source(&self) -> ConfigDiffSource {
+        ConfigDiffSource::new(self.build_key(self.tenant.clone()), self.history_id)
+    }
+
+    pub fn to_target(&self) -> ConfigDiffSource {
+        let tenant = self.target_tenant.clone().or_else(|| self.tenant.clone());
+        ConfigDiffSource::new(self.build_key(tenant), self.target_history_id)
+    }
+
+    fn build_key(&self, tenant: Option<String>) -> ConfigKey {
+        let group = self
+            .group
+            .clone()
+            .unwrap_or(Arc::new("DEFAULT_GROUP".to_owned()));
+        let tenant = ConfigUtils::default_tenant(tenant.unwrap_or_default());
+        ConfigKey::new_by_arc(self.data_id.clone(), group, Arc::new(tenant))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigGrayParams {
+    pub data_id: Arc<String>,
+    pub group: Option<Arc<String>>,
+    pub tenant: Option<String>,
+    pub content: Option<Arc<String>>,
+    pub client_ips: Option<Vec<String>>,
+    pub labels: Option<HashMap<String, String>>,
+}
+
+impl ConfigGrayParams {
+    pub fn to_key(&self) -> ConfigKey {
+        let group = self
+            .group
+            .clone()
+            .unwrap_or(Arc::new("DEFAULT_GROUP".to_owned()));
+        let tenant = ConfigUtils::default_tenant(self.tenant.clone().unwrap_or_default());
+        ConfigKey::new_by_arc(self.data_id.clone(), group, Arc::new(tenant))
+    }
+
+    pub fn to_rule(&self) -> ConfigGrayRule {
+        let client_ips = self
+            .client_ips
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| e.trim().to_owned())
+            .filter(|e| !e.is_empty())
+            .collect();
+        ConfigGrayRule {
+            client_ips,
+            labels: self.labels.clone().unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigInfo {
@@ -111,4 +217,33 @@ pub struct ConfigInfo {
     pub md5: Option<Arc<String>>,
     pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    pub tags: Option<Arc<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWebhookIdParam {
+    pub id: Arc<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryQueryRequest {
+    pub page_no: Option<usize>,
+    pub page_size: Option<usize>,
+    pub webhook_id: Option<String>,
+    pub status: Option<String>,
+}
+
+impl WebhookDeliveryQueryRequest {
+    pub fn to_param(self) -> WebhookDeliveryQueryParam {
+        let limit = self.page_size.unwrap_or(20);
+        let offset = (self.page_no.unwrap_or(1).max(1) - 1) * limit;
+        WebhookDeliveryQueryParam {
+            webhook_id: StringUtils::map_not_empty(self.webhook_id),
+            status: StringUtils::map_not_empty(self.status),
+            offset,
+            limit,
+        }
+    }
 }
@@ -13,4 +13,5 @@ pub struct LoginParam {
 #[serde(rename_all = "camelCase")]
 pub struct LoginToken {
     pub token: String,
+    pub need_change_password: bool,
 }
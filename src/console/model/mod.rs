@@ -1,3 +1,4 @@
+pub mod audit_model;
 pub mod cluster_model;
 pub mod config_model;
 pub mod login_model;
@@ -10,8 +10,8 @@ use crate::naming::{
     model::{Instance, ServiceKey},
     NamingUtils,
 };
-use crate::user_namespace_privilege;
 use crate::utils::get_bool_from_string;
+use crate::{user_namespace_privilege, user_service_group_privilege};
 
 /*
 #[derive(Debug,Serialize,Deserialize,Default)]
@@ -40,10 +40,12 @@ impl ServiceQueryListRequest {
         let limit = self.page_size.unwrap_or(0xffff_ffff);
         let offset = (self.page_no.unwrap_or(1) - 1) * limit;
         let namespace_privilege = user_namespace_privilege!(req);
+        let group_privilege = user_service_group_privilege!(req);
         let mut param = ServiceQueryParam {
             limit,
             offset,
             namespace_privilege,
+            group_privilege,
             ..Default::default()
         };
         if let Some(namespace_id) = self.namespace_id {
@@ -74,6 +76,7 @@ pub struct ServiceDto {
     pub trigger_flag: Option<bool>,
     pub metadata: Option<String>,
     pub protect_threshold: Option<f32>,
+    pub selector: Option<Arc<String>>,
 }
 
 impl From<ServiceInfoDto> for ServiceDto {
@@ -91,6 +94,7 @@ impl From<ServiceInfoDto> for ServiceDto {
             trigger_flag: Some(value.trigger_flag),
             metadata,
             protect_threshold: value.protect_threshold,
+            selector: value.selector,
         }
     }
 }
@@ -141,6 +145,7 @@ pub struct ServiceParam {
     pub group_name: Option<String>,
     pub metadata: Option<String>,
     pub protect_threshold: Option<f32>,
+    pub selector: Option<String>,
 }
 
 impl ServiceParam {
@@ -155,6 +160,26 @@ impl ServiceParam {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceSubscriberPushParam {
+    pub service_name: Arc<String>,
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    /// 只返回持有过期实例列表的订阅者
+    pub only_stale: Option<bool>,
+}
+
+impl ServiceSubscriberPushParam {
+    pub fn to_key(&self) -> ServiceKey {
+        ServiceKey::new(
+            &NamingUtils::default_namespace(self.namespace_id.clone().unwrap_or_default()),
+            &NamingUtils::default_group(self.group_name.clone().unwrap_or_default()),
+            &self.service_name,
+        )
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ServiceInfo {
@@ -220,3 +245,11 @@ impl InstanceParams {
         Ok(instance)
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckQueryRequest {
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: Option<String>,
+}
@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use crate::common::model::privilege::PrivilegeGroupOptionParam;
-use crate::user::{model::UserDto, permission::UserRoleHelper};
+use crate::user::{
+    model::{UserDto, UserGroupPrivilegeParam},
+    permission::UserRoleHelper,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -29,9 +32,18 @@ pub struct UpdateUserInfoParam {
     pub enable: Option<bool>,
     pub roles: Option<String>,
     pub namespace_privilege_param: Option<PrivilegeGroupOptionParam<Arc<String>>>,
+    pub config_group_privilege_param: Option<PrivilegeGroupOptionParam<Arc<String>>>,
+    pub service_group_privilege_param: Option<PrivilegeGroupOptionParam<Arc<String>>>,
 }
 
 impl UpdateUserInfoParam {
+    pub fn get_group_privilege_param(&self) -> UserGroupPrivilegeParam {
+        UserGroupPrivilegeParam {
+            config_group: self.config_group_privilege_param.clone(),
+            service_group: self.service_group_privilege_param.clone(),
+        }
+    }
+
     pub fn get_role_vec(&self) -> Option<Vec<Arc<String>>> {
         if let Some(roles) = self.roles.as_ref() {
             if roles.is_empty() {
@@ -74,3 +86,15 @@ impl UserPageParams {
         (limit, offset)
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleCodeParam {
+    pub code: Arc<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessKeyIdParam {
+    pub key_id: Arc<String>,
+}
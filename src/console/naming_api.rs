@@ -9,7 +9,7 @@ use super::model::{
     PageResult,
 };
 use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
-use crate::user_namespace_privilege;
+use crate::{user_namespace_privilege, user_service_group_privilege};
 
 pub async fn query_ops_instances_list(
     req: HttpRequest,
@@ -25,6 +25,12 @@ pub async fn query_ops_instances_list(
                     &key.namespace_id
                 ));
             }
+            if !user_service_group_privilege!(req).check_permission(&key.group_name) {
+                return HttpResponse::Unauthorized().body(format!(
+                    "user no such group permission: {}",
+                    &key.group_name
+                ));
+            }
             match naming_addr.send(NamingCmd::QueryAllInstanceList(key)).await {
                 Ok(res) => match res as anyhow::Result<NamingResult> {
                     Ok(result) => match result {
@@ -56,6 +56,9 @@ pub async fn import_transfer_file(
     if let Some(v) = req.headers().get("import-cache") {
         param.cache = String::from_utf8_lossy(v.as_bytes()).as_ref() == "1";
     };
+    if let Some(v) = req.headers().get("import-naming") {
+        param.naming = String::from_utf8_lossy(v.as_bytes()).as_ref() == "1";
+    };
     for mut f in form.files {
         let mut data = Vec::new();
         f.file.read_to_end(&mut data).unwrap();
@@ -15,7 +15,16 @@ use crate::{
         constant::EMPTY_STR,
         model::{ApiResult, PageResultOld, UserSession},
     },
-    user::{model::UserDto, permission::UserRole, UserManagerReq, UserManagerResult},
+    raft::cache::{
+        model::{CacheKey, CacheType},
+        CacheUserChangeReq,
+    },
+    user::{
+        model::UserDto,
+        permission::UserRole,
+        role::{RoleReq, RoleResult},
+        UserManagerReq, UserManagerResult,
+    },
 };
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -43,11 +52,26 @@ pub async fn get_user_info(req: HttpRequest) -> actix_web::Result<impl Responder
 ///
 /// 获取用户权限资源列表
 /// 这里把取不到UserSession当成旧控制台，后继可以考虑单独实现一个接口
-pub async fn get_user_web_resources(req: HttpRequest) -> actix_web::Result<impl Responder> {
-    if let Some(session) = req.extensions().get::<Arc<UserSession>>() {
-        let resources = UserRole::get_web_resources_by_roles(
+pub async fn get_user_web_resources(
+    req: HttpRequest,
+    app: Data<Arc<AppShareData>>,
+) -> actix_web::Result<impl Responder> {
+    let session = req.extensions().get::<Arc<UserSession>>().cloned();
+    if let Some(session) = session {
+        let mut resources = UserRole::get_web_resources_by_roles(
             session.roles.iter().map(|e| e.as_str()).collect(),
         );
+        if let Ok(Ok(RoleResult::WebResources(custom_resources))) = app
+            .role_manager
+            .send(RoleReq::QueryWebResources(session.roles.clone()))
+            .await
+        {
+            for item in custom_resources {
+                if !resources.contains(&item) {
+                    resources.push(item);
+                }
+            }
+        }
         let data = UserPermissions {
             resources,
             from: EMPTY_STR,
@@ -98,8 +122,10 @@ pub async fn reset_password(
                             ..Default::default()
                         },
                         namespace_privilege_param: None,
+                        group_privilege_param: Default::default(),
                     };
                     if let Ok(Ok(_r)) = app.user_manager.send(msg).await {
+                        clear_session_need_change_password(&app, &req);
                         return Ok(HttpResponse::Ok().json(ApiResult::success(Some(true))));
                     }
                 }
@@ -115,6 +141,29 @@ pub async fn reset_password(
     Ok(HttpResponse::Ok().json(ApiResult::<()>::error("SYSTEM_ERROR".to_owned(), None)))
 }
 
+///
+/// 修改密码后当前登录会话不再限制访问,不用等待会话按权限变更时间刷新
+pub(crate) fn clear_session_need_change_password(app: &Data<Arc<AppShareData>>, req: &HttpRequest) {
+    let session = match req.extensions().get::<Arc<UserSession>>() {
+        Some(session) if session.need_change_password => session.clone(),
+        _ => return,
+    };
+    let token = if let Some(ck) = req.cookie("token") {
+        ck.value().to_owned()
+    } else if let Some(v) = req.headers().get("Token") {
+        v.to_str().unwrap_or_default().to_owned()
+    } else {
+        return;
+    };
+    let mut new_session = session.as_ref().clone();
+    new_session.need_change_password = false;
+    app.cache_manager
+        .do_send(CacheUserChangeReq::UpdateUserSession {
+            key: CacheKey::new(CacheType::UserSession, Arc::new(token)),
+            session: Arc::new(new_session),
+        });
+}
+
 pub async fn get_user_page_list(
     app: Data<Arc<AppShareData>>,
     web::Query(param): web::Query<UserPageParams>,
@@ -142,6 +191,7 @@ pub async fn add_user(
     web::Form(user_param): web::Form<UpdateUserInfoParam>,
 ) -> actix_web::Result<impl Responder> {
     let namespace_privilege_param = user_param.namespace_privilege_param.clone();
+    let group_privilege_param = user_param.get_group_privilege_param();
     let user: UserDto = user_param.into();
     if user.roles.is_none() {
         return Ok(HttpResponse::Ok().json(ApiResult::<()>::error(
@@ -158,6 +208,7 @@ pub async fn add_user(
             ..user
         },
         namespace_privilege_param,
+        group_privilege_param,
     };
     app.user_manager.send(msg).await.ok();
     Ok(HttpResponse::Ok().json(ApiResult::success(Some(true))))
@@ -168,6 +219,7 @@ pub async fn update_user(
     web::Form(user_param): web::Form<UpdateUserInfoParam>,
 ) -> actix_web::Result<impl Responder> {
     let namespace_privilege_param = user_param.namespace_privilege_param.clone();
+    let group_privilege_param = user_param.get_group_privilege_param();
     let user: UserDto = user_param.into();
     let msg = UserManagerReq::UpdateUser {
         user: UserDto {
@@ -176,6 +228,7 @@ pub async fn update_user(
             ..user
         },
         namespace_privilege_param,
+        group_privilege_param,
     };
     app.user_manager.send(msg).await.ok();
     Ok(HttpResponse::Ok().json(ApiResult::success(Some(true))))
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use crate::audit::model::{
+    AUDIT_ACTION_ADD, AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_USER,
+};
+use crate::audit::AuditLogUtils;
+use crate::common::appdata::AppShareData;
+use crate::common::model::ApiResult;
+use crate::console::model::user_model::AccessKeyIdParam;
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::user::access_key::{AccessKeyDto, AccessKeyReq, AccessKeyResult};
+
+fn access_key_resource(key_id: &str) -> String {
+    format!("access_key/{}", key_id)
+}
+
+fn error_response(err: String) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        ERROR_CODE_SYSTEM_ERROR.to_string(),
+        Some(err),
+    ))
+}
+
+pub async fn query_access_key_list(appdata: Data<Arc<AppShareData>>) -> impl Responder {
+    match appdata
+        .access_key_manager
+        .send(AccessKeyReq::QueryList)
+        .await
+    {
+        Ok(Ok(AccessKeyResult::AccessKeyList(list))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+        _ => error_response("unexpected result".to_owned()),
+    }
+}
+
+///
+/// 创建访问密钥,secret只在创建结果中返回一次
+pub async fn add_access_key(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(mut param): web::Json<AccessKeyDto>,
+) -> impl Responder {
+    param.creator = Some(AuditLogUtils::get_op_user(&req).to_string());
+    set_access_key(
+        req,
+        appdata,
+        AccessKeyReq::Create(Box::new(param)),
+        AUDIT_ACTION_ADD,
+    )
+    .await
+}
+
+pub async fn update_access_key(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<AccessKeyDto>,
+) -> impl Responder {
+    set_access_key(
+        req,
+        appdata,
+        AccessKeyReq::Update(Box::new(param)),
+        AUDIT_ACTION_UPDATE,
+    )
+    .await
+}
+
+async fn set_access_key(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    access_key_req: AccessKeyReq,
+    action: &str,
+) -> HttpResponse {
+    let result = appdata.access_key_manager.send(access_key_req).await;
+    let (resource, success, resp) = match result {
+        Ok(Ok(AccessKeyResult::AccessKey(v))) => (
+            access_key_resource(v.key_id.as_ref().map(|e| e.as_str()).unwrap_or_default()),
+            true,
+            HttpResponse::Ok().json(ApiResult::success(Some(v))),
+        ),
+        Ok(Err(err)) => (
+            access_key_resource(""),
+            false,
+            error_response(err.to_string()),
+        ),
+        Err(err) => (
+            access_key_resource(""),
+            false,
+            error_response(err.to_string()),
+        ),
+        _ => (
+            access_key_resource(""),
+            false,
+            error_response("unexpected result".to_owned()),
+        ),
+    };
+    AuditLogUtils::record(&appdata, &req, AUDIT_MODULE_USER, action, resource, success);
+    resp
+}
+
+///
+/// 删除访问密钥,删除后立即失效
+pub async fn remove_access_key(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<AccessKeyIdParam>,
+) -> impl Responder {
+    let resource = access_key_resource(&param.key_id);
+    let result = appdata
+        .access_key_manager
+        .send(AccessKeyReq::Remove(param.key_id))
+        .await;
+    let success = matches!(result, Ok(Ok(_)));
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_USER,
+        AUDIT_ACTION_DELETE,
+        resource,
+        success,
+    );
+    match result {
+        Ok(Ok(_)) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+    }
+}
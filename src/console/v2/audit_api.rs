@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse, Responder};
+
+use crate::audit::{AuditManagerReq, AuditManagerResult};
+use crate::common::appdata::AppShareData;
+use crate::common::model::{ApiResult, PageResult};
+use crate::console::model::audit_model::AuditLogQueryRequest;
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+
+pub async fn query_audit_log_page(
+    request: web::Query<AuditLogQueryRequest>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = request.0.to_param();
+    match appdata
+        .audit_manager
+        .send(AuditManagerReq::QueryPage(Box::new(param)))
+        .await
+    {
+        Ok(Ok(AuditManagerResult::PageResult(total_count, list))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(PageResult { total_count, list })))
+        }
+        Ok(Err(err)) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+        _ => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            None,
+        )),
+    }
+}
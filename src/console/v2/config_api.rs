@@ -1,11 +1,25 @@
+use crate::audit::model::{
+    AUDIT_ACTION_DELETE, AUDIT_ACTION_GRAY_PROMOTE, AUDIT_ACTION_GRAY_PUBLISH,
+    AUDIT_ACTION_GRAY_REMOVE, AUDIT_ACTION_PUBLISH, AUDIT_ACTION_ROLLBACK, AUDIT_MODULE_CONFIG,
+};
+use crate::audit::AuditLogUtils;
 use crate::common::appdata::AppShareData;
+use crate::common::error_code::CONFIG_CONTENT_INVALID;
 use crate::common::model::{ApiResult, PageResult, UserSession};
+use crate::config::config_type::ConfigContentInvalidError;
 use crate::config::core::{ConfigActor, ConfigCmd, ConfigResult};
+use crate::config::ConfigUtils;
 pub use crate::console::config_api::{download_config, import_config};
-use crate::console::model::config_model::{ConfigInfo, ConfigParams, OpsConfigQueryListRequest};
+use crate::console::model::config_model::{
+    ConfigDiffParams, ConfigGrayParams, ConfigInfo, ConfigParams, ConfigRollbackParams,
+    OpsConfigQueryListRequest,
+};
 use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
 use crate::raft::cluster::model::{DelConfigReq, SetConfigReq};
-use crate::{user_namespace_privilege, user_no_namespace_permission};
+use crate::{
+    user_config_group_privilege, user_namespace_privilege, user_no_group_permission,
+    user_no_namespace_permission,
+};
 use actix::Addr;
 use actix_web::web::Data;
 use actix_web::HttpMessage;
@@ -64,6 +78,10 @@ pub async fn query_history_config_page(
     {
         user_no_namespace_permission!(&param.tenant);
     }
+    let group = param.group.as_deref().unwrap_or_default();
+    if !user_config_group_privilege!(req).check_permission(group) {
+        user_no_group_permission!(&param.group);
+    }
     let cmd = ConfigCmd::QueryHistoryPageInfo(Box::new(param));
     match config_addr.send(cmd).await {
         Ok(res) => {
@@ -84,6 +102,79 @@ pub async fn query_history_config_page(
     }
 }
 
+pub async fn rollback_config(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigRollbackParams>,
+) -> impl Responder {
+    let op_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|session| session.username.clone());
+    let config_key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&config_key.tenant) {
+        user_no_namespace_permission!(&config_key.tenant);
+    }
+    if !user_config_group_privilege!(req).check_permission(&config_key.group) {
+        user_no_group_permission!(&config_key.group);
+    }
+    let resource = AuditLogUtils::config_resource(&config_key);
+    let res = appdata
+        .config_route
+        .rollback_config(config_key, param.history_id, op_user)
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_CONFIG,
+        AUDIT_ACTION_ROLLBACK,
+        resource,
+        res.is_ok(),
+    );
+    match res {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(e) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        )),
+    }
+}
+
+pub async fn diff_config(
+    req: HttpRequest,
+    web::Query(param): web::Query<ConfigDiffParams>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let source = param.to_source();
+    let target = param.to_target();
+    let namespace_privilege = user_namespace_privilege!(req);
+    let group_privilege = user_config_group_privilege!(req);
+    for key in [source.get_key(), target.get_key()] {
+        if !namespace_privilege.check_permission(&key.tenant) {
+            user_no_namespace_permission!(&key.tenant);
+        }
+        if !group_privilege.check_permission(&key.group) {
+            user_no_group_permission!(&key.group);
+        }
+    }
+    match appdata
+        .config_addr
+        .send(ConfigCmd::Diff(source, target))
+        .await
+    {
+        Ok(Ok(ConfigResult::Diff(diff))) => HttpResponse::Ok().json(ApiResult::success(Some(diff))),
+        Ok(Err(e)) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        )),
+        _ => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            None,
+        )),
+    }
+}
+
 pub(crate) async fn get_config(
     req: HttpRequest,
     web::Query(param): web::Query<ConfigParams>,
@@ -94,12 +185,16 @@ pub(crate) async fn get_config(
     if !namespace_privilege.check_permission(&config_key.tenant) {
         user_no_namespace_permission!(&config_key.tenant);
     }
+    if !user_config_group_privilege!(req).check_permission(&config_key.group) {
+        user_no_group_permission!(&config_key.group);
+    }
     let cmd = ConfigCmd::GET(config_key);
     if let Ok(Ok(ConfigResult::Data {
         value: v,
         md5,
         config_type,
         desc,
+        tags,
         ..
     })) = appdata.config_addr.send(cmd).await
     {
@@ -108,6 +203,7 @@ pub(crate) async fn get_config(
             md5: Some(md5),
             config_type,
             desc,
+            tags,
         })))
     } else {
         HttpResponse::Ok().json(ApiResult::<()>::error(
@@ -133,23 +229,40 @@ pub async fn add_config(
     if !namespace_privilege.check_permission(&config_key.tenant) {
         user_no_namespace_permission!(&config_key.tenant);
     }
+    if !user_config_group_privilege!(req).check_permission(&config_key.group) {
+        user_no_group_permission!(&config_key.group);
+    }
     if let Err(e) = config_key.is_valid() {
         return HttpResponse::Ok().json(ApiResult::<()>::error(
             ERROR_CODE_SYSTEM_ERROR.to_string(),
             Some(e.to_string()),
         ));
     }
-    let mut req = SetConfigReq::new(config_key, content);
-    req.config_type = param.config_type;
-    req.desc = param.desc;
-    req.op_user = op_user;
-    if appdata.config_route.set_config(req).await.is_ok() {
-        HttpResponse::Ok().json(ApiResult::success(Some(true)))
-    } else {
-        HttpResponse::Ok().json(ApiResult::<()>::error(
+    let resource = AuditLogUtils::config_resource(&config_key);
+    let mut set_req = SetConfigReq::new(config_key, content);
+    set_req.config_type = param.config_type;
+    set_req.desc = param.desc;
+    set_req.tags = param.tags.as_ref().map(|v| ConfigUtils::format_tags(v));
+    set_req.op_user = op_user;
+    set_req.force = param.force.unwrap_or(false);
+    let res = appdata.config_route.set_config(set_req).await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_CONFIG,
+        AUDIT_ACTION_PUBLISH,
+        resource,
+        res.is_ok(),
+    );
+    match res {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) if err.is::<ConfigContentInvalidError>() => HttpResponse::Ok().json(
+            ApiResult::<()>::error(CONFIG_CONTENT_INVALID.to_string(), Some(err.to_string())),
+        ),
+        Err(_) => HttpResponse::Ok().json(ApiResult::<()>::error(
             ERROR_CODE_SYSTEM_ERROR.to_string(),
             None,
-        ))
+        )),
     }
 }
 
@@ -163,8 +276,24 @@ pub async fn remove_config(
     if !namespace_privilege.check_permission(&config_key.tenant) {
         user_no_namespace_permission!(&config_key.tenant);
     }
-    let req = DelConfigReq::new(config_key);
-    if appdata.config_route.del_config(req).await.is_ok() {
+    if !user_config_group_privilege!(req).check_permission(&config_key.group) {
+        user_no_group_permission!(&config_key.group);
+    }
+    let resource = AuditLogUtils::config_resource(&config_key);
+    let success = appdata
+        .config_route
+        .del_config(DelConfigReq::new(config_key))
+        .await
+        .is_ok();
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_CONFIG,
+        AUDIT_ACTION_DELETE,
+        resource,
+        success,
+    );
+    if success {
         HttpResponse::Ok().json(ApiResult::success(Some(true)))
     } else {
         HttpResponse::Ok().json(ApiResult::<()>::error(
@@ -173,3 +302,146 @@ pub async fn remove_config(
         ))
     }
 }
+
+pub(crate) async fn get_gray_config(
+    req: HttpRequest,
+    web::Query(param): web::Query<ConfigParams>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let config_key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&config_key.tenant) {
+        user_no_namespace_permission!(&config_key.tenant);
+    }
+    if !user_config_group_privilege!(req).check_permission(&config_key.group) {
+        user_no_group_permission!(&config_key.group);
+    }
+    let cmd = ConfigCmd::GetGray(config_key);
+    match appdata.config_addr.send(cmd).await {
+        Ok(Ok(ConfigResult::GrayInfo(info))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(*info)))
+        }
+        Ok(_) => HttpResponse::Ok().json(ApiResult::<()>::success(None)),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        )),
+    }
+}
+
+pub async fn publish_gray_config(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigGrayParams>,
+) -> impl Responder {
+    let op_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|session| session.username.clone());
+    let config_key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&config_key.tenant) {
+        user_no_namespace_permission!(&config_key.tenant);
+    }
+    if !user_config_group_privilege!(req).check_permission(&config_key.group) {
+        user_no_group_permission!(&config_key.group);
+    }
+    if let Err(e) = config_key.is_valid() {
+        return HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        ));
+    }
+    let content = param.content.clone().unwrap_or_default();
+    let resource = AuditLogUtils::config_resource(&config_key);
+    let res = appdata
+        .config_route
+        .set_gray_config(config_key, content, param.to_rule(), op_user)
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_CONFIG,
+        AUDIT_ACTION_GRAY_PUBLISH,
+        resource,
+        res.is_ok(),
+    );
+    match res {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(e) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        )),
+    }
+}
+
+pub async fn promote_gray_config(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigParams>,
+) -> impl Responder {
+    let op_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|session| session.username.clone());
+    let config_key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&config_key.tenant) {
+        user_no_namespace_permission!(&config_key.tenant);
+    }
+    if !user_config_group_privilege!(req).check_permission(&config_key.group) {
+        user_no_group_permission!(&config_key.group);
+    }
+    let resource = AuditLogUtils::config_resource(&config_key);
+    let res = appdata
+        .config_route
+        .promote_gray_config(config_key, op_user)
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_CONFIG,
+        AUDIT_ACTION_GRAY_PROMOTE,
+        resource,
+        res.is_ok(),
+    );
+    match res {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(e) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        )),
+    }
+}
+
+pub async fn abort_gray_config(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigParams>,
+) -> impl Responder {
+    let config_key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&config_key.tenant) {
+        user_no_namespace_permission!(&config_key.tenant);
+    }
+    if !user_config_group_privilege!(req).check_permission(&config_key.group) {
+        user_no_group_permission!(&config_key.group);
+    }
+    let resource = AuditLogUtils::config_resource(&config_key);
+    let res = appdata.config_route.remove_gray_config(config_key).await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_CONFIG,
+        AUDIT_ACTION_GRAY_REMOVE,
+        resource,
+        res.is_ok(),
+    );
+    match res {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(e) => HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(e.to_string()),
+        )),
+    }
+}
@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use crate::audit::model::{
+    AUDIT_ACTION_ADD, AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_CONFIG,
+};
+use crate::audit::AuditLogUtils;
+use crate::common::appdata::AppShareData;
+use crate::common::error_code::NO_NAMESPACE_PERMISSION;
+use crate::common::model::privilege::GroupPrivilegeGroup;
+use crate::common::model::{ApiResult, PageResult};
+use crate::config::webhook::model::{
+    ConfigWebhookDto, WebhookDeliveryDto, WebhookDeliveryQueryParam,
+};
+use crate::config::webhook::{ConfigWebhookReq, ConfigWebhookResult};
+use crate::config::ConfigUtils;
+use crate::console::model::config_model::{ConfigWebhookIdParam, WebhookDeliveryQueryRequest};
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::grpc::handler::NAMING_ROUTE_REQUEST;
+use crate::grpc::PayloadUtils;
+use crate::naming::cluster::model::{NamingRouteRequest, NamingRouterResponse};
+use crate::{
+    user_config_group_privilege, user_namespace_privilege, user_no_group_permission,
+    user_no_namespace_permission,
+};
+
+fn webhook_resource(id: &str) -> String {
+    format!("webhook/{}", id)
+}
+
+fn error_response(err: String) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        ERROR_CODE_SYSTEM_ERROR.to_string(),
+        Some(err),
+    ))
+}
+
+fn no_webhook_permission(id: &str) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        NO_NAMESPACE_PERMISSION.to_string(),
+        Some(format!("user no such webhook permission: {}", id)),
+    ))
+}
+
+///
+/// webhook投递不区分用户,分组规则为空或含通配符时需要全部分组的权限
+fn check_group_pattern_permission(
+    group_privilege: &GroupPrivilegeGroup,
+    group_pattern: Option<&str>,
+) -> bool {
+    match group_pattern {
+        Some(v) if !v.is_empty() && !v.contains('*') => group_privilege.check_permission(v),
+        _ => group_privilege.is_all(),
+    }
+}
+
+pub async fn query_webhook_list(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let namespace_privilege = user_namespace_privilege!(req);
+    let group_privilege = user_config_group_privilege!(req);
+    match appdata
+        .config_webhook_manager
+        .send(ConfigWebhookReq::QueryList)
+        .await
+    {
+        Ok(Ok(ConfigWebhookResult::WebhookList(list))) => {
+            let list: Vec<ConfigWebhookDto> = list
+                .into_iter()
+                .filter(|e| {
+                    namespace_privilege
+                        .check_permission(&Arc::new(e.tenant.clone().unwrap_or_default()))
+                        && check_group_pattern_permission(
+                            &group_privilege,
+                            e.group_pattern.as_deref(),
+                        )
+                })
+                .collect();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+        _ => error_response("unexpected result".to_owned()),
+    }
+}
+
+///
+/// 校验用户对已有webhook所属命名空间及分组的权限
+async fn check_webhook_privilege(
+    req: &HttpRequest,
+    appdata: &AppShareData,
+    id: &str,
+) -> anyhow::Result<bool> {
+    let namespace_privilege = user_namespace_privilege!(req);
+    let group_privilege = user_config_group_privilege!(req);
+    if let ConfigWebhookResult::WebhookList(list) = appdata
+        .config_webhook_manager
+        .send(ConfigWebhookReq::QueryList)
+        .await??
+    {
+        for item in list {
+            if item.id.as_deref().map(|e| e.as_str()) == Some(id) {
+                return Ok(namespace_privilege
+                    .check_permission(&Arc::new(item.tenant.unwrap_or_default()))
+                    && check_group_pattern_permission(
+                        &group_privilege,
+                        item.group_pattern.as_deref(),
+                    ));
+            }
+        }
+    }
+    Ok(true)
+}
+
+///
+/// 新增或更新webhook;参数中有id时为更新
+pub async fn set_webhook(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(mut param): web::Json<ConfigWebhookDto>,
+) -> impl Responder {
+    let namespace_privilege = user_namespace_privilege!(req);
+    if let Some(tenant) = param.tenant.take() {
+        let tenant = Arc::new(ConfigUtils::default_tenant(tenant));
+        if !namespace_privilege.check_permission(&tenant) {
+            user_no_namespace_permission!(&tenant);
+        }
+        param.tenant = Some(tenant.as_ref().to_owned());
+    }
+    //更新时未传分组规则则沿用原规则,由已有webhook的权限校验覆盖
+    if (param.id.is_none() || param.group_pattern.is_some())
+        && !check_group_pattern_permission(
+            &user_config_group_privilege!(req),
+            param.group_pattern.as_deref(),
+        )
+    {
+        user_no_group_permission!(&param.group_pattern);
+    }
+    let action = match &param.id {
+        Some(id) => {
+            match check_webhook_privilege(&req, &appdata, id).await {
+                Ok(true) => {}
+                Ok(false) => return no_webhook_permission(id),
+                Err(err) => return error_response(err.to_string()),
+            }
+            AUDIT_ACTION_UPDATE
+        }
+        None => AUDIT_ACTION_ADD,
+    };
+    let result = appdata
+        .config_webhook_manager
+        .send(ConfigWebhookReq::Set(Box::new(param)))
+        .await;
+    let (success, resource, resp) = match result {
+        Ok(Ok(ConfigWebhookResult::Webhook(v))) => (
+            true,
+            webhook_resource(v.id.as_deref().map(|e| e.as_str()).unwrap_or_default()),
+            HttpResponse::Ok().json(ApiResult::success(Some(v))),
+        ),
+        Ok(Err(err)) => (false, webhook_resource(""), error_response(err.to_string())),
+        Err(err) => (false, webhook_resource(""), error_response(err.to_string())),
+        _ => (
+            false,
+            webhook_resource(""),
+            error_response("unexpected result".to_owned()),
+        ),
+    };
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_CONFIG,
+        action,
+        resource,
+        success,
+    );
+    resp
+}
+
+pub async fn remove_webhook(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigWebhookIdParam>,
+) -> impl Responder {
+    match check_webhook_privilege(&req, &appdata, &param.id).await {
+        Ok(true) => {}
+        Ok(false) => return no_webhook_permission(&param.id),
+        Err(err) => return error_response(err.to_string()),
+    }
+    let resource = webhook_resource(&param.id);
+    let result = appdata
+        .config_webhook_manager
+        .send(ConfigWebhookReq::Remove(param.id))
+        .await;
+    let success = matches!(result, Ok(Ok(_)));
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_CONFIG,
+        AUDIT_ACTION_DELETE,
+        resource,
+        success,
+    );
+    match result {
+        Ok(Ok(_)) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+    }
+}
+
+pub async fn query_delivery_page(
+    request: web::Query<WebhookDeliveryQueryRequest>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = request.0.to_param();
+    match do_query_delivery_page(&appdata, param).await {
+        Ok((total_count, list)) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(PageResult { total_count, list })))
+        }
+        Err(err) => error_response(err.to_string()),
+    }
+}
+
+///
+/// 投递记录只保存在主节点内存中(重启或切换主节点后清空),非主节点转发到主节点查询
+async fn do_query_delivery_page(
+    app: &Data<Arc<AppShareData>>,
+    param: WebhookDeliveryQueryParam,
+) -> anyhow::Result<(usize, Vec<WebhookDeliveryDto>)> {
+    let leader_id = app.raft.current_leader().await;
+    match leader_id {
+        Some(leader_id) if leader_id != app.sys_config.raft_node_id => {
+            let addr = app.naming_node_manage.get_node_addr(leader_id).await?;
+            let req = NamingRouteRequest::QueryWebhookDeliveryPage(param);
+            let request = serde_json::to_string(&req).unwrap_or_default();
+            let payload = PayloadUtils::build_payload(NAMING_ROUTE_REQUEST, request);
+            let resp_payload = app
+                .cluster_sender
+                .send_request(addr.clone(), payload)
+                .await?;
+            let body_vec = resp_payload.body.unwrap_or_default().value;
+            let resp: NamingRouterResponse = serde_json::from_slice(&body_vec)?;
+            if let NamingRouterResponse::WebhookDeliveryPage(v) = resp {
+                Ok(v)
+            } else {
+                Err(anyhow::anyhow!(
+                    "query webhook delivery from leader error,node:{},addr:{}",
+                    leader_id,
+                    addr
+                ))
+            }
+        }
+        _ => {
+            if let ConfigWebhookResult::DeliveryPageResult(total_count, list) = app
+                .config_webhook_manager
+                .send(ConfigWebhookReq::QueryDeliveries(Box::new(param)))
+                .await??
+            {
+                Ok((total_count, list))
+            } else {
+                Err(anyhow::anyhow!("query webhook delivery error"))
+            }
+        }
+    }
+}
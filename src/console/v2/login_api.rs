@@ -1 +1,3 @@
-pub use crate::console::login_api::{gen_captcha, login, logout};
+pub use crate::console::login_api::{
+    gen_captcha, login, logout, oidc_authorize, oidc_callback, oidc_config,
+};
@@ -1,12 +1,21 @@
 use crate::common::model::ApiResult;
 use actix_web::HttpResponse;
 
+pub mod access_key_api;
+pub mod audit_api;
 pub mod cluster_api;
 pub mod config_api;
+pub mod config_webhook_api;
 pub mod login_api;
 pub mod metrics_api;
 pub mod namespace_api;
 pub mod naming_api;
+pub mod naming_event_api;
+pub mod naming_health_check_api;
+pub mod naming_instance_drain_api;
+pub mod naming_service_cluster_api;
+pub mod naming_subscriber_api;
+pub mod role_api;
 pub mod user_api;
 
 pub const ERROR_CODE_SYSTEM_ERROR: &str = "SYSTEM_ERROR";
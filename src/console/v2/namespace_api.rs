@@ -1,3 +1,7 @@
+use crate::audit::model::{
+    AUDIT_ACTION_ADD, AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_NAMESPACE,
+};
+use crate::audit::AuditLogUtils;
 use crate::common::appdata::AppShareData;
 use crate::common::model::ApiResult;
 use crate::common::string_utils::StringUtils;
@@ -41,7 +45,17 @@ pub async fn add_namespace(
     if !namespace_privilege.check_option_value_permission(&param.namespace_id, false) {
         user_no_namespace_permission!(&param.namespace_id);
     }
-    match NamespaceUtils::add_namespace(&app_data, param).await {
+    let resource = param.namespace_id.as_deref().cloned().unwrap_or_default();
+    let res = NamespaceUtils::add_namespace(&app_data, param).await;
+    AuditLogUtils::record(
+        &app_data,
+        &req,
+        AUDIT_MODULE_NAMESPACE,
+        AUDIT_ACTION_ADD,
+        resource,
+        res.is_ok(),
+    );
+    match res {
         Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
         Err(e) => HttpResponse::Ok().json(ApiResult::<()>::error(
             "SYSTEM_ERROR".to_string(),
@@ -59,7 +73,17 @@ pub async fn update_namespace(
     if !namespace_privilege.check_option_value_permission(&param.namespace_id, false) {
         user_no_namespace_permission!(&param.namespace_id);
     }
-    match NamespaceUtils::update_namespace(&app_data, param.0).await {
+    let resource = param.namespace_id.as_deref().cloned().unwrap_or_default();
+    let res = NamespaceUtils::update_namespace(&app_data, param.0).await;
+    AuditLogUtils::record(
+        &app_data,
+        &req,
+        AUDIT_MODULE_NAMESPACE,
+        AUDIT_ACTION_UPDATE,
+        resource,
+        res.is_ok(),
+    );
+    match res {
         Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
         Err(e) => HttpResponse::Ok().json(ApiResult::<()>::error(
             "SYSTEM_ERROR".to_string(),
@@ -77,7 +101,17 @@ pub async fn remove_namespace(
     if !namespace_privilege.check_option_value_permission(&param.namespace_id, false) {
         user_no_namespace_permission!(&param.namespace_id);
     }
-    match NamespaceUtils::remove_namespace(&app_data, param.0.namespace_id).await {
+    let resource = param.namespace_id.as_deref().cloned().unwrap_or_default();
+    let res = NamespaceUtils::remove_namespace(&app_data, param.0.namespace_id).await;
+    AuditLogUtils::record(
+        &app_data,
+        &req,
+        AUDIT_MODULE_NAMESPACE,
+        AUDIT_ACTION_DELETE,
+        resource,
+        res.is_ok(),
+    );
+    match res {
         Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
         Err(e) => HttpResponse::Ok().json(ApiResult::<()>::error(
             "SYSTEM_ERROR".to_string(),
@@ -1,3 +1,5 @@
+use crate::audit::model::{AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_NAMING};
+use crate::audit::AuditLogUtils;
 use crate::common::appdata::AppShareData;
 use crate::common::model::{ApiResult, PageResult};
 use crate::console::model::naming_model::{
@@ -9,11 +11,16 @@ use crate::grpc::PayloadUtils;
 use crate::naming::api_model::InstanceVO;
 use crate::naming::cluster::model::{NamingRouteRequest, NamingRouterResponse};
 use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
-use crate::naming::model::{InstanceUpdateTag, ServiceDetailDto};
+use crate::naming::model::{Instance, InstanceUpdateTag, ServiceDetailDto};
+use crate::naming::persistent_instance::NamingRaftReq;
+use crate::naming::selector::ServiceSelector;
 use crate::naming::service::SubscriberInfoDto;
 use crate::naming::service_index::ServiceQueryParam;
 use crate::naming::NamingUtils;
-use crate::{user_namespace_privilege, user_no_namespace_permission};
+use crate::{
+    user_namespace_privilege, user_no_group_permission, user_no_namespace_permission,
+    user_service_group_privilege,
+};
 use actix::Addr;
 use actix_web::web::Data;
 use actix_web::HttpMessage;
@@ -69,6 +76,26 @@ pub async fn add_service(
     if !namespace_privilege.check_permission(&service_key.namespace_id) {
         user_no_namespace_permission!(&service_key.namespace_id);
     }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    let resource = AuditLogUtils::service_resource(
+        &service_key.namespace_id,
+        &service_key.group_name,
+        &service_key.service_name,
+    );
+    if let Some(Err(err)) = param.selector.as_ref().map(|e| ServiceSelector::parse(e)) {
+        return HttpResponse::Ok().json(ApiResult::<()>::error(
+            ERROR_CODE_SYSTEM_ERROR.to_string(),
+            Some(err.to_string()),
+        ));
+    }
+    let detail = Some(format!(
+        "metadata: {}, protect_threshold: {:?}, selector: {}",
+        param.metadata.as_deref().unwrap_or_default(),
+        &param.protect_threshold,
+        param.selector.as_deref().unwrap_or_default()
+    ));
     let metadata = if let Some(metadata_str) = param.metadata {
         match NamingUtils::parse_metadata(&metadata_str) {
             Ok(metadata) => Some(Arc::new(metadata)),
@@ -83,25 +110,30 @@ pub async fn add_service(
         group_name: service_key.group_name,
         metadata,
         protect_threshold: param.protect_threshold,
+        selector: param.selector.map(Arc::new),
         ..Default::default()
     };
-    if let Ok(res) = appdata
-        .naming_addr
-        .send(NamingCmd::UpdateService(service_info))
-        .await
-    {
-        match res {
-            Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
-            Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
-                ERROR_CODE_SYSTEM_ERROR.to_string(),
-                Some(err.to_string()),
-            )),
-        }
-    } else {
-        HttpResponse::Ok().json(ApiResult::<()>::error(
+    let res = appdata
+        .raft_request_route
+        .request_naming(NamingRaftReq::UpdateService {
+            service: Box::new(service_info),
+        })
+        .await;
+    AuditLogUtils::record_detail(
+        &appdata,
+        &req,
+        AUDIT_MODULE_NAMING,
+        AUDIT_ACTION_UPDATE,
+        resource,
+        res.is_ok(),
+        detail,
+    );
+    match res {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
             ERROR_CODE_SYSTEM_ERROR.to_string(),
-            None,
-        ))
+            Some(err.to_string()),
+        )),
     }
 }
 
@@ -115,11 +147,44 @@ pub async fn remove_service(
     if !namespace_privilege.check_permission(&service_key.namespace_id) {
         user_no_namespace_permission!(&service_key.namespace_id);
     }
-    if let Ok(res) = appdata
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    let resource = AuditLogUtils::service_resource(
+        &service_key.namespace_id,
+        &service_key.group_name,
+        &service_key.service_name,
+    );
+    let service_info = ServiceDetailDto {
+        namespace_id: service_key.namespace_id.clone(),
+        service_name: service_key.service_name.clone(),
+        group_name: service_key.group_name.clone(),
+        ..Default::default()
+    };
+    //先在本节点校验服务下没有实例,再通过raft移除持久化的服务定义
+    let res = match appdata
         .naming_addr
         .send(NamingCmd::RemoveService(service_key))
         .await
     {
+        Ok(Ok(_)) => Ok(appdata
+            .raft_request_route
+            .request_naming(NamingRaftReq::RemoveService {
+                service: Box::new(service_info),
+            })
+            .await
+            .map(|_| NamingResult::NULL)),
+        res => res,
+    };
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_NAMING,
+        AUDIT_ACTION_DELETE,
+        resource,
+        matches!(res, Ok(Ok(_))),
+    );
+    if let Ok(res) = res {
         match res {
             Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
             Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
@@ -145,6 +210,9 @@ pub async fn query_instances_list(
     if !namespace_privilege.check_permission(&service_key.namespace_id) {
         user_no_namespace_permission!(&service_key.namespace_id);
     }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
     match appdata
         .naming_addr
         .send(NamingCmd::QueryAllInstanceList(service_key))
@@ -186,6 +254,9 @@ pub async fn get_instance(
             if !namespace_privilege.check_permission(&instance.namespace_id) {
                 user_no_namespace_permission!(&instance.namespace_id);
             }
+            if !user_service_group_privilege!(req).check_permission(&instance.group_name) {
+                user_no_group_permission!(&instance.group_name);
+            }
             match appdata.naming_addr.send(NamingCmd::Query(instance)).await {
                 Ok(res) => {
                     let result: NamingResult = res.unwrap();
@@ -242,17 +313,29 @@ pub async fn add_instance(
             if !namespace_privilege.check_permission(&instance.namespace_id) {
                 user_no_namespace_permission!(&instance.namespace_id);
             }
+            if !user_service_group_privilege!(req).check_permission(&instance.group_name) {
+                user_no_group_permission!(&instance.group_name);
+            }
             if !instance.check_valid() {
                 HttpResponse::Ok().json(ApiResult::<()>::error(
                     ERROR_CODE_SYSTEM_ERROR.to_string(),
                     Some("instance check is invalid".to_string()),
                 ))
             } else {
-                match appdata
+                let resource = build_instance_resource(&instance);
+                let res = appdata
                     .naming_route
                     .update_instance(instance, Some(update_tag))
-                    .await
-                {
+                    .await;
+                AuditLogUtils::record(
+                    &appdata,
+                    &req,
+                    AUDIT_MODULE_NAMING,
+                    AUDIT_ACTION_UPDATE,
+                    resource,
+                    res.is_ok(),
+                );
+                match res {
                     Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
                     Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
                         ERROR_CODE_SYSTEM_ERROR.to_string(),
@@ -279,13 +362,26 @@ pub async fn remove_instance(
             if !namespace_privilege.check_permission(&instance.namespace_id) {
                 user_no_namespace_permission!(&instance.namespace_id);
             }
+            if !user_service_group_privilege!(req).check_permission(&instance.group_name) {
+                user_no_group_permission!(&instance.group_name);
+            }
             if !instance.check_valid() {
                 HttpResponse::Ok().json(ApiResult::<()>::error(
                     ERROR_CODE_SYSTEM_ERROR.to_string(),
                     Some("instance check is invalid".to_string()),
                 ))
             } else {
-                match appdata.naming_route.delete_instance(instance).await {
+                let resource = build_instance_resource(&instance);
+                let res = appdata.naming_route.delete_instance(instance).await;
+                AuditLogUtils::record(
+                    &appdata,
+                    &req,
+                    AUDIT_MODULE_NAMING,
+                    AUDIT_ACTION_DELETE,
+                    resource,
+                    res.is_ok(),
+                );
+                match res {
                     Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
                     Err(err) => HttpResponse::Ok().json(ApiResult::<()>::error(
                         ERROR_CODE_SYSTEM_ERROR.to_string(),
@@ -356,3 +452,16 @@ async fn do_query_subscribers_list(
         }
     }
 }
+
+fn build_instance_resource(instance: &Instance) -> String {
+    format!(
+        "{}#{}:{}",
+        AuditLogUtils::service_resource(
+            &instance.namespace_id,
+            &instance.group_name,
+            &instance.service_name
+        ),
+        &instance.ip,
+        instance.port
+    )
+}
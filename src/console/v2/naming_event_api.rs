@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use crate::common::appdata::AppShareData;
+use crate::common::model::ApiResult;
+use crate::console::model::naming_model::ServiceParam;
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::naming::core::{NamingCmd, NamingResult};
+use crate::{
+    user_namespace_privilege, user_no_group_permission, user_no_namespace_permission,
+    user_service_group_privilege,
+};
+
+fn error_response(err: String) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        ERROR_CODE_SYSTEM_ERROR.to_string(),
+        Some(err),
+    ))
+}
+
+///
+/// 服务最近的实例变更事件(本节点视角),按时间倒序
+pub async fn query_service_event_list(
+    req: HttpRequest,
+    request: web::Query<ServiceParam>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let service_key = request.0.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&service_key.namespace_id) {
+        user_no_namespace_permission!(&service_key.namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    match appdata
+        .naming_addr
+        .send(NamingCmd::QueryEventHistory(service_key, 0))
+        .await
+    {
+        Ok(Ok(NamingResult::EventHistory(list))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+        _ => error_response("unexpected result".to_owned()),
+    }
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use crate::audit::model::{AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_NAMING};
+use crate::audit::AuditLogUtils;
+use crate::common::appdata::AppShareData;
+use crate::common::model::ApiResult;
+use crate::console::model::naming_model::HealthCheckQueryRequest;
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::naming::health_check::model::HealthCheckConfigDto;
+use crate::naming::health_check::{HealthCheckReq, HealthCheckResult};
+use crate::{
+    user_namespace_privilege, user_no_group_permission, user_no_namespace_permission,
+    user_service_group_privilege,
+};
+
+fn error_response(err: String) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        ERROR_CODE_SYSTEM_ERROR.to_string(),
+        Some(err),
+    ))
+}
+
+fn health_check_resource(param: &HealthCheckConfigDto) -> String {
+    let service_key = param.to_service_key();
+    let resource = AuditLogUtils::service_resource(
+        &service_key.namespace_id,
+        &service_key.group_name,
+        &service_key.service_name,
+    );
+    match param.cluster_name.as_ref().filter(|e| !e.is_empty()) {
+        Some(cluster_name) => format!("{}/{}", resource, cluster_name),
+        None => resource,
+    }
+}
+
+fn match_option(condition: &Option<String>, value: &str) -> bool {
+    condition
+        .as_ref()
+        .filter(|e| !e.is_empty())
+        .map(|e| e == value)
+        .unwrap_or(true)
+}
+
+pub async fn query_health_check_list(
+    req: HttpRequest,
+    request: web::Query<HealthCheckQueryRequest>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let namespace_privilege = user_namespace_privilege!(req);
+    let group_privilege = user_service_group_privilege!(req);
+    let param = request.0;
+    match appdata
+        .naming_health_check_manager
+        .send(HealthCheckReq::QueryList)
+        .await
+    {
+        Ok(Ok(HealthCheckResult::ConfigList(list))) => {
+            let list: Vec<HealthCheckConfigDto> = list
+                .into_iter()
+                .filter(|e| {
+                    let service_key = e.to_service_key();
+                    namespace_privilege.check_permission(&service_key.namespace_id)
+                        && group_privilege.check_permission(&service_key.group_name)
+                        && match_option(&param.namespace_id, &service_key.namespace_id)
+                        && match_option(&param.group_name, &service_key.group_name)
+                        && match_option(&param.service_name, &service_key.service_name)
+                })
+                .collect();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+        _ => error_response("unexpected result".to_owned()),
+    }
+}
+
+pub async fn set_health_check(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<HealthCheckConfigDto>,
+) -> impl Responder {
+    let service_key = param.to_service_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&service_key.namespace_id) {
+        user_no_namespace_permission!(&service_key.namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    let resource = health_check_resource(&param);
+    let result = appdata
+        .naming_health_check_manager
+        .send(HealthCheckReq::Set(Box::new(param)))
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_NAMING,
+        AUDIT_ACTION_UPDATE,
+        resource,
+        matches!(result, Ok(Ok(_))),
+    );
+    match result {
+        Ok(Ok(HealthCheckResult::Config(v))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(v)))
+        }
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+        _ => error_response("unexpected result".to_owned()),
+    }
+}
+
+pub async fn remove_health_check(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<HealthCheckConfigDto>,
+) -> impl Responder {
+    let service_key = param.to_service_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&service_key.namespace_id) {
+        user_no_namespace_permission!(&service_key.namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    let resource = health_check_resource(&param);
+    let result = appdata
+        .naming_health_check_manager
+        .send(HealthCheckReq::Remove(Box::new(param)))
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_NAMING,
+        AUDIT_ACTION_DELETE,
+        resource,
+        matches!(result, Ok(Ok(_))),
+    );
+    match result {
+        Ok(Ok(_)) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use crate::audit::model::{AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_NAMING};
+use crate::audit::AuditLogUtils;
+use crate::common::appdata::AppShareData;
+use crate::common::model::ApiResult;
+use crate::console::model::naming_model::ServiceParam;
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::naming::core::{NamingCmd, NamingResult};
+use crate::naming::instance_drain::{InstanceDrainDo, InstanceDrainDto};
+use crate::naming::persistent_instance::NamingRaftReq;
+use crate::now_millis_i64;
+use crate::{
+    user_namespace_privilege, user_no_group_permission, user_no_namespace_permission,
+    user_service_group_privilege,
+};
+
+fn error_response(err: String) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        ERROR_CODE_SYSTEM_ERROR.to_string(),
+        Some(err),
+    ))
+}
+
+fn drain_resource(drain: &InstanceDrainDo) -> String {
+    format!(
+        "{}/{}",
+        AuditLogUtils::service_resource(
+            &drain.namespace_id,
+            &drain.group_name,
+            &drain.service_name,
+        ),
+        drain.get_target()
+    )
+}
+
+pub async fn query_drain_list(
+    req: HttpRequest,
+    request: web::Query<ServiceParam>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let service_key = request.0.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&service_key.namespace_id) {
+        user_no_namespace_permission!(&service_key.namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    match appdata
+        .naming_addr
+        .send(NamingCmd::QueryDrainList(Some(service_key)))
+        .await
+    {
+        Ok(Ok(NamingResult::DrainList(list))) => {
+            let list: Vec<InstanceDrainDto> = list.iter().map(|e| e.as_ref().into()).collect();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+        _ => error_response("unexpected result".to_owned()),
+    }
+}
+
+///
+/// 实例摘流,匹配的实例立即对订阅者下线,可指定自动恢复时间
+pub async fn drain_instance(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<InstanceDrainDto>,
+) -> impl Responder {
+    let service_key = param.to_service_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&service_key.namespace_id) {
+        user_no_namespace_permission!(&service_key.namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    let drain = match param.to_do(AuditLogUtils::get_operator(&req), now_millis_i64()) {
+        Ok(v) => v,
+        Err(err) => return error_response(err.to_string()),
+    };
+    let resource = drain_resource(&drain);
+    let result = appdata
+        .raft_request_route
+        .request_naming(NamingRaftReq::UpdateDrain {
+            drain: Box::new(drain),
+        })
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_NAMING,
+        AUDIT_ACTION_UPDATE,
+        resource,
+        result.is_ok(),
+    );
+    match result {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => error_response(err.to_string()),
+    }
+}
+
+///
+/// 恢复摘流,按摘流时的目标(ip、端口、metadata)匹配
+pub async fn restore_instance(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<InstanceDrainDto>,
+) -> impl Responder {
+    let service_key = param.to_service_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&service_key.namespace_id) {
+        user_no_namespace_permission!(&service_key.namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    let drain = match param.to_restore_do(AuditLogUtils::get_operator(&req), now_millis_i64()) {
+        Ok(v) => v,
+        Err(err) => return error_response(err.to_string()),
+    };
+    let resource = drain_resource(&drain);
+    let result = appdata
+        .raft_request_route
+        .request_naming(NamingRaftReq::RemoveDrain {
+            drain: Box::new(drain),
+        })
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_NAMING,
+        AUDIT_ACTION_DELETE,
+        resource,
+        result.is_ok(),
+    );
+    match result {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => error_response(err.to_string()),
+    }
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use crate::audit::model::{AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_NAMING};
+use crate::audit::AuditLogUtils;
+use crate::common::appdata::AppShareData;
+use crate::common::model::ApiResult;
+use crate::console::model::naming_model::ServiceParam;
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::naming::core::{NamingCmd, NamingResult};
+use crate::naming::model::ServiceDetailDto;
+use crate::naming::persistent_instance::NamingRaftReq;
+use crate::naming::service_cluster::ServiceClusterDto;
+use crate::now_millis_i64;
+use crate::{
+    user_namespace_privilege, user_no_group_permission, user_no_namespace_permission,
+    user_service_group_privilege,
+};
+
+fn error_response(err: String) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        ERROR_CODE_SYSTEM_ERROR.to_string(),
+        Some(err),
+    ))
+}
+
+fn cluster_resource(param: &ServiceClusterDto) -> String {
+    let service_key = param.to_service_key();
+    format!(
+        "{}/{}",
+        AuditLogUtils::service_resource(
+            &service_key.namespace_id,
+            &service_key.group_name,
+            &service_key.service_name,
+        ),
+        &param.cluster_name
+    )
+}
+
+async fn query_service_detail(
+    appdata: &Data<Arc<AppShareData>>,
+    param: &ServiceParam,
+) -> anyhow::Result<Option<ServiceDetailDto>> {
+    match appdata
+        .naming_addr
+        .send(NamingCmd::QueryServiceDetail(param.to_key()))
+        .await??
+    {
+        NamingResult::ServiceDetail(detail) => Ok(detail),
+        _ => Err(anyhow::anyhow!("unexpected result")),
+    }
+}
+
+///
+/// 服务详情,包含集群配置
+pub async fn query_service_info(
+    req: HttpRequest,
+    request: web::Query<ServiceParam>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = request.0;
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&param.to_key().namespace_id) {
+        user_no_namespace_permission!(&param.to_key().namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&param.to_key().group_name) {
+        user_no_group_permission!(&param.to_key().group_name);
+    }
+    match query_service_detail(&appdata, &param).await {
+        Ok(detail) => HttpResponse::Ok().json(ApiResult::success(detail)),
+        Err(err) => error_response(err.to_string()),
+    }
+}
+
+pub async fn query_service_cluster_list(
+    req: HttpRequest,
+    request: web::Query<ServiceParam>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = request.0;
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&param.to_key().namespace_id) {
+        user_no_namespace_permission!(&param.to_key().namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&param.to_key().group_name) {
+        user_no_group_permission!(&param.to_key().group_name);
+    }
+    match query_service_detail(&appdata, &param).await {
+        Ok(detail) => {
+            let list = detail.and_then(|e| e.clusters).unwrap_or_default();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Err(err) => error_response(err.to_string()),
+    }
+}
+
+pub async fn update_service_cluster(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ServiceClusterDto>,
+) -> impl Responder {
+    let service_key = param.to_service_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&service_key.namespace_id) {
+        user_no_namespace_permission!(&service_key.namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    let mut cluster = match param.to_do() {
+        Ok(v) => v,
+        Err(err) => return error_response(err.to_string()),
+    };
+    cluster.gmt_modified = now_millis_i64();
+    let resource = cluster_resource(&param);
+    let result = appdata
+        .raft_request_route
+        .request_naming(NamingRaftReq::UpdateCluster {
+            cluster: Box::new(cluster),
+        })
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_NAMING,
+        AUDIT_ACTION_UPDATE,
+        resource,
+        result.is_ok(),
+    );
+    match result {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => error_response(err.to_string()),
+    }
+}
+
+pub async fn remove_service_cluster(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ServiceClusterDto>,
+) -> impl Responder {
+    let service_key = param.to_service_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&service_key.namespace_id) {
+        user_no_namespace_permission!(&service_key.namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    let cluster = match param.to_do() {
+        Ok(v) => v,
+        Err(err) => return error_response(err.to_string()),
+    };
+    let resource = cluster_resource(&param);
+    let result = appdata
+        .raft_request_route
+        .request_naming(NamingRaftReq::RemoveCluster {
+            cluster: Box::new(cluster),
+        })
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_NAMING,
+        AUDIT_ACTION_DELETE,
+        resource,
+        result.is_ok(),
+    );
+    match result {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => error_response(err.to_string()),
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use crate::common::appdata::AppShareData;
+use crate::common::model::ApiResult;
+use crate::console::model::naming_model::ServiceSubscriberPushParam;
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::grpc::bistream_manage::{BiStreamManageCmd, BiStreamManageResult};
+use crate::grpc::naming_push_tracker::NamingPushStatusDto;
+use crate::naming::core::{NamingCmd, NamingResult};
+use crate::naming::model::ServiceKey;
+use crate::{
+    user_namespace_privilege, user_no_group_permission, user_no_namespace_permission,
+    user_service_group_privilege,
+};
+
+fn error_response(err: String) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        ERROR_CODE_SYSTEM_ERROR.to_string(),
+        Some(err),
+    ))
+}
+
+async fn query_push_status(
+    appdata: &Data<Arc<AppShareData>>,
+    service_key: ServiceKey,
+) -> anyhow::Result<Vec<NamingPushStatusDto>> {
+    let (checksum, client_ids) = match appdata
+        .naming_addr
+        .send(NamingCmd::QuerySubscriberClients(service_key.clone()))
+        .await??
+    {
+        NamingResult::SubscriberClients(checksum, client_ids) => (checksum, client_ids),
+        _ => return Err(anyhow::anyhow!("unexpected result")),
+    };
+    match appdata
+        .bi_stream_manage
+        .send(BiStreamManageCmd::QueryNamingPushStatus(
+            service_key,
+            client_ids,
+        ))
+        .await??
+    {
+        BiStreamManageResult::NamingPushStatus(list) => Ok(list
+            .into_iter()
+            .map(|(client_id, status)| NamingPushStatusDto::new(client_id, status, checksum))
+            .collect()),
+        _ => Err(anyhow::anyhow!("unexpected result")),
+    }
+}
+
+///
+/// 服务订阅者的推送状态(本节点的连接),可只查询持有过期实例列表的订阅者
+pub async fn query_subscriber_push_list(
+    req: HttpRequest,
+    request: web::Query<ServiceSubscriberPushParam>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = request.0;
+    let service_key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&service_key.namespace_id) {
+        user_no_namespace_permission!(&service_key.namespace_id);
+    }
+    if !user_service_group_privilege!(req).check_permission(&service_key.group_name) {
+        user_no_group_permission!(&service_key.group_name);
+    }
+    match query_push_status(&appdata, service_key).await {
+        Ok(mut list) => {
+            if param.only_stale.unwrap_or(false) {
+                list.retain(|e| e.stale);
+            }
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Err(err) => error_response(err.to_string()),
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use crate::audit::model::{
+    AUDIT_ACTION_ADD, AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_USER,
+};
+use crate::audit::AuditLogUtils;
+use crate::common::appdata::AppShareData;
+use crate::common::model::ApiResult;
+use crate::console::model::user_model::RoleCodeParam;
+use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::user::permission::UserRoleHelper;
+use crate::user::role::{RoleDto, RoleReq, RoleResult};
+
+fn role_resource(code: &str) -> String {
+    format!("role/{}", code)
+}
+
+fn error_response(err: String) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        ERROR_CODE_SYSTEM_ERROR.to_string(),
+        Some(err),
+    ))
+}
+
+pub async fn query_role_list(appdata: Data<Arc<AppShareData>>) -> impl Responder {
+    match appdata.role_manager.send(RoleReq::QueryList).await {
+        Ok(Ok(RoleResult::RoleList(list))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+        _ => error_response("unexpected result".to_owned()),
+    }
+}
+
+///
+/// 自定义角色可授权的功能模块
+pub async fn query_module_list() -> impl Responder {
+    HttpResponse::Ok().json(ApiResult::success(Some(UserRoleHelper::get_module_names())))
+}
+
+pub async fn add_role(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<RoleDto>,
+) -> impl Responder {
+    set_role(
+        req,
+        appdata,
+        RoleReq::Add(Box::new(param)),
+        AUDIT_ACTION_ADD,
+    )
+    .await
+}
+
+pub async fn update_role(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<RoleDto>,
+) -> impl Responder {
+    set_role(
+        req,
+        appdata,
+        RoleReq::Update(Box::new(param)),
+        AUDIT_ACTION_UPDATE,
+    )
+    .await
+}
+
+async fn set_role(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    role_req: RoleReq,
+    action: &str,
+) -> HttpResponse {
+    let resource = match &role_req {
+        RoleReq::Add(v) | RoleReq::Update(v) => role_resource(&v.code),
+        _ => role_resource(""),
+    };
+    let result = appdata.role_manager.send(role_req).await;
+    let (success, resp) = match result {
+        Ok(Ok(RoleResult::Role(v))) => (true, HttpResponse::Ok().json(ApiResult::success(Some(v)))),
+        Ok(Err(err)) => (false, error_response(err.to_string())),
+        Err(err) => (false, error_response(err.to_string())),
+        _ => (false, error_response("unexpected result".to_owned())),
+    };
+    AuditLogUtils::record(&appdata, &req, AUDIT_MODULE_USER, action, resource, success);
+    resp
+}
+
+pub async fn remove_role(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<RoleCodeParam>,
+) -> impl Responder {
+    let resource = role_resource(&param.code);
+    let result = appdata.role_manager.send(RoleReq::Remove(param.code)).await;
+    let success = matches!(result, Ok(Ok(_)));
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_USER,
+        AUDIT_ACTION_DELETE,
+        resource,
+        success,
+    );
+    match result {
+        Ok(Ok(_)) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Ok(Err(err)) => error_response(err.to_string()),
+        Err(err) => error_response(err.to_string()),
+    }
+}
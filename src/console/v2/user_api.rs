@@ -1,15 +1,21 @@
+use crate::audit::model::{
+    AUDIT_ACTION_ADD, AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_USER,
+};
+use crate::audit::AuditLogUtils;
 use crate::common::appdata::AppShareData;
 use crate::common::model::{ApiResult, PageResult, UserSession};
 use crate::console::model::user_model::{UpdateUserInfoParam, UserPageParams};
+use crate::user::role::RoleReq;
 use crate::user::{UserManagerReq, UserManagerResult};
 use actix_http::HttpMessage;
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use std::sync::Arc;
 
-use crate::console::user_api::ResetPasswordParam;
+use crate::console::user_api::{clear_session_need_change_password, ResetPasswordParam};
 pub use crate::console::user_api::{get_user_info, get_user_web_resources};
 use crate::user::model::UserDto;
+use crate::user::password_policy::PasswordPolicyError;
 
 pub async fn reset_password(
     req: HttpRequest,
@@ -35,6 +41,7 @@ pub async fn reset_password(
         match v {
             UserManagerResult::CheckUserResult(valid, _user) => {
                 if valid {
+                    let username_resource = username.as_ref().to_owned();
                     let msg = UserManagerReq::UpdateUser {
                         user: UserDto {
                             username,
@@ -42,8 +49,24 @@ pub async fn reset_password(
                             ..Default::default()
                         },
                         namespace_privilege_param: None,
+                        group_privilege_param: Default::default(),
                     };
-                    if let Ok(Ok(_r)) = app.user_manager.send(msg).await {
+                    let res = app.user_manager.send(msg).await;
+                    let success = matches!(res, Ok(Ok(_)));
+                    AuditLogUtils::record_detail(
+                        &app,
+                        &req,
+                        AUDIT_MODULE_USER,
+                        AUDIT_ACTION_UPDATE,
+                        username_resource,
+                        success,
+                        Some("reset password".to_owned()),
+                    );
+                    if let Ok(Err(err)) = res {
+                        return Ok(build_user_error_result(err));
+                    }
+                    if success {
+                        clear_session_need_change_password(&app, &req);
                         return Ok(HttpResponse::Ok().json(ApiResult::success(Some(true))));
                     }
                 }
@@ -82,10 +105,12 @@ pub async fn get_user_page_list(
 }
 
 pub async fn add_user(
+    req: HttpRequest,
     app: Data<Arc<AppShareData>>,
     web::Json(user_param): web::Json<UpdateUserInfoParam>,
 ) -> actix_web::Result<impl Responder> {
     let namespace_privilege_param = user_param.namespace_privilege_param.clone();
+    let group_privilege_param = user_param.get_group_privilege_param();
     let user: UserDto = user_param.into();
     if user.roles.is_none() {
         return Ok(HttpResponse::Ok().json(ApiResult::<()>::error(
@@ -93,6 +118,13 @@ pub async fn add_user(
             Some("user roles is empty".to_owned()),
         )));
     }
+    if let Err(err) = check_user_roles(&app, &user).await {
+        return Ok(HttpResponse::Ok().json(ApiResult::<()>::error(
+            "USER_ROLE_IS_INVALID".to_string(),
+            Some(err.to_string()),
+        )));
+    }
+    let (resource, detail) = build_user_audit_info(&user);
     let msg = UserManagerReq::AddUser {
         user: UserDto {
             username: user.username,
@@ -102,17 +134,40 @@ pub async fn add_user(
             ..user
         },
         namespace_privilege_param,
+        group_privilege_param,
     };
-    app.user_manager.send(msg).await.ok();
+    let res = app.user_manager.send(msg).await;
+    let success = matches!(res, Ok(Ok(_)));
+    AuditLogUtils::record_detail(
+        &app,
+        &req,
+        AUDIT_MODULE_USER,
+        AUDIT_ACTION_ADD,
+        resource,
+        success,
+        detail,
+    );
+    if let Ok(Err(err)) = res {
+        return Ok(build_user_error_result(err));
+    }
     Ok(HttpResponse::Ok().json(ApiResult::success(Some(true))))
 }
 
 pub async fn update_user(
+    req: HttpRequest,
     app: Data<Arc<AppShareData>>,
     web::Json(user_param): web::Json<UpdateUserInfoParam>,
 ) -> actix_web::Result<impl Responder> {
     let namespace_privilege_param = user_param.namespace_privilege_param.clone();
+    let group_privilege_param = user_param.get_group_privilege_param();
     let user: UserDto = user_param.into();
+    if let Err(err) = check_user_roles(&app, &user).await {
+        return Ok(HttpResponse::Ok().json(ApiResult::<()>::error(
+            "USER_ROLE_IS_INVALID".to_string(),
+            Some(err.to_string()),
+        )));
+    }
+    let (resource, detail) = build_user_audit_info(&user);
     let msg = UserManagerReq::UpdateUser {
         user: UserDto {
             username: user.username,
@@ -120,18 +175,105 @@ pub async fn update_user(
             ..user
         },
         namespace_privilege_param,
+        group_privilege_param,
     };
-    app.user_manager.send(msg).await.ok();
+    let res = app.user_manager.send(msg).await;
+    let success = matches!(res, Ok(Ok(_)));
+    AuditLogUtils::record_detail(
+        &app,
+        &req,
+        AUDIT_MODULE_USER,
+        AUDIT_ACTION_UPDATE,
+        resource,
+        success,
+        detail,
+    );
+    if let Ok(Err(err)) = res {
+        return Ok(build_user_error_result(err));
+    }
     Ok(HttpResponse::Ok().json(ApiResult::success(Some(true))))
 }
 
 pub async fn remove_user(
+    req: HttpRequest,
     app: Data<Arc<AppShareData>>,
     web::Json(user): web::Json<UpdateUserInfoParam>,
 ) -> actix_web::Result<impl Responder> {
+    let resource = user.username.as_ref().to_owned();
     let msg = UserManagerReq::Remove {
         username: user.username,
     };
-    app.user_manager.send(msg).await.ok();
+    let success = matches!(app.user_manager.send(msg).await, Ok(Ok(_)));
+    AuditLogUtils::record(
+        &app,
+        &req,
+        AUDIT_MODULE_USER,
+        AUDIT_ACTION_DELETE,
+        resource,
+        success,
+    );
     Ok(HttpResponse::Ok().json(ApiResult::success(Some(true))))
 }
+
+///
+/// 解除用户因登录失败次数过多导致的锁定
+pub async fn unlock_user(
+    req: HttpRequest,
+    app: Data<Arc<AppShareData>>,
+    web::Json(user): web::Json<UpdateUserInfoParam>,
+) -> actix_web::Result<impl Responder> {
+    let resource = user.username.as_ref().to_owned();
+    let msg = UserManagerReq::Unlock {
+        username: user.username,
+    };
+    let res = app.user_manager.send(msg).await;
+    let success = matches!(res, Ok(Ok(_)));
+    AuditLogUtils::record_detail(
+        &app,
+        &req,
+        AUDIT_MODULE_USER,
+        AUDIT_ACTION_UPDATE,
+        resource,
+        success,
+        Some("unlock".to_owned()),
+    );
+    if let Ok(Err(err)) = res {
+        return Ok(build_user_error_result(err));
+    }
+    Ok(HttpResponse::Ok().json(ApiResult::success(Some(success))))
+}
+
+///
+/// 密码不满足密码策略时返回具体原因
+fn build_user_error_result(err: anyhow::Error) -> HttpResponse {
+    let code = if err.downcast_ref::<PasswordPolicyError>().is_some() {
+        "PASSWORD_POLICY_ERROR"
+    } else {
+        "SYSTEM_ERROR"
+    };
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        code.to_owned(),
+        Some(err.to_string()),
+    ))
+}
+
+///
+/// 用户角色可以是内置角色或已定义的自定义角色
+async fn check_user_roles(app: &Data<Arc<AppShareData>>, user: &UserDto) -> anyhow::Result<()> {
+    if let Some(roles) = &user.roles {
+        app.role_manager
+            .send(RoleReq::CheckRoles(roles.clone()))
+            .await??;
+    }
+    Ok(())
+}
+
+///
+/// 用户审计信息,角色变更记录在detail中
+fn build_user_audit_info(user: &UserDto) -> (String, Option<String>) {
+    let detail = user.roles.as_ref().map(|roles| {
+        let roles: Vec<&str> = roles.iter().map(|e| e.as_str()).collect();
+        format!("roles: {}", roles.join(","))
+    });
+    (user.username.as_ref().to_owned(), detail)
+}
@@ -210,6 +210,45 @@ pub struct ConfigChangeNotifyRequest {
     pub tenant: Arc<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFuzzyWatchRequest {
+    pub module: Option<String>,
+    pub request_id: Option<String>,
+    pub headers: Option<HashMap<String, String>>,
+
+    pub tenant: Option<String>,
+    pub group_pattern: Option<String>,
+    pub data_id_pattern: Option<String>,
+    /// WATCH 或 CANCEL_WATCH
+    pub watch_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFuzzyWatchResponse {
+    pub result_code: u16,
+    pub error_code: u16,
+    pub message: Option<String>,
+    pub request_id: Option<String>,
+
+    pub matched_configs: Vec<ConfigContext>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFuzzyWatchChangeNotifyRequest {
+    pub module: Option<String>,
+    pub request_id: Option<String>,
+    pub headers: HashMap<String, String>,
+
+    pub data_id: Arc<String>,
+    pub group: Arc<String>,
+    pub tenant: Arc<String>,
+    /// ADD_CONFIG, CONFIG_CHANGED, DELETE_CONFIG
+    pub change_type: String,
+}
+
 // ----- naming model -----
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -5,24 +5,32 @@ use std::{
 };
 
 use crate::{
-    config::core::{ConfigActor, ConfigCmd, ConfigKey},
+    config::{
+        config_fuzzy::ConfigChangeType,
+        core::{ConfigActor, ConfigCmd, ConfigKey},
+    },
     naming::{
         core::{NamingActor, NamingCmd},
+        filter::InstanceFilterUtils,
         model::{ServiceInfo, ServiceKey},
     },
     now_millis,
 };
 
 use super::{
-    api_model::{ConfigChangeNotifyRequest, NotifySubscriberRequest, CONFIG_MODEL, NAMING_MODEL},
+    api_model::{
+        ConfigChangeNotifyRequest, ConfigFuzzyWatchChangeNotifyRequest, NotifySubscriberRequest,
+        CONFIG_MODEL, NAMING_MODEL,
+    },
     bistream_conn::{BiStreamConn, BiStreamSenderCmd},
     handler::converter::ModelConverter,
     nacos_proto::Payload,
+    naming_push_tracker::{NamingPushStatus, NamingPushTracker},
     PayloadUtils,
 };
 use crate::common::constant::EMPTY_CLIENT_VERSION;
 use crate::common::model::ClientVersion;
-use crate::grpc::api_model::ConnectionSetupRequest;
+use crate::grpc::api_model::{BaseResponse, ConnectionSetupRequest, SUCCESS_CODE};
 use actix::prelude::*;
 use bean_factory::{bean, Inject};
 use inner_mem_cache::TimeoutSet;
@@ -31,6 +39,7 @@ pub(crate) struct ConnCacheItem {
     last_active_time: u64,
     conn: Addr<BiStreamConn>,
     pub(crate) client_version: Arc<ClientVersion>,
+    pub(crate) labels: Arc<HashMap<String, String>>,
 }
 
 impl ConnCacheItem {
@@ -39,6 +48,7 @@ impl ConnCacheItem {
             last_active_time,
             conn,
             client_version: EMPTY_CLIENT_VERSION.clone(),
+            labels: Default::default(),
         }
     }
 }
@@ -52,6 +62,7 @@ pub struct BiStreamManage {
     detection_time_out: u64,
     response_time_out: u64,
     request_id: u64,
+    naming_push_tracker: NamingPushTracker,
     config_addr: Option<Addr<ConfigActor>>,
     naming_addr: Option<Addr<NamingActor>>,
 }
@@ -77,12 +88,15 @@ impl BiStreamManage {
             .add(now + self.detection_time_out, client_id);
     }
 
-    fn active_client(&mut self, client_id: Arc<String>) -> anyhow::Result<Arc<ClientVersion>> {
+    fn active_client(&mut self, client_id: Arc<String>) -> anyhow::Result<BiStreamManageResult> {
         let now = now_millis();
         if let Some(item) = self.conn_cache.get_mut(&client_id) {
             //log::info!("active_client success client_id:{}",&client_id);
             item.last_active_time = now;
-            Ok(item.client_version.clone())
+            Ok(BiStreamManageResult::ClientInfo(
+                item.client_version.clone(),
+                item.labels.clone(),
+            ))
         } else {
             //log::info!("active_client empty client_id:{}",&client_id);
             Err(anyhow::anyhow!("Connection is unregistered."))
@@ -98,6 +112,26 @@ impl BiStreamManage {
         self.request_id.to_string()
     }
 
+    fn build_notify_naming_payload(
+        service_key: &ServiceKey,
+        service_info: ServiceInfo,
+        request_id: String,
+    ) -> Payload {
+        let request = NotifySubscriberRequest {
+            namespace: Some(service_key.namespace_id.clone()),
+            group_name: Some(service_key.group_name.clone()),
+            service_name: Some(service_key.service_name.clone()),
+            service_info: Some(ModelConverter::to_api_service_info(service_info)),
+            request_id: Some(request_id),
+            module: Some(NAMING_MODEL.to_string()),
+            ..Default::default()
+        };
+        PayloadUtils::build_payload(
+            "NotifySubscriberRequest",
+            serde_json::to_string(&request).unwrap(),
+        )
+    }
+
     fn check_active_time_set(&mut self, now: u64) {
         let keys = self.active_time_set.timeout(now);
         let mut check_keys = vec![];
@@ -140,6 +174,7 @@ impl BiStreamManage {
             log::info!("check timeout close client, size:{}", del_keys.len());
         }
         for key in &del_keys {
+            self.naming_push_tracker.remove_client(key);
             if let Some(item) = self.conn_cache.remove(key) {
                 //item.conn.do_send(BiStreamSenderCmd::Reset(self.next_request_id(),None,None));
                 item.conn.do_send(BiStreamSenderCmd::Close);
@@ -157,11 +192,43 @@ impl BiStreamManage {
         }
     }
 
+    ///
+    /// 超时未确认的服务推送按退避时间重发
+    fn check_naming_push_timeout(&mut self, now: u64) {
+        for (client_id, payload) in self.naming_push_tracker.timeout(now) {
+            if let Some(item) = self.conn_cache.get(&client_id) {
+                item.conn.do_send(BiStreamSenderCmd::Send(payload));
+            }
+        }
+    }
+
+    fn push_naming(
+        &mut self,
+        client_id: &Arc<String>,
+        service_key: &ServiceKey,
+        checksum: i64,
+        payload: Arc<Payload>,
+        request_id: Arc<String>,
+    ) {
+        if let Some(item) = self.conn_cache.get(client_id) {
+            item.conn.do_send(BiStreamSenderCmd::Send(payload.clone()));
+            self.naming_push_tracker.on_push(
+                client_id.clone(),
+                service_key.clone(),
+                checksum,
+                payload,
+                request_id,
+                now_millis(),
+            );
+        }
+    }
+
     pub fn time_out_heartbeat(&self, ctx: &mut actix::Context<Self>) {
         ctx.run_later(Duration::new(2, 0), |act, ctx| {
             let now = now_millis();
             act.check_active_time_set(now);
             act.check_response_time_set(now);
+            act.check_naming_push_timeout(now);
             act.time_out_heartbeat(ctx);
         });
     }
@@ -205,13 +272,20 @@ pub enum BiStreamManageCmd {
     AddConn(Arc<String>, BiStreamConn),
     ActiveClinet(Arc<String>),
     NotifyConfig(ConfigKey, HashSet<Arc<String>>),
+    NotifyConfigFuzzy(ConfigKey, ConfigChangeType, HashSet<Arc<String>>),
     NotifyNaming(ServiceKey, HashSet<Arc<String>>, ServiceInfo),
+    /// 订阅请求已返回实例列表(client_id,service_key,checksum)
+    NamingSubscribed(Arc<String>, ServiceKey, i64),
+    /// 取消订阅(client_id,service_key)
+    NamingUnsubscribed(Arc<String>, ServiceKey),
+    QueryNamingPushStatus(ServiceKey, Vec<Arc<String>>),
     QueryConnList,
 }
 
 pub enum BiStreamManageResult {
     ConnList(Vec<Arc<String>>),
-    ClientInfo(Arc<ClientVersion>),
+    ClientInfo(Arc<ClientVersion>, Arc<HashMap<String, String>>),
+    NamingPushStatus(Vec<(Arc<String>, Option<NamingPushStatus>)>),
     None,
 }
 
@@ -236,6 +310,20 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
                                 item.client_version =
                                     Arc::new(ClientVersion::from_string(&client_version));
                             }
+                            if let Some(labels) = request.labels {
+                                item.labels = Arc::new(labels);
+                            }
+                        }
+                    } else if t.as_str() == "NotifySubscriberResponse" {
+                        let body_vec = payload.body.unwrap_or_default().value;
+                        let response: BaseResponse = serde_json::from_slice(&body_vec)?;
+                        if let Some(request_id) = response.request_id {
+                            self.naming_push_tracker.on_ack(
+                                client_id.clone(),
+                                Arc::new(request_id),
+                                response.result_code == SUCCESS_CODE,
+                                now_millis(),
+                            );
                         }
                     }
                     self.active_client(client_id).ok();
@@ -243,6 +331,7 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
             }
             BiStreamManageCmd::ConnClose(client_id) => {
                 self.conn_cache.remove(&client_id);
+                self.naming_push_tracker.remove_client(&client_id);
                 if let Some(config_addr) = &self.config_addr {
                     config_addr.do_send(ConfigCmd::RemoveSubscribeClient(client_id.clone()))
                 }
@@ -256,8 +345,7 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
                 //println!("|AddConn|conn size: {}",self.conn_cache.len());
             }
             BiStreamManageCmd::ActiveClinet(client_id) => {
-                let client_version = self.active_client(client_id)?;
-                return Ok(BiStreamManageResult::ClientInfo(client_version));
+                return self.active_client(client_id);
             }
             BiStreamManageCmd::NotifyConfig(config_key, client_id_set) => {
                 let request = ConfigChangeNotifyRequest {
@@ -278,19 +366,18 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
                     }
                 }
             }
-            BiStreamManageCmd::NotifyNaming(service_key, client_id_set, service_info) => {
-                let service_info = ModelConverter::to_api_service_info(service_info);
-                let request = NotifySubscriberRequest {
-                    namespace: Some(service_key.namespace_id),
-                    group_name: Some(service_key.group_name),
-                    service_name: Some(service_key.service_name),
-                    service_info: Some(service_info),
+            BiStreamManageCmd::NotifyConfigFuzzy(config_key, change_type, client_id_set) => {
+                let request = ConfigFuzzyWatchChangeNotifyRequest {
+                    group: config_key.group,
+                    data_id: config_key.data_id,
+                    tenant: config_key.tenant,
+                    change_type: change_type.as_str().to_owned(),
                     request_id: Some(self.next_request_id()),
-                    module: Some(NAMING_MODEL.to_string()),
+                    module: Some(CONFIG_MODEL.to_string()),
                     ..Default::default()
                 };
                 let payload = Arc::new(PayloadUtils::build_payload(
-                    "NotifySubscriberRequest",
+                    "ConfigFuzzyWatchChangeNotifyRequest",
                     serde_json::to_string(&request).unwrap(),
                 ));
                 for item in &client_id_set {
@@ -299,6 +386,67 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
                     }
                 }
             }
+            BiStreamManageCmd::NotifyNaming(service_key, client_id_set, service_info) => {
+                let checksum = service_info.checksum;
+                if service_info.selector.is_some() {
+                    //label选择器需要按每个订阅连接的labels分别过滤
+                    for client_id in &client_id_set {
+                        let labels = match self.conn_cache.get(client_id) {
+                            Some(item) => item.labels.clone(),
+                            None => continue,
+                        };
+                        let service_info =
+                            InstanceFilterUtils::consumer_service_filter(&service_info, &labels);
+                        let request_id = Arc::new(self.next_request_id());
+                        let payload = Arc::new(Self::build_notify_naming_payload(
+                            &service_key,
+                            service_info,
+                            request_id.as_ref().to_owned(),
+                        ));
+                        self.push_naming(client_id, &service_key, checksum, payload, request_id);
+                    }
+                    return Ok(BiStreamManageResult::None);
+                }
+                let request_id = Arc::new(self.next_request_id());
+                let payload = Arc::new(Self::build_notify_naming_payload(
+                    &service_key,
+                    service_info,
+                    request_id.as_ref().to_owned(),
+                ));
+                for client_id in &client_id_set {
+                    self.push_naming(
+                        client_id,
+                        &service_key,
+                        checksum,
+                        payload.clone(),
+                        request_id.clone(),
+                    );
+                }
+            }
+            BiStreamManageCmd::NamingSubscribed(client_id, service_key, checksum) => {
+                self.naming_push_tracker.on_subscribe(
+                    client_id,
+                    service_key,
+                    checksum,
+                    now_millis(),
+                );
+            }
+            BiStreamManageCmd::NamingUnsubscribed(client_id, service_key) => {
+                self.naming_push_tracker
+                    .remove_service(&client_id, &service_key);
+            }
+            BiStreamManageCmd::QueryNamingPushStatus(service_key, client_ids) => {
+                let list = client_ids
+                    .into_iter()
+                    .map(|client_id| {
+                        let status = self
+                            .naming_push_tracker
+                            .get_status(&client_id, &service_key);
+                        (client_id, status)
+                    })
+                    .collect();
+                return Ok(BiStreamManageResult::NamingPushStatus(list));
+            }
             BiStreamManageCmd::QueryConnList => {
                 let mut list = Vec::with_capacity(self.conn_cache.len());
                 for key in self.conn_cache.keys() {
@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use crate::config::config_gray::ConfigClient;
 use crate::config::ConfigUtils;
 use crate::grpc::HandlerResult;
 use crate::{
@@ -49,7 +50,11 @@ impl PayloadHandler for ConfigChangeBatchListenRequestHandler {
             listener_items.push(ListenerItem::new(key, item.md5));
         }
         let cmd = if request.listen {
-            ConfigCmd::Subscribe(listener_items, request_meta.connection_id)
+            let client = ConfigClient::new(
+                Arc::new(request_meta.client_ip.clone()),
+                request_meta.labels.clone(),
+            );
+            ConfigCmd::Subscribe(listener_items, request_meta.connection_id, Arc::new(client))
         } else {
             ConfigCmd::RemoveSubscribe(listener_items, request_meta.connection_id)
         };
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use crate::config::config_fuzzy::{ConfigFuzzyPattern, ConfigFuzzyWatcher, FUZZY_CANCEL_WATCH};
+use crate::config::ConfigUtils;
+use crate::grpc::HandlerResult;
+use crate::{
+    common::appdata::AppShareData,
+    config::core::{ConfigCmd, ConfigResult},
+    grpc::{
+        api_model::{
+            BaseResponse, ConfigContext, ConfigFuzzyWatchRequest, ConfigFuzzyWatchResponse,
+            ERROR_CODE, SUCCESS_CODE,
+        },
+        PayloadHandler, PayloadUtils,
+    },
+};
+use async_trait::async_trait;
+
+///
+/// 按dataId/group通配规则订阅配置,新增、变更、删除匹配的配置时通过双向流推送
+pub struct ConfigFuzzyWatchRequestHandler {
+    app_data: Arc<AppShareData>,
+}
+
+impl ConfigFuzzyWatchRequestHandler {
+    pub fn new(app_data: Arc<AppShareData>) -> Self {
+        Self { app_data }
+    }
+}
+
+#[async_trait]
+impl PayloadHandler for ConfigFuzzyWatchRequestHandler {
+    async fn handle(
+        &self,
+        request_payload: crate::grpc::nacos_proto::Payload,
+        request_meta: crate::grpc::RequestMeta,
+    ) -> anyhow::Result<HandlerResult> {
+        let body_vec = request_payload.body.unwrap_or_default().value;
+        let request: ConfigFuzzyWatchRequest = serde_json::from_slice(&body_vec)?;
+        let pattern = ConfigFuzzyPattern::new(
+            request.data_id_pattern.as_deref().unwrap_or_default(),
+            request.group_pattern.as_deref().unwrap_or_default(),
+            &ConfigUtils::default_tenant(request.tenant.unwrap_or_default()),
+        );
+        let cmd = if request.watch_type.as_deref() == Some(FUZZY_CANCEL_WATCH) {
+            ConfigCmd::RemoveFuzzySubscribe(Box::new(pattern), request_meta.connection_id)
+        } else {
+            let watcher = ConfigFuzzyWatcher::new(pattern, request_meta.config_group_privilege());
+            let namespace_permission = request_meta
+                .token_session
+                .as_ref()
+                .map(|e| e.check_namespace_permission(&watcher.pattern.tenant))
+                .unwrap_or(true);
+            let message = if !namespace_permission {
+                Some(format!(
+                    "user no such namespace permission: {}",
+                    &watcher.pattern.tenant
+                ))
+            } else if !watcher.check_group_permission() {
+                Some(format!(
+                    "user no such group permission: {}",
+                    &watcher.pattern.group
+                ))
+            } else {
+                None
+            };
+            if let Some(message) = message {
+                let mut response = BaseResponse::build_error_response(403u16, message);
+                response.request_id = request.request_id;
+                return Ok(HandlerResult::success(PayloadUtils::build_payload(
+                    "ErrorResponse",
+                    serde_json::to_string(&response)?,
+                )));
+            }
+            ConfigCmd::FuzzySubscribe(Box::new(watcher), request_meta.connection_id)
+        };
+        let mut response = ConfigFuzzyWatchResponse {
+            request_id: request.request_id,
+            message: Some("".to_string()),
+            ..Default::default()
+        };
+        match self.app_data.config_addr.send(cmd).await {
+            Ok(res) => {
+                response.result_code = SUCCESS_CODE;
+                if let Ok(ConfigResult::ChangeKey(keys)) = res {
+                    for key in keys {
+                        response.matched_configs.push(ConfigContext {
+                            data_id: key.data_id,
+                            group: key.group,
+                            tenant: key.tenant,
+                        });
+                    }
+                }
+                Ok(HandlerResult::success(PayloadUtils::build_payload(
+                    "ConfigFuzzyWatchResponse",
+                    serde_json::to_string(&response)?,
+                )))
+            }
+            Err(err) => {
+                response.result_code = ERROR_CODE;
+                response.error_code = ERROR_CODE;
+                response.message = Some(err.to_string());
+                Ok(HandlerResult::success(PayloadUtils::build_payload(
+                    "ErrorResponse",
+                    serde_json::to_string(&response)?,
+                )))
+            }
+        }
+    }
+}
@@ -2,8 +2,10 @@
 
 use std::sync::Arc;
 
+use crate::audit::model::{AUDIT_ACTION_PUBLISH, AUDIT_MODULE_CONFIG};
+use crate::audit::AuditLogUtils;
 use crate::common::string_utils::StringUtils;
-use crate::config::config_type::ConfigType;
+use crate::config::config_type::{ConfigContentInvalidError, ConfigType};
 use crate::config::ConfigUtils;
 use crate::grpc::HandlerResult;
 use crate::{
@@ -34,14 +36,31 @@ impl PayloadHandler for ConfigPublishRequestHandler {
     async fn handle(
         &self,
         request_payload: crate::grpc::nacos_proto::Payload,
-        _request_meta: crate::grpc::RequestMeta,
+        request_meta: crate::grpc::RequestMeta,
     ) -> anyhow::Result<HandlerResult> {
         let body_vec = request_payload.body.unwrap_or_default().value;
         let request: ConfigPublishRequest = serde_json::from_slice(&body_vec)?;
+        if !request_meta
+            .config_group_privilege()
+            .check_permission(&request.group)
+        {
+            let mut response = BaseResponse::build_error_response(
+                403u16,
+                format!("user no such group permission: {}", &request.group),
+            );
+            response.request_id = request.request_id;
+            return Ok(HandlerResult::success(PayloadUtils::build_payload(
+                "ErrorResponse",
+                serde_json::to_string(&response)?,
+            )));
+        }
         let config_type = StringUtils::map_not_empty(request.get_addition_param("type").cloned())
             .map(|v| ConfigType::new_by_value(v.as_ref()).get_value());
         let desc =
             StringUtils::map_not_empty(request.get_addition_param("desc").cloned()).map(Arc::new);
+        let tags = request
+            .get_addition_param("config_tags")
+            .map(|v| ConfigUtils::format_tags(v));
         let mut req = SetConfigReq::new(
             ConfigKey::new(
                 &request.data_id,
@@ -52,7 +71,19 @@ impl PayloadHandler for ConfigPublishRequestHandler {
         );
         req.config_type = config_type;
         req.desc = desc;
-        match self.app_data.config_route.set_config(req).await {
+        req.tags = tags;
+        let resource = AuditLogUtils::config_resource(&req.config_key);
+        let res = self.app_data.config_route.set_config(req).await;
+        AuditLogUtils::record_grpc(
+            &self.app_data,
+            &request_meta,
+            "ConfigPublishRequest",
+            AUDIT_MODULE_CONFIG,
+            AUDIT_ACTION_PUBLISH,
+            resource,
+            res.is_ok(),
+        );
+        match res {
             Ok(_res) => {
                 //let res:ConfigResult = res.unwrap();
                 let mut response = BaseResponse::build_success_response();
@@ -63,7 +94,12 @@ impl PayloadHandler for ConfigPublishRequestHandler {
                 )))
             }
             Err(err) => {
-                let mut response = BaseResponse::build_error_response(500u16, err.to_string());
+                let error_code = if err.is::<ConfigContentInvalidError>() {
+                    400u16
+                } else {
+                    500u16
+                };
+                let mut response = BaseResponse::build_error_response(error_code, err.to_string());
                 response.request_id = request.request_id;
                 Ok(HandlerResult::success(PayloadUtils::build_payload(
                     "ErrorResponse",
@@ -3,6 +3,7 @@
 use std::sync::Arc;
 
 use crate::common::model::client_version::ClientNameType;
+use crate::config::config_gray::ConfigClient;
 use crate::config::config_type::ConfigType;
 use crate::config::ConfigUtils;
 use crate::grpc::api_model::NOT_FOUND;
@@ -41,11 +42,18 @@ impl PayloadHandler for ConfigQueryRequestHandler {
     ) -> anyhow::Result<HandlerResult> {
         let body_vec = request_payload.body.unwrap_or_default().value;
         let request: ConfigQueryRequest = serde_json::from_slice(&body_vec)?;
-        let cmd = ConfigCmd::GET(ConfigKey::new(
-            &request.data_id,
-            &request.group,
-            &ConfigUtils::default_tenant(request.tenant),
-        ));
+        let client = ConfigClient::new(
+            Arc::new(request_meta.client_ip.clone()),
+            request_meta.labels.clone(),
+        );
+        let cmd = ConfigCmd::GetByClient(
+            ConfigKey::new(
+                &request.data_id,
+                &request.group,
+                &ConfigUtils::default_tenant(request.tenant),
+            ),
+            Arc::new(client),
+        );
         let mut response = ConfigQueryResponse {
             request_id: request.request_id,
             ..Default::default()
@@ -66,6 +74,7 @@ impl PayloadHandler for ConfigQueryRequestHandler {
                         md5,
                         config_type,
                         last_modified,
+                        gray,
                         ..
                     } => {
                         //v.to_owned()
@@ -86,6 +95,7 @@ impl PayloadHandler for ConfigQueryRequestHandler {
                         }
                         response.last_modified = last_modified;
                         response.md5 = Some(md5);
+                        response.beta = gray;
                     }
                     _ => {
                         response.result_code = ERROR_CODE;
@@ -2,6 +2,8 @@
 
 use std::sync::Arc;
 
+use crate::audit::model::{AUDIT_ACTION_DELETE, AUDIT_MODULE_CONFIG};
+use crate::audit::AuditLogUtils;
 use crate::config::ConfigUtils;
 use crate::grpc::HandlerResult;
 use crate::{
@@ -32,16 +34,41 @@ impl PayloadHandler for ConfigRemoveRequestHandler {
     async fn handle(
         &self,
         request_payload: crate::grpc::nacos_proto::Payload,
-        _request_meta: crate::grpc::RequestMeta,
+        request_meta: crate::grpc::RequestMeta,
     ) -> anyhow::Result<HandlerResult> {
         let body_vec = request_payload.body.unwrap_or_default().value;
         let request: ConfigRemoveRequest = serde_json::from_slice(&body_vec)?;
+        if !request_meta
+            .config_group_privilege()
+            .check_permission(&request.group)
+        {
+            let mut response = BaseResponse::build_error_response(
+                403u16,
+                format!("user no such group permission: {}", &request.group),
+            );
+            response.request_id = request.request_id;
+            return Ok(HandlerResult::success(PayloadUtils::build_payload(
+                "ErrorResponse",
+                serde_json::to_string(&response)?,
+            )));
+        }
         let req = DelConfigReq::new(ConfigKey::new(
             &request.data_id,
             &request.group,
             &ConfigUtils::default_tenant(request.tenant),
         ));
-        match self.app_data.config_route.del_config(req).await {
+        let resource = AuditLogUtils::config_resource(&req.config_key);
+        let res = self.app_data.config_route.del_config(req).await;
+        AuditLogUtils::record_grpc(
+            &self.app_data,
+            &request_meta,
+            "ConfigRemoveRequest",
+            AUDIT_MODULE_CONFIG,
+            AUDIT_ACTION_DELETE,
+            resource,
+            res.is_ok(),
+        );
+        match res {
             Ok(_res) => {
                 let mut response = BaseResponse::build_success_response();
                 response.request_id = request.request_id;
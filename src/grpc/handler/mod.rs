@@ -4,6 +4,7 @@ use crate::common::appdata::AppShareData;
 
 use self::{
     config_change_batch_listen::ConfigChangeBatchListenRequestHandler,
+    config_fuzzy_watch::ConfigFuzzyWatchRequestHandler,
     config_publish::ConfigPublishRequestHandler, config_query::ConfigQueryRequestHandler,
     config_remove::ConfigRemoveRequestHandler, naming_batch_instance::BatchInstanceRequestHandler,
     naming_instance::InstanceRequestHandler, naming_route::NamingRouteRequestHandler,
@@ -21,8 +22,10 @@ use crate::grpc::handler::raft_append::RaftAppendRequestHandler;
 use crate::grpc::handler::raft_snapshot::RaftSnapshotRequestHandler;
 use crate::grpc::handler::raft_vote::RaftVoteRequestHandler;
 use async_trait::async_trait;
+use serde::Deserialize;
 
 pub mod config_change_batch_listen;
+pub mod config_fuzzy_watch;
 pub mod config_publish;
 pub mod config_query;
 pub mod config_remove;
@@ -53,6 +56,7 @@ pub(crate) const CONFIG_QUERY_REQUEST: &str = "ConfigQueryRequest";
 pub(crate) const CONFIG_PUBLISH_REQUEST: &str = "ConfigPublishRequest";
 pub(crate) const CONFIG_REMOVE_REQUEST: &str = "ConfigRemoveRequest";
 pub(crate) const CONFIG_BATCH_LISTEN_REQUEST: &str = "ConfigBatchListenRequest";
+pub(crate) const CONFIG_FUZZY_WATCH_REQUEST: &str = "ConfigFuzzyWatchRequest";
 
 pub(crate) const INSTANCE_REQUEST: &str = "InstanceRequest";
 pub(crate) const BATCH_INSTANCE_REQUEST: &str = "BatchInstanceRequest";
@@ -60,6 +64,12 @@ pub(crate) const SUBSCRIBE_SERVICE_REQUEST: &str = "SubscribeServiceRequest";
 pub(crate) const SERVICE_QUERY_REQUEST: &str = "ServiceQueryRequest";
 pub(crate) const SERVICE_LIST_REQUEST: &str = "ServiceListRequest";
 
+#[derive(Deserialize, Default)]
+struct RequestNamespace {
+    tenant: Option<String>,
+    namespace: Option<String>,
+}
+
 pub struct InvokerHandler {
     app: Arc<AppShareData>,
     handlers: Vec<(String, Box<dyn PayloadHandler + Send + Sync + 'static>)>,
@@ -123,6 +133,43 @@ impl InvokerHandler {
             || NAMING_ROUTE_REQUEST.eq(t)
     }
 
+    pub fn is_write_request(&self, t: &str) -> bool {
+        CONFIG_PUBLISH_REQUEST.eq(t)
+            || CONFIG_REMOVE_REQUEST.eq(t)
+            || INSTANCE_REQUEST.eq(t)
+            || BATCH_INSTANCE_REQUEST.eq(t)
+    }
+
+    ///
+    /// 访问密钥会话的只读与命名空间范围校验;监听类请求的命名空间在子项中,不做范围校验
+    fn check_access_key_scope(
+        &self,
+        t: &str,
+        request_meta: &RequestMeta,
+        request_payload: &Payload,
+    ) -> bool {
+        let session = match &request_meta.token_session {
+            Some(v) => v,
+            None => return true,
+        };
+        if session.read_only && self.is_write_request(t) {
+            return false;
+        }
+        if session.namespace_privilege.is_none()
+            || CONFIG_BATCH_LISTEN_REQUEST.eq(t)
+            || CONFIG_FUZZY_WATCH_REQUEST.eq(t)
+        {
+            return true;
+        }
+        let namespace = request_payload
+            .body
+            .as_ref()
+            .and_then(|e| serde_json::from_slice::<RequestNamespace>(&e.value).ok())
+            .and_then(|e| e.namespace.or(e.tenant))
+            .unwrap_or_default();
+        session.check_namespace_permission(&Arc::new(namespace))
+    }
+
     pub fn add_raft_handler(&mut self, app_data: &Arc<AppShareData>) {
         self.add_handler(
             RAFT_APPEND_REQUEST,
@@ -163,6 +210,10 @@ impl InvokerHandler {
             CONFIG_BATCH_LISTEN_REQUEST,
             Box::new(ConfigChangeBatchListenRequestHandler::new(app_data.clone())),
         );
+        self.add_handler(
+            CONFIG_FUZZY_WATCH_REQUEST,
+            Box::new(ConfigFuzzyWatchRequestHandler::new(app_data.clone())),
+        );
     }
 
     pub fn add_naming_handler(&mut self, app_data: &Arc<AppShareData>) {
@@ -223,6 +274,22 @@ impl PayloadHandler for InvokerHandler {
             {
                 //开启鉴权，但取不到用户会话信息
                 return Ok(HandlerResult::error(403u16, "unknown user!".to_string()));
+            } else if !self.check_access_key_scope(url, &request_meta, &request_payload) {
+                //访问密钥只读或命名空间范围校验不通过
+                return Ok(HandlerResult::error(403u16, "no permission!".to_string()));
+            } else if self.app.sys_config.grpc_tls_enable
+                && self.is_cluster_request(url)
+                && !self
+                    .app
+                    .sys_config
+                    .grpc_tls_client_auth
+                    .allow_cluster_request(request_meta.peer_cert_verified)
+            {
+                //开启tls客户端校验时,集群请求必须携带校验通过的客户端证书
+                return Ok(HandlerResult::error(
+                    403u16,
+                    "cluster request require verified client certificate".to_string(),
+                ));
             } else if !self.app.sys_config.cluster_token.is_empty()
                 && self.is_cluster_request(url)
                 && !request_meta.cluster_token_is_valid
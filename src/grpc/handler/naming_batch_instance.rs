@@ -118,12 +118,28 @@ impl PayloadHandler for BatchInstanceRequestHandler {
                 is_de_register = true;
             }
         }
+        let group_privilege = request_meta.service_group_privilege();
         let instances = Self::convert_to_instances(request, request_meta.connection_id)?;
         let mut response = InstanceResponse {
             request_id,
             message: Some("".to_string()),
             ..Default::default()
         };
+        if let Some(instance) = instances
+            .iter()
+            .find(|e| !group_privilege.check_permission(&e.group_name))
+        {
+            response.result_code = ERROR_CODE;
+            response.error_code = 403u16;
+            response.message = Some(format!(
+                "user no such group permission: {}",
+                &instance.group_name
+            ));
+            return Ok(HandlerResult::success(PayloadUtils::build_payload(
+                "ErrorResponse",
+                serde_json::to_string(&response)?,
+            )));
+        }
         for instance in instances {
             let cmd = if is_de_register {
                 NamingCmd::Delete(instance)
@@ -110,7 +110,24 @@ impl PayloadHandler for InstanceRequestHandler {
                 is_de_register = true;
             }
         }
+        let group_privilege = request_meta.service_group_privilege();
         let instance = Self::convert_to_instance(request, request_meta.connection_id)?;
+        if !group_privilege.check_permission(&instance.group_name) {
+            let response = InstanceResponse {
+                request_id,
+                result_code: ERROR_CODE,
+                error_code: 403u16,
+                message: Some(format!(
+                    "user no such group permission: {}",
+                    &instance.group_name
+                )),
+                ..Default::default()
+            };
+            return Ok(HandlerResult::success(PayloadUtils::build_payload(
+                "ErrorResponse",
+                serde_json::to_string(&response)?,
+            )));
+        }
         let cmd = if is_de_register {
             NamingCmd::Delete(instance)
         } else {
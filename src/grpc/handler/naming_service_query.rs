@@ -44,7 +44,7 @@ impl PayloadHandler for ServiceQueryRequestHandler {
     async fn handle(
         &self,
         request_payload: crate::grpc::nacos_proto::Payload,
-        _request_meta: crate::grpc::RequestMeta,
+        request_meta: crate::grpc::RequestMeta,
     ) -> anyhow::Result<HandlerResult> {
         let body_vec = request_payload.body.unwrap_or_default().value;
         let request: ServiceQueryRequest = serde_json::from_slice(&body_vec)?;
@@ -69,7 +69,8 @@ impl PayloadHandler for ServiceQueryRequestHandler {
             &NamingUtils::default_group(request.group_name.unwrap_or_default()),
             &request.service_name.unwrap_or_default(),
         );
-        let cmd = NamingCmd::QueryServiceInfo(key, cluster, true);
+        let cmd =
+            NamingCmd::QueryServiceInfo(key, cluster, true, Some(request_meta.labels.clone()));
         match self.app_data.naming_addr.send(cmd).await {
             Ok(res) => {
                 let result: NamingResult = res.unwrap();
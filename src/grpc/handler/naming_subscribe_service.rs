@@ -10,6 +10,7 @@ use crate::{
             Instance as ApiInstance, ServiceInfo as ApiServiceInfo, SubscribeServiceRequest,
             SubscribeServiceResponse, ERROR_CODE, SUCCESS_CODE,
         },
+        bistream_manage::BiStreamManageCmd,
         nacos_proto::Payload,
         PayloadHandler, PayloadUtils,
     },
@@ -94,12 +95,34 @@ impl PayloadHandler for SubscribeServiceRequestHandler {
             request_meta.connection_id.clone(),
         );
         self.app_data.naming_addr.do_send(subscribe_cmd);
-        let cmd = NamingCmd::QueryServiceInfo(key, cluster, true);
+        if !request.subscribe {
+            self.app_data
+                .bi_stream_manage
+                .do_send(BiStreamManageCmd::NamingUnsubscribed(
+                    request_meta.connection_id.clone(),
+                    key.clone(),
+                ));
+        }
+        let cmd = NamingCmd::QueryServiceInfo(
+            key.clone(),
+            cluster,
+            true,
+            Some(request_meta.labels.clone()),
+        );
         match self.app_data.naming_addr.send(cmd).await {
             Ok(res) => {
                 let result: NamingResult = res.unwrap();
                 match result {
                     NamingResult::ServiceInfo(service_info) => {
+                        if request.subscribe {
+                            self.app_data.bi_stream_manage.do_send(
+                                BiStreamManageCmd::NamingSubscribed(
+                                    request_meta.connection_id.clone(),
+                                    key.clone(),
+                                    service_info.checksum,
+                                ),
+                            );
+                        }
                         let api_service_info = self.convert_to_service_info(service_info);
                         response.service_info = Some(api_service_info);
                         response.result_code = SUCCESS_CODE;
@@ -1,4 +1,5 @@
 use self::api_model::BaseResponse;
+use crate::common::model::privilege::GroupPrivilegeGroup;
 use crate::common::model::{ClientVersion, TokenSession};
 use async_trait::async_trait;
 use std::fmt::Display;
@@ -10,16 +11,40 @@ pub mod bistream_manage;
 pub mod handler;
 pub mod metrics;
 pub mod nacos_proto;
+pub mod naming_push_tracker;
 pub mod server;
 
 #[derive(Default)]
 pub struct RequestMeta {
     pub connection_id: Arc<String>,
     pub client_ip: String,
-    pub labels: HashMap<String, String>,
+    pub labels: Arc<HashMap<String, String>>,
     pub token_session: Option<Arc<TokenSession>>,
     pub cluster_token_is_valid: bool,
     pub client_version: Arc<ClientVersion>,
+    ///
+    /// tls连接中客户端提供了CA校验通过的证书
+    pub peer_cert_verified: bool,
+}
+
+impl RequestMeta {
+    ///
+    /// 登录用户的配置分组数据权限;未开启鉴权或未登录时不限制
+    pub fn config_group_privilege(&self) -> GroupPrivilegeGroup {
+        self.token_session
+            .as_ref()
+            .map(|e| e.get_config_group_privilege())
+            .unwrap_or_default()
+    }
+
+    ///
+    /// 登录用户的服务分组数据权限;未开启鉴权或未登录时不限制
+    pub fn service_group_privilege(&self) -> GroupPrivilegeGroup {
+        self.token_session
+            .as_ref()
+            .map(|e| e.get_service_group_privilege())
+            .unwrap_or_default()
+    }
 }
 
 pub struct HandlerResult {
@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use inner_mem_cache::TimeoutSet;
+use serde::{Deserialize, Serialize};
+
+use super::nacos_proto::Payload;
+use crate::naming::model::ServiceKey;
+
+pub const PUSH_ACK_TIMEOUT_MILLIS: u64 = 3000;
+pub const PUSH_MAX_RETRY: u32 = 3;
+const PUSH_RETRY_BASE_MILLIS: u64 = 1000;
+const PUSH_RETRY_MAX_MILLIS: u64 = 30000;
+
+///
+/// 订阅连接在单个服务上的推送状态
+#[derive(Debug, Clone, Default)]
+pub struct NamingPushStatus {
+    pub last_push_checksum: i64,
+    pub last_push_time: u64,
+    /// 客户端已确认的实例列表checksum
+    pub last_ack_checksum: i64,
+    pub last_ack_time: u64,
+    /// 连续失败次数,确认后清零
+    pub fail_count: u32,
+    pub total_fail_count: u64,
+    pub retry_count: u32,
+    pending_request_id: Option<Arc<String>>,
+}
+
+impl NamingPushStatus {
+    pub fn is_pending(&self) -> bool {
+        self.pending_request_id.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamingPushStatusDto {
+    pub client_id: Arc<String>,
+    /// 是否有推送记录,没有记录的订阅者无法判断是否过期
+    pub tracked: bool,
+    /// 客户端确认的checksum与服务当前checksum不一致
+    pub stale: bool,
+    pub pending: bool,
+    pub last_push_checksum: i64,
+    pub last_push_time: u64,
+    pub last_ack_checksum: i64,
+    pub last_ack_time: u64,
+    pub fail_count: u32,
+    pub total_fail_count: u64,
+    pub retry_count: u32,
+}
+
+impl NamingPushStatusDto {
+    pub fn new(client_id: Arc<String>, status: Option<NamingPushStatus>, checksum: i64) -> Self {
+        match status {
+            Some(status) => Self {
+                client_id,
+                tracked: true,
+                stale: status.last_ack_checksum != checksum,
+                pending: status.is_pending(),
+                last_push_checksum: status.last_push_checksum,
+                last_push_time: status.last_push_time,
+                last_ack_checksum: status.last_ack_checksum,
+                last_ack_time: status.last_ack_time,
+                fail_count: status.fail_count,
+                total_fail_count: status.total_fail_count,
+                retry_count: status.retry_count,
+            },
+            None => Self {
+                client_id,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+struct PendingPush {
+    service_key: ServiceKey,
+    checksum: i64,
+    payload: Arc<Payload>,
+    retry_count: u32,
+    wait_retry: bool,
+    /// 下次检查时间,早于该时间的超时记录已失效
+    next_time: u64,
+}
+
+/// (client_id, request_id)
+type PushKey = (Arc<String>, Arc<String>);
+
+///
+/// 跟踪grpc服务推送的确认情况,超时未确认的推送按退避时间重试
+#[derive(Default)]
+pub struct NamingPushTracker {
+    status_map: HashMap<Arc<String>, HashMap<ServiceKey, NamingPushStatus>>,
+    pending_map: HashMap<PushKey, PendingPush>,
+    timeout_set: TimeoutSet<PushKey>,
+}
+
+impl NamingPushTracker {
+    pub fn on_push(
+        &mut self,
+        client_id: Arc<String>,
+        service_key: ServiceKey,
+        checksum: i64,
+        payload: Arc<Payload>,
+        request_id: Arc<String>,
+        now: u64,
+    ) {
+        let status = self
+            .status_map
+            .entry(client_id.clone())
+            .or_default()
+            .entry(service_key.clone())
+            .or_default();
+        status.last_push_checksum = checksum;
+        status.last_push_time = now;
+        //新的推送覆盖未确认的旧推送
+        if let Some(old_request_id) = status.pending_request_id.replace(request_id.clone()) {
+            self.pending_map
+                .remove(&(client_id.clone(), old_request_id));
+        }
+        let key = (client_id, request_id);
+        self.pending_map.insert(
+            key.clone(),
+            PendingPush {
+                service_key,
+                checksum,
+                payload,
+                retry_count: 0,
+                wait_retry: false,
+                next_time: now + PUSH_ACK_TIMEOUT_MILLIS,
+            },
+        );
+        self.timeout_set.add(now + PUSH_ACK_TIMEOUT_MILLIS, key);
+    }
+
+    ///
+    /// 订阅请求直接返回了实例列表,视为已确认
+    pub fn on_subscribe(
+        &mut self,
+        client_id: Arc<String>,
+        service_key: ServiceKey,
+        checksum: i64,
+        now: u64,
+    ) {
+        let status = self
+            .status_map
+            .entry(client_id.clone())
+            .or_default()
+            .entry(service_key)
+            .or_default();
+        if let Some(old_request_id) = status.pending_request_id.take() {
+            self.pending_map.remove(&(client_id, old_request_id));
+        }
+        status.last_push_checksum = checksum;
+        status.last_push_time = now;
+        status.last_ack_checksum = checksum;
+        status.last_ack_time = now;
+        status.fail_count = 0;
+        status.retry_count = 0;
+    }
+
+    pub fn on_ack(
+        &mut self,
+        client_id: Arc<String>,
+        request_id: Arc<String>,
+        success: bool,
+        now: u64,
+    ) {
+        let key = (client_id, request_id);
+        if !success {
+            self.on_fail(key, now);
+            return;
+        }
+        let pending = if let Some(pending) = self.pending_map.remove(&key) {
+            pending
+        } else {
+            return;
+        };
+        if let Some(status) = self.get_status_mut(&key.0, &pending.service_key) {
+            status.pending_request_id = None;
+            status.last_ack_checksum = pending.checksum;
+            status.last_ack_time = now;
+            status.fail_count = 0;
+            status.retry_count = 0;
+        }
+    }
+
+    ///
+    /// 处理超时的推送,返回需要重发的推送
+    pub fn timeout(&mut self, now: u64) -> Vec<(Arc<String>, Arc<Payload>)> {
+        let mut resend_list = vec![];
+        for key in self.timeout_set.timeout(now) {
+            let pending = match self.pending_map.get_mut(&key) {
+                Some(pending) if pending.next_time <= now => pending,
+                _ => continue,
+            };
+            if pending.wait_retry {
+                pending.wait_retry = false;
+                pending.next_time = now + PUSH_ACK_TIMEOUT_MILLIS;
+                resend_list.push((key.0.clone(), pending.payload.clone()));
+                self.timeout_set.add(pending.next_time, key);
+            } else {
+                self.on_fail(key, now);
+            }
+        }
+        resend_list
+    }
+
+    fn on_fail(&mut self, key: PushKey, now: u64) {
+        let pending = if let Some(pending) = self.pending_map.get_mut(&key) {
+            pending
+        } else {
+            return;
+        };
+        pending.retry_count += 1;
+        let retry_count = pending.retry_count;
+        let service_key = pending.service_key.clone();
+        let give_up = retry_count > PUSH_MAX_RETRY;
+        if give_up {
+            self.pending_map.remove(&key);
+            log::warn!(
+                "naming push retry exhausted,client_id:{},service:{:?}",
+                &key.0,
+                &service_key
+            );
+        } else {
+            pending.wait_retry = true;
+            pending.next_time = now + Self::retry_backoff(retry_count);
+            self.timeout_set.add(pending.next_time, key.clone());
+        }
+        if let Some(status) = self.get_status_mut(&key.0, &service_key) {
+            status.fail_count += 1;
+            status.total_fail_count += 1;
+            status.retry_count = retry_count.min(PUSH_MAX_RETRY);
+            if give_up {
+                status.pending_request_id = None;
+            }
+        }
+    }
+
+    fn retry_backoff(retry_count: u32) -> u64 {
+        let shift = retry_count.saturating_sub(1).min(16);
+        (PUSH_RETRY_BASE_MILLIS << shift).min(PUSH_RETRY_MAX_MILLIS)
+    }
+
+    fn get_status_mut(
+        &mut self,
+        client_id: &Arc<String>,
+        service_key: &ServiceKey,
+    ) -> Option<&mut NamingPushStatus> {
+        self.status_map
+            .get_mut(client_id)
+            .and_then(|e| e.get_mut(service_key))
+    }
+
+    pub fn get_status(
+        &self,
+        client_id: &Arc<String>,
+        service_key: &ServiceKey,
+    ) -> Option<NamingPushStatus> {
+        self.status_map
+            .get(client_id)
+            .and_then(|e| e.get(service_key))
+            .cloned()
+    }
+
+    ///
+    /// 取消订阅时移除该服务的推送状态及未确认的推送
+    pub fn remove_service(&mut self, client_id: &Arc<String>, service_key: &ServiceKey) {
+        let map = if let Some(map) = self.status_map.get_mut(client_id) {
+            map
+        } else {
+            return;
+        };
+        if let Some(request_id) = map
+            .remove(service_key)
+            .and_then(|status| status.pending_request_id)
+        {
+            self.pending_map.remove(&(client_id.clone(), request_id));
+        }
+        if map.is_empty() {
+            self.status_map.remove(client_id);
+        }
+    }
+
+    pub fn remove_client(&mut self, client_id: &Arc<String>) {
+        if let Some(map) = self.status_map.remove(client_id) {
+            for status in map.into_values() {
+                if let Some(request_id) = status.pending_request_id {
+                    self.pending_map.remove(&(client_id.clone(), request_id));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naming_push_retry() {
+        let mut tracker = NamingPushTracker::default();
+        let client_id = Arc::new("c1".to_owned());
+        let key = ServiceKey::new("public", "DEFAULT_GROUP", "foo");
+        let payload = Arc::new(Payload::default());
+        tracker.on_subscribe(client_id.clone(), key.clone(), 1, 0);
+        tracker.on_push(
+            client_id.clone(),
+            key.clone(),
+            2,
+            payload.clone(),
+            Arc::new("1".to_owned()),
+            0,
+        );
+        let status = tracker.get_status(&client_id, &key).unwrap();
+        assert!(status.is_pending());
+        assert_eq!(status.last_ack_checksum, 1);
+
+        //确认超时后按退避时间重发
+        assert!(tracker.timeout(PUSH_ACK_TIMEOUT_MILLIS).is_empty());
+        assert_eq!(tracker.get_status(&client_id, &key).unwrap().fail_count, 1);
+        let resend = tracker.timeout(PUSH_ACK_TIMEOUT_MILLIS + PUSH_RETRY_BASE_MILLIS);
+        assert_eq!(resend.len(), 1);
+
+        tracker.on_ack(client_id.clone(), Arc::new("1".to_owned()), true, 5000);
+        let status = tracker.get_status(&client_id, &key).unwrap();
+        assert!(!status.is_pending());
+        assert_eq!(status.last_ack_checksum, 2);
+        assert_eq!(status.fail_count, 0);
+        assert_eq!(status.total_fail_count, 1);
+
+        //重试次数用完后放弃
+        tracker.on_push(
+            client_id.clone(),
+            key.clone(),
+            3,
+            payload,
+            Arc::new("2".to_owned()),
+            10000,
+        );
+        let mut now = 10000;
+        for _ in 0..(PUSH_MAX_RETRY * 2 + 2) {
+            now += PUSH_RETRY_MAX_MILLIS;
+            tracker.timeout(now);
+        }
+        let status = tracker.get_status(&client_id, &key).unwrap();
+        assert!(!status.is_pending());
+        assert_eq!(status.retry_count, PUSH_MAX_RETRY);
+        let dto = NamingPushStatusDto::new(client_id.clone(), Some(status), 3);
+        assert!(dto.stale);
+
+        tracker.remove_client(&client_id);
+        assert!(tracker.get_status(&client_id, &key).is_none());
+
+        //取消订阅时移除未确认的推送,不再重发
+        tracker.on_push(
+            client_id.clone(),
+            key.clone(),
+            4,
+            Arc::new(Payload::default()),
+            Arc::new("3".to_owned()),
+            0,
+        );
+        tracker.remove_service(&client_id, &key);
+        assert!(tracker.get_status(&client_id, &key).is_none());
+        assert!(tracker.pending_map.is_empty());
+        assert!(tracker.status_map.is_empty());
+        assert!(tracker
+            .timeout(PUSH_ACK_TIMEOUT_MILLIS + PUSH_RETRY_MAX_MILLIS)
+            .is_empty());
+    }
+}
@@ -16,6 +16,9 @@ use crate::metrics::metrics_key::MetricsKey;
 use crate::metrics::model::{MetricsItem, MetricsRecord, MetricsRequest};
 use crate::raft::cache::model::{CacheKey, CacheType, CacheValue};
 use crate::raft::cache::{CacheManager, CacheManagerReq, CacheManagerResult};
+use crate::user::access_key::{
+    check_access_key_session, check_access_key_signature, AccessKeySignature,
+};
 
 use super::bistream_conn::BiStreamConn;
 use super::bistream_manage::BiStreamManageCmd;
@@ -48,14 +51,22 @@ impl RequestServerImpl {
             EMPTY_ARC_STRING.clone()
         };
         if self.app.sys_config.openapi_enable_auth && !token.is_empty() {
-            if let Ok(Some(session)) = get_user_session(
+            let session = get_user_session(
                 &self.app.cache_manager,
                 CacheManagerReq::Get(CacheKey::new(CacheType::ApiTokenSession, token.clone())),
             )
             .await
-            {
-                request_meta.token_session = Some(session);
-            }
+            .unwrap_or_default();
+            request_meta.token_session =
+                check_access_key_session(&self.app.access_key_manager, session).await;
+        } else if let Some(sign) = payload
+            .metadata
+            .as_ref()
+            .filter(|_| self.app.sys_config.openapi_enable_auth)
+            .and_then(|meta| AccessKeySignature::from_headers(|key| meta.headers.get(key).cloned()))
+        {
+            request_meta.token_session =
+                check_access_key_signature(&self.app.access_key_manager, sign).await;
         } else if !self.app.sys_config.cluster_token.is_empty() {
             if let Some(Some(token)) = payload
                 .metadata
@@ -93,9 +104,14 @@ impl request_server::Request for RequestServerImpl {
     ) -> Result<tonic::Response<Payload>, tonic::Status> {
         let start = SystemTime::now();
         let remote_addr = request.remote_addr().unwrap();
+        let peer_cert_verified = request
+            .peer_certs()
+            .map(|certs| !certs.is_empty())
+            .unwrap_or(false);
         let payload = request.into_inner();
         let mut request_meta = RequestMeta {
             client_ip: remote_addr.ip().to_string(),
+            peer_cert_verified,
             client_version: EMPTY_CLIENT_VERSION.clone(),
             connection_id: Arc::new(format!(
                 "{}_{}",
@@ -122,8 +138,11 @@ impl request_server::Request for RequestServerImpl {
                 let result: anyhow::Result<BiStreamManageResult> = result;
                 match result {
                     Ok(conn_result) => {
-                        if let BiStreamManageResult::ClientInfo(client_version) = conn_result {
+                        if let BiStreamManageResult::ClientInfo(client_version, labels) =
+                            conn_result
+                        {
                             request_meta.client_version = client_version;
+                            request_meta.labels = labels;
                         }
                     }
                     Err(err) => {
@@ -103,6 +103,7 @@ impl LdapMsgActor {
                                 .send(UserManagerReq::InitUser {
                                     user,
                                     namespace_privilege_param: None,
+                                    group_privilege_param: Default::default(),
                                 })
                                 .await
                         {
@@ -1,3 +1,4 @@
+pub mod audit;
 pub mod common;
 pub mod config;
 pub mod console;
@@ -16,6 +17,7 @@ pub mod health;
 pub mod transfer;
 
 pub mod ldap;
+pub mod oidc;
 
 pub use inner_mem_cache::TimeoutSet;
 
@@ -38,6 +38,7 @@ use env_logger_timezone_fmt::{TimeZoneFormat, TimeZoneFormatEnv};
 //use mimalloc::MiMalloc;
 use crate::cli::{Cli, Commands};
 use rnacos::common::appdata::AppShareData;
+use rnacos::common::tls_utils::TlsUtils;
 use rnacos::openapi::middle::auth_middle::ApiCheckAuth;
 use rnacos::raft::NacosRaft;
 use rnacos::transfer::data_to_sqlite::data_to_sqlite;
@@ -81,17 +82,28 @@ async fn main() -> Result<(), Box<dyn Error>> {
     invoker.add_raft_handler(&app_data);
 
     let grpc_app_data = app_data.clone();
+    let grpc_tls_config = if sys_config.grpc_tls_enable {
+        log::info!("grpc server tls enabled");
+        Some(TlsUtils::build_grpc_tls_config(&sys_config)?)
+    } else {
+        None
+    };
 
     tokio::spawn(async move {
-        let addr = grpc_addr.parse().unwrap();
         let request_server = RequestServerImpl::new(grpc_app_data.clone(), invoker);
         let bi_request_stream_server = BiRequestStreamServerImpl::new(grpc_app_data.clone());
-        Server::builder()
+        let router = Server::builder()
             .add_service(RequestServer::new(request_server))
-            .add_service(BiRequestStreamServer::new(bi_request_stream_server))
-            .serve(addr)
-            .await
-            .unwrap();
+            .add_service(BiRequestStreamServer::new(bi_request_stream_server));
+        if let Some(tls_config) = grpc_tls_config {
+            let listener = tokio::net::TcpListener::bind(&grpc_addr).await.unwrap();
+            router
+                .serve_with_incoming(TlsUtils::grpc_tls_incoming(listener, tls_config))
+                .await
+                .unwrap();
+        } else {
+            router.serve(grpc_addr.parse().unwrap()).await.unwrap();
+        }
     });
 
     if sys_config.http_console_port > 0 {
@@ -108,7 +120,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
         });
     }
 
-    let mut server = HttpServer::new(move || {
+    let http_tls_config = if sys_config.http_tls_enable {
+        log::info!("http server tls enabled");
+        Some(TlsUtils::build_http_tls_config(&sys_config)?)
+    } else {
+        None
+    };
+    let app_factory = move || {
         let app_data = app_data.clone();
         let config_addr = app_data.config_addr.clone();
         let naming_addr = app_data.naming_addr.clone();
@@ -123,12 +141,18 @@ async fn main() -> Result<(), Box<dyn Error>> {
             .wrap(ApiCheckAuth::new(source_app_data))
             .wrap(middleware::Logger::default())
             .configure(app_config(app_config_shard))
-    });
+    };
+    // 这里不使用log:info避免日志等级高于info时不打印
+    println!("rnacos started");
+    if let Some(tls_config) = http_tls_config {
+        TlsUtils::build_https_server(&http_addr, sys_config.http_workers, tls_config, app_factory)?
+            .await?;
+        return Ok(());
+    }
+    let mut server = HttpServer::new(app_factory);
     if let Some(num) = sys_config.http_workers {
         server = server.workers(num);
     }
-    // 这里不使用log:info避免日志等级高于info时不打印
-    println!("rnacos started");
     server.bind(http_addr)?.run().await?;
     Ok(())
 }
@@ -173,7 +197,8 @@ async fn run_console_web(source_app_data: Arc<AppShareData>) {
     let http_console_addr = source_app_data.sys_config.get_http_console_addr();
     log::info!("new console server http addr:{}", &http_console_addr);
     let app_data = Data::new(source_app_data.clone());
-    HttpServer::new(move || {
+    let sys_config = source_app_data.sys_config.clone();
+    let app_factory = move || {
         let source_app_data = source_app_data.clone();
         let config_addr = app_data.config_addr.clone();
         let naming_addr = app_data.naming_addr.clone();
@@ -188,11 +213,21 @@ async fn run_console_web(source_app_data: Arc<AppShareData>) {
             .wrap(middleware::Logger::default())
             .wrap(middleware::Compress::default())
             .configure(console_config)
-    })
-    .workers(2)
-    .bind(http_console_addr)
-    .unwrap()
-    .run()
-    .await
-    .ok();
+    };
+    if sys_config.console_tls_enable {
+        log::info!("console server tls enabled");
+        let tls_config = TlsUtils::build_http_tls_config(&sys_config).unwrap();
+        TlsUtils::build_https_server(&http_console_addr, Some(2), tls_config, app_factory)
+            .unwrap()
+            .await
+            .ok();
+        return;
+    }
+    HttpServer::new(app_factory)
+        .workers(2)
+        .bind(http_console_addr)
+        .unwrap()
+        .run()
+        .await
+        .ok();
 }
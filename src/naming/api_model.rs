@@ -1,6 +1,7 @@
 use crate::now_millis_i64;
 
 use super::model::{Instance, ServiceDetailDto, ServiceKey};
+use super::selector::ServiceSelector;
 use super::NamingUtils;
 use crate::common::option_utils::OptionUtils;
 use chrono::Local;
@@ -159,6 +160,9 @@ impl ServiceInfoParam {
             } else {
                 None
             };
+            if let Some(selector) = self.selector.as_ref() {
+                ServiceSelector::parse(selector)?;
+            }
 
             Ok(ServiceDetailDto {
                 namespace_id: Arc::new(NamingUtils::default_namespace(
@@ -170,6 +174,7 @@ impl ServiceInfoParam {
                 )),
                 metadata,
                 protect_threshold: self.protect_threshold,
+                selector: self.selector.map(Arc::new),
                 ..Default::default()
             })
         } else {
@@ -14,6 +14,7 @@ use self::{
     node_manage::{NodeManageRequest, NodeManageResponse},
 };
 use crate::common::constant::GRPC_HEAD_KEY_CLUSTER_ID;
+use crate::config::webhook::{ConfigWebhookReq, ConfigWebhookResult};
 use crate::metrics::model::{MetricsRequest, MetricsResponse};
 use crate::naming::cluster::model::SnapshotForSend;
 use crate::naming::model::{DistroData, Instance};
@@ -233,6 +234,15 @@ pub async fn handle_naming_route(
                 return Ok(NamingRouterResponse::ServiceSubscribersPage((total, list)));
             }
         }
+        NamingRouteRequest::QueryWebhookDeliveryPage(param) => {
+            let resp = app
+                .config_webhook_manager
+                .send(ConfigWebhookReq::QueryDeliveries(Box::new(param)))
+                .await??;
+            if let ConfigWebhookResult::DeliveryPageResult(total, list) = resp {
+                return Ok(NamingRouterResponse::WebhookDeliveryPage((total, list)));
+            }
+        }
     };
     Ok(NamingRouterResponse::None)
 }
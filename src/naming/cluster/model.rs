@@ -1,3 +1,4 @@
+use crate::config::webhook::model::{WebhookDeliveryDto, WebhookDeliveryQueryParam};
 use crate::metrics::timeline::model::{TimelineQueryParam, TimelineQueryResponse};
 use crate::naming::model::{Instance, InstanceKey, InstanceUpdateTag, ServiceDetailDto};
 use crate::naming::service::SubscriberInfoDto;
@@ -50,6 +51,7 @@ pub enum NamingRouteRequest {
     SyncDistroClientInstances(HashMap<Arc<String>, HashSet<InstanceKey>>),
     QueryDistroInstanceSnapshot(Vec<InstanceKey>),
     QueryServiceSubscriberPage(ServiceQueryParam),
+    QueryWebhookDeliveryPage(WebhookDeliveryQueryParam),
 }
 
 impl NamingRouteRequest {
@@ -69,6 +71,7 @@ impl NamingRouteRequest {
             NamingRouteRequest::SyncDistroClientInstances(_) => "SyncDistroClientInstances",
             NamingRouteRequest::QueryDistroInstanceSnapshot(_) => "QueryDistroInstanceSnapshot",
             NamingRouteRequest::QueryServiceSubscriberPage(_) => "QueryServiceSubscriberPage",
+            NamingRouteRequest::QueryWebhookDeliveryPage(_) => "QueryWebhookDeliveryPage",
         }
     }
 }
@@ -78,6 +81,7 @@ pub enum NamingRouterResponse {
     None,
     MetricsTimeLineResponse(TimelineQueryResponse),
     ServiceSubscribersPage((usize, Vec<SubscriberInfoDto>)),
+    WebhookDeliveryPage((usize, Vec<WebhookDeliveryDto>)),
 }
 
 #[derive(Message, Debug, Clone)]
@@ -6,8 +6,9 @@ use crate::{
     naming::{
         core::{NamingActor, NamingCmd, NamingResult},
         model::{Instance, InstanceUpdateTag},
+        persistent_instance::NamingRaftReq,
     },
-    raft::network::factory::RaftClusterRequestSender,
+    raft::{cluster::route::RaftRequestRoute, network::factory::RaftClusterRequestSender},
 };
 
 use super::{
@@ -24,6 +25,7 @@ pub struct NamingRoute {
     naming_addr: Addr<NamingActor>,
     node_manage: Arc<NodeManage>,
     cluster_sender: Arc<RaftClusterRequestSender>,
+    raft_request_route: Arc<RaftRequestRoute>,
     send_extend_infos: HashMap<String, String>,
 }
 
@@ -33,6 +35,7 @@ impl NamingRoute {
         naming_addr: Addr<NamingActor>,
         node_manage: Arc<NodeManage>,
         cluster_sender: Arc<RaftClusterRequestSender>,
+        raft_request_route: Arc<RaftRequestRoute>,
     ) -> Self {
         let mut send_extend_infos = HashMap::default();
         send_extend_infos.insert(GRPC_HEAD_KEY_CLUSTER_ID.to_owned(), local_id.to_string());
@@ -40,6 +43,7 @@ impl NamingRoute {
             naming_addr,
             node_manage,
             cluster_sender,
+            raft_request_route,
             send_extend_infos,
         }
     }
@@ -49,6 +53,15 @@ impl NamingRoute {
         instance: Instance,
         tag: Option<InstanceUpdateTag>,
     ) -> anyhow::Result<()> {
+        if !instance.ephemeral {
+            //持久化实例通过raft同步到所有节点
+            let req = NamingRaftReq::Update {
+                instance: Box::new((&instance).into()),
+                tag,
+            };
+            self.raft_request_route.request_naming(req).await?;
+            return Ok(());
+        }
         let key = instance.get_service_key();
         match self.node_manage.route_addr(&key).await {
             NamingRouteAddr::Local(_) => {
@@ -126,6 +139,13 @@ impl NamingRoute {
     }
 
     pub async fn delete_instance(&self, instance: Instance) -> anyhow::Result<()> {
+        if self.is_persistent_instance(&instance).await? {
+            let req = NamingRaftReq::Remove {
+                key: instance.get_instance_key(),
+            };
+            self.raft_request_route.request_naming(req).await?;
+            return Ok(());
+        }
         let key = instance.get_service_key();
         match self.node_manage.route_addr(&key).await {
             NamingRouteAddr::Local(_) => {
@@ -139,4 +159,21 @@ impl NamingRoute {
         };
         Ok(())
     }
+
+    ///
+    /// 注销时请求可能未带ephemeral参数,以已注册的实例为准
+    async fn is_persistent_instance(&self, instance: &Instance) -> anyhow::Result<bool> {
+        if !instance.ephemeral {
+            return Ok(true);
+        }
+        let res: NamingResult = self
+            .naming_addr
+            .send(NamingCmd::Query(instance.clone()))
+            .await??;
+        if let NamingResult::Instance(v) = res {
+            Ok(!v.ephemeral)
+        } else {
+            Ok(false)
+        }
+    }
 }
@@ -14,7 +14,9 @@ use super::cluster::model::{
     NamingRouteRequest, ProcessRange, SnapshotForReceive, SnapshotForSend,
 };
 use super::cluster::node_manage::{InnerNodeManage, NodeManageRequest};
+use super::event_history::{NamingEvent, NamingEventHistory};
 use super::filter::InstanceFilterUtils;
+use super::instance_drain::InstanceDrainDo;
 use super::listener::{InnerNamingListener, ListenerItem, NamingListenerCmd};
 use super::model::InstanceKey;
 use super::model::InstanceShortKey;
@@ -28,9 +30,14 @@ use super::naming_delay_nofity::DelayNotifyActor;
 use super::naming_delay_nofity::DelayNotifyCmd;
 use super::naming_subscriber::NamingListenerItem;
 use super::naming_subscriber::Subscriber;
+use super::order::{InstanceOrderConfig, InstanceOrderParam, ZoneCidrMap};
+use super::persistent_instance::{NamingRaftReq, NamingRaftResult, PersistentInstanceDo};
+use super::selector::{ConsumerLabelIndex, ServiceSelector};
 use super::service::ServiceInfoDto;
 use super::service::ServiceMetadata;
 use super::service::{Service, SubscriberInfoDto};
+use super::service_cluster::ServiceClusterDo;
+use super::service_definition::ServiceDefinitionDo;
 use super::service_index::NamespaceIndex;
 use super::service_index::ServiceQueryParam;
 use super::NamingUtils;
@@ -55,14 +62,29 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::common::constant::EMPTY_ARC_STRING;
+use crate::common::constant::{
+    EMPTY_ARC_STRING, NAMING_INSTANCE_DRAIN_TREE_NAME, NAMING_PERSISTENT_INSTANCE_TREE_NAME,
+    NAMING_SERVICE_CLUSTER_TREE_NAME, NAMING_SERVICE_TREE_NAME,
+};
 use crate::common::model::privilege::NamespacePrivilegeGroup;
 use crate::metrics::metrics_key::MetricsKey;
 use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
 use crate::namespace::NamespaceActor;
+use crate::raft::cluster::model::RouteAddr;
+use crate::raft::cluster::route::{RaftAddrRouter, RaftRequestRoute};
+use crate::raft::filestore::model::SnapshotRecordDto;
+use crate::raft::filestore::raftapply::{RaftApplyDataRequest, RaftApplyDataResponse};
+use crate::raft::filestore::raftsnapshot::{SnapshotWriterActor, SnapshotWriterRequest};
+use crate::transfer::model::{
+    TransferDataRequest, TransferDataResponse, TransferRecordDto, TransferWriterRequest,
+};
+use crate::transfer::writer::TransferWriterActor;
 use actix::prelude::*;
 use regex::Regex;
 
+/// 过期摘流记录移除失败或当前节点不是leader时的重试间隔
+const DRAIN_EXPIRE_RETRY_MILLIS: u64 = 30_000;
+
 //#[derive(Default)]
 #[bean(inject)]
 pub struct NamingActor {
@@ -74,8 +96,14 @@ pub struct NamingActor {
     pub(crate) subscriber: Subscriber,
     pub(crate) sys_config: NamingSysConfig,
     pub(crate) empty_service_set: TimeoutSet<ServiceKey>,
+    /// 摘流记录自动恢复时间
+    pub(crate) drain_timeout_set: TimeoutSet<ServiceKey>,
+    /// 实例变更事件历史
+    pub(crate) event_history: NamingEventHistory,
     pub(crate) instance_metadate_set: TimeoutSet<InstanceKey>,
     pub(crate) namespace_index: NamespaceIndex,
+    /// 消费者ip对应的实例metadata,用于label选择器
+    pub(crate) consumer_label_index: ConsumerLabelIndex,
     pub(crate) client_instance_set: HashMap<Arc<String>, HashSet<InstanceKey>>,
     pub(crate) cluster_node_manage: Option<Addr<InnerNodeManage>>,
     pub(crate) cluster_delay_notify: Option<Addr<ClusterInstanceDelayNotifyActor>>,
@@ -84,6 +112,8 @@ pub struct NamingActor {
     pub(crate) node_id: u64,
     /// 用于注入测试异常场景
     pub(crate) disable_notify: bool,
+    pub(crate) raft_addr_route: Option<Arc<RaftAddrRouter>>,
+    pub(crate) raft_request_route: Option<Arc<RaftRequestRoute>>,
     //dal_addr: Addr<ServiceDalActor>,
 }
 
@@ -114,6 +144,8 @@ impl Inject for NamingActor {
         self.cluster_delay_notify = factory_data.get_actor();
         self.namespace_actor = factory_data.get_actor();
         self.namespace_index.namespace_actor = self.namespace_actor.clone();
+        self.raft_addr_route = factory_data.get_bean();
+        self.raft_request_route = factory_data.get_bean();
         let sys_config: Option<Arc<AppSysConfig>> = factory_data.get_bean();
         if let Some(sys_config) = sys_config {
             self.sys_config.instance_health_timeout_millis =
@@ -121,6 +153,22 @@ impl Inject for NamingActor {
             self.sys_config.instance_timeout_millis =
                 sys_config.naming_instance_timeout as i64 + 3000;
             self.node_id = sys_config.raft_node_id;
+            self.sys_config.instance_order = Self::build_instance_order_config(&sys_config);
+            self.event_history.set_node_id(self.node_id);
+            self.event_history
+                .set_capacity(sys_config.naming_event_history_size);
+            if !sys_config.naming_event_log_file.is_empty() {
+                if let Err(err) = self.event_history.open_log_file(
+                    &sys_config.naming_event_log_file,
+                    sys_config.naming_event_log_max_size * 1024 * 1024,
+                ) {
+                    log::warn!(
+                        "open naming event log file {} error,{}",
+                        &sys_config.naming_event_log_file,
+                        err
+                    );
+                }
+            }
             log::info!("NamingActor change naming timeout info from env,health_timeout:{},instance_timeout:{}"
                 ,self.sys_config.instance_health_timeout_millis,self.sys_config.instance_timeout_millis)
         }
@@ -128,6 +176,23 @@ impl Inject for NamingActor {
     }
 }
 
+impl NamingActor {
+    fn build_instance_order_config(sys_config: &AppSysConfig) -> InstanceOrderConfig {
+        let zone_cidr_map = match ZoneCidrMap::parse(&sys_config.naming_zone_cidr) {
+            Ok(v) => v,
+            Err(err) => {
+                log::warn!("RNACOS_NAMING_ZONE_CIDR is invalid,{}", err);
+                ZoneCidrMap::default()
+            }
+        };
+        InstanceOrderConfig {
+            default_order_type: sys_config.naming_instance_order.as_str().into(),
+            zone_cidr_map,
+            zone_healthy_threshold: sys_config.naming_zone_healthy_threshold,
+        }
+    }
+}
+
 impl Default for NamingActor {
     fn default() -> Self {
         Self::new()
@@ -146,7 +211,10 @@ impl NamingActor {
             delay_notify_addr: None,
             sys_config: NamingSysConfig::new(),
             empty_service_set: Default::default(),
+            drain_timeout_set: Default::default(),
+            event_history: Default::default(),
             namespace_index: NamespaceIndex::new(),
+            consumer_label_index: Default::default(),
             instance_metadate_set: Default::default(),
             client_instance_set: Default::default(),
             cluster_node_manage: None,
@@ -155,6 +223,8 @@ impl NamingActor {
             namespace_actor: None,
             node_id: 0,
             disable_notify: false,
+            raft_addr_route: None,
+            raft_request_route: None,
             //dal_addr,
         }
     }
@@ -218,10 +288,17 @@ impl NamingActor {
             Some(service) => {
                 if let Some(protect_threshold) = service_info.protect_threshold {
                     service.protect_threshold = protect_threshold;
+                    service.recalculate_checksum();
                 }
                 if let Some(metadata) = service_info.metadata {
                     service.metadata = metadata;
                 }
+                if let Some(selector) = service_info.selector {
+                    service.selector = ServiceSelector::parse(&selector)
+                        .ok()
+                        .flatten()
+                        .map(Arc::new);
+                }
             }
             None => {
                 let mut service = Service::default();
@@ -240,6 +317,12 @@ impl NamingActor {
                 if let Some(metadata) = service_info.metadata {
                     service.metadata = metadata;
                 }
+                if let Some(selector) = service_info.selector {
+                    service.selector = ServiceSelector::parse(&selector)
+                        .ok()
+                        .flatten()
+                        .map(Arc::new);
+                }
                 service.recalculate_checksum();
                 self.namespace_index.insert_service(key.clone());
                 //self.dal_addr.do_send(ServiceDalMsg::AddService(service.get_service_do()));
@@ -254,7 +337,15 @@ impl NamingActor {
 
     fn remove_empty_service(&mut self, service_map_key: ServiceKey) -> anyhow::Result<()> {
         if let Some(service) = self.service_map.get(&service_map_key) {
-            if service.instance_size <= 0 {
+            if !service.cluster_map.is_empty() {
+                Err(anyhow::anyhow!(
+                    "The service has clusters,remove the clusters first!"
+                ))
+            } else if service.has_active_drain(now_millis_i64()) {
+                Err(anyhow::anyhow!(
+                    "The service has drained instances,restore them first!"
+                ))
+            } else if service.instance_size <= 0 {
                 //控制台发起的不校验过期时间标记
                 self.clear_one_empty_service(service_map_key.clone(), 0x7fff_ffff_ffff_ffff);
                 Ok(())
@@ -269,10 +360,25 @@ impl NamingActor {
     }
 
     /// 单独订阅者通知
-    fn notify_to_subscriber(&mut self, _tag: &UpdateInstanceType, key: ServiceKey) {
+    fn notify_to_subscriber(&mut self, tag: &UpdateInstanceType, key: ServiceKey) {
+        self.update_checksum(tag, &key);
         self.subscriber.notify(key);
     }
 
+    ///
+    /// 实例列表变化时重新计算服务checksum,查询时直接使用缓存值
+    fn update_checksum(&mut self, tag: &UpdateInstanceType, key: &ServiceKey) {
+        if !matches!(
+            tag,
+            UpdateInstanceType::New | UpdateInstanceType::Remove | UpdateInstanceType::UpdateValue
+        ) {
+            return;
+        }
+        if let Some(service) = self.service_map.get_mut(key) {
+            service.recalculate_checksum();
+        }
+    }
+
     /// 变更通知，包含给其它集群节点通知和给本节点监听器通知
     fn do_notify(
         &mut self,
@@ -280,6 +386,7 @@ impl NamingActor {
         key: ServiceKey,
         instance: Option<Arc<Instance>>,
     ) {
+        self.update_checksum(tag, &key);
         #[cfg(feature = "debug")]
         if self.disable_notify {
             return;
@@ -357,6 +464,12 @@ impl NamingActor {
         let tag = if let Some(old_instance) = &old_instance {
             real_client_id = Some(old_instance.client_id.clone());
             let short_key = old_instance.get_short_key();
+            self.consumer_label_index
+                .remove(&InstanceKey::new_by_service_key(
+                    key,
+                    short_key.ip.clone(),
+                    short_key.port,
+                ));
             if service.exist_priority_metadata(&short_key) {
                 let instance_key =
                     InstanceKey::new_by_service_key(key, short_key.ip, short_key.port);
@@ -369,11 +482,17 @@ impl NamingActor {
         } else {
             UpdateInstanceType::None
         };
+        if let Some(event) =
+            NamingEvent::diff(key, old_instance.as_deref(), None, false, now as i64)
+        {
+            self.event_history.record(event);
+        }
         if service.instance_size <= 0 {
             self.empty_service_set
                 .add(now + self.sys_config.service_time_out_millis, key.clone());
         }
-        let remove_instance = old_instance.filter(|e| !e.is_from_cluster());
+        //持久化实例由raft同步,不需要通知其它节点
+        let remove_instance = old_instance.filter(|e| e.ephemeral && !e.is_from_cluster());
         self.do_notify(&tag, key.clone(), remove_instance);
         if let Some(client_id) = real_client_id {
             if !client_id.as_ref().is_empty() {
@@ -425,8 +544,13 @@ impl NamingActor {
             }
         }
         let instance_short_key = instance.get_short_key();
+        let old_instance = service.get_instance(&instance_short_key);
 
         let (tag, replace_old_client_id) = service.update_instance(instance, tag, from_sync);
+        if let Some(instance) = service.get_instance(&instance_short_key) {
+            self.consumer_label_index
+                .update(instance_key.clone(), instance.metadata.clone());
+        }
         #[cfg(feature = "debug")]
         log::info!(
             "update_instance tag:{:?},key:{:?},replace_old_client_id:{:?}",
@@ -442,9 +566,18 @@ impl NamingActor {
                 set.remove(&instance_key);
             }
         }
+        let instance = service.get_instance(&instance_short_key);
+        if let Some(event) = NamingEvent::diff(
+            key,
+            old_instance.as_deref(),
+            instance.as_deref(),
+            false,
+            now_millis_i64(),
+        ) {
+            self.event_history.record(event);
+        }
         if !from_sync {
             //change notify
-            let instance = service.get_instance(&instance_short_key);
             self.do_notify(&tag, key.clone(), instance);
         } else {
             //如果不通知其它集群，则单独通知订阅者
@@ -486,13 +619,42 @@ impl NamingActor {
         key: &ServiceKey,
         cluster_str: &str,
         only_healthy: bool,
+    ) -> Vec<Arc<Instance>> {
+        self.get_consumer_instance_list(key, cluster_str, only_healthy, None, None)
+    }
+
+    ///
+    /// order为None时不排序;排序在健康过滤前进行,以便按全部实例计算本zone健康比例
+    pub fn get_consumer_instance_list(
+        &self,
+        key: &ServiceKey,
+        cluster_str: &str,
+        only_healthy: bool,
+        consumer_ip: Option<&str>,
+        order: Option<&InstanceOrderParam>,
     ) -> Vec<Arc<Instance>> {
         let cluster_names = NamingUtils::split_filters(cluster_str);
         if let Some(service) = self.service_map.get(key) {
+            let metadata = service.get_metadata();
+            let consumer_labels = match (metadata.selector.as_ref(), consumer_ip) {
+                (Some(selector), Some(ip)) if selector.is_label() => {
+                    Some(self.consumer_label_index.get_labels(&key.namespace_id, ip))
+                }
+                _ => None,
+            };
+            let mut instances = service.get_instance_list(cluster_names, false, true);
+            if let Some(order) = order {
+                instances = self.sys_config.instance_order.order_instances(
+                    instances,
+                    Some(order),
+                    consumer_ip,
+                );
+            }
             return InstanceFilterUtils::default_instance_filter(
-                service.get_instance_list(cluster_names, false, true),
-                Some(service.get_metadata()),
+                instances,
+                Some(metadata),
                 only_healthy,
+                consumer_labels.as_ref(),
             );
         }
         vec![]
@@ -542,19 +704,31 @@ impl NamingActor {
         key: &ServiceKey,
         cluster_str: String,
         only_healthy: bool,
+        consumer_labels: Option<&HashMap<String, String>>,
     ) -> ServiceInfo {
         let (hosts, metadata) = self.get_instances_and_metadata(key, &cluster_str, false);
+        let checksum = self
+            .service_map
+            .get(key)
+            .map(|e| e.check_sum)
+            .unwrap_or_default();
         let service_info = ServiceInfo {
             name: Some(key.service_name.clone()),
             group_name: Some(key.group_name.clone()),
             cache_millis: 10000i64,
             last_ref_time: now_millis_i64(),
+            checksum,
             reach_protection_threshold: false,
             hosts: Some(hosts),
             clusters: Some(cluster_str),
             ..Default::default()
         };
-        InstanceFilterUtils::default_service_filter(service_info, metadata, only_healthy)
+        InstanceFilterUtils::default_service_filter(
+            service_info,
+            metadata,
+            only_healthy,
+            consumer_labels,
+        )
     }
 
     pub fn get_instance_list_string(
@@ -562,8 +736,16 @@ impl NamingActor {
         key: &ServiceKey,
         cluster_str: String,
         only_healthy: bool,
+        consumer_ip: Option<&str>,
+        order: &InstanceOrderParam,
     ) -> String {
-        let list = self.get_instance_list(key, &cluster_str, only_healthy);
+        let list = self.get_consumer_instance_list(
+            key,
+            &cluster_str,
+            only_healthy,
+            consumer_ip,
+            Some(order),
+        );
         QueryListResult::get_instance_list_string(cluster_str, key, list)
     }
 
@@ -576,7 +758,32 @@ impl NamingActor {
         let mut change_list = vec![];
         for item in self.service_map.values_mut() {
             let service_key = item.get_service_key();
-            let (rlist, ulist) = item.time_check(healthy_time, offline_time);
+            let (removed_list, unhealthy_list) = item.time_check(healthy_time, offline_time);
+            for old in &removed_list {
+                self.consumer_label_index
+                    .remove(&InstanceKey::new_by_service_key(
+                        &service_key,
+                        old.ip.clone(),
+                        old.port,
+                    ));
+                if let Some(event) =
+                    NamingEvent::diff(&service_key, Some(old), None, true, current_time)
+                {
+                    self.event_history.record(event);
+                }
+            }
+            for old in &unhealthy_list {
+                let new = item.get_instance(&old.get_short_key());
+                if let Some(event) =
+                    NamingEvent::diff(&service_key, Some(old), new.as_deref(), false, current_time)
+                {
+                    self.event_history.record(event);
+                }
+            }
+            let rlist: Vec<InstanceShortKey> =
+                removed_list.iter().map(|e| e.get_short_key()).collect();
+            let ulist: Vec<InstanceShortKey> =
+                unhealthy_list.iter().map(|e| e.get_short_key()).collect();
             size += rlist.len() + ulist.len();
             if !rlist.is_empty() {
                 for short_key in &rlist {
@@ -814,17 +1021,99 @@ impl NamingActor {
     fn clear_one_empty_service(&mut self, service_map_key: ServiceKey, now: u64) {
         if let Some(service) = self.service_map.get(&service_map_key) {
             if service.instance_size <= 0
+                && service.cluster_map.is_empty()
+                && !service.has_active_drain(now as i64)
+                && !service.persistent
                 && now - self.sys_config.service_time_out_millis >= service.last_empty_times
             {
                 //self.dal_addr.do_send(ServiceDalMsg::DeleteService(service.get_service_do().get_key_param().unwrap()));
                 self.namespace_index
                     .remove_service(&service.get_service_key());
                 self.service_map.remove(&service_map_key);
+                self.event_history.remove_service(&service_map_key);
                 log::info!("clear_empty_service:{:?}", &service_map_key);
             }
         }
     }
 
+    ///
+    /// 摘流记录过期在读取时判断,记录本身只由raft命令移除,避免各节点按本地时钟修改状态机数据;
+    /// 到期时通知本节点订阅者刷新实例,并由leader提交移除过期记录
+    fn notify_expired_drain(&mut self, ctx: &mut Context<Self>) {
+        let now = now_millis();
+        for key in self.drain_timeout_set.timeout(now) {
+            let drains = match self.service_map.get(&key) {
+                Some(service) => service.get_expired_drains(now as i64),
+                None => continue,
+            };
+            if drains.is_empty() {
+                continue;
+            }
+            self.notify_drain_change(key.clone());
+            self.remove_expired_drain(key, drains, ctx);
+        }
+    }
+
+    ///
+    /// 非leader节点或提交失败时稍后重试,记录被移除后不再处理
+    fn remove_expired_drain(
+        &mut self,
+        key: ServiceKey,
+        drains: Vec<InstanceDrainDo>,
+        ctx: &mut Context<Self>,
+    ) {
+        let (raft_addr_route, raft_request_route) =
+            match (&self.raft_addr_route, &self.raft_request_route) {
+                (Some(a), Some(b)) => (a.clone(), b.clone()),
+                _ => return,
+            };
+        async move {
+            if !matches!(raft_addr_route.get_route_addr().await?, RouteAddr::Local) {
+                return Ok(false);
+            }
+            for drain in drains {
+                raft_request_route
+                    .request_naming(NamingRaftReq::RemoveDrain {
+                        drain: Box::new(drain),
+                    })
+                    .await?;
+            }
+            Ok(true)
+        }
+        .into_actor(self)
+        .map(move |r: anyhow::Result<bool>, act, _ctx| {
+            let removed = match r {
+                Ok(v) => v,
+                Err(err) => {
+                    log::warn!("remove expired drain error,{}", err);
+                    false
+                }
+            };
+            if !removed {
+                act.drain_timeout_set
+                    .add(now_millis() + DRAIN_EXPIRE_RETRY_MILLIS, key);
+            }
+        })
+        .spawn(ctx);
+    }
+
+    ///
+    /// 摘流变更在每个节点的状态机中应用,只需通知本节点的订阅者与监听器
+    fn notify_drain_change(&mut self, key: ServiceKey) {
+        self.update_checksum(&UpdateInstanceType::UpdateValue, &key);
+        self.subscriber.notify(key.clone());
+        self.notify_listener(key, 0);
+    }
+
+    fn notify_listener(&self, service_key: ServiceKey, id: u64) {
+        if let Some(listener_addr) = self.listener_addr.as_ref() {
+            let map = self.get_instance_map(&service_key, vec![], false);
+            //notify listener
+            let msg = NamingListenerCmd::Notify(service_key, "".to_string(), map, id);
+            listener_addr.do_send(msg);
+        }
+    }
+
     fn clear_timeout_instance_metadata(&mut self) {
         for instance_key in self.instance_metadate_set.timeout(now_millis()) {
             self.clear_one_timeout_instance_metadata(instance_key);
@@ -845,6 +1134,7 @@ impl NamingActor {
         ctx.run_later(Duration::from_millis(2000), |act, ctx| {
             act.clear_empty_service();
             act.clear_timeout_instance_metadata();
+            act.notify_expired_drain(ctx);
             let addr = ctx.address();
             addr.do_send(NamingCmd::PeekListenerTimeout);
             act.instance_time_out_heartbeat(ctx);
@@ -1053,13 +1343,33 @@ pub enum NamingCmd {
     Delete(Instance),
     DeleteBatch(Vec<Instance>),
     Query(Instance),
-    QueryList(ServiceKey, String, bool, Option<SocketAddr>),
+    QueryList(
+        ServiceKey,
+        String,
+        bool,
+        Option<SocketAddr>,
+        InstanceOrderParam,
+    ),
     QueryAllInstanceList(ServiceKey),
-    QueryListString(ServiceKey, String, bool, Option<SocketAddr>),
-    QueryServiceInfo(ServiceKey, String, bool),
+    QueryListString(
+        ServiceKey,
+        String,
+        bool,
+        Option<SocketAddr>,
+        Option<String>,
+        InstanceOrderParam,
+    ),
+    QueryServiceInfo(
+        ServiceKey,
+        String,
+        bool,
+        Option<Arc<HashMap<String, String>>>,
+    ),
     QueryServicePage(ServiceKey, usize, usize),
     QueryServiceSubscribersPage(ServiceKey, usize, usize),
     QueryServiceSubscribersPageV2(ServiceQueryParam),
+    /// 查询服务当前的checksum与订阅连接
+    QuerySubscriberClients(ServiceKey),
     //查询服务实际信息列表
     QueryServiceInfoPage(ServiceQueryParam),
     //CreateService(ServiceDetailDto),
@@ -1079,7 +1389,15 @@ pub enum NamingCmd {
     ClusterRefreshProcessRange(ProcessRange),
     ReceiveSnapshot(SnapshotForReceive),
     QueryGrpcDistroData,
-    DiffGrpcDistroData { cluster_id: u64, data: DistroData },
+    DiffGrpcDistroData {
+        cluster_id: u64,
+        data: DistroData,
+    },
+    QueryHealthCheckInstances(ServiceKey),
+    QueryServiceDetail(ServiceKey),
+    QueryDrainList(Option<ServiceKey>),
+    /// 查询服务最近的实例变更事件,limit为0时返回全部
+    QueryEventHistory(ServiceKey, usize),
     QueryDistroInstanceSnapshot(Vec<InstanceKey>),
 }
 
@@ -1098,6 +1416,12 @@ pub enum NamingResult {
     GrpcDistroData(DistroData),
     DiffDistroData(DistroData),
     DistroInstancesSnapshot(Vec<Arc<Instance>>),
+    /// 需要健康检查的实例,及集群指定的检查端口
+    HealthCheckInstances(Vec<Arc<Instance>>, HashMap<String, u32>),
+    ServiceDetail(Option<ServiceDetailDto>),
+    DrainList(Vec<Arc<InstanceDrainDo>>),
+    EventHistory(Vec<Arc<NamingEvent>>),
+    SubscriberClients(i64, Vec<Arc<String>>),
 }
 
 impl Supervised for NamingActor {
@@ -1161,28 +1485,63 @@ impl Handler<NamingCmd> for NamingActor {
                 }
                 Ok(NamingResult::NULL)
             }
-            NamingCmd::QueryList(service_key, cluster_str, only_healthy, addr) => {
+            NamingCmd::QueryList(service_key, cluster_str, only_healthy, addr, order) => {
                 let cluster_names = NamingUtils::split_filters(&cluster_str);
                 if let Some(addr) = addr {
                     self.update_listener(&service_key, &cluster_names, addr, only_healthy);
                 }
-                let list = self.get_instance_list(&service_key, &cluster_str, only_healthy);
+                let list = self.get_consumer_instance_list(
+                    &service_key,
+                    &cluster_str,
+                    only_healthy,
+                    None,
+                    Some(&order),
+                );
                 Ok(NamingResult::InstanceList(list))
             }
-            NamingCmd::QueryListString(service_key, cluster_str, only_healthy, addr) => {
+            NamingCmd::QueryListString(
+                service_key,
+                cluster_str,
+                only_healthy,
+                addr,
+                client_ip,
+                order,
+            ) => {
                 //println!("QUERY_LIST_STRING addr: {:?}",&addr);
                 let cluster_names = NamingUtils::split_filters(&cluster_str);
                 if let Some(addr) = addr {
                     self.update_listener(&service_key, &cluster_names, addr, only_healthy);
                 }
-                let data = self.get_instance_list_string(&service_key, cluster_str, only_healthy);
+                let data = self.get_instance_list_string(
+                    &service_key,
+                    cluster_str,
+                    only_healthy,
+                    client_ip.as_deref(),
+                    &order,
+                );
                 Ok(NamingResult::InstanceListString(data))
             }
-            NamingCmd::QueryServiceInfo(service_key, cluster_str, only_healthy) => {
+            NamingCmd::QueryServiceInfo(service_key, cluster_str, only_healthy, labels) => {
                 let cluster_names = NamingUtils::split_filters(&cluster_str);
-                let service_info = self.get_service_info(&service_key, cluster_str, only_healthy);
+                let service_info = self.get_service_info(
+                    &service_key,
+                    cluster_str,
+                    only_healthy,
+                    labels.as_ref().map(|e| e.as_ref()),
+                );
                 Ok(NamingResult::ServiceInfo(service_info))
             }
+            NamingCmd::QuerySubscriberClients(service_key) => {
+                let checksum = self
+                    .service_map
+                    .get(&service_key)
+                    .map(|e| e.check_sum)
+                    .unwrap_or_default();
+                Ok(NamingResult::SubscriberClients(
+                    checksum,
+                    self.subscriber.get_service_clients(&service_key),
+                ))
+            }
             NamingCmd::QueryServicePage(service_key, page_size, page_index) => {
                 Ok(NamingResult::ServicePage(self.get_service_list(
                     page_size,
@@ -1207,12 +1566,7 @@ impl Handler<NamingCmd> for NamingActor {
                 Ok(NamingResult::NULL)
             }
             NamingCmd::NotifyListener(service_key, id) => {
-                if let Some(listener_addr) = self.listener_addr.as_ref() {
-                    let map = self.get_instance_map(&service_key, vec![], false);
-                    //notify listener
-                    let msg = NamingListenerCmd::Notify(service_key, "".to_string(), map, id);
-                    listener_addr.do_send(msg);
-                }
+                self.notify_listener(service_key, id);
                 Ok(NamingResult::NULL)
             }
             NamingCmd::Subscribe(items, client_id) => {
@@ -1319,6 +1673,26 @@ impl Handler<NamingCmd> for NamingActor {
                     Ok(NamingResult::NULL)
                 }
             }
+            NamingCmd::QueryHealthCheckInstances(service_key) => {
+                let check_ports = self
+                    .service_map
+                    .get(&service_key)
+                    .map(|e| e.get_cluster_check_ports())
+                    .unwrap_or_default();
+                Ok(NamingResult::HealthCheckInstances(
+                    self.get_health_check_instances(&service_key),
+                    check_ports,
+                ))
+            }
+            NamingCmd::QueryServiceDetail(service_key) => Ok(NamingResult::ServiceDetail(
+                self.get_service_detail(&service_key),
+            )),
+            NamingCmd::QueryDrainList(service_key) => Ok(NamingResult::DrainList(
+                self.get_drain_list(service_key.as_ref()),
+            )),
+            NamingCmd::QueryEventHistory(service_key, limit) => Ok(NamingResult::EventHistory(
+                self.event_history.query(&service_key, limit),
+            )),
             NamingCmd::QueryDistroInstanceSnapshot(instance_keys) => {
                 let instances = self.build_distro_instances(instance_keys);
                 Ok(NamingResult::DistroInstancesSnapshot(instances))
@@ -1327,6 +1701,374 @@ impl Handler<NamingCmd> for NamingActor {
     }
 }
 
+impl NamingActor {
+    fn apply_persistent_req(&mut self, req: NamingRaftReq) {
+        match req {
+            NamingRaftReq::Update { instance, tag } => {
+                let instance: Instance = (*instance).into();
+                let key = instance.get_service_key();
+                //持久化标记以raft数据为准
+                let tag = tag.map(|mut e| {
+                    e.ephemeral = true;
+                    e
+                });
+                self.update_instance(&key, instance, tag, true);
+            }
+            NamingRaftReq::Remove { key } => {
+                self.remove_instance(&key.get_service_key(), &key.get_short_key(), None);
+            }
+            NamingRaftReq::UpdateHealthy { results } => {
+                let mut service_results: HashMap<ServiceKey, Vec<(InstanceShortKey, bool)>> =
+                    HashMap::new();
+                for (key, healthy) in results {
+                    service_results
+                        .entry(key.get_service_key())
+                        .or_default()
+                        .push((key.get_short_key(), healthy));
+                }
+                for (key, results) in service_results {
+                    self.update_instance_healthy(&key, results);
+                }
+            }
+            NamingRaftReq::UpdateCluster { cluster } => {
+                let key = cluster.get_service_key();
+                self.create_empty_service(&key);
+                if let Some(service) = self.service_map.get_mut(&key) {
+                    service
+                        .cluster_map
+                        .insert(cluster.cluster_name.to_owned(), Arc::new(*cluster));
+                }
+            }
+            NamingRaftReq::RemoveCluster { cluster } => {
+                let key = cluster.get_service_key();
+                if let Some(service) = self.service_map.get_mut(&key) {
+                    service.cluster_map.remove(&cluster.cluster_name);
+                    if service.cluster_map.is_empty()
+                        && !service.persistent
+                        && service.instance_size <= 0
+                    {
+                        //没有实例与集群配置的服务按空服务过期清理
+                        self.empty_service_set
+                            .add(now_millis() + self.sys_config.service_time_out_millis, key);
+                    }
+                }
+            }
+            NamingRaftReq::UpdateDrain { drain } => {
+                let key = drain.get_service_key();
+                self.create_empty_service(&key);
+                if drain.expire_time > 0 {
+                    self.drain_timeout_set
+                        .add(drain.expire_time as u64, key.clone());
+                }
+                if let Some(service) = self.service_map.get_mut(&key) {
+                    service
+                        .drain_map
+                        .insert(drain.build_key(), Arc::new(*drain));
+                }
+                self.notify_drain_change(key);
+            }
+            NamingRaftReq::RemoveDrain { drain } => {
+                let key = drain.get_service_key();
+                if let Some(service) = self.service_map.get_mut(&key) {
+                    let drain_key = drain.build_key();
+                    match service.drain_map.get(&drain_key) {
+                        None => return,
+                        //过期移除请求只移除对应的记录,记录已被重新摘流时保留
+                        Some(v) if drain.expire_time > 0 && v.expire_time != drain.expire_time => {
+                            return
+                        }
+                        _ => {}
+                    }
+                    service.drain_map.remove(&drain_key);
+                    if !service.has_active_drain(now_millis_i64())
+                        && service.cluster_map.is_empty()
+                        && !service.persistent
+                        && service.instance_size <= 0
+                    {
+                        self.empty_service_set.add(
+                            now_millis() + self.sys_config.service_time_out_millis,
+                            key.clone(),
+                        );
+                    }
+                    self.notify_drain_change(key);
+                }
+            }
+            NamingRaftReq::UpdateService { service } => {
+                let key = service.to_service_key();
+                self.update_service(*service);
+                if let Some(service) = self.service_map.get_mut(&key) {
+                    service.persistent = true;
+                }
+            }
+            NamingRaftReq::RemoveService { service } => {
+                let key = service.to_service_key();
+                if let Some(service) = self.service_map.get_mut(&key) {
+                    service.persistent = false;
+                }
+                //还有实例或集群配置时只删除持久化的服务定义
+                self.remove_empty_service(key).ok();
+            }
+        }
+    }
+
+    fn get_persistent_services(&self) -> Vec<ServiceDefinitionDo> {
+        self.service_map
+            .values()
+            .filter(|e| e.persistent)
+            .map(|e| e.into())
+            .collect()
+    }
+
+    fn get_drain_list(&self, key: Option<&ServiceKey>) -> Vec<Arc<InstanceDrainDo>> {
+        let now = now_millis_i64();
+        match key {
+            Some(key) => self
+                .service_map
+                .get(key)
+                .map(|e| e.get_drain_list(now))
+                .unwrap_or_default(),
+            None => self
+                .service_map
+                .values()
+                .flat_map(|e| e.get_drain_list(now))
+                .collect(),
+        }
+    }
+
+    fn get_instance_drains(&self) -> Vec<InstanceDrainDo> {
+        let mut list = vec![];
+        for service in self.service_map.values() {
+            for drain in service.drain_map.values() {
+                list.push(drain.as_ref().to_owned());
+            }
+        }
+        list
+    }
+
+    fn get_service_clusters(&self) -> Vec<ServiceClusterDo> {
+        let mut list = vec![];
+        for service in self.service_map.values() {
+            for cluster in service.cluster_map.values() {
+                list.push(cluster.as_ref().to_owned());
+            }
+        }
+        list
+    }
+
+    pub(crate) fn get_service_detail(&self, key: &ServiceKey) -> Option<ServiceDetailDto> {
+        self.service_map.get(key).map(|service| {
+            let mut detail = service.get_service_detail();
+            detail.clusters = Some(service.get_cluster_list());
+            detail
+        })
+    }
+
+    ///
+    /// 本节点负责范围内需要主动健康检查的持久化实例
+    fn get_health_check_instances(&self, key: &ServiceKey) -> Vec<Arc<Instance>> {
+        if let Some(range) = &self.current_range {
+            if !range.is_range(get_hash_value(key) as usize) {
+                return vec![];
+            }
+        }
+        if let Some(service) = self.service_map.get(key) {
+            service
+                .instances
+                .values()
+                .filter(|e| !e.ephemeral)
+                .cloned()
+                .collect()
+        } else {
+            vec![]
+        }
+    }
+
+    ///
+    /// 持久化实例健康状态由raft同步,每个节点应用后只通知本节点订阅者
+    fn update_instance_healthy(
+        &mut self,
+        key: &ServiceKey,
+        results: Vec<(InstanceShortKey, bool)>,
+    ) {
+        let service = if let Some(service) = self.service_map.get_mut(key) {
+            service
+        } else {
+            return;
+        };
+        let mut changed = false;
+        for (short_key, healthy) in results {
+            if let Some(instance) = service.update_instance_healthy(&short_key, healthy) {
+                let mut old = instance.as_ref().clone();
+                old.healthy = !healthy;
+                if let Some(event) =
+                    NamingEvent::diff(key, Some(&old), Some(&instance), false, now_millis_i64())
+                {
+                    self.event_history.record(event);
+                }
+                changed = true;
+            }
+        }
+        if changed {
+            self.notify_to_subscriber(&UpdateInstanceType::UpdateValue, key.clone());
+        }
+    }
+
+    fn get_persistent_instances(&self) -> Vec<PersistentInstanceDo> {
+        let mut list = vec![];
+        for service in self.service_map.values() {
+            for instance in service.instances.values() {
+                if !instance.ephemeral {
+                    list.push(instance.as_ref().into());
+                }
+            }
+        }
+        list
+    }
+
+    fn build_persistent_snapshot(&self, writer: Addr<SnapshotWriterActor>) -> anyhow::Result<()> {
+        //服务定义放在最前面,加载快照时先于实例恢复
+        for value in self.get_persistent_services() {
+            let record = SnapshotRecordDto {
+                tree: NAMING_SERVICE_TREE_NAME.clone(),
+                key: value.build_key().into_bytes(),
+                value: value.to_bytes()?,
+                op_type: 0,
+            };
+            writer.do_send(SnapshotWriterRequest::Record(record));
+        }
+        for value in self.get_persistent_instances() {
+            let record = SnapshotRecordDto {
+                tree: NAMING_PERSISTENT_INSTANCE_TREE_NAME.clone(),
+                key: value.build_key().into_bytes(),
+                value: value.to_bytes()?,
+                op_type: 0,
+            };
+            writer.do_send(SnapshotWriterRequest::Record(record));
+        }
+        for value in self.get_service_clusters() {
+            let record = SnapshotRecordDto {
+                tree: NAMING_SERVICE_CLUSTER_TREE_NAME.clone(),
+                key: value.build_key().into_bytes(),
+                value: value.to_bytes()?,
+                op_type: 0,
+            };
+            writer.do_send(SnapshotWriterRequest::Record(record));
+        }
+        for value in self.get_instance_drains() {
+            let record = SnapshotRecordDto {
+                tree: NAMING_INSTANCE_DRAIN_TREE_NAME.clone(),
+                key: value.build_key().into_bytes(),
+                value: value.to_bytes()?,
+                op_type: 0,
+            };
+            writer.do_send(SnapshotWriterRequest::Record(record));
+        }
+        Ok(())
+    }
+
+    fn transfer_backup(&self, writer: Addr<TransferWriterActor>) -> anyhow::Result<()> {
+        for value in self.get_persistent_services() {
+            let record = TransferRecordDto {
+                table_name: Some(NAMING_SERVICE_TREE_NAME.clone()),
+                key: value.build_key().into_bytes(),
+                value: value.to_bytes()?,
+                table_id: 0,
+            };
+            writer.do_send(TransferWriterRequest::AddRecord(record));
+        }
+        for value in self.get_persistent_instances() {
+            let record = TransferRecordDto {
+                table_name: Some(NAMING_PERSISTENT_INSTANCE_TREE_NAME.clone()),
+                key: value.build_key().into_bytes(),
+                value: value.to_bytes()?,
+                table_id: 0,
+            };
+            writer.do_send(TransferWriterRequest::AddRecord(record));
+        }
+        for value in self.get_service_clusters() {
+            let record = TransferRecordDto {
+                table_name: Some(NAMING_SERVICE_CLUSTER_TREE_NAME.clone()),
+                key: value.build_key().into_bytes(),
+                value: value.to_bytes()?,
+                table_id: 0,
+            };
+            writer.do_send(TransferWriterRequest::AddRecord(record));
+        }
+        for value in self.get_instance_drains() {
+            let record = TransferRecordDto {
+                table_name: Some(NAMING_INSTANCE_DRAIN_TREE_NAME.clone()),
+                key: value.build_key().into_bytes(),
+                value: value.to_bytes()?,
+                table_id: 0,
+            };
+            writer.do_send(TransferWriterRequest::AddRecord(record));
+        }
+        Ok(())
+    }
+}
+
+impl Handler<NamingRaftReq> for NamingActor {
+    type Result = anyhow::Result<NamingRaftResult>;
+
+    fn handle(&mut self, msg: NamingRaftReq, _ctx: &mut Self::Context) -> Self::Result {
+        self.apply_persistent_req(msg);
+        Ok(NamingRaftResult::None)
+    }
+}
+
+impl Handler<RaftApplyDataRequest> for NamingActor {
+    type Result = anyhow::Result<RaftApplyDataResponse>;
+
+    fn handle(&mut self, msg: RaftApplyDataRequest, _ctx: &mut Self::Context) -> Self::Result {
+        match msg {
+            RaftApplyDataRequest::BuildSnapshot(writer) => {
+                self.build_persistent_snapshot(writer)?;
+            }
+            RaftApplyDataRequest::LoadSnapshotRecord(record) => {
+                if record.tree.as_str() == NAMING_SERVICE_TREE_NAME.as_str() {
+                    let service = ServiceDefinitionDo::from_bytes(&record.value)?;
+                    self.apply_persistent_req(NamingRaftReq::UpdateService {
+                        service: Box::new(service.into()),
+                    });
+                } else if record.tree.as_str() == NAMING_SERVICE_CLUSTER_TREE_NAME.as_str() {
+                    let cluster = ServiceClusterDo::from_bytes(&record.value)?;
+                    self.apply_persistent_req(NamingRaftReq::UpdateCluster {
+                        cluster: Box::new(cluster),
+                    });
+                } else if record.tree.as_str() == NAMING_INSTANCE_DRAIN_TREE_NAME.as_str() {
+                    let drain = InstanceDrainDo::from_bytes(&record.value)?;
+                    self.apply_persistent_req(NamingRaftReq::UpdateDrain {
+                        drain: Box::new(drain),
+                    });
+                } else {
+                    let instance = PersistentInstanceDo::from_bytes(&record.value)?;
+                    self.apply_persistent_req(NamingRaftReq::Update {
+                        instance: Box::new(instance),
+                        tag: None,
+                    });
+                }
+            }
+            RaftApplyDataRequest::LoadCompleted => {}
+        };
+        Ok(RaftApplyDataResponse::None)
+    }
+}
+
+impl Handler<TransferDataRequest> for NamingActor {
+    type Result = anyhow::Result<TransferDataResponse>;
+
+    fn handle(&mut self, msg: TransferDataRequest, _ctx: &mut Self::Context) -> Self::Result {
+        match msg {
+            TransferDataRequest::Backup(writer_actor, param) => {
+                if param.naming {
+                    self.transfer_backup(writer_actor)?;
+                }
+                Ok(TransferDataResponse::None)
+            }
+        }
+    }
+}
+
 #[actix_rt::test]
 async fn query_healthy_instances() {
     use super::*;
@@ -1424,3 +2166,167 @@ fn test_remove_has_instance_service() {
     assert!(naming.remove_empty_service(service_key.clone()).is_ok());
     assert!(naming.namespace_index.service_size == 0);
 }
+
+#[test]
+fn test_drain_instance() {
+    let mut naming = NamingActor::new();
+    let mut instance = Instance::new("127.0.0.1".to_owned(), 8080);
+    instance.namespace_id = Arc::new("public".to_owned());
+    instance.service_name = Arc::new("foo".to_owned());
+    instance.group_name = Arc::new("DEFUALT".to_owned());
+    instance.cluster_name = "DEFUALT".to_owned();
+    instance.init();
+    let service_key = instance.get_service_key();
+    naming.update_instance(&service_key, instance, None, false);
+    assert_eq!(naming.get_instance_list(&service_key, "", false).len(), 1);
+
+    let drain = InstanceDrainDo {
+        namespace_id: "public".to_owned(),
+        group_name: "DEFUALT".to_owned(),
+        service_name: "foo".to_owned(),
+        ip: "127.0.0.1".to_owned(),
+        ..Default::default()
+    };
+    naming.apply_persistent_req(NamingRaftReq::UpdateDrain {
+        drain: Box::new(drain.clone()),
+    });
+    assert!(naming.get_instance_list(&service_key, "", false).is_empty());
+    assert_eq!(naming.get_drain_list(Some(&service_key)).len(), 1);
+    assert!(naming.remove_empty_service(service_key.clone()).is_err());
+
+    naming.apply_persistent_req(NamingRaftReq::RemoveDrain {
+        drain: Box::new(drain),
+    });
+    let list = naming.get_instance_list(&service_key, "", false);
+    assert_eq!(list.len(), 1);
+    assert!(list[0].enabled);
+}
+
+#[test]
+fn test_expired_drain_not_block_remove_service() {
+    let mut naming = NamingActor::new();
+    let service_key = ServiceKey::new("public", "DEFUALT", "foo");
+    let drain = InstanceDrainDo {
+        namespace_id: "public".to_owned(),
+        group_name: "DEFUALT".to_owned(),
+        service_name: "foo".to_owned(),
+        ip: "127.0.0.1".to_owned(),
+        expire_time: now_millis_i64() - 1,
+        ..Default::default()
+    };
+    naming.apply_persistent_req(NamingRaftReq::UpdateDrain {
+        drain: Box::new(drain.clone()),
+    });
+    assert!(naming.get_drain_list(Some(&service_key)).is_empty());
+    assert!(naming.remove_empty_service(service_key.clone()).is_ok());
+    assert!(!naming.service_map.contains_key(&service_key));
+
+    //过期移除请求不能移除重新摘流的记录
+    naming.apply_persistent_req(NamingRaftReq::UpdateDrain {
+        drain: Box::new(InstanceDrainDo {
+            expire_time: now_millis_i64() + 60_000,
+            ..drain.clone()
+        }),
+    });
+    naming.apply_persistent_req(NamingRaftReq::RemoveDrain {
+        drain: Box::new(drain),
+    });
+    assert_eq!(naming.get_drain_list(Some(&service_key)).len(), 1);
+}
+
+#[test]
+fn test_service_checksum_cached() {
+    let mut naming = NamingActor::new();
+    let mut instance = Instance::new("127.0.0.1".to_owned(), 8080);
+    instance.namespace_id = Arc::new("public".to_owned());
+    instance.service_name = Arc::new("foo".to_owned());
+    instance.group_name = Arc::new("DEFUALT".to_owned());
+    instance.cluster_name = "DEFUALT".to_owned();
+    instance.init();
+    let service_key = instance.get_service_key();
+    naming.create_empty_service(&service_key);
+    let empty_check_sum = naming.service_map.get(&service_key).unwrap().check_sum;
+    naming.update_instance(&service_key, instance, None, false);
+    let check_sum = naming.service_map.get(&service_key).unwrap().check_sum;
+    assert_ne!(check_sum, empty_check_sum);
+    assert_eq!(
+        naming
+            .get_service_info(&service_key, "".to_owned(), false, None)
+            .checksum,
+        check_sum
+    );
+
+    //摘流变更后重新计算
+    naming.apply_persistent_req(NamingRaftReq::UpdateDrain {
+        drain: Box::new(InstanceDrainDo {
+            namespace_id: "public".to_owned(),
+            group_name: "DEFUALT".to_owned(),
+            service_name: "foo".to_owned(),
+            ip: "127.0.0.1".to_owned(),
+            ..Default::default()
+        }),
+    });
+    assert_ne!(
+        naming.service_map.get(&service_key).unwrap().check_sum,
+        check_sum
+    );
+}
+
+#[test]
+fn test_consumer_label_index() {
+    let mut naming = NamingActor::new();
+    let mut instance = Instance::new("127.0.0.1".to_owned(), 8080);
+    instance.namespace_id = Arc::new("public".to_owned());
+    instance.service_name = Arc::new("foo".to_owned());
+    instance.group_name = Arc::new("DEFUALT".to_owned());
+    instance.cluster_name = "DEFUALT".to_owned();
+    instance.metadata = Arc::new(HashMap::from([("zone".to_owned(), "a".to_owned())]));
+    instance.init();
+    let service_key = instance.get_service_key();
+    naming.update_instance(&service_key, instance.clone(), None, false);
+    let labels = naming
+        .consumer_label_index
+        .get_labels(&service_key.namespace_id, "127.0.0.1");
+    assert_eq!(labels.get("zone").map(|v| v.as_str()), Some("a"));
+    assert!(naming
+        .consumer_label_index
+        .get_labels(&service_key.namespace_id, "127.0.0.2")
+        .is_empty());
+
+    naming.remove_instance(&service_key, &instance.get_short_key(), None);
+    assert!(naming
+        .consumer_label_index
+        .get_labels(&service_key.namespace_id, "127.0.0.1")
+        .is_empty());
+}
+
+#[test]
+fn test_persistent_instance_healthy() {
+    let mut naming = NamingActor::new();
+    let mut instance = Instance::new("127.0.0.1".to_owned(), 8080);
+    instance.namespace_id = Arc::new("public".to_owned());
+    instance.service_name = Arc::new("mysql".to_owned());
+    instance.group_name = Arc::new("DEFUALT".to_owned());
+    instance.cluster_name = "DEFUALT".to_owned();
+    instance.ephemeral = false;
+    naming.apply_persistent_req(NamingRaftReq::Update {
+        instance: Box::new(PersistentInstanceDo::from(&instance)),
+        tag: None,
+    });
+    let service_key = instance.get_service_key();
+    assert_eq!(
+        naming
+            .service_map
+            .get(&service_key)
+            .unwrap()
+            .healthy_instance_size,
+        1
+    );
+
+    naming.apply_persistent_req(NamingRaftReq::UpdateHealthy {
+        results: vec![(instance.get_instance_key(), false)],
+    });
+    let service = naming.service_map.get(&service_key).unwrap();
+    assert_eq!(service.instance_size, 1);
+    assert_eq!(service.healthy_instance_size, 0);
+}
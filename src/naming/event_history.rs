@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::model::{Instance, ServiceKey};
+use crate::common::actor_utils::create_actor_at_thread;
+use crate::common::cycle_queue::CycleQueue;
+
+pub const DEFAULT_EVENT_HISTORY_SIZE: usize = 100;
+/// 事件日志文件默认轮转大小(MB)
+pub const DEFAULT_EVENT_LOG_MAX_SIZE_MB: u64 = 100;
+/// 轮转后保留的历史文件数,文件名为 file.1 ~ file.N
+const EVENT_LOG_BACKUP_COUNT: usize = 5;
+const EVENT_LOG_FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NamingEventType {
+    Register,
+    Deregister,
+    /// 心跳超时被移除
+    Expire,
+    HealthyChange,
+    /// 权重、上下线或metadata变更
+    Update,
+}
+
+///
+/// 实例变更事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamingEvent {
+    pub event_type: NamingEventType,
+    pub namespace_id: Arc<String>,
+    pub group_name: Arc<String>,
+    pub service_name: Arc<String>,
+    /// ip:port
+    pub instance_key: String,
+    pub cluster_name: String,
+    pub old_healthy: Option<bool>,
+    pub new_healthy: Option<bool>,
+    pub old_weight: Option<f32>,
+    pub new_weight: Option<f32>,
+    pub old_enabled: Option<bool>,
+    pub new_enabled: Option<bool>,
+    pub metadata_changed: bool,
+    /// 实例归属的节点id
+    pub source_node: u64,
+    pub client_id: Arc<String>,
+    pub time: i64,
+}
+
+impl NamingEvent {
+    ///
+    /// 根据变更前后的实例生成事件,关注的属性都没有变化时返回None
+    pub fn diff(
+        key: &ServiceKey,
+        old: Option<&Instance>,
+        new: Option<&Instance>,
+        expire: bool,
+        time: i64,
+    ) -> Option<Self> {
+        let (event_type, instance) = match (old, new) {
+            (None, Some(new)) => (NamingEventType::Register, new),
+            (Some(old), None) if expire => (NamingEventType::Expire, old),
+            (Some(old), None) => (NamingEventType::Deregister, old),
+            (Some(old), Some(new)) => {
+                let healthy_changed = old.healthy != new.healthy;
+                let value_changed = old.weight != new.weight
+                    || old.enabled != new.enabled
+                    || old.metadata != new.metadata;
+                if value_changed {
+                    (NamingEventType::Update, new)
+                } else if healthy_changed {
+                    (NamingEventType::HealthyChange, new)
+                } else {
+                    return None;
+                }
+            }
+            (None, None) => return None,
+        };
+        Some(Self {
+            event_type,
+            namespace_id: key.namespace_id.clone(),
+            group_name: key.group_name.clone(),
+            service_name: key.service_name.clone(),
+            instance_key: format!("{}:{}", &instance.ip, instance.port),
+            cluster_name: instance.cluster_name.to_owned(),
+            old_healthy: old.map(|e| e.healthy),
+            new_healthy: new.map(|e| e.healthy),
+            old_weight: old.map(|e| e.weight),
+            new_weight: new.map(|e| e.weight),
+            old_enabled: old.map(|e| e.enabled),
+            new_enabled: new.map(|e| e.enabled),
+            metadata_changed: match (old, new) {
+                (Some(old), Some(new)) => old.metadata != new.metadata,
+                _ => false,
+            },
+            source_node: instance.from_cluster,
+            client_id: instance.client_id.clone(),
+            time,
+        })
+    }
+}
+
+///
+/// 实例变更事件日志写入器,运行在独立线程,文件io不阻塞NamingActor;文件超过max_size后轮转
+pub struct NamingEventLogWriter {
+    path: String,
+    max_size: u64,
+    size: u64,
+    writer: BufWriter<File>,
+}
+
+impl NamingEventLogWriter {
+    ///
+    /// max_size为0时不轮转
+    pub fn new(path: &str, max_size: u64) -> anyhow::Result<Self> {
+        let (writer, size) = Self::open(path)?;
+        Ok(Self {
+            path: path.to_owned(),
+            max_size,
+            size,
+            writer,
+        })
+    }
+
+    fn open(path: &str) -> anyhow::Result<(BufWriter<File>, u64)> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok((BufWriter::new(file), size))
+    }
+
+    fn write_line(&mut self, line: &str) -> anyhow::Result<()> {
+        let line_size = line.len() as u64 + 1;
+        if self.max_size > 0 && self.size > 0 && self.size + line_size > self.max_size {
+            self.rotate()?;
+        }
+        writeln!(self.writer, "{}", line)?;
+        self.size += line_size;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> anyhow::Result<()> {
+        self.writer.flush()?;
+        for i in (1..EVENT_LOG_BACKUP_COUNT).rev() {
+            let from = format!("{}.{}", &self.path, i);
+            if Path::new(&from).exists() {
+                std::fs::rename(&from, format!("{}.{}", &self.path, i + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, format!("{}.1", &self.path))?;
+        let (writer, size) = Self::open(&self.path)?;
+        self.writer = writer;
+        self.size = size;
+        Ok(())
+    }
+
+    fn flush(&mut self) {
+        if let Err(err) = self.writer.flush() {
+            log::warn!("flush naming event log error,{}", err);
+        }
+    }
+}
+
+impl Actor for NamingEventLogWriter {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(EVENT_LOG_FLUSH_INTERVAL, |act, _ctx| act.flush());
+    }
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        self.flush();
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct NamingEventLogWrite(pub Arc<NamingEvent>);
+
+impl Handler<NamingEventLogWrite> for NamingEventLogWriter {
+    type Result = ();
+
+    fn handle(&mut self, msg: NamingEventLogWrite, _ctx: &mut Self::Context) -> Self::Result {
+        let line = match serde_json::to_string(msg.0.as_ref()) {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        if let Err(err) = self.write_line(&line) {
+            log::warn!("write naming event log error,{}", err);
+        }
+    }
+}
+
+///
+/// 按服务维护有界的实例变更事件环,可选追加写入本地文件(每行一个json)
+#[derive(Debug)]
+pub struct NamingEventHistory {
+    capacity: usize,
+    node_id: u64,
+    service_events: HashMap<ServiceKey, CycleQueue<Arc<NamingEvent>>>,
+    log_writer: Option<Addr<NamingEventLogWriter>>,
+}
+
+impl Default for NamingEventHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_HISTORY_SIZE)
+    }
+}
+
+impl NamingEventHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            node_id: 0,
+            service_events: Default::default(),
+            log_writer: None,
+        }
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.service_events.clear();
+    }
+
+    pub fn set_node_id(&mut self, node_id: u64) {
+        self.node_id = node_id;
+    }
+
+    pub fn open_log_file(&mut self, path: &str, max_size: u64) -> anyhow::Result<()> {
+        let writer = NamingEventLogWriter::new(path, max_size)?;
+        self.log_writer = Some(create_actor_at_thread(writer));
+        Ok(())
+    }
+
+    pub fn record(&mut self, mut event: NamingEvent) {
+        if event.source_node == 0 {
+            //本节点管理的实例
+            event.source_node = self.node_id;
+        }
+        let event = Arc::new(event);
+        if let Some(writer) = self.log_writer.as_ref() {
+            writer.do_send(NamingEventLogWrite(event.clone()));
+        }
+        if self.capacity == 0 {
+            return;
+        }
+        let key = ServiceKey::new_by_arc(
+            event.namespace_id.clone(),
+            event.group_name.clone(),
+            event.service_name.clone(),
+        );
+        let capacity = self.capacity;
+        self.service_events
+            .entry(key)
+            .or_insert_with(|| CycleQueue::new(capacity))
+            .push(event);
+    }
+
+    ///
+    /// 查询服务最近的变更事件,按时间倒序
+    pub fn query(&self, key: &ServiceKey, limit: usize) -> Vec<Arc<NamingEvent>> {
+        let queue = if let Some(queue) = self.service_events.get(key) {
+            queue
+        } else {
+            return vec![];
+        };
+        let len = queue.len();
+        let limit = if limit == 0 { len } else { limit.min(len) };
+        (0..limit)
+            .filter_map(|i| queue.get(len - 1 - i).cloned())
+            .collect()
+    }
+
+    pub fn remove_service(&mut self, key: &ServiceKey) {
+        self.service_events.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naming_event_history() {
+        let key = ServiceKey::new("public", "DEFAULT_GROUP", "foo");
+        let mut history = NamingEventHistory::new(3);
+        history.set_node_id(2);
+        let old = Instance::new("10.0.0.1".to_owned(), 8080);
+        let mut new = old.clone();
+        assert!(NamingEvent::diff(&key, Some(&old), Some(&new), false, 1).is_none());
+
+        history.record(NamingEvent::diff(&key, None, Some(&old), false, 1).unwrap());
+        new.healthy = !old.healthy;
+        history.record(NamingEvent::diff(&key, Some(&old), Some(&new), false, 2).unwrap());
+        new.weight = 0f32;
+        history.record(NamingEvent::diff(&key, Some(&old), Some(&new), false, 3).unwrap());
+        new.from_cluster = 3;
+        history.record(NamingEvent::diff(&key, Some(&new), None, true, 4).unwrap());
+
+        let list = history.query(&key, 0);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0].event_type, NamingEventType::Expire);
+        assert_eq!(list[0].source_node, 3);
+        assert_eq!(list[1].event_type, NamingEventType::Update);
+        assert_eq!(list[1].new_weight, Some(0f32));
+        assert_eq!(list[2].event_type, NamingEventType::HealthyChange);
+        assert_eq!(list[2].source_node, 2);
+        assert_eq!(history.query(&key, 1).len(), 1);
+
+        history.remove_service(&key);
+        assert!(history.query(&key, 0).is_empty());
+    }
+
+    #[test]
+    fn naming_event_log_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("naming_event.log");
+        let path = path.to_str().unwrap();
+        let mut writer = NamingEventLogWriter::new(path, 20).unwrap();
+        for i in 0..(EVENT_LOG_BACKUP_COUNT + 2) {
+            writer.write_line(&format!("event-{:08}", i)).unwrap();
+            writer.write_line(&format!("event-{:08}", i)).unwrap();
+        }
+        writer.flush();
+        // 每个文件只能容纳一行,超出保留数量的历史文件被覆盖
+        let content = std::fs::read_to_string(path).unwrap();
+        assert_eq!(content.lines().count(), 1);
+        let last_backup = format!("{}.{}", path, EVENT_LOG_BACKUP_COUNT);
+        assert!(Path::new(&last_backup).exists());
+        assert!(!Path::new(&format!("{}.{}", path, EVENT_LOG_BACKUP_COUNT + 1)).exists());
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use super::{
@@ -12,11 +13,15 @@ impl InstanceFilterUtils {
         instances.into_iter().filter(|i| i.healthy).collect()
     }
     pub fn default_instance_filter(
-        all_instances: Vec<Arc<Instance>>,
+        mut all_instances: Vec<Arc<Instance>>,
         metadata: Option<ServiceMetadata>,
         filter_headlthy: bool,
+        consumer_labels: Option<&HashMap<String, String>>,
     ) -> Vec<Arc<Instance>> {
         if let Some(metadata) = metadata {
+            if let Some(selector) = metadata.selector.as_ref() {
+                all_instances = selector.select(all_instances, consumer_labels);
+            }
             let original_total = all_instances.len();
             // all_instances = from metadata select
             let mut healthy_count = 0;
@@ -53,11 +58,21 @@ impl InstanceFilterUtils {
         }
     }
 
+    ///
+    /// consumer_labels为None时不应用label选择器,由推送时按连接labels再过滤
     pub fn default_service_filter(
         mut service_info: ServiceInfo,
         metadata: Option<ServiceMetadata>,
         filter_headlthy: bool,
+        consumer_labels: Option<&HashMap<String, String>>,
     ) -> ServiceInfo {
+        if let Some(selector) = metadata.as_ref().and_then(|e| e.selector.as_ref()) {
+            if selector.is_label() && consumer_labels.is_none() {
+                service_info.selector = Some(selector.clone());
+            } else if let Some(hosts) = service_info.hosts.take() {
+                service_info.hosts = Some(selector.select(hosts, consumer_labels));
+            }
+        }
         if let (Some(all_instances), Some(metadata)) = (service_info.hosts.as_ref(), metadata) {
             let original_total = all_instances.len();
             // all_instances = from metadata select
@@ -95,4 +110,19 @@ impl InstanceFilterUtils {
         }
         service_info
     }
+
+    ///
+    /// 按订阅连接的labels应用推迟的label选择器
+    pub fn consumer_service_filter(
+        service_info: &ServiceInfo,
+        consumer_labels: &HashMap<String, String>,
+    ) -> ServiceInfo {
+        let mut service_info = service_info.clone();
+        if let Some(selector) = service_info.selector.take() {
+            service_info.hosts = service_info
+                .hosts
+                .map(|hosts| selector.select(hosts, Some(consumer_labels)));
+        }
+        service_info
+    }
 }
@@ -0,0 +1,363 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::prelude::*;
+use bean_factory::{bean, Inject};
+use tokio::net::TcpStream;
+
+use self::model::{HealthCheckConfigDo, HealthCheckConfigDto, CHECK_TYPE_TCP};
+use crate::common::constant::NAMING_HEALTH_CHECK_TREE_NAME;
+use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
+use crate::naming::model::{Instance, InstanceShortKey, ServiceKey};
+use crate::naming::persistent_instance::NamingRaftReq;
+use crate::now_millis_i64;
+use crate::raft::cluster::route::RaftRequestRoute;
+use crate::raft::db::table_store::{RaftTableStore, TABLE_RELOAD_INTERVAL};
+
+pub mod model;
+
+///
+/// 持久化实例的主动健康检查
+/// 检查配置通过raft表T_NAMING_HEALTH_CHECK在集群内同步;
+/// 每个节点只检查自己负责范围(ProcessRange)内服务的实例,健康状态变更通过raft提交后在各节点应用
+#[bean(inject)]
+pub struct NamingHealthCheckManager {
+    store: RaftTableStore<HealthCheckConfigDo>,
+    naming_addr: Option<Addr<NamingActor>>,
+    raft_request_route: Option<Arc<RaftRequestRoute>>,
+    client: reqwest::Client,
+    configs: Vec<HealthCheckConfigDo>,
+    last_load_time: i64,
+    next_check_time: HashMap<String, i64>,
+    checking: HashSet<String>,
+}
+
+impl Default for NamingHealthCheckManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NamingHealthCheckManager {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap_or_default();
+        Self {
+            store: RaftTableStore::new(NAMING_HEALTH_CHECK_TREE_NAME.clone()),
+            naming_addr: None,
+            raft_request_route: None,
+            client,
+            configs: vec![],
+            last_load_time: 0,
+            next_check_time: HashMap::new(),
+            checking: HashSet::new(),
+        }
+    }
+
+    fn build_key_do(dto: &HealthCheckConfigDto) -> HealthCheckConfigDo {
+        let service_key = dto.to_service_key();
+        HealthCheckConfigDo {
+            namespace_id: service_key.namespace_id.as_ref().to_owned(),
+            group_name: service_key.group_name.as_ref().to_owned(),
+            service_name: service_key.service_name.as_ref().to_owned(),
+            cluster_name: dto.cluster_name.clone().unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    async fn set_config(
+        store: RaftTableStore<HealthCheckConfigDo>,
+        dto: HealthCheckConfigDto,
+    ) -> anyhow::Result<HealthCheckResult> {
+        let key_do = Self::build_key_do(&dto);
+        let mut value = match store.get(key_do.build_key()).await? {
+            Some(v) => v,
+            None => HealthCheckConfigDo {
+                check_type: CHECK_TYPE_TCP.to_owned(),
+                enable: true,
+                ..key_do
+            },
+        };
+        if let Some(v) = dto.check_type {
+            value.check_type = v;
+        }
+        if let Some(v) = dto.interval_millis {
+            value.interval_millis = v;
+        }
+        if let Some(v) = dto.timeout_millis {
+            value.timeout_millis = v;
+        }
+        if let Some(v) = dto.port {
+            value.port = v;
+        }
+        if let Some(v) = dto.path {
+            value.path = v.trim().to_owned();
+        }
+        if let Some(v) = dto.expected_code {
+            value.expected_code = v;
+        }
+        if let Some(v) = dto.enable {
+            value.enable = v;
+        }
+        value.check_valid()?;
+        value.gmt_modified = now_millis_i64();
+        store.save(&value).await?;
+        Ok(HealthCheckResult::Config(value.into()))
+    }
+
+    async fn remove_config(
+        store: RaftTableStore<HealthCheckConfigDo>,
+        dto: HealthCheckConfigDto,
+    ) -> anyhow::Result<HealthCheckResult> {
+        store.remove(Self::build_key_do(&dto).build_key()).await?;
+        Ok(HealthCheckResult::None)
+    }
+
+    async fn probe(
+        client: reqwest::Client,
+        config: Arc<HealthCheckConfigDo>,
+        instance: Arc<Instance>,
+        cluster_port: Option<u32>,
+    ) -> (InstanceShortKey, bool) {
+        let timeout = Duration::from_millis(config.timeout_millis);
+        let port = config.check_port(&instance, cluster_port);
+        let healthy = if config.is_http() {
+            let url = format!("http://{}:{}{}", &instance.ip, port, &config.path);
+            match client.get(url).timeout(timeout).send().await {
+                Ok(resp) => resp.status().as_u16() as u32 == config.expected_code,
+                Err(_) => false,
+            }
+        } else {
+            let addr = format!("{}:{}", &instance.ip, port);
+            matches!(
+                tokio::time::timeout(timeout, TcpStream::connect(addr)).await,
+                Ok(Ok(_))
+            )
+        };
+        (instance.get_short_key(), healthy)
+    }
+
+    ///
+    /// 检查一个配置覆盖的实例,健康状态有变化的实例通过raft提交
+    async fn check_service(
+        naming_addr: Addr<NamingActor>,
+        raft_request_route: Arc<RaftRequestRoute>,
+        client: reqwest::Client,
+        config: Arc<HealthCheckConfigDo>,
+        exclude_clusters: HashSet<String>,
+    ) -> anyhow::Result<()> {
+        let service_key = config.get_service_key();
+        let (instances, check_ports) = match naming_addr
+            .send(NamingCmd::QueryHealthCheckInstances(service_key.clone()))
+            .await??
+        {
+            NamingResult::HealthCheckInstances(list, check_ports) => (list, check_ports),
+            _ => (vec![], HashMap::new()),
+        };
+        let instances = instances
+            .into_iter()
+            .filter(|e| {
+                if config.cluster_name.is_empty() {
+                    !exclude_clusters.contains(&e.cluster_name)
+                } else {
+                    e.cluster_name == config.cluster_name
+                }
+            })
+            .collect::<Vec<_>>();
+        if instances.is_empty() {
+            return Ok(());
+        }
+        let futures = instances
+            .iter()
+            .map(|e| {
+                let cluster_port = check_ports.get(&e.cluster_name).cloned();
+                Self::probe(client.clone(), config.clone(), e.clone(), cluster_port)
+            })
+            .collect::<Vec<_>>();
+        let results = futures_util::future::join_all(futures).await;
+        let results = instances
+            .iter()
+            .zip(results)
+            .filter(|(instance, (_, healthy))| instance.healthy != *healthy)
+            .map(|(instance, (_, healthy))| (instance.get_instance_key(), healthy))
+            .collect::<Vec<_>>();
+        if !results.is_empty() {
+            raft_request_route
+                .request_naming(NamingRaftReq::UpdateHealthy { results })
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn load_configs(&mut self, ctx: &mut Context<Self>) {
+        self.store.clone().reload(self, ctx, |act, configs| {
+            let keys: HashSet<String> = configs.iter().map(|e| e.build_key()).collect();
+            act.next_check_time.retain(|k, _| keys.contains(k));
+            act.configs = configs;
+        });
+    }
+
+    fn do_check(&mut self, ctx: &mut Context<Self>) {
+        let now = now_millis_i64();
+        if now - self.last_load_time >= TABLE_RELOAD_INTERVAL.as_millis() as i64 {
+            self.last_load_time = now;
+            self.load_configs(ctx);
+        }
+        let (naming_addr, raft_request_route) = match (&self.naming_addr, &self.raft_request_route)
+        {
+            (Some(a), Some(b)) => (a.clone(), b.clone()),
+            _ => return,
+        };
+        //单独配置了检查的集群不再使用服务级别的配置
+        let cluster_configs: HashSet<(ServiceKey, String)> = self
+            .configs
+            .iter()
+            .filter(|e| e.enable && !e.cluster_name.is_empty())
+            .map(|e| (e.get_service_key(), e.cluster_name.clone()))
+            .collect();
+        for config in &self.configs {
+            if !config.enable {
+                continue;
+            }
+            let key = config.build_key();
+            if self.checking.contains(&key)
+                || self.next_check_time.get(&key).cloned().unwrap_or_default() > now
+            {
+                continue;
+            }
+            self.next_check_time
+                .insert(key.clone(), now + config.interval_millis as i64);
+            self.checking.insert(key.clone());
+            let exclude_clusters = if config.cluster_name.is_empty() {
+                let service_key = config.get_service_key();
+                cluster_configs
+                    .iter()
+                    .filter(|(s, _)| s == &service_key)
+                    .map(|(_, c)| c.to_owned())
+                    .collect()
+            } else {
+                HashSet::new()
+            };
+            Self::check_service(
+                naming_addr.clone(),
+                raft_request_route.clone(),
+                self.client.clone(),
+                Arc::new(config.clone()),
+                exclude_clusters,
+            )
+            .into_actor(self)
+            .map(move |r, act, _ctx| {
+                if let Err(err) = r {
+                    log::warn!("health check error,{}", err);
+                }
+                act.checking.remove(&key);
+            })
+            .spawn(ctx);
+        }
+    }
+
+    fn heartbeat(&mut self, ctx: &mut Context<Self>) {
+        ctx.run_later(Duration::from_millis(1000), |act, ctx| {
+            act.do_check(ctx);
+            act.heartbeat(ctx);
+        });
+    }
+}
+
+impl Actor for NamingHealthCheckManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        log::info!("NamingHealthCheckManager started");
+        self.heartbeat(ctx);
+    }
+}
+
+impl Inject for NamingHealthCheckManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        _ctx: &mut Self::Context,
+    ) {
+        self.store.inject(&factory_data);
+        self.naming_addr = factory_data.get_actor();
+        self.raft_request_route = factory_data.get_bean();
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<HealthCheckResult>")]
+pub enum HealthCheckReq {
+    Set(Box<HealthCheckConfigDto>),
+    Remove(Box<HealthCheckConfigDto>),
+    QueryList,
+}
+
+pub enum HealthCheckResult {
+    None,
+    Config(HealthCheckConfigDto),
+    ConfigList(Vec<HealthCheckConfigDto>),
+}
+
+impl Handler<HealthCheckReq> for NamingHealthCheckManager {
+    type Result = ResponseActFuture<Self, anyhow::Result<HealthCheckResult>>;
+
+    fn handle(&mut self, msg: HealthCheckReq, _ctx: &mut Self::Context) -> Self::Result {
+        let store = self.store.clone();
+        let fut = async move {
+            match msg {
+                HealthCheckReq::Set(dto) => Self::set_config(store, *dto).await,
+                HealthCheckReq::Remove(dto) => Self::remove_config(store, *dto).await,
+                HealthCheckReq::QueryList => {
+                    let list = store.query_list().await?;
+                    Ok(HealthCheckResult::ConfigList(
+                        list.into_iter().map(|e| e.into()).collect(),
+                    ))
+                }
+            }
+        }
+        .into_actor(self)
+        .map(|r, act, _ctx| {
+            //配置变更后下次检查时重新加载
+            if let Ok(HealthCheckResult::Config(_) | HealthCheckResult::None) = &r {
+                act.last_load_time = 0;
+            }
+            r
+        });
+        Box::pin(fut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[actix_rt::test]
+    async fn tcp_probe() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port() as u32;
+        let mut config = HealthCheckConfigDo {
+            service_name: "mysql".to_owned(),
+            check_type: CHECK_TYPE_TCP.to_owned(),
+            ..Default::default()
+        };
+        config.check_valid().unwrap();
+        let config = Arc::new(config);
+        let client = reqwest::Client::new();
+        let instance = Arc::new(Instance::new("127.0.0.1".to_owned(), port));
+        let (_, healthy) =
+            NamingHealthCheckManager::probe(client.clone(), config.clone(), instance, None).await;
+        assert!(healthy);
+        drop(listener);
+        let instance = Arc::new(Instance::new("127.0.0.1".to_owned(), port));
+        let (_, healthy) = NamingHealthCheckManager::probe(client, config, instance, None).await;
+        assert!(!healthy);
+    }
+}
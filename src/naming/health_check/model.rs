@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::naming::model::{Instance, ServiceKey};
+use crate::naming::NamingUtils;
+use crate::raft::db::table_store::TableValue;
+
+pub const CHECK_TYPE_TCP: &str = "TCP";
+pub const CHECK_TYPE_HTTP: &str = "HTTP";
+
+pub const DEFAULT_CHECK_INTERVAL_MILLIS: u64 = 5000;
+pub const MIN_CHECK_INTERVAL_MILLIS: u64 = 1000;
+pub const DEFAULT_CHECK_TIMEOUT_MILLIS: u64 = 2000;
+pub const MIN_CHECK_TIMEOUT_MILLIS: u64 = 100;
+pub const DEFAULT_EXPECTED_CODE: u32 = 200;
+
+///
+/// 服务主动健康检查配置
+/// cluster_name为空时对服务下未单独配置的集群生效
+#[derive(Clone, PartialEq, prost_derive::Message, Deserialize, Serialize)]
+pub struct HealthCheckConfigDo {
+    #[prost(string, tag = "1")]
+    pub namespace_id: String,
+    #[prost(string, tag = "2")]
+    pub group_name: String,
+    #[prost(string, tag = "3")]
+    pub service_name: String,
+    #[prost(string, tag = "4")]
+    pub cluster_name: String,
+    /// TCP 或 HTTP
+    #[prost(string, tag = "5")]
+    pub check_type: String,
+    #[prost(uint64, tag = "6")]
+    pub interval_millis: u64,
+    #[prost(uint64, tag = "7")]
+    pub timeout_millis: u64,
+    /// 检查端口,为0时使用实例端口
+    #[prost(uint32, tag = "8")]
+    pub port: u32,
+    /// HTTP检查的请求路径
+    #[prost(string, tag = "9")]
+    pub path: String,
+    /// HTTP检查期望的响应码
+    #[prost(uint32, tag = "10")]
+    pub expected_code: u32,
+    #[prost(bool, tag = "11")]
+    pub enable: bool,
+    #[prost(int64, tag = "12")]
+    pub gmt_modified: i64,
+}
+
+impl HealthCheckConfigDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        use prost::Message;
+        let mut v = Vec::new();
+        self.encode(&mut v)?;
+        Ok(v)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> anyhow::Result<Self> {
+        use prost::Message;
+        let s = Self::decode(data)?;
+        Ok(s)
+    }
+
+    ///
+    /// 存储key: namespace_id#group_name#service_name#cluster_name
+    pub fn build_key(&self) -> String {
+        format!(
+            "{}#{}#{}#{}",
+            &self.namespace_id, &self.group_name, &self.service_name, &self.cluster_name
+        )
+    }
+
+    pub fn get_service_key(&self) -> ServiceKey {
+        ServiceKey::new(&self.namespace_id, &self.group_name, &self.service_name)
+    }
+
+    pub fn is_http(&self) -> bool {
+        self.check_type == CHECK_TYPE_HTTP
+    }
+
+    ///
+    /// 检查端口优先级: 检查配置端口 > 集群配置的健康检查端口 > 实例端口
+    pub fn check_port(&self, instance: &Instance, cluster_port: Option<u32>) -> u32 {
+        if self.port > 0 {
+            self.port
+        } else {
+            cluster_port.unwrap_or(instance.port)
+        }
+    }
+
+    ///
+    /// 校验并补齐默认值
+    pub fn check_valid(&mut self) -> anyhow::Result<()> {
+        if self.service_name.is_empty() {
+            return Err(anyhow::anyhow!("serviceName is empty"));
+        }
+        self.check_type = self.check_type.trim().to_uppercase();
+        if self.check_type != CHECK_TYPE_TCP && self.check_type != CHECK_TYPE_HTTP {
+            return Err(anyhow::anyhow!(
+                "unknown health check type: {}",
+                &self.check_type
+            ));
+        }
+        if self.interval_millis == 0 {
+            self.interval_millis = DEFAULT_CHECK_INTERVAL_MILLIS;
+        }
+        self.interval_millis = self.interval_millis.max(MIN_CHECK_INTERVAL_MILLIS);
+        if self.timeout_millis == 0 {
+            self.timeout_millis = DEFAULT_CHECK_TIMEOUT_MILLIS;
+        }
+        self.timeout_millis = self
+            .timeout_millis
+            .max(MIN_CHECK_TIMEOUT_MILLIS)
+            .min(self.interval_millis);
+        if self.expected_code == 0 {
+            self.expected_code = DEFAULT_EXPECTED_CODE;
+        }
+        if !self.path.starts_with('/') {
+            self.path = format!("/{}", &self.path);
+        }
+        Ok(())
+    }
+}
+
+impl TableValue for HealthCheckConfigDo {
+    fn table_key(&self) -> String {
+        self.build_key()
+    }
+
+    fn encode_value(&self) -> anyhow::Result<Vec<u8>> {
+        self.to_bytes()
+    }
+
+    fn decode_value(v: &[u8]) -> anyhow::Result<Self> {
+        Self::from_bytes(v)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckConfigDto {
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: Arc<String>,
+    pub cluster_name: Option<String>,
+    pub check_type: Option<String>,
+    pub interval_millis: Option<u64>,
+    pub timeout_millis: Option<u64>,
+    pub port: Option<u32>,
+    pub path: Option<String>,
+    pub expected_code: Option<u32>,
+    pub enable: Option<bool>,
+    pub gmt_modified: Option<i64>,
+}
+
+impl HealthCheckConfigDto {
+    pub fn to_service_key(&self) -> ServiceKey {
+        ServiceKey::new(
+            &NamingUtils::default_namespace(self.namespace_id.clone().unwrap_or_default()),
+            &NamingUtils::default_group(self.group_name.clone().unwrap_or_default()),
+            &self.service_name,
+        )
+    }
+}
+
+impl From<HealthCheckConfigDo> for HealthCheckConfigDto {
+    fn from(v: HealthCheckConfigDo) -> Self {
+        Self {
+            namespace_id: Some(v.namespace_id),
+            group_name: Some(v.group_name),
+            service_name: Arc::new(v.service_name),
+            cluster_name: Some(v.cluster_name),
+            check_type: Some(v.check_type),
+            interval_millis: Some(v.interval_millis),
+            timeout_millis: Some(v.timeout_millis),
+            port: Some(v.port),
+            path: Some(v.path),
+            expected_code: Some(v.expected_code),
+            enable: Some(v.enable),
+            gmt_modified: Some(v.gmt_modified),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn health_check_config_valid() {
+        let mut config = HealthCheckConfigDo {
+            namespace_id: "public".to_owned(),
+            group_name: "DEFAULT_GROUP".to_owned(),
+            service_name: "mysql".to_owned(),
+            check_type: "http".to_owned(),
+            interval_millis: 500,
+            timeout_millis: 3000,
+            path: "health".to_owned(),
+            ..Default::default()
+        };
+        config.check_valid().unwrap();
+        assert!(config.is_http());
+        assert_eq!(config.interval_millis, MIN_CHECK_INTERVAL_MILLIS);
+        assert_eq!(config.timeout_millis, MIN_CHECK_INTERVAL_MILLIS);
+        assert_eq!(config.expected_code, DEFAULT_EXPECTED_CODE);
+        assert_eq!(config.path, "/health");
+        assert_eq!(config.build_key(), "public#DEFAULT_GROUP#mysql#");
+        let config = HealthCheckConfigDo::from_bytes(&config.to_bytes().unwrap()).unwrap();
+        assert_eq!(config.check_type, CHECK_TYPE_HTTP);
+
+        let mut instance = Instance::new("127.0.0.1".to_owned(), 3306);
+        assert_eq!(config.check_port(&instance, None), 3306);
+        assert_eq!(config.check_port(&instance, Some(3307)), 3307);
+        instance.port = 0;
+        assert_eq!(config.check_port(&instance, None), 0);
+
+        let mut config = HealthCheckConfigDo {
+            service_name: "mysql".to_owned(),
+            check_type: "UDP".to_owned(),
+            ..Default::default()
+        };
+        assert!(config.check_valid().is_err());
+    }
+}
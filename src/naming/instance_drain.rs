@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::naming::model::{Instance, ServiceKey};
+use crate::naming::NamingUtils;
+
+///
+/// 实例摘流记录,匹配的实例对消费者展示为禁用且权重为0
+/// 通过raft状态机同步,不修改实例本身,移除记录即恢复
+#[derive(Clone, PartialEq, prost_derive::Message, Deserialize, Serialize)]
+pub struct InstanceDrainDo {
+    #[prost(string, tag = "1")]
+    pub namespace_id: String,
+    #[prost(string, tag = "2")]
+    pub group_name: String,
+    #[prost(string, tag = "3")]
+    pub service_name: String,
+    /// 为空时按metadata匹配整个服务的实例
+    #[prost(string, tag = "4")]
+    pub ip: String,
+    /// 为0时匹配该ip的所有端口
+    #[prost(uint32, tag = "5")]
+    pub port: u32,
+    #[prost(map = "string, string", tag = "6")]
+    pub metadata: HashMap<String, String>,
+    /// 自动恢复时间,为0时不自动恢复
+    #[prost(int64, tag = "7")]
+    pub expire_time: i64,
+    #[prost(string, tag = "8")]
+    pub operator: String,
+    #[prost(int64, tag = "9")]
+    pub gmt_create: i64,
+}
+
+impl InstanceDrainDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        use prost::Message;
+        let mut v = Vec::new();
+        self.encode(&mut v)?;
+        Ok(v)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> anyhow::Result<Self> {
+        use prost::Message;
+        let s = Self::decode(data)?;
+        Ok(s)
+    }
+
+    ///
+    /// 摘流目标: ip:port、ip:* 或 排序后的metadata条件
+    pub fn get_target(&self) -> String {
+        let metadata = self
+            .metadata
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("&");
+        let address = if self.ip.is_empty() {
+            String::new()
+        } else if self.port == 0 {
+            format!("{}:*", &self.ip)
+        } else {
+            format!("{}:{}", &self.ip, self.port)
+        };
+        match (address.is_empty(), metadata.is_empty()) {
+            (false, false) => format!("{}?{}", address, metadata),
+            (false, true) => address,
+            _ => metadata,
+        }
+    }
+
+    ///
+    /// 存储key: namespace_id#group_name#service_name#target
+    pub fn build_key(&self) -> String {
+        format!(
+            "{}#{}#{}#{}",
+            &self.namespace_id,
+            &self.group_name,
+            &self.service_name,
+            self.get_target()
+        )
+    }
+
+    pub fn get_service_key(&self) -> ServiceKey {
+        ServiceKey::new(&self.namespace_id, &self.group_name, &self.service_name)
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expire_time > 0 && self.expire_time <= now
+    }
+
+    pub fn is_match(&self, instance: &Instance) -> bool {
+        if !self.ip.is_empty() {
+            if self.ip.as_str() != instance.ip.as_str() {
+                return false;
+            }
+            if self.port > 0 && self.port != instance.port {
+                return false;
+            }
+        }
+        self.metadata
+            .iter()
+            .all(|(k, v)| instance.metadata.get(k) == Some(v))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceDrainDto {
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: Arc<String>,
+    pub ip: Option<String>,
+    pub port: Option<u32>,
+    pub metadata: Option<HashMap<String, String>>,
+    /// 自动恢复的秒数,为空或0时不自动恢复
+    pub ttl_seconds: Option<i64>,
+    pub expire_time: Option<i64>,
+    pub operator: Option<String>,
+    pub gmt_create: Option<i64>,
+}
+
+impl InstanceDrainDto {
+    pub fn to_service_key(&self) -> ServiceKey {
+        ServiceKey::new(
+            &NamingUtils::default_namespace(self.namespace_id.clone().unwrap_or_default()),
+            &NamingUtils::default_group(self.group_name.clone().unwrap_or_default()),
+            &self.service_name,
+        )
+    }
+
+    pub fn to_do(&self, operator: String, now: i64) -> anyhow::Result<InstanceDrainDo> {
+        if self.service_name.is_empty() {
+            return Err(anyhow::anyhow!("serviceName is empty"));
+        }
+        let ip = self.ip.clone().unwrap_or_default();
+        let metadata = self.metadata.clone().unwrap_or_default();
+        if ip.is_empty() && metadata.is_empty() {
+            return Err(anyhow::anyhow!("ip or metadata is required"));
+        }
+        let ttl_seconds = self.ttl_seconds.unwrap_or_default();
+        if ttl_seconds < 0 {
+            return Err(anyhow::anyhow!("ttlSeconds is invalid"));
+        }
+        let service_key = self.to_service_key();
+        Ok(InstanceDrainDo {
+            namespace_id: service_key.namespace_id.as_ref().to_owned(),
+            group_name: service_key.group_name.as_ref().to_owned(),
+            service_name: service_key.service_name.as_ref().to_owned(),
+            ip,
+            port: self.port.unwrap_or_default(),
+            metadata,
+            expire_time: if ttl_seconds > 0 {
+                now + ttl_seconds * 1000
+            } else {
+                0
+            },
+            operator,
+            gmt_create: now,
+        })
+    }
+
+    ///
+    /// 手动恢复不带过期时间,移除对应目标的摘流记录;
+    /// 带过期时间的移除请求由leader在记录过期后发起,只移除过期时间相同的记录
+    pub fn to_restore_do(&self, operator: String, now: i64) -> anyhow::Result<InstanceDrainDo> {
+        let mut drain = self.to_do(operator, now)?;
+        drain.expire_time = 0;
+        Ok(drain)
+    }
+}
+
+impl From<&InstanceDrainDo> for InstanceDrainDto {
+    fn from(v: &InstanceDrainDo) -> Self {
+        Self {
+            namespace_id: Some(v.namespace_id.to_owned()),
+            group_name: Some(v.group_name.to_owned()),
+            service_name: Arc::new(v.service_name.to_owned()),
+            ip: Some(v.ip.to_owned()),
+            port: Some(v.port),
+            metadata: Some(v.metadata.clone()),
+            ttl_seconds: None,
+            expire_time: Some(v.expire_time),
+            operator: Some(v.operator.to_owned()),
+            gmt_create: Some(v.gmt_create),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_drain_match() {
+        let mut dto = InstanceDrainDto {
+            service_name: Arc::new("foo".to_owned()),
+            ip: Some("10.0.0.1".to_owned()),
+            ttl_seconds: Some(60),
+            ..Default::default()
+        };
+        let value = dto.to_do("admin".to_owned(), 1000).unwrap();
+        assert_eq!(value.build_key(), "public#DEFAULT_GROUP#foo#10.0.0.1:*");
+        assert_eq!(value.expire_time, 61000);
+        assert!(!value.is_expired(60999));
+        assert!(value.is_expired(61000));
+
+        let mut instance = Instance::new("10.0.0.1".to_owned(), 8080);
+        assert!(value.is_match(&instance));
+        dto.port = Some(8081);
+        assert!(!dto
+            .to_do("admin".to_owned(), 0)
+            .unwrap()
+            .is_match(&instance));
+
+        let mut metadata = HashMap::new();
+        metadata.insert("version".to_owned(), "1.2".to_owned());
+        let dto = InstanceDrainDto {
+            service_name: Arc::new("foo".to_owned()),
+            metadata: Some(metadata.clone()),
+            ..Default::default()
+        };
+        let value = InstanceDrainDo::from_bytes(
+            &dto.to_do("admin".to_owned(), 0)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value.get_target(), "version=1.2");
+        assert!(!value.is_match(&instance));
+        instance.metadata = Arc::new(metadata);
+        assert!(value.is_match(&instance));
+
+        let dto = InstanceDrainDto {
+            service_name: Arc::new("foo".to_owned()),
+            ..Default::default()
+        };
+        assert!(dto.to_do("admin".to_owned(), 0).is_err());
+    }
+}
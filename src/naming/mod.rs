@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 pub mod api_model;
 pub mod core;
+pub mod event_history;
 pub(crate) mod filter;
+pub mod health_check;
+pub mod instance_drain;
 pub mod listener;
 pub mod model;
 pub mod naming_delay_nofity;
@@ -13,6 +16,11 @@ pub mod udp_actor;
 pub mod cluster;
 pub mod metrics;
 pub mod ops;
+pub mod order;
+pub mod persistent_instance;
+pub mod selector;
+pub mod service_cluster;
+pub mod service_definition;
 pub mod service_index;
 
 #[cfg(feature = "debug")]
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::{collections::HashMap, sync::Arc};
 
+use crate::naming::selector::ServiceSelector;
+use crate::naming::service_cluster::ServiceClusterDto;
 use crate::now_millis_i64;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,8 +45,8 @@ impl Instance {
     }
 
     pub fn is_enable_timeout(&self) -> bool {
-        //grpc 不走过期检查
-        !self.from_grpc && !self.is_from_cluster()
+        //grpc与持久化实例不走过期检查
+        self.ephemeral && !self.from_grpc && !self.is_from_cluster()
     }
 
     pub fn generate_key(&mut self) {
@@ -149,6 +151,9 @@ pub struct ServiceInfo {
     pub all_ips: bool,
     pub reach_protection_threshold: bool,
     //pub metadata:Option<HashMap<String,String>>,
+    /// 未应用的label选择器,推送时按订阅连接的labels过滤
+    #[serde(skip)]
+    pub selector: Option<Arc<ServiceSelector>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -159,7 +164,11 @@ pub struct ServiceDetailDto {
     pub group_name: Arc<String>,
     pub metadata: Option<Arc<HashMap<String, String>>>,
     pub protect_threshold: Option<f32>,
+    /// 服务选择器表达式,为空字符串时清除
+    pub selector: Option<Arc<String>>,
     pub grpc_instance_count: Option<i32>,
+    /// 集群配置,只在服务详情查询时返回
+    pub clusters: Option<Vec<ServiceClusterDto>>,
 }
 
 impl ServiceDetailDto {
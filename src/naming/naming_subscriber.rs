@@ -164,6 +164,13 @@ impl Subscriber {
         }
     }
 
+    pub fn get_service_clients(&self, key: &ServiceKey) -> Vec<Arc<String>> {
+        self.listener
+            .get(key)
+            .map(|set| set.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
     pub fn get_listener_key_size(&self) -> usize {
         self.listener.len()
     }
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 
 use crate::naming::{service::ServiceInfoDto, service_index::ServiceQueryParam, NamingUtils};
-use crate::user_namespace_privilege;
+use crate::{user_namespace_privilege, user_service_group_privilege};
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -23,10 +23,12 @@ impl OpsServiceQueryListRequest {
         let limit = self.page_size.unwrap_or(0xffff_ffff);
         let offset = (self.page_no.unwrap_or(1) - 1) * limit;
         let namespace_privilege = user_namespace_privilege!(req);
+        let group_privilege = user_service_group_privilege!(req);
         let mut param = ServiceQueryParam {
             limit,
             offset,
             namespace_privilege,
+            group_privilege,
             ..Default::default()
         };
         if let Some(namespace_id) = self.namespace_id {
@@ -0,0 +1,295 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use super::model::Instance;
+
+pub const ZONE_METADATA_KEY: &str = "zone";
+pub const DEFAULT_ZONE_HEALTHY_THRESHOLD: f32 = 0.5f32;
+
+///
+/// 服务端实例列表排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InstanceOrderType {
+    #[default]
+    None,
+    /// 按实例权重随机打散
+    Weight,
+    /// 同zone实例优先,组内按权重随机打散
+    Zone,
+}
+
+impl From<&str> for InstanceOrderType {
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "weight" => Self::Weight,
+            "zone" => Self::Zone,
+            _ => Self::None,
+        }
+    }
+}
+
+///
+/// 单次查询的排序参数,未指定时使用服务端默认配置
+#[derive(Debug, Clone, Default)]
+pub struct InstanceOrderParam {
+    pub order_type: Option<InstanceOrderType>,
+    pub zone: Option<String>,
+}
+
+impl InstanceOrderParam {
+    pub fn new(order: Option<&str>, zone: Option<String>) -> Self {
+        Self {
+            order_type: order.filter(|e| !e.is_empty()).map(InstanceOrderType::from),
+            zone: zone.filter(|e| !e.is_empty()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ZoneCidr {
+    network: IpAddr,
+    prefix: u32,
+    zone: Arc<String>,
+}
+
+impl ZoneCidr {
+    fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => Self::match_prefix(
+                u32::from(network) as u128,
+                u32::from(*ip) as u128,
+                32,
+                self.prefix,
+            ),
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                Self::match_prefix(u128::from(network), u128::from(*ip), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+
+    fn match_prefix(network: u128, ip: u128, bits: u32, prefix: u32) -> bool {
+        if prefix == 0 {
+            return true;
+        }
+        let shift = bits - prefix;
+        (network >> shift) == (ip >> shift)
+    }
+}
+
+///
+/// 网段到zone的映射,格式: `zone-a:10.0.0.0/16,10.1.0.0/16;zone-b:192.168.0.0/16`
+#[derive(Debug, Clone, Default)]
+pub struct ZoneCidrMap {
+    items: Vec<ZoneCidr>,
+}
+
+impl ZoneCidrMap {
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        let mut items = vec![];
+        for zone_item in value.split(';').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            let (zone, cidr_list) = zone_item
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("invalid zone cidr item: {}", zone_item))?;
+            let zone = Arc::new(zone.trim().to_owned());
+            if zone.is_empty() {
+                return Err(anyhow::anyhow!("invalid zone cidr item: {}", zone_item));
+            }
+            for cidr in cidr_list
+                .split(',')
+                .map(|e| e.trim())
+                .filter(|e| !e.is_empty())
+            {
+                let (network, prefix) = match cidr.split_once('/') {
+                    Some((network, prefix)) => (network.parse::<IpAddr>()?, Some(prefix.parse()?)),
+                    None => (cidr.parse::<IpAddr>()?, None),
+                };
+                let bits = if network.is_ipv4() { 32 } else { 128 };
+                let prefix = prefix.unwrap_or(bits);
+                if prefix > bits {
+                    return Err(anyhow::anyhow!("invalid cidr: {}", cidr));
+                }
+                items.push(ZoneCidr {
+                    network,
+                    prefix,
+                    zone: zone.clone(),
+                });
+            }
+        }
+        Ok(Self { items })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    ///
+    /// 返回ip所在网段对应的zone,多个网段匹配时取前缀最长的
+    pub fn get_zone(&self, ip: &str) -> Option<Arc<String>> {
+        let ip: IpAddr = ip.trim().parse().ok()?;
+        self.items
+            .iter()
+            .filter(|e| e.contains(&ip))
+            .max_by_key(|e| e.prefix)
+            .map(|e| e.zone.clone())
+    }
+}
+
+///
+/// 服务端实例排序配置
+#[derive(Debug, Clone)]
+pub struct InstanceOrderConfig {
+    pub default_order_type: InstanceOrderType,
+    pub zone_cidr_map: ZoneCidrMap,
+    /// 本zone健康实例比例低于该值时不再优先本zone
+    pub zone_healthy_threshold: f32,
+}
+
+impl Default for InstanceOrderConfig {
+    fn default() -> Self {
+        Self {
+            default_order_type: InstanceOrderType::None,
+            zone_cidr_map: ZoneCidrMap::default(),
+            zone_healthy_threshold: DEFAULT_ZONE_HEALTHY_THRESHOLD,
+        }
+    }
+}
+
+impl InstanceOrderConfig {
+    ///
+    /// 请求未指定zone时按消费者ip从网段映射中推导
+    pub fn order_instances(
+        &self,
+        instances: Vec<Arc<Instance>>,
+        param: Option<&InstanceOrderParam>,
+        consumer_ip: Option<&str>,
+    ) -> Vec<Arc<Instance>> {
+        let order_type = param
+            .and_then(|e| e.order_type)
+            .unwrap_or(self.default_order_type);
+        match order_type {
+            InstanceOrderType::None => instances,
+            InstanceOrderType::Weight => InstanceOrderUtils::weight_shuffle(instances),
+            InstanceOrderType::Zone => {
+                let zone = match param.and_then(|e| e.zone.as_ref()) {
+                    Some(zone) => Some(Arc::new(zone.to_owned())),
+                    None => consumer_ip.and_then(|ip| self.zone_cidr_map.get_zone(ip)),
+                };
+                match zone {
+                    Some(zone) => InstanceOrderUtils::zone_order(
+                        instances,
+                        &zone,
+                        self.zone_healthy_threshold,
+                    ),
+                    None => InstanceOrderUtils::weight_shuffle(instances),
+                }
+            }
+        }
+    }
+}
+
+pub struct InstanceOrderUtils;
+
+impl InstanceOrderUtils {
+    ///
+    /// 加权随机打散(A-Res算法),权重越大越可能排在前面;权重<=0的实例排在最后
+    pub fn weight_shuffle(instances: Vec<Arc<Instance>>) -> Vec<Arc<Instance>> {
+        if instances.len() < 2 {
+            return instances;
+        }
+        let mut rng = rand::thread_rng();
+        let mut list: Vec<(f64, Arc<Instance>)> = instances
+            .into_iter()
+            .map(|e| {
+                let score = if e.weight > 0f32 {
+                    rng.gen::<f64>().powf(1f64 / e.weight as f64)
+                } else {
+                    -1f64
+                };
+                (score, e)
+            })
+            .collect();
+        list.sort_by(|a, b| b.0.total_cmp(&a.0));
+        list.into_iter().map(|(_, e)| e).collect()
+    }
+
+    ///
+    /// metadata中zone与消费者相同的实例排在前面;
+    /// 本zone没有实例或健康比例低于阈值时退化为整体加权打散
+    pub fn zone_order(
+        instances: Vec<Arc<Instance>>,
+        zone: &str,
+        healthy_threshold: f32,
+    ) -> Vec<Arc<Instance>> {
+        let (local, other): (Vec<Arc<Instance>>, Vec<Arc<Instance>>) =
+            instances.into_iter().partition(|e| {
+                e.metadata
+                    .get(ZONE_METADATA_KEY)
+                    .map(|v| v == zone)
+                    .unwrap_or(false)
+            });
+        let healthy_count = local.iter().filter(|e| e.healthy).count();
+        if local.is_empty() || (healthy_count as f32) / (local.len() as f32) < healthy_threshold {
+            let mut all = local;
+            all.extend(other);
+            return Self::weight_shuffle(all);
+        }
+        let mut list = Self::weight_shuffle(local);
+        list.extend(Self::weight_shuffle(other));
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_instance(ip: &str, zone: &str, weight: f32, healthy: bool) -> Arc<Instance> {
+        let mut instance = Instance::new(ip.to_owned(), 8080);
+        instance.weight = weight;
+        instance.healthy = healthy;
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert(ZONE_METADATA_KEY.to_owned(), zone.to_owned());
+        instance.metadata = Arc::new(metadata);
+        Arc::new(instance)
+    }
+
+    #[test]
+    fn instance_zone_order() {
+        let map = ZoneCidrMap::parse(
+            "zone-a:10.0.0.0/16,10.2.0.0/16;zone-b:10.1.0.0/16;zone-c:10.1.1.0/24",
+        )
+        .unwrap();
+        assert_eq!(map.get_zone("10.0.3.4").unwrap().as_str(), "zone-a");
+        assert_eq!(map.get_zone("10.1.3.4").unwrap().as_str(), "zone-b");
+        assert_eq!(map.get_zone("10.1.1.4").unwrap().as_str(), "zone-c");
+        assert!(map.get_zone("192.168.1.1").is_none());
+        assert!(ZoneCidrMap::parse("zone-a:10.0.0.0/33").is_err());
+
+        let instances = vec![
+            build_instance("10.0.0.1", "zone-b", 1f32, true),
+            build_instance("10.0.0.2", "zone-a", 1f32, true),
+            build_instance("10.0.0.3", "zone-a", 1f32, false),
+            build_instance("10.0.0.4", "zone-b", 0f32, true),
+        ];
+        let list = InstanceOrderUtils::zone_order(instances.clone(), "zone-a", 0.5f32);
+        assert_eq!(list[0].metadata.get(ZONE_METADATA_KEY).unwrap(), "zone-a");
+        assert_eq!(list[1].metadata.get(ZONE_METADATA_KEY).unwrap(), "zone-a");
+        assert_eq!(list[3].ip.as_str(), "10.0.0.4");
+
+        //本zone健康比例低于阈值,不再优先本zone
+        let list = InstanceOrderUtils::zone_order(instances.clone(), "zone-a", 0.8f32);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list[3].ip.as_str(), "10.0.0.4");
+
+        let config = InstanceOrderConfig {
+            default_order_type: InstanceOrderType::Zone,
+            zone_cidr_map: map,
+            zone_healthy_threshold: 0.5f32,
+        };
+        let list = config.order_instances(instances, None, Some("10.1.3.4"));
+        assert_eq!(list[0].ip.as_str(), "10.0.0.1");
+    }
+}
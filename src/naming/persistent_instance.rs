@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use actix::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::instance_drain::InstanceDrainDo;
+use super::model::{Instance, InstanceKey, InstanceUpdateTag, ServiceDetailDto};
+use super::service_cluster::ServiceClusterDo;
+
+///
+/// 持久化实例(ephemeral=false)的存储对象
+/// 通过raft状态机同步,不参与心跳过期检查
+#[derive(Clone, PartialEq, prost_derive::Message, Deserialize, Serialize)]
+pub struct PersistentInstanceDo {
+    #[prost(string, tag = "1")]
+    pub namespace_id: String,
+    #[prost(string, tag = "2")]
+    pub group_name: String,
+    #[prost(string, tag = "3")]
+    pub service_name: String,
+    #[prost(string, tag = "4")]
+    pub ip: String,
+    #[prost(uint32, tag = "5")]
+    pub port: u32,
+    #[prost(float, tag = "6")]
+    pub weight: f32,
+    #[prost(bool, tag = "7")]
+    pub enabled: bool,
+    #[prost(bool, tag = "8")]
+    pub healthy: bool,
+    #[prost(string, tag = "9")]
+    pub cluster_name: String,
+    #[prost(map = "string, string", tag = "10")]
+    pub metadata: HashMap<String, String>,
+    #[prost(string, tag = "11")]
+    pub app_name: String,
+    #[prost(int64, tag = "12")]
+    pub register_time: i64,
+}
+
+impl PersistentInstanceDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        use prost::Message;
+        let mut v = Vec::new();
+        self.encode(&mut v)?;
+        Ok(v)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> anyhow::Result<Self> {
+        use prost::Message;
+        let s = Self::decode(data)?;
+        Ok(s)
+    }
+
+    ///
+    /// 存储key: namespace_id#group_name#service_name#ip#port
+    pub fn build_key(&self) -> String {
+        format!(
+            "{}#{}#{}#{}#{}",
+            &self.namespace_id, &self.group_name, &self.service_name, &self.ip, self.port
+        )
+    }
+
+    pub fn get_instance_key(&self) -> InstanceKey {
+        InstanceKey {
+            namespace_id: Arc::new(self.namespace_id.to_owned()),
+            group_name: Arc::new(self.group_name.to_owned()),
+            service_name: Arc::new(self.service_name.to_owned()),
+            ip: Arc::new(self.ip.to_owned()),
+            port: self.port,
+        }
+    }
+}
+
+impl From<&Instance> for PersistentInstanceDo {
+    fn from(value: &Instance) -> Self {
+        Self {
+            namespace_id: value.namespace_id.as_ref().to_owned(),
+            group_name: value.group_name.as_ref().to_owned(),
+            service_name: value.service_name.as_ref().to_owned(),
+            ip: value.ip.as_ref().to_owned(),
+            port: value.port,
+            weight: value.weight,
+            enabled: value.enabled,
+            healthy: value.healthy,
+            cluster_name: value.cluster_name.to_owned(),
+            metadata: value.metadata.as_ref().to_owned(),
+            app_name: value.app_name.to_owned(),
+            register_time: value.register_time,
+        }
+    }
+}
+
+impl From<PersistentInstanceDo> for Instance {
+    fn from(value: PersistentInstanceDo) -> Self {
+        let mut instance = Instance {
+            ip: Arc::new(value.ip),
+            port: value.port,
+            weight: value.weight,
+            enabled: value.enabled,
+            healthy: value.healthy,
+            ephemeral: false,
+            cluster_name: value.cluster_name,
+            service_name: Arc::new(value.service_name),
+            group_name: Arc::new(value.group_name),
+            metadata: Arc::new(value.metadata),
+            register_time: value.register_time,
+            namespace_id: Arc::new(value.namespace_id),
+            app_name: value.app_name,
+            ..Default::default()
+        };
+        instance.generate_key();
+        instance
+    }
+}
+
+///
+/// 持久化实例、服务定义、服务集群配置与实例摘流的raft请求,在每个节点的状态机中应用
+#[derive(Message, Clone, Debug, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<NamingRaftResult>")]
+pub enum NamingRaftReq {
+    Update {
+        instance: Box<PersistentInstanceDo>,
+        tag: Option<InstanceUpdateTag>,
+    },
+    Remove {
+        key: InstanceKey,
+    },
+    UpdateCluster {
+        cluster: Box<ServiceClusterDo>,
+    },
+    RemoveCluster {
+        cluster: Box<ServiceClusterDo>,
+    },
+    UpdateDrain {
+        drain: Box<InstanceDrainDo>,
+    },
+    /// 只使用摘流目标key
+    RemoveDrain {
+        drain: Box<InstanceDrainDo>,
+    },
+    UpdateService {
+        service: Box<ServiceDetailDto>,
+    },
+    /// 只使用服务key
+    RemoveService {
+        service: Box<ServiceDetailDto>,
+    },
+    /// 主节点主动健康检查后提交的持久化实例健康状态变更
+    UpdateHealthy {
+        results: Vec<(InstanceKey, bool)>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum NamingRaftResult {
+    None,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persistent_instance_convert() {
+        let mut metadata = HashMap::new();
+        metadata.insert("version".to_owned(), "1.0".to_owned());
+        let mut instance = Instance::new("127.0.0.1".to_owned(), 8080);
+        instance.namespace_id = Arc::new("public".to_owned());
+        instance.group_name = Arc::new("DEFAULT_GROUP".to_owned());
+        instance.service_name = Arc::new("foo".to_owned());
+        instance.ephemeral = false;
+        instance.weight = 2f32;
+        instance.metadata = Arc::new(metadata);
+        let value = PersistentInstanceDo::from(&instance);
+        let value = PersistentInstanceDo::from_bytes(&value.to_bytes().unwrap()).unwrap();
+        assert_eq!(value.build_key(), "public#DEFAULT_GROUP#foo#127.0.0.1#8080");
+        let new_instance: Instance = value.into();
+        assert!(!new_instance.ephemeral);
+        assert!(!new_instance.is_enable_timeout());
+        assert_eq!(new_instance.id.as_str(), "127.0.0.1#8080");
+        assert_eq!(new_instance.weight, 2f32);
+        assert_eq!(new_instance.metadata.get("version").unwrap(), "1.0");
+        assert_eq!(new_instance.get_instance_key(), instance.get_instance_key());
+    }
+}
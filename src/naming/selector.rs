@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use super::model::{Instance, InstanceKey};
+
+const CONSUMER_LABEL_PREFIX: &str = "CONSUMER.label.";
+const PROVIDER_LABEL_PREFIX: &str = "PROVIDER.label.";
+const METADATA_PREFIX: &str = "metadata.";
+
+pub const SELECTOR_TYPE_NONE: &str = "none";
+
+///
+/// metadata表达式中的单个条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataCondition {
+    Eq(String, String),
+    NotEq(String, String),
+    In(String, Vec<String>),
+}
+
+impl MetadataCondition {
+    fn is_match(&self, metadata: &HashMap<String, String>) -> bool {
+        match self {
+            MetadataCondition::Eq(key, value) => metadata.get(key) == Some(value),
+            MetadataCondition::NotEq(key, value) => metadata.get(key) != Some(value),
+            MetadataCondition::In(key, values) => metadata
+                .get(key)
+                .map(|v| values.contains(v))
+                .unwrap_or(false),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectorRule {
+    /// nacos label选择器,消费者与提供者指定标签值相同
+    Label(Vec<String>),
+    /// 按提供者metadata过滤
+    Metadata(Vec<MetadataCondition>),
+}
+
+///
+/// 服务选择器
+/// 支持 `CONSUMER.label.zone = PROVIDER.label.zone & ...`
+/// 与 `metadata.env == prod && metadata.version in (1.2,1.3)` 两种表达式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceSelector {
+    pub expression: Arc<String>,
+    pub rule: SelectorRule,
+}
+
+///
+/// 兼容nacos接口的json格式: {"type":"label","expression":"..."}
+#[derive(Debug, Deserialize)]
+struct NacosSelectorParam {
+    #[serde(rename = "type")]
+    selector_type: Option<String>,
+    expression: Option<String>,
+}
+
+impl ServiceSelector {
+    ///
+    /// 解析选择器,表达式为空或type为none时返回None
+    pub fn parse(value: &str) -> anyhow::Result<Option<Self>> {
+        let value = value.trim();
+        let expression = if value.starts_with('{') {
+            let param: NacosSelectorParam = serde_json::from_str(value)?;
+            if param
+                .selector_type
+                .as_ref()
+                .map(|e| e.eq_ignore_ascii_case(SELECTOR_TYPE_NONE))
+                .unwrap_or(false)
+            {
+                return Ok(None);
+            }
+            param.expression.unwrap_or_default().trim().to_owned()
+        } else {
+            value.to_owned()
+        };
+        if expression.is_empty() {
+            return Ok(None);
+        }
+        let rule = if expression.contains(CONSUMER_LABEL_PREFIX) {
+            Self::parse_label_rule(&expression)?
+        } else {
+            Self::parse_metadata_rule(&expression)?
+        };
+        Ok(Some(Self {
+            expression: Arc::new(expression),
+            rule,
+        }))
+    }
+
+    fn parse_label_rule(expression: &str) -> anyhow::Result<SelectorRule> {
+        let mut labels = vec![];
+        for item in expression
+            .split('&')
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+        {
+            let parts: Vec<&str> = item
+                .split('=')
+                .map(|e| e.trim())
+                .filter(|e| !e.is_empty())
+                .collect();
+            if parts.len() != 2 {
+                return Err(anyhow::anyhow!("invalid label expression: {}", item));
+            }
+            let (consumer, provider) = if parts[0].starts_with(CONSUMER_LABEL_PREFIX) {
+                (parts[0], parts[1])
+            } else {
+                (parts[1], parts[0])
+            };
+            match (
+                consumer.strip_prefix(CONSUMER_LABEL_PREFIX),
+                provider.strip_prefix(PROVIDER_LABEL_PREFIX),
+            ) {
+                (Some(a), Some(b)) if a == b && !a.is_empty() => labels.push(a.to_owned()),
+                _ => return Err(anyhow::anyhow!("invalid label expression: {}", item)),
+            }
+        }
+        if labels.is_empty() {
+            return Err(anyhow::anyhow!("label expression is empty"));
+        }
+        Ok(SelectorRule::Label(labels))
+    }
+
+    fn parse_metadata_rule(expression: &str) -> anyhow::Result<SelectorRule> {
+        let mut conditions = vec![];
+        for item in expression
+            .split("&&")
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+        {
+            let condition = if let Some((key, value)) = item.split_once("!=") {
+                MetadataCondition::NotEq(Self::parse_metadata_key(key)?, Self::unquote(value))
+            } else if let Some((key, value)) = item.split_once("==") {
+                MetadataCondition::Eq(Self::parse_metadata_key(key)?, Self::unquote(value))
+            } else if let Some(index) = item.find(" in ") {
+                let values = item[index + 4..].trim();
+                let values = values
+                    .strip_prefix('(')
+                    .and_then(|e| e.strip_suffix(')'))
+                    .ok_or_else(|| anyhow::anyhow!("invalid metadata expression: {}", item))?;
+                MetadataCondition::In(
+                    Self::parse_metadata_key(&item[..index])?,
+                    values
+                        .split(',')
+                        .map(Self::unquote)
+                        .filter(|e| !e.is_empty())
+                        .collect(),
+                )
+            } else {
+                return Err(anyhow::anyhow!("invalid metadata expression: {}", item));
+            };
+            conditions.push(condition);
+        }
+        if conditions.is_empty() {
+            return Err(anyhow::anyhow!("metadata expression is empty"));
+        }
+        Ok(SelectorRule::Metadata(conditions))
+    }
+
+    fn parse_metadata_key(key: &str) -> anyhow::Result<String> {
+        match key.trim().strip_prefix(METADATA_PREFIX) {
+            Some(v) if !v.is_empty() => Ok(v.to_owned()),
+            _ => Err(anyhow::anyhow!("invalid metadata key: {}", key.trim())),
+        }
+    }
+
+    fn unquote(value: &str) -> String {
+        value
+            .trim()
+            .trim_matches(|c| c == '\'' || c == '"')
+            .to_owned()
+    }
+
+    pub fn is_label(&self) -> bool {
+        matches!(self.rule, SelectorRule::Label(_))
+    }
+
+    ///
+    /// 按选择器过滤提供者实例
+    /// label选择器只比较消费者存在的标签;无匹配实例时返回全部实例(与nacos一致)
+    pub fn select(
+        &self,
+        instances: Vec<Arc<Instance>>,
+        consumer_labels: Option<&HashMap<String, String>>,
+    ) -> Vec<Arc<Instance>> {
+        match &self.rule {
+            SelectorRule::Metadata(conditions) => instances
+                .into_iter()
+                .filter(|i| conditions.iter().all(|c| c.is_match(&i.metadata)))
+                .collect(),
+            SelectorRule::Label(labels) => {
+                let consumer_labels: Vec<(&String, &String)> = match consumer_labels {
+                    Some(consumer_labels) => labels
+                        .iter()
+                        .filter_map(|k| consumer_labels.get(k).map(|v| (k, v)))
+                        .filter(|(_, v)| !v.is_empty())
+                        .collect(),
+                    None => vec![],
+                };
+                if consumer_labels.is_empty() {
+                    return instances;
+                }
+                let selected: Vec<Arc<Instance>> = instances
+                    .iter()
+                    .filter(|i| {
+                        consumer_labels
+                            .iter()
+                            .all(|(k, v)| i.metadata.get(*k) == Some(*v))
+                    })
+                    .cloned()
+                    .collect();
+                if selected.is_empty() {
+                    instances
+                } else {
+                    selected
+                }
+            }
+        }
+    }
+}
+
+/// ip -> 该ip上各实例的metadata
+type IpInstanceMetadata = HashMap<Arc<String>, HashMap<InstanceKey, Arc<HashMap<String, String>>>>;
+
+///
+/// 按命名空间与ip索引实例metadata,查询消费者labels时只需合并该ip上的实例
+#[derive(Debug, Clone, Default)]
+pub struct ConsumerLabelIndex {
+    index: HashMap<Arc<String>, IpInstanceMetadata>,
+}
+
+impl ConsumerLabelIndex {
+    pub fn update(&mut self, key: InstanceKey, metadata: Arc<HashMap<String, String>>) {
+        self.index
+            .entry(key.namespace_id.clone())
+            .or_default()
+            .entry(key.ip.clone())
+            .or_default()
+            .insert(key, metadata);
+    }
+
+    pub fn remove(&mut self, key: &InstanceKey) {
+        if let Some(ip_map) = self.index.get_mut(&key.namespace_id) {
+            if let Some(instances) = ip_map.get_mut(&key.ip) {
+                instances.remove(key);
+                if instances.is_empty() {
+                    ip_map.remove(&key.ip);
+                }
+            }
+            if ip_map.is_empty() {
+                self.index.remove(&key.namespace_id);
+            }
+        }
+    }
+
+    ///
+    /// http消费者没有连接labels,以同命名空间下该ip注册实例的metadata作为消费者标签
+    /// 同一ip上多个实例存在相同label时取其中一个实例的值
+    pub fn get_labels(&self, namespace_id: &Arc<String>, ip: &str) -> HashMap<String, String> {
+        let mut labels = HashMap::new();
+        if let Some(instances) = self
+            .index
+            .get(namespace_id)
+            .and_then(|ip_map| ip_map.get(&ip.to_owned()))
+        {
+            for metadata in instances.values() {
+                for (k, v) in metadata.iter() {
+                    labels.entry(k.to_owned()).or_insert_with(|| v.to_owned());
+                }
+            }
+        }
+        labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_instance(ip: &str, metadata: &[(&str, &str)]) -> Arc<Instance> {
+        let mut instance = Instance::new(ip.to_owned(), 8080);
+        instance.metadata = Arc::new(
+            metadata
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        );
+        Arc::new(instance)
+    }
+
+    #[test]
+    fn service_selector_select() {
+        let instances = vec![
+            build_instance(
+                "10.0.0.1",
+                &[("env", "prod"), ("version", "1.2"), ("zone", "a")],
+            ),
+            build_instance(
+                "10.0.0.2",
+                &[("env", "prod"), ("version", "1.4"), ("zone", "b")],
+            ),
+            build_instance(
+                "10.0.0.3",
+                &[("env", "test"), ("version", "1.3"), ("zone", "a")],
+            ),
+        ];
+
+        let selector =
+            ServiceSelector::parse("metadata.env == prod && metadata.version in (1.2,1.3)")
+                .unwrap()
+                .unwrap();
+        let list = selector.select(instances.clone(), None);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].ip.as_str(), "10.0.0.1");
+
+        let selector = ServiceSelector::parse(
+            r#"{"type":"label","expression":"CONSUMER.label.zone = PROVIDER.label.zone"}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(selector.is_label());
+        let mut labels = HashMap::new();
+        assert_eq!(selector.select(instances.clone(), Some(&labels)).len(), 3);
+        labels.insert("zone".to_owned(), "a".to_owned());
+        assert_eq!(selector.select(instances.clone(), Some(&labels)).len(), 2);
+        labels.insert("zone".to_owned(), "c".to_owned());
+        assert_eq!(selector.select(instances, Some(&labels)).len(), 3);
+
+        assert!(ServiceSelector::parse(r#"{"type":"none"}"#)
+            .unwrap()
+            .is_none());
+        assert!(ServiceSelector::parse("CONSUMER.label.zone = PROVIDER.label.idc").is_err());
+        assert!(ServiceSelector::parse("env == prod").is_err());
+    }
+}
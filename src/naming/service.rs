@@ -1,29 +1,34 @@
 #![allow(unused_assignments, unused_imports)]
 
 use std::{
-    collections::{HashMap, LinkedList},
-    hash::Hash,
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap, LinkedList},
+    hash::{Hash, Hasher},
     sync::{atomic::Ordering, Arc},
 };
 
 use crate::common::constant::EMPTY_ARC_STRING;
 use crate::naming::cluster::model::ProcessRange;
 use crate::now_millis;
+use crate::now_millis_i64;
 use actix_web::rt;
 use inner_mem_cache::TimeoutSet;
 use serde::{Deserialize, Serialize};
 
 use super::{
     api_model::QueryListResult,
+    instance_drain::InstanceDrainDo,
     model::{
         Instance, InstanceShortKey, InstanceUpdateTag, ServiceDetailDto, ServiceKey,
         UpdateInstanceType,
     },
+    selector::ServiceSelector,
+    service_cluster::{ServiceClusterDo, ServiceClusterDto},
 };
 
 #[derive(Debug, Clone, Default)]
 pub struct ServiceMetadata {
     pub protect_threshold: f32,
+    pub selector: Option<Arc<ServiceSelector>>,
 }
 
 type InstanceMetaData = Arc<HashMap<String, String>>;
@@ -35,17 +40,25 @@ pub struct Service {
     pub group_service: Arc<String>,
     pub metadata: Arc<HashMap<String, String>>,
     pub protect_threshold: f32,
+    pub selector: Option<Arc<ServiceSelector>>,
     pub last_modified_millis: i64,
     //pub has_instance:bool,
     pub namespace_id: Arc<String>,
     pub app_name: String,
-    pub check_sum: String,
+    /// 当前实例列表的checksum,用于判断订阅者持有的列表是否过期
+    pub check_sum: i64,
     pub(crate) last_empty_times: u64,
     pub(crate) instance_size: i64,
     pub(crate) healthy_instance_size: i64,
     //pub cluster_map:HashMap<String,Cluster>,
     pub(crate) instances: HashMap<InstanceShortKey, Arc<Instance>>,
     pub(crate) instance_metadata_map: HashMap<InstanceShortKey, InstanceMetaData>,
+    /// 集群配置,由raft同步
+    pub(crate) cluster_map: HashMap<String, Arc<ServiceClusterDo>>,
+    /// 实例摘流记录,由raft同步
+    pub(crate) drain_map: HashMap<String, Arc<InstanceDrainDo>>,
+    /// 服务定义已通过raft持久化,没有实例时也不会被清理
+    pub(crate) persistent: bool,
     /// 健康状态过期记录，过期后把实例状态改为不健康
     pub(crate) healthy_timeout_set: TimeoutSet<InstanceShortKey>,
     /// 不健康状态过期记录，过期后反实例删除
@@ -53,8 +66,25 @@ pub struct Service {
 }
 
 impl Service {
+    ///
+    /// 按对外展示的实例列表(包含摘流状态)重新计算checksum
     pub(crate) fn recalculate_checksum(&mut self) {
-        "".clone_into(&mut self.check_sum);
+        let mut instances = self.get_all_instances(false, false);
+        instances.sort_by(|a, b| (&a.ip, a.port).cmp(&(&b.ip, b.port)));
+        let mut hasher = DefaultHasher::new();
+        self.protect_threshold.to_bits().hash(&mut hasher);
+        for instance in &instances {
+            instance.ip.hash(&mut hasher);
+            instance.port.hash(&mut hasher);
+            instance.cluster_name.hash(&mut hasher);
+            instance.weight.to_bits().hash(&mut hasher);
+            instance.healthy.hash(&mut hasher);
+            instance.enabled.hash(&mut hasher);
+            instance.ephemeral.hash(&mut hasher);
+            let metadata: BTreeMap<&String, &String> = instance.metadata.iter().collect();
+            metadata.hash(&mut hasher);
+        }
+        self.check_sum = hasher.finish() as i64;
     }
 
     /*
@@ -214,7 +244,7 @@ impl Service {
         &mut self,
         healthy_time: i64,
         offline_time: i64,
-    ) -> (Vec<InstanceShortKey>, Vec<InstanceShortKey>) {
+    ) -> (Vec<Arc<Instance>>, Vec<Arc<Instance>>) {
         let mut remove_list = vec![];
         #[cfg(feature = "debug")]
         log::info!(
@@ -228,8 +258,9 @@ impl Service {
                     continue;
                 }
             }
-            self.remove_instance(&key, None);
-            remove_list.push(key);
+            if let Some(old) = self.remove_instance(&key, None) {
+                remove_list.push(old);
+            }
         }
         let mut update_list = vec![];
         for key in self.healthy_timeout_set.timeout(healthy_time as u64) {
@@ -238,8 +269,9 @@ impl Service {
                     continue;
                 }
             }
-            self.update_instance_healthy_invalid(&key);
-            update_list.push(key);
+            if let Some(old) = self.update_instance_healthy_invalid(&key) {
+                update_list.push(old);
+            }
         }
         (remove_list, update_list)
     }
@@ -277,17 +309,45 @@ impl Service {
         }
     }
 
-    pub(crate) fn update_instance_healthy_invalid(&mut self, instance_id: &InstanceShortKey) {
-        if let Some(i) = self.instances.remove(instance_id) {
-            if i.healthy {
-                self.healthy_instance_size -= 1;
-            }
-            let mut i = i.as_ref().clone();
-            i.healthy = false;
-            self.unhealthy_timeout_set
-                .add(i.last_modified_millis as u64, instance_id.clone());
-            self.instances.insert(instance_id.clone(), Arc::new(i));
+    ///
+    /// 标记实例为不健康,返回变更前的实例
+    pub(crate) fn update_instance_healthy_invalid(
+        &mut self,
+        instance_id: &InstanceShortKey,
+    ) -> Option<Arc<Instance>> {
+        let old = self.instances.remove(instance_id)?;
+        if old.healthy {
+            self.healthy_instance_size -= 1;
         }
+        let mut i = old.as_ref().clone();
+        i.healthy = false;
+        self.unhealthy_timeout_set
+            .add(i.last_modified_millis as u64, instance_id.clone());
+        self.instances.insert(instance_id.clone(), Arc::new(i));
+        Some(old)
+    }
+
+    ///
+    /// 更新主动健康检查结果,状态有变化时返回新的实例
+    pub(crate) fn update_instance_healthy(
+        &mut self,
+        instance_id: &InstanceShortKey,
+        healthy: bool,
+    ) -> Option<Arc<Instance>> {
+        let old = self.instances.get(instance_id)?;
+        if old.healthy == healthy {
+            return None;
+        }
+        let mut i = old.as_ref().clone();
+        i.healthy = healthy;
+        if healthy {
+            self.healthy_instance_size += 1;
+        } else {
+            self.healthy_instance_size -= 1;
+        }
+        let i = Arc::new(i);
+        self.instances.insert(instance_id.clone(), i.clone());
+        Some(i)
     }
 
     pub(crate) fn get_instance(&self, instance_key: &InstanceShortKey) -> Option<Arc<Instance>> {
@@ -299,13 +359,56 @@ impl Service {
         only_healthy: bool,
         only_enable: bool,
     ) -> Vec<Arc<Instance>> {
+        let now = now_millis_i64();
         self.instances
             .values()
+            .map(|x| self.apply_drain(x, now))
             .filter(|x| (x.enabled || !only_enable) && (x.healthy || !only_healthy))
-            .cloned()
             .collect::<Vec<_>>()
     }
 
+    ///
+    /// 被摘流的实例对外展示为禁用且权重为0,不修改实例本身
+    fn apply_drain(&self, instance: &Arc<Instance>, now: i64) -> Arc<Instance> {
+        if self
+            .drain_map
+            .values()
+            .any(|e| !e.is_expired(now) && e.is_match(instance))
+        {
+            let mut raw = instance.as_ref().clone();
+            raw.enabled = false;
+            raw.weight = 0f32;
+            Arc::new(raw)
+        } else {
+            instance.clone()
+        }
+    }
+
+    ///
+    /// 已过期的摘流记录不再影响服务,不阻止空服务清理
+    pub fn has_active_drain(&self, now: i64) -> bool {
+        self.drain_map.values().any(|e| !e.is_expired(now))
+    }
+
+    pub fn get_expired_drains(&self, now: i64) -> Vec<InstanceDrainDo> {
+        self.drain_map
+            .values()
+            .filter(|e| e.is_expired(now))
+            .map(|e| e.as_ref().to_owned())
+            .collect()
+    }
+
+    pub fn get_drain_list(&self, now: i64) -> Vec<Arc<InstanceDrainDo>> {
+        let mut list: Vec<Arc<InstanceDrainDo>> = self
+            .drain_map
+            .values()
+            .filter(|e| !e.is_expired(now))
+            .cloned()
+            .collect();
+        list.sort_by_key(|e| e.gmt_create);
+        list
+    }
+
     /*
     pub(crate) fn notify_listener(&mut self,cluster_name:&str,updateType:UpdateInstanceType) -> UpdateInstanceType {
         if match updateType {
@@ -340,6 +443,7 @@ impl Service {
     pub fn get_metadata(&self) -> ServiceMetadata {
         ServiceMetadata {
             protect_threshold: self.protect_threshold,
+            selector: self.selector.clone(),
         }
     }
 
@@ -353,6 +457,7 @@ impl Service {
             trigger_flag: false,
             metadata: Some(self.metadata.clone()),
             protect_threshold: Some(self.protect_threshold),
+            selector: self.selector.as_ref().map(|e| e.expression.clone()),
         }
     }
 
@@ -368,14 +473,34 @@ impl Service {
             group_name: self.group_name.clone(),
             metadata,
             protect_threshold: Some(self.protect_threshold),
+            selector: self.selector.as_ref().map(|e| e.expression.clone()),
             ..Default::default()
         }
     }
 
+    pub fn get_cluster_list(&self) -> Vec<ServiceClusterDto> {
+        let mut list: Vec<ServiceClusterDto> = self
+            .cluster_map
+            .values()
+            .map(|e| e.as_ref().into())
+            .collect();
+        list.sort_by(|a, b| a.cluster_name.cmp(&b.cluster_name));
+        list
+    }
+
+    ///
+    /// 集群指定的健康检查端口
+    pub fn get_cluster_check_ports(&self) -> HashMap<String, u32> {
+        self.cluster_map
+            .iter()
+            .filter_map(|(k, v)| v.check_port().map(|port| (k.to_owned(), port)))
+            .collect()
+    }
+
     pub fn get_owner_http_instances(&self) -> Vec<Arc<Instance>> {
         self.instances
             .values()
-            .filter(|x| x.client_id.is_empty())
+            .filter(|x| x.ephemeral && x.client_id.is_empty())
             .cloned()
             .collect::<Vec<_>>()
     }
@@ -403,6 +528,7 @@ pub struct ServiceInfoDto {
     pub trigger_flag: bool,
     pub metadata: Option<Arc<HashMap<String, String>>>,
     pub protect_threshold: Option<f32>,
+    pub selector: Option<Arc<String>>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
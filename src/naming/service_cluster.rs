@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::naming::model::ServiceKey;
+use crate::naming::NamingUtils;
+
+///
+/// 服务下的集群配置,对应nacos的ClusterInfo
+/// 通过raft状态机同步
+#[derive(Clone, PartialEq, prost_derive::Message, Deserialize, Serialize)]
+pub struct ServiceClusterDo {
+    #[prost(string, tag = "1")]
+    pub namespace_id: String,
+    #[prost(string, tag = "2")]
+    pub group_name: String,
+    #[prost(string, tag = "3")]
+    pub service_name: String,
+    #[prost(string, tag = "4")]
+    pub cluster_name: String,
+    /// 健康检查端口
+    #[prost(uint32, tag = "5")]
+    pub health_check_port: u32,
+    /// 健康检查使用实例端口
+    #[prost(bool, tag = "6")]
+    pub use_instance_port: bool,
+    #[prost(map = "string, string", tag = "7")]
+    pub metadata: HashMap<String, String>,
+    #[prost(int64, tag = "8")]
+    pub gmt_modified: i64,
+}
+
+impl ServiceClusterDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        use prost::Message;
+        let mut v = Vec::new();
+        self.encode(&mut v)?;
+        Ok(v)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> anyhow::Result<Self> {
+        use prost::Message;
+        let s = Self::decode(data)?;
+        Ok(s)
+    }
+
+    ///
+    /// 存储key: namespace_id#group_name#service_name#cluster_name
+    pub fn build_key(&self) -> String {
+        format!(
+            "{}#{}#{}#{}",
+            &self.namespace_id, &self.group_name, &self.service_name, &self.cluster_name
+        )
+    }
+
+    pub fn get_service_key(&self) -> ServiceKey {
+        ServiceKey::new(&self.namespace_id, &self.group_name, &self.service_name)
+    }
+
+    ///
+    /// 集群指定的健康检查端口,使用实例端口时返回None
+    pub fn check_port(&self) -> Option<u32> {
+        if self.use_instance_port || self.health_check_port == 0 {
+            None
+        } else {
+            Some(self.health_check_port)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceClusterDto {
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: Arc<String>,
+    pub cluster_name: Arc<String>,
+    pub health_check_port: Option<u32>,
+    pub use_instance_port: Option<bool>,
+    pub metadata: Option<HashMap<String, String>>,
+    pub gmt_modified: Option<i64>,
+}
+
+impl ServiceClusterDto {
+    pub fn to_service_key(&self) -> ServiceKey {
+        ServiceKey::new(
+            &NamingUtils::default_namespace(self.namespace_id.clone().unwrap_or_default()),
+            &NamingUtils::default_group(self.group_name.clone().unwrap_or_default()),
+            &self.service_name,
+        )
+    }
+
+    pub fn to_do(&self) -> anyhow::Result<ServiceClusterDo> {
+        if self.service_name.is_empty() {
+            return Err(anyhow::anyhow!("serviceName is empty"));
+        }
+        if self.cluster_name.is_empty() {
+            return Err(anyhow::anyhow!("clusterName is empty"));
+        }
+        let service_key = self.to_service_key();
+        Ok(ServiceClusterDo {
+            namespace_id: service_key.namespace_id.as_ref().to_owned(),
+            group_name: service_key.group_name.as_ref().to_owned(),
+            service_name: service_key.service_name.as_ref().to_owned(),
+            cluster_name: self.cluster_name.as_ref().to_owned(),
+            health_check_port: self.health_check_port.unwrap_or_default(),
+            //与nacos一致,默认使用实例端口做健康检查
+            use_instance_port: self.use_instance_port.unwrap_or(true),
+            metadata: self.metadata.clone().unwrap_or_default(),
+            gmt_modified: self.gmt_modified.unwrap_or_default(),
+        })
+    }
+}
+
+impl From<&ServiceClusterDo> for ServiceClusterDto {
+    fn from(v: &ServiceClusterDo) -> Self {
+        Self {
+            namespace_id: Some(v.namespace_id.to_owned()),
+            group_name: Some(v.group_name.to_owned()),
+            service_name: Arc::new(v.service_name.to_owned()),
+            cluster_name: Arc::new(v.cluster_name.to_owned()),
+            health_check_port: Some(v.health_check_port),
+            use_instance_port: Some(v.use_instance_port),
+            metadata: Some(v.metadata.clone()),
+            gmt_modified: Some(v.gmt_modified),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_cluster_convert() {
+        let dto = ServiceClusterDto {
+            service_name: Arc::new("foo".to_owned()),
+            cluster_name: Arc::new("zone-a".to_owned()),
+            health_check_port: Some(9090),
+            ..Default::default()
+        };
+        let value = dto.to_do().unwrap();
+        assert_eq!(value.build_key(), "public#DEFAULT_GROUP#foo#zone-a");
+        assert!(value.use_instance_port);
+        assert_eq!(value.check_port(), None);
+
+        let mut value = ServiceClusterDo::from_bytes(&value.to_bytes().unwrap()).unwrap();
+        value.use_instance_port = false;
+        assert_eq!(value.check_port(), Some(9090));
+        let dto = ServiceClusterDto::from(&value);
+        assert_eq!(dto.to_service_key(), value.get_service_key());
+
+        let dto = ServiceClusterDto {
+            service_name: Arc::new("foo".to_owned()),
+            ..Default::default()
+        };
+        assert!(dto.to_do().is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::model::ServiceDetailDto;
+use super::service::Service;
+
+///
+/// 服务定义(metadata、保护阈值、选择器)的存储对象
+/// 通过raft状态机同步,重启后从快照恢复
+#[derive(Clone, PartialEq, prost_derive::Message, Deserialize, Serialize)]
+pub struct ServiceDefinitionDo {
+    #[prost(string, tag = "1")]
+    pub namespace_id: String,
+    #[prost(string, tag = "2")]
+    pub group_name: String,
+    #[prost(string, tag = "3")]
+    pub service_name: String,
+    #[prost(map = "string, string", tag = "4")]
+    pub metadata: HashMap<String, String>,
+    #[prost(float, tag = "5")]
+    pub protect_threshold: f32,
+    #[prost(string, tag = "6")]
+    pub selector: String,
+    #[prost(int64, tag = "7")]
+    pub gmt_modified: i64,
+}
+
+impl ServiceDefinitionDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        use prost::Message;
+        let mut v = Vec::new();
+        self.encode(&mut v)?;
+        Ok(v)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> anyhow::Result<Self> {
+        use prost::Message;
+        let s = Self::decode(data)?;
+        Ok(s)
+    }
+
+    ///
+    /// 存储key: namespace_id#group_name#service_name
+    pub fn build_key(&self) -> String {
+        format!(
+            "{}#{}#{}",
+            &self.namespace_id, &self.group_name, &self.service_name
+        )
+    }
+}
+
+impl From<&Service> for ServiceDefinitionDo {
+    fn from(value: &Service) -> Self {
+        Self {
+            namespace_id: value.namespace_id.as_ref().to_owned(),
+            group_name: value.group_name.as_ref().to_owned(),
+            service_name: value.service_name.as_ref().to_owned(),
+            metadata: value.metadata.as_ref().to_owned(),
+            protect_threshold: value.protect_threshold,
+            selector: value
+                .selector
+                .as_ref()
+                .map(|e| e.expression.as_ref().to_owned())
+                .unwrap_or_default(),
+            gmt_modified: value.last_modified_millis,
+        }
+    }
+}
+
+impl From<ServiceDefinitionDo> for ServiceDetailDto {
+    fn from(value: ServiceDefinitionDo) -> Self {
+        Self {
+            namespace_id: Arc::new(value.namespace_id),
+            service_name: Arc::new(value.service_name),
+            group_name: Arc::new(value.group_name),
+            metadata: Some(Arc::new(value.metadata)),
+            protect_threshold: Some(value.protect_threshold),
+            selector: Some(Arc::new(value.selector)),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_definition_convert() {
+        let mut metadata = HashMap::new();
+        metadata.insert("owner".to_owned(), "rnacos".to_owned());
+        let value = ServiceDefinitionDo {
+            namespace_id: "public".to_owned(),
+            group_name: "DEFAULT_GROUP".to_owned(),
+            service_name: "foo".to_owned(),
+            metadata,
+            protect_threshold: 0.5f32,
+            selector: "metadata.env == prod".to_owned(),
+            gmt_modified: 1,
+        };
+        let value = ServiceDefinitionDo::from_bytes(&value.to_bytes().unwrap()).unwrap();
+        assert_eq!(value.build_key(), "public#DEFAULT_GROUP#foo");
+        let detail: ServiceDetailDto = value.into();
+        assert_eq!(detail.protect_threshold, Some(0.5f32));
+        assert_eq!(detail.metadata.unwrap().get("owner").unwrap(), "rnacos");
+        assert_eq!(detail.selector.unwrap().as_str(), "metadata.env == prod");
+    }
+}
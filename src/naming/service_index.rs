@@ -1,5 +1,5 @@
 use super::model::ServiceKey;
-use crate::common::model::privilege::NamespacePrivilegeGroup;
+use crate::common::model::privilege::{GroupPrivilegeGroup, NamespacePrivilegeGroup};
 use crate::common::string_utils::StringUtils;
 use crate::namespace::model::{NamespaceActorReq, WeakNamespaceFromType, WeakNamespaceParam};
 use crate::namespace::NamespaceActor;
@@ -18,6 +18,8 @@ pub struct ServiceQueryParam {
     pub like_group: Option<String>,
     pub like_service: Option<String>,
     pub namespace_privilege: NamespacePrivilegeGroup,
+    #[serde(default)]
+    pub group_privilege: GroupPrivilegeGroup,
     pub offset: usize,
     pub limit: usize,
 }
@@ -32,6 +34,9 @@ impl ServiceQueryParam {
     }
 
     pub fn match_group(&self, g: &Arc<String>) -> bool {
+        if !self.group_privilege.check_permission(g) {
+            return false;
+        }
         if let Some(group) = &self.group {
             group.is_empty() || StringUtils::eq(g, group)
         } else if let Some(like_group) = &self.like_group {
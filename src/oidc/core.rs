@@ -0,0 +1,595 @@
+use crate::oidc::model::{
+    OidcConfig, OidcProviderMetadata, OidcReq, OidcResult, OidcTokenResponse, OidcUserMeta,
+};
+use crate::user::model::{UserDto, UserSourceType};
+use crate::user::{permission, UserManager, UserManagerReq, UserManagerResult};
+use actix::prelude::*;
+use bean_factory::{bean, BeanFactory, FactoryData, Inject};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Header, Validation};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+type Claims = Map<String, Value>;
+
+///
+/// OIDC授权码登录
+/// 身份提供方的discovery信息与签名公钥(jwks)在首次使用时加载并缓存,公钥找不到对应kid时重新加载
+#[bean(inject)]
+pub struct OidcManager {
+    oidc_config: Arc<OidcConfig>,
+    enable_oidc: bool,
+    client: reqwest::Client,
+    metadata: Option<Arc<OidcProviderMetadata>>,
+    jwks: Option<Arc<JwkSet>>,
+    user_manager_addr: Option<Addr<UserManager>>,
+}
+
+impl OidcManager {
+    pub fn new(oidc_config: Arc<OidcConfig>, enable_oidc: bool) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self {
+            oidc_config,
+            enable_oidc,
+            client,
+            metadata: None,
+            jwks: None,
+            user_manager_addr: None,
+        }
+    }
+
+    async fn discover(
+        client: &reqwest::Client,
+        config: &OidcConfig,
+    ) -> anyhow::Result<OidcProviderMetadata> {
+        let url = format!("{}/.well-known/openid-configuration", &config.issuer);
+        let metadata: OidcProviderMetadata = client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if metadata.issuer.trim_end_matches('/') != config.issuer.as_str() {
+            return Err(anyhow::anyhow!(
+                "oidc issuer mismatch,expect:{},actual:{}",
+                &config.issuer,
+                &metadata.issuer
+            ));
+        }
+        Ok(metadata)
+    }
+
+    fn build_authorize_url(
+        metadata: &OidcProviderMetadata,
+        config: &OidcConfig,
+        state: &str,
+        nonce: &str,
+    ) -> anyhow::Result<String> {
+        let url = reqwest::Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", config.client_id.as_str()),
+                ("redirect_uri", config.redirect_url.as_str()),
+                ("scope", config.scopes.as_str()),
+                ("state", state),
+                ("nonce", nonce),
+            ],
+        )?;
+        Ok(url.to_string())
+    }
+
+    async fn exchange_code(
+        client: &reqwest::Client,
+        metadata: &OidcProviderMetadata,
+        config: &OidcConfig,
+        code: &str,
+    ) -> anyhow::Result<OidcTokenResponse> {
+        let params = [
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", config.redirect_url.as_str()),
+            ("client_id", config.client_id.as_str()),
+            ("client_secret", config.client_secret.as_str()),
+        ];
+        let res = client
+            .post(&metadata.token_endpoint)
+            .form(&params)
+            .send()
+            .await?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "oidc token request failed,status:{},body:{}",
+                status,
+                body
+            ));
+        }
+        Ok(res.json().await?)
+    }
+
+    async fn query_jwks(
+        client: &reqwest::Client,
+        metadata: &OidcProviderMetadata,
+    ) -> anyhow::Result<JwkSet> {
+        let jwks_uri = metadata
+            .jwks_uri
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("oidc provider has no jwks_uri"))?;
+        Ok(client
+            .get(jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    fn is_hmac(alg: Algorithm) -> bool {
+        matches!(alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512)
+    }
+
+    ///
+    /// 非对称签名从jwks中按kid查找公钥;HS系列签名按OIDC规范使用client_secret
+    fn build_decoding_key(
+        header: &Header,
+        config: &OidcConfig,
+        jwks: &JwkSet,
+    ) -> anyhow::Result<Option<DecodingKey>> {
+        if Self::is_hmac(header.alg) {
+            return Ok(Some(DecodingKey::from_secret(
+                config.client_secret.as_bytes(),
+            )));
+        }
+        let jwk = match &header.kid {
+            Some(kid) => jwks.find(kid),
+            None if jwks.keys.len() == 1 => jwks.keys.first(),
+            None => None,
+        };
+        match jwk {
+            Some(jwk) => Ok(Some(DecodingKey::from_jwk(jwk)?)),
+            None => Ok(None),
+        }
+    }
+
+    ///
+    /// 校验id_token签名及iss、aud、exp与nonce
+    fn decode_id_token(
+        id_token: &str,
+        key: &DecodingKey,
+        alg: Algorithm,
+        config: &OidcConfig,
+        nonce: &str,
+    ) -> anyhow::Result<Claims> {
+        let mut validation = Validation::new(alg);
+        validation.set_issuer(&[
+            config.issuer.as_str().to_owned(),
+            format!("{}/", &config.issuer),
+        ]);
+        validation.set_audience(&[config.client_id.as_str()]);
+        let claims = jsonwebtoken::decode::<Claims>(id_token, key, &validation)
+            .map_err(|err| anyhow::anyhow!("invalid id_token,{}", err))?
+            .claims;
+        if claims.get("nonce").and_then(|v| v.as_str()) != Some(nonce) {
+            return Err(anyhow::anyhow!("id_token nonce mismatch"));
+        }
+        Ok(claims)
+    }
+
+    ///
+    /// 缓存的jwks中找不到签名公钥时(身份提供方轮换公钥)重新加载一次
+    async fn verify_id_token(
+        client: &reqwest::Client,
+        metadata: &OidcProviderMetadata,
+        config: &OidcConfig,
+        jwks: &mut Option<Arc<JwkSet>>,
+        id_token: &str,
+        nonce: &str,
+    ) -> anyhow::Result<Claims> {
+        let header = jsonwebtoken::decode_header(id_token)
+            .map_err(|err| anyhow::anyhow!("invalid id_token,{}", err))?;
+        let mut key = None;
+        if let Some(cached) = jwks.as_ref() {
+            key = Self::build_decoding_key(&header, config, cached)?;
+        }
+        if key.is_none() {
+            let new_jwks = Arc::new(Self::query_jwks(client, metadata).await?);
+            key = Self::build_decoding_key(&header, config, &new_jwks)?;
+            *jwks = Some(new_jwks);
+        }
+        let key = key.ok_or_else(|| anyhow::anyhow!("id_token signing key not found"))?;
+        Self::decode_id_token(id_token, &key, header.alg, config, nonce)
+    }
+
+    async fn query_userinfo(
+        client: &reqwest::Client,
+        userinfo_endpoint: &str,
+        access_token: &str,
+    ) -> anyhow::Result<Claims> {
+        Ok(client
+            .get(userinfo_endpoint)
+            .bearer_auth(access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    fn get_claim_values(claims: &Claims, name: &str) -> Vec<String> {
+        match claims.get(name) {
+            Some(Value::String(v)) => vec![v.to_owned()],
+            Some(Value::Array(list)) => list
+                .iter()
+                .filter_map(|v| v.as_str().map(|v| v.to_owned()))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    ///
+    /// 按用户组映射角色,规则与ldap一致:管理员组优先于开发者组,都不匹配时使用默认角色
+    fn build_role(config: &OidcConfig, groups: &[String]) -> Arc<String> {
+        if groups.iter().any(|g| config.user_admin_groups.contains(g)) {
+            permission::USER_ROLE_MANAGER.clone()
+        } else if groups
+            .iter()
+            .any(|g| config.user_developer_groups.contains(g))
+        {
+            permission::USER_ROLE_DEVELOPER.clone()
+        } else {
+            config.user_default_role.clone()
+        }
+    }
+
+    fn build_user_meta(config: &OidcConfig, claims: &Claims) -> anyhow::Result<OidcUserMeta> {
+        let user_name = Self::get_claim_values(claims, &config.username_claim)
+            .pop()
+            .or_else(|| Self::get_claim_values(claims, "sub").pop())
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("oidc username claim is empty"))?;
+        let nickname = Self::get_claim_values(claims, "name")
+            .pop()
+            .unwrap_or_else(|| user_name.clone());
+        let groups = Self::get_claim_values(claims, &config.groups_claim);
+        let role = Self::build_role(config, &groups);
+        Ok(OidcUserMeta {
+            user_name,
+            nickname,
+            groups,
+            role,
+            namespace_privilege: None,
+            config_group_privilege: None,
+            service_group_privilege: None,
+        })
+    }
+
+    ///
+    /// 首次登录时创建OIDC来源的用户;已存在的同名用户需为OIDC来源且处于启用状态
+    async fn init_user(
+        user_manager_addr: &Addr<UserManager>,
+        meta: &mut OidcUserMeta,
+    ) -> anyhow::Result<()> {
+        let user = UserDto {
+            username: Arc::new(meta.user_name.clone()),
+            nickname: Some(meta.nickname.clone()),
+            source: Some(UserSourceType::Oidc.to_str().to_owned()),
+            roles: Some(vec![meta.role.clone()]),
+            ..Default::default()
+        };
+        let res = user_manager_addr
+            .send(UserManagerReq::InitUser {
+                user,
+                namespace_privilege_param: None,
+                group_privilege_param: Default::default(),
+            })
+            .await??;
+        if let UserManagerResult::QueryUser(Some(user_dto)) = res {
+            if user_dto.source.as_deref() != Some(UserSourceType::Oidc.to_str()) {
+                return Err(anyhow::anyhow!(
+                    "user {} already exists with another source",
+                    &meta.user_name
+                ));
+            }
+            if !user_dto.enable.unwrap_or(true) {
+                return Err(anyhow::anyhow!("user {} is disabled", &meta.user_name));
+            }
+            meta.namespace_privilege = user_dto.namespace_privilege;
+            meta.config_group_privilege = user_dto.config_group_privilege;
+            meta.service_group_privilege = user_dto.service_group_privilege;
+        }
+        Ok(())
+    }
+
+    async fn login(
+        client: &reqwest::Client,
+        metadata: &OidcProviderMetadata,
+        config: &OidcConfig,
+        jwks: &mut Option<Arc<JwkSet>>,
+        user_manager_addr: Option<Addr<UserManager>>,
+        code: &str,
+        nonce: &str,
+    ) -> anyhow::Result<OidcUserMeta> {
+        let token = Self::exchange_code(client, metadata, config, code).await?;
+        let id_token = token
+            .id_token
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("oidc token response has no id_token"))?;
+        let mut claims =
+            Self::verify_id_token(client, metadata, config, jwks, id_token, nonce).await?;
+        if let Some(userinfo_endpoint) = &metadata.userinfo_endpoint {
+            let userinfo =
+                Self::query_userinfo(client, userinfo_endpoint, &token.access_token).await?;
+            if let (Some(sub), Some(userinfo_sub)) = (claims.get("sub"), userinfo.get("sub")) {
+                if sub != userinfo_sub {
+                    return Err(anyhow::anyhow!("oidc userinfo subject mismatch"));
+                }
+            }
+            claims.extend(userinfo);
+        }
+        let mut meta = Self::build_user_meta(config, &claims)?;
+        if let Some(user_manager_addr) = &user_manager_addr {
+            Self::init_user(user_manager_addr, &mut meta).await?;
+        }
+        Ok(meta)
+    }
+
+    async fn handle_req(
+        client: reqwest::Client,
+        metadata: Option<Arc<OidcProviderMetadata>>,
+        mut jwks: Option<Arc<JwkSet>>,
+        config: Arc<OidcConfig>,
+        user_manager_addr: Option<Addr<UserManager>>,
+        msg: OidcReq,
+    ) -> anyhow::Result<(Arc<OidcProviderMetadata>, Option<Arc<JwkSet>>, OidcResult)> {
+        let metadata = match metadata {
+            Some(v) => v,
+            None => Arc::new(Self::discover(&client, &config).await?),
+        };
+        let result = match msg {
+            OidcReq::AuthorizeUrl { state, nonce } => OidcResult::AuthorizeUrl(
+                Self::build_authorize_url(&metadata, &config, &state, &nonce)?,
+            ),
+            OidcReq::Login { code, nonce } => OidcResult::UserMeta(
+                Self::login(
+                    &client,
+                    &metadata,
+                    &config,
+                    &mut jwks,
+                    user_manager_addr,
+                    &code,
+                    &nonce,
+                )
+                .await?,
+            ),
+        };
+        Ok((metadata, jwks, result))
+    }
+}
+
+impl Actor for OidcManager {
+    type Context = Context<Self>;
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("OidcManager started");
+    }
+}
+
+impl Inject for OidcManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: FactoryData,
+        _factory: BeanFactory,
+        _ctx: &mut Self::Context,
+    ) {
+        self.user_manager_addr = factory_data.get_actor();
+    }
+}
+
+impl Handler<OidcReq> for OidcManager {
+    type Result = ResponseActFuture<Self, anyhow::Result<OidcResult>>;
+
+    fn handle(&mut self, msg: OidcReq, _ctx: &mut Self::Context) -> Self::Result {
+        if !self.enable_oidc {
+            return Box::pin(actix::fut::ready(Err(anyhow::anyhow!(
+                "oidc login is not enabled"
+            ))));
+        }
+        let fut = Self::handle_req(
+            self.client.clone(),
+            self.metadata.clone(),
+            self.jwks.clone(),
+            self.oidc_config.clone(),
+            self.user_manager_addr.clone(),
+            msg,
+        )
+        .into_actor(self)
+        .map(|result, act, _ctx| {
+            let (metadata, jwks, result) = result?;
+            act.metadata = Some(metadata);
+            act.jwks = jwks;
+            Ok(result)
+        });
+        Box::pin(fut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::now_millis_i64;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use jsonwebtoken::EncodingKey;
+    use std::collections::HashSet;
+
+    const CLIENT_ID: &str = "rnacos";
+    const CODE: &str = "mock_code";
+    /// 返回使用其它密钥签名的id_token
+    const FORGED_CODE: &str = "forged_code";
+    const NONCE: &str = "mock_nonce";
+    const RSA_KEY: &[u8] = include_bytes!("testdata/rsa_key.pem");
+    const JWKS: &str = include_str!("testdata/jwks.json");
+
+    fn sign_id_token(claims: &Value, forged: bool) -> String {
+        if forged {
+            let header = Header::new(Algorithm::HS256);
+            let key = EncodingKey::from_secret(b"other_secret");
+            return jsonwebtoken::encode(&header, claims, &key).unwrap();
+        }
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some("test-key".to_owned());
+        let key = EncodingKey::from_rsa_pem(RSA_KEY).unwrap();
+        jsonwebtoken::encode(&header, claims, &key).unwrap()
+    }
+
+    ///
+    /// 本地模拟的OIDC身份提供方
+    fn start_mock_provider() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let data = web::Data::new(issuer.clone());
+        let server = HttpServer::new(move || {
+            App::new()
+                    .app_data(data.clone())
+                    .route(
+                        "/.well-known/openid-configuration",
+                        web::get().to(|issuer: web::Data<String>| async move {
+                            HttpResponse::Ok().json(OidcProviderMetadata {
+                                issuer: issuer.to_string(),
+                                authorization_endpoint: format!("{}/authorize", issuer.as_str()),
+                                token_endpoint: format!("{}/token", issuer.as_str()),
+                                userinfo_endpoint: Some(format!("{}/userinfo", issuer.as_str())),
+                                jwks_uri: Some(format!("{}/jwks", issuer.as_str())),
+                            })
+                        }),
+                    )
+                    .route(
+                        "/jwks",
+                        web::get().to(|| async {
+                            HttpResponse::Ok()
+                                .content_type("application/json")
+                                .body(JWKS)
+                        }),
+                    )
+                    .route(
+                        "/token",
+                        web::post().to(
+                            |issuer: web::Data<String>,
+                             web::Form(form): web::Form<
+                                std::collections::HashMap<String, String>,
+                            >| async move {
+                                let code = form.get("code").map(|v| v.as_str());
+                                if (code != Some(CODE) && code != Some(FORGED_CODE))
+                                    || form.get("client_secret").map(|v| v.as_str())
+                                        != Some("secret")
+                                {
+                                    return HttpResponse::BadRequest().body("invalid_grant");
+                                }
+                                let claims = serde_json::json!({
+                                    "iss": issuer.as_str(),
+                                    "aud": [CLIENT_ID],
+                                    "sub": "u001",
+                                    "exp": now_millis_i64() / 1000 + 300,
+                                    "nonce": NONCE,
+                                    "preferred_username": "alice",
+                                });
+                                let id_token = sign_id_token(&claims, code == Some(FORGED_CODE));
+                                HttpResponse::Ok().json(OidcTokenResponse {
+                                    access_token: "mock_access_token".to_owned(),
+                                    id_token: Some(id_token),
+                                    token_type: Some("Bearer".to_owned()),
+                                })
+                            },
+                        ),
+                    )
+                    .route(
+                        "/userinfo",
+                        web::get().to(|| async {
+                            HttpResponse::Ok().json(serde_json::json!({
+                                "sub": "u001",
+                                "name": "Alice",
+                                "groups": ["dev", "ops"],
+                            }))
+                        }),
+                    )
+        })
+        .listen(listener)
+        .unwrap()
+        .run();
+        actix_rt::spawn(server);
+        issuer
+    }
+
+    #[actix_rt::test]
+    async fn oidc_login_with_mock_provider() {
+        let issuer = start_mock_provider();
+        let config = Arc::new(OidcConfig {
+            issuer: Arc::new(issuer.clone()),
+            client_id: Arc::new(CLIENT_ID.to_owned()),
+            client_secret: Arc::new("secret".to_owned()),
+            redirect_url: Arc::new("http://127.0.0.1:8848/callback".to_owned()),
+            scopes: Arc::new("openid profile".to_owned()),
+            username_claim: Arc::new("preferred_username".to_owned()),
+            groups_claim: Arc::new("groups".to_owned()),
+            user_developer_groups: Arc::new(HashSet::from(["dev".to_owned()])),
+            user_admin_groups: Arc::new(HashSet::from(["admin".to_owned()])),
+            user_default_role: permission::USER_ROLE_VISITOR.clone(),
+        });
+        let addr = OidcManager::new(config, true).start();
+        let url = match addr
+            .send(OidcReq::AuthorizeUrl {
+                state: "s1".to_owned(),
+                nonce: NONCE.to_owned(),
+            })
+            .await
+            .unwrap()
+            .unwrap()
+        {
+            OidcResult::AuthorizeUrl(v) => v,
+            _ => panic!("unexpected result"),
+        };
+        assert!(url.starts_with(&format!("{}/authorize?response_type=code", issuer)));
+        assert!(url.contains("state=s1"));
+
+        let meta = match addr
+            .send(OidcReq::Login {
+                code: CODE.to_owned(),
+                nonce: NONCE.to_owned(),
+            })
+            .await
+            .unwrap()
+            .unwrap()
+        {
+            OidcResult::UserMeta(v) => v,
+            _ => panic!("unexpected result"),
+        };
+        assert_eq!(&meta.user_name, "alice");
+        assert_eq!(&meta.nickname, "Alice");
+        assert_eq!(meta.role, permission::USER_ROLE_DEVELOPER.clone());
+
+        let err = addr
+            .send(OidcReq::Login {
+                code: CODE.to_owned(),
+                nonce: "other".to_owned(),
+            })
+            .await
+            .unwrap();
+        assert!(err.is_err());
+
+        let err = addr
+            .send(OidcReq::Login {
+                code: FORGED_CODE.to_owned(),
+                nonce: NONCE.to_owned(),
+            })
+            .await
+            .unwrap();
+        assert!(err.is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::common::model::privilege::PrivilegeGroup;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Default)]
+pub struct OidcConfig {
+    pub issuer: Arc<String>,
+    pub client_id: Arc<String>,
+    pub client_secret: Arc<String>,
+    pub redirect_url: Arc<String>,
+    pub scopes: Arc<String>,
+    pub username_claim: Arc<String>,
+    pub groups_claim: Arc<String>,
+    pub user_developer_groups: Arc<HashSet<String>>,
+    pub user_admin_groups: Arc<HashSet<String>>,
+    pub user_default_role: Arc<String>,
+}
+
+///
+/// 身份提供方的discovery信息(`/.well-known/openid-configuration`)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OidcProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub userinfo_endpoint: Option<String>,
+    #[serde(default)]
+    pub jwks_uri: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OidcTokenResponse {
+    pub access_token: String,
+    #[serde(default)]
+    pub id_token: Option<String>,
+    #[serde(default)]
+    pub token_type: Option<String>,
+}
+
+///
+/// 发起登录时缓存的state信息,回调时校验
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OidcLoginState {
+    pub nonce: String,
+    pub redirect_url: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OidcCallbackParam {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OidcAuthorizeParam {
+    pub redirect_url: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OidcUserMeta {
+    pub user_name: String,
+    pub nickname: String,
+    pub groups: Vec<String>,
+    pub role: Arc<String>,
+    pub namespace_privilege: Option<PrivilegeGroup<Arc<String>>>,
+    pub config_group_privilege: Option<PrivilegeGroup<Arc<String>>>,
+    pub service_group_privilege: Option<PrivilegeGroup<Arc<String>>>,
+}
+
+#[derive(Clone, Debug, actix::Message)]
+#[rtype(result = "anyhow::Result<OidcResult>")]
+pub enum OidcReq {
+    /// 构建跳转到身份提供方的授权地址
+    AuthorizeUrl { state: String, nonce: String },
+    /// 使用授权码换取用户信息
+    Login { code: String, nonce: String },
+}
+
+#[derive(Clone, Debug)]
+pub enum OidcResult {
+    AuthorizeUrl(String),
+    UserMeta(OidcUserMeta),
+}
@@ -2,8 +2,10 @@ use crate::common::appdata::AppShareData;
 use crate::common::model::TokenSession;
 use crate::common::option_utils::OptionUtils;
 use crate::merge_web_param_with_result;
+use crate::now_millis_i64;
 use crate::raft::cache::model::{CacheKey, CacheType, CacheValue};
 use crate::raft::cache::{CacheLimiterReq, CacheManagerReq, CacheManagerResult};
+use crate::user::access_key::{AccessKeyReq, AccessKeyResult};
 use crate::user::{UserManagerReq, UserManagerResult};
 use actix_web::{web, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
@@ -80,46 +82,83 @@ async fn do_login(
         return Err(anyhow::anyhow!("SYSTEM_ERROR"));
     }
     let msg = UserManagerReq::CheckUser {
-        name: username,
-        password,
+        name: username.clone(),
+        password: password.clone(),
     };
     if let Ok(Ok(UserManagerResult::CheckUserResult(valid, user))) =
         app.user_manager.send(msg).await
     {
+        //与控制台登录一致:锁定或需要修改密码(含密码过期)的用户不发放token
+        if user.locked.unwrap_or_default() {
+            return Err(anyhow::anyhow!("USER_LOCKED,user is locked"));
+        }
         if valid {
-            //增加长度避免遍历
-            let token = Arc::new(
-                uuid::Uuid::new_v4().to_string().replace('-', "")
-                    + &uuid::Uuid::new_v4().to_string().replace('-', ""),
-            );
-            let session = Arc::new(TokenSession {
+            if user.need_change_password.unwrap_or_default() {
+                return Err(anyhow::anyhow!(
+                    "PASSWORD_NEED_CHANGE,password must be changed before login"
+                ));
+            }
+            let session = TokenSession {
                 username: user.username,
                 roles: user.roles.unwrap_or_default(),
                 extend_infos: user.extend_info.unwrap_or_default(),
-            });
-            let cache_req = CacheManagerReq::Set {
-                key: CacheKey::new(CacheType::ApiTokenSession, token.clone()),
-                value: CacheValue::ApiTokenSession(session),
-                ttl: app.sys_config.openapi_login_timeout,
+                config_group_privilege: user.config_group_privilege,
+                service_group_privilege: user.service_group_privilege,
+                ..Default::default()
             };
-            app.cache_manager.do_send(cache_req);
-            //登录成功后清除登陆限流计数
-            let clear_limit_req =
-                CacheManagerReq::Remove(CacheKey::new(CacheType::String, limit_key));
-            app.cache_manager.do_send(clear_limit_req);
-            let login_result = LoginResult {
-                access_token: Some(token),
-                token_ttl: app.sys_config.openapi_login_timeout as i64,
-                global_admin: false,
-            };
-            return Ok(HttpResponse::Ok().json(login_result));
-        } else {
-            return Err(anyhow::anyhow!(UNKNOWN_USER));
+            return Ok(issue_token(
+                app,
+                session,
+                app.sys_config.openapi_login_timeout,
+                limit_key,
+            ));
         }
     }
+    //用户校验不通过时,按访问密钥(username为keyId,password为secret)校验
+    let msg = AccessKeyReq::CheckSecret {
+        key_id: username,
+        secret: password,
+    };
+    if let Ok(Ok(AccessKeyResult::Valid(Some(access_key)))) = app.access_key_manager.send(msg).await
+    {
+        let mut ttl = app.sys_config.openapi_login_timeout;
+        if access_key.expire_time > 0 {
+            let remain = (access_key.expire_time - now_millis_i64()) / 1000;
+            ttl = remain.clamp(1, ttl as i64) as i32;
+        }
+        return Ok(issue_token(app, access_key.build_session(), ttl, limit_key));
+    }
     Err(anyhow::anyhow!(UNKNOWN_USER))
 }
 
+fn issue_token(
+    app: &web::Data<Arc<AppShareData>>,
+    session: TokenSession,
+    ttl: i32,
+    limit_key: Arc<String>,
+) -> HttpResponse {
+    //增加长度避免遍历
+    let token = Arc::new(
+        uuid::Uuid::new_v4().to_string().replace('-', "")
+            + &uuid::Uuid::new_v4().to_string().replace('-', ""),
+    );
+    let cache_req = CacheManagerReq::Set {
+        key: CacheKey::new(CacheType::ApiTokenSession, token.clone()),
+        value: CacheValue::ApiTokenSession(Arc::new(session)),
+        ttl,
+    };
+    app.cache_manager.do_send(cache_req);
+    //登录成功后清除登陆限流计数
+    let clear_limit_req = CacheManagerReq::Remove(CacheKey::new(CacheType::String, limit_key));
+    app.cache_manager.do_send(clear_limit_req);
+    let login_result = LoginResult {
+        access_token: Some(token),
+        token_ttl: ttl as i64,
+        global_admin: false,
+    };
+    HttpResponse::Ok().json(login_result)
+}
+
 pub(crate) async fn mock_token() -> impl Responder {
     "{\"accessToken\":\"mock_token\",\"tokenTtl\":18000,\"globalAdmin\":true}"
 }
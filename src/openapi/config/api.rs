@@ -2,27 +2,35 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use actix::Addr;
-use actix_web::{web, HttpRequest, HttpResponse, Responder, Scope};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder, Scope};
 use chrono::Local;
 use serde::{Deserialize, Serialize};
 
+use crate::audit::model::{
+    AUDIT_ACTION_DELETE, AUDIT_ACTION_GRAY_PROMOTE, AUDIT_ACTION_GRAY_PUBLISH,
+    AUDIT_ACTION_GRAY_REMOVE, AUDIT_ACTION_PUBLISH, AUDIT_ACTION_ROLLBACK, AUDIT_MODULE_CONFIG,
+};
+use crate::audit::AuditLogUtils;
 use crate::common::appdata::AppShareData;
+use crate::common::error_code::CONFIG_CONTENT_INVALID;
 use crate::common::model::ApiResult;
 use crate::common::option_utils::OptionUtils;
 use crate::common::string_utils::StringUtils;
 use crate::common::web_utils::get_req_body;
+use crate::config::config_fuzzy::{ConfigFuzzyChangeDto, ConfigFuzzyPattern, ConfigFuzzyWatcher};
+use crate::config::config_gray::{ConfigClient, ConfigGrayRule};
 use crate::config::config_index::ConfigQueryParam;
-use crate::config::config_type::ConfigType;
+use crate::config::config_type::{ConfigContentInvalidError, ConfigType};
 use crate::config::core::{
     ConfigActor, ConfigCmd, ConfigInfoDto, ConfigKey, ConfigResult, ListenerItem, ListenerResult,
 };
 use crate::config::utils::param_utils;
 use crate::config::ConfigUtils;
 use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
-use crate::merge_web_param;
 use crate::openapi::constant::EMPTY;
 use crate::raft::cluster::model::{DelConfigReq, SetConfigReq};
 use crate::utils::select_option_by_clone;
+use crate::{merge_web_param, user_config_group_privilege, user_namespace_privilege};
 
 pub(super) fn service() -> Scope {
     web::scope("/configs")
@@ -34,6 +42,44 @@ pub(super) fn service() -> Scope {
                 .route(web::delete().to(del_config)),
         )
         .service(web::resource("/listener").route(web::post().to(listener_config)))
+        .service(web::resource("/fuzzy-listener").route(web::post().to(fuzzy_listener_config)))
+        .service(
+            web::resource("/beta")
+                .route(web::get().to(get_gray_config))
+                .route(web::post().to(add_gray_config))
+                .route(web::put().to(add_gray_config))
+                .route(web::delete().to(del_gray_config)),
+        )
+        .service(web::resource("/beta/promote").route(web::post().to(promote_gray_config)))
+}
+
+pub(super) fn history_service() -> Scope {
+    web::scope("/history")
+        .service(web::resource("/rollback").route(web::post().to(rollback_config)))
+}
+
+/// nacos http客户端通过此header传递应用名
+const CLIENT_APP_NAME_HEADER: &str = "Client-AppName";
+const CLIENT_APP_NAME_LABEL: &str = "AppName";
+const BETA_IPS_HEADER: &str = "betaIps";
+
+///
+/// 构建http请求对应的配置客户端信息,用于匹配灰度规则
+/// 客户端ip取连接的对端地址,不使用可被伪造的X-Forwarded-For等请求头
+pub(crate) fn build_config_client(req: &HttpRequest) -> Arc<ConfigClient> {
+    let ip = req
+        .peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_default();
+    let mut labels = HashMap::new();
+    if let Some(Ok(app_name)) = req
+        .headers()
+        .get(CLIENT_APP_NAME_HEADER)
+        .map(|v| v.to_str())
+    {
+        labels.insert(CLIENT_APP_NAME_LABEL.to_owned(), app_name.to_owned());
+    }
+    Arc::new(ConfigClient::new(Arc::new(ip), Arc::new(labels)))
 }
 
 #[derive(Serialize, Deserialize)]
@@ -48,6 +94,30 @@ pub struct ConfigWebParams {
     pub search: Option<String>,   //search type
     pub page_no: Option<usize>,   //use at search
     pub page_size: Option<usize>, //use at search
+    pub beta_ips: Option<String>, //use at gray publish
+    pub labels: Option<String>,   //use at gray publish
+    #[serde(rename = "config_tags")]
+    pub config_tags: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigHistoryWebParams {
+    pub data_id: Option<String>,
+    pub group: Option<String>,
+    pub tenant: Option<String>,
+    pub id: Option<u64>,
+}
+
+impl ConfigHistoryWebParams {
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            data_id: OptionUtils::select(self.data_id, other.data_id),
+            group: OptionUtils::select(self.group, other.group),
+            tenant: OptionUtils::select(self.tenant, other.tenant),
+            id: OptionUtils::select(self.id, other.id),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -99,6 +169,9 @@ impl ConfigWebParams {
             search: OptionUtils::select(self.search, other.search),
             page_no: OptionUtils::select(self.page_no, other.page_no),
             page_size: OptionUtils::select(self.page_size, other.page_size),
+            beta_ips: OptionUtils::select(self.beta_ips, other.beta_ips),
+            labels: OptionUtils::select(self.labels, other.labels),
+            config_tags: OptionUtils::select(self.config_tags, other.config_tags),
         }
     }
 
@@ -130,14 +203,24 @@ impl ConfigWebParams {
         Ok(param)
     }
 
+    fn build_query_tags(&self) -> Option<Vec<String>> {
+        self.config_tags.as_ref().map(|v| {
+            ConfigUtils::split_tags(v)
+                .map(|e| e.to_owned())
+                .collect::<Vec<_>>()
+        })
+    }
+
     pub fn build_like_search_param(self) -> ConfigQueryParam {
         let limit = self.page_size.unwrap_or(0xffff_ffff);
         let offset = (self.page_no.unwrap_or(1) - 1) * limit;
+        let tags = self.build_query_tags();
         let mut param = ConfigQueryParam {
             limit,
             offset,
             like_group: self.group,
             like_data_id: self.data_id,
+            tags,
             query_context: true,
             ..Default::default()
         };
@@ -150,11 +233,13 @@ impl ConfigWebParams {
     pub fn build_search_param(self) -> ConfigQueryParam {
         let limit = self.page_size.unwrap_or(0xffff_ffff);
         let offset = (self.page_no.unwrap_or(1) - 1) * limit;
+        let tags = self.build_query_tags();
         let mut param = ConfigQueryParam {
             limit,
             offset,
             group: self.group.map(Arc::new),
             data_id: self.data_id.map(Arc::new),
+            tags,
             query_context: true,
             ..Default::default()
         };
@@ -173,12 +258,29 @@ pub struct ConfigWebConfirmedParam {
     pub content: String,
 }
 
+///
+/// 校验openapi用户的配置分组数据权限,无权限时返回403
+fn check_group_permission(req: &HttpRequest, group: &str) -> Option<HttpResponse> {
+    if user_config_group_privilege!(req).check_permission(group) {
+        None
+    } else {
+        Some(HttpResponse::Forbidden().body(format!("user no such group permission: {}", group)))
+    }
+}
+
 pub(crate) async fn add_config(
+    req: HttpRequest,
     a: web::Query<ConfigWebParams>,
     payload: web::Payload,
     appdata: web::Data<Arc<AppShareData>>,
 ) -> impl Responder {
-    let selected_param = merge_web_param!(a.0, payload);
+    let mut selected_param = merge_web_param!(a.0, payload);
+    if let Some(Ok(beta_ips)) = req.headers().get(BETA_IPS_HEADER).map(|v| v.to_str()) {
+        if !beta_ips.is_empty() {
+            selected_param.beta_ips = Some(beta_ips.to_owned());
+            return do_add_gray_config(&req, selected_param, appdata).await;
+        }
+    }
     match param_utils::check_tenant(&selected_param.tenant) {
         Ok(v) => v,
         Err(err) => {
@@ -200,19 +302,41 @@ pub(crate) async fn add_config(
 
     let config_type = StringUtils::map_not_empty(selected_param.r#type.clone());
     let desc = StringUtils::map_not_empty(selected_param.desc.clone());
+    let tags = selected_param
+        .config_tags
+        .as_ref()
+        .map(|v| ConfigUtils::format_tags(v));
     let param = selected_param.to_confirmed_param();
     match param {
         Ok(p) => {
-            let mut req = SetConfigReq::new(
+            if let Some(resp) = check_group_permission(&req, &p.group) {
+                return resp;
+            }
+            let mut set_req = SetConfigReq::new(
                 ConfigKey::new(&p.data_id, &p.group, &p.tenant),
                 Arc::new(p.content.to_owned()),
             );
-            req.config_type = config_type.map(|v| ConfigType::new_by_value(v.as_ref()).get_value());
-            req.desc = desc.map(Arc::new);
-            match appdata.config_route.set_config(req).await {
+            set_req.config_type =
+                config_type.map(|v| ConfigType::new_by_value(v.as_ref()).get_value());
+            set_req.desc = desc.map(Arc::new);
+            set_req.tags = tags;
+            let resource = AuditLogUtils::config_resource(&set_req.config_key);
+            let res = appdata.config_route.set_config(set_req).await;
+            AuditLogUtils::record(
+                &appdata,
+                &req,
+                AUDIT_MODULE_CONFIG,
+                AUDIT_ACTION_PUBLISH,
+                resource,
+                res.is_ok(),
+            );
+            match res {
                 Ok(_) => HttpResponse::Ok()
                     .content_type("text/html; charset=utf-8")
                     .body("true"),
+                Err(err) if err.is::<ConfigContentInvalidError>() => {
+                    HttpResponse::BadRequest().body(format!("{}: {}", CONFIG_CONTENT_INVALID, err))
+                }
                 Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
             }
         }
@@ -221,6 +345,7 @@ pub(crate) async fn add_config(
 }
 
 pub(crate) async fn del_config(
+    req: HttpRequest,
     a: web::Query<ConfigWebParams>,
     payload: web::Payload,
     appdata: web::Data<Arc<AppShareData>>,
@@ -248,8 +373,24 @@ pub(crate) async fn del_config(
     let param = selected_param.to_confirmed_param();
     match param {
         Ok(p) => {
-            let req = DelConfigReq::new(ConfigKey::new(&p.data_id, &p.group, &p.tenant));
-            match appdata.config_route.del_config(req).await {
+            if let Some(resp) = check_group_permission(&req, &p.group) {
+                return resp;
+            }
+            let config_key = ConfigKey::new(&p.data_id, &p.group, &p.tenant);
+            let resource = AuditLogUtils::config_resource(&config_key);
+            let res = appdata
+                .config_route
+                .del_config(DelConfigReq::new(config_key))
+                .await;
+            AuditLogUtils::record(
+                &appdata,
+                &req,
+                AUDIT_MODULE_CONFIG,
+                AUDIT_ACTION_DELETE,
+                resource,
+                res.is_ok(),
+            );
+            match res {
                 Ok(_) => HttpResponse::Ok()
                     .content_type("text/html; charset=utf-8")
                     .body("true"),
@@ -261,22 +402,31 @@ pub(crate) async fn del_config(
 }
 
 pub(crate) async fn get_config(
+    req: HttpRequest,
     web_param: web::Query<ConfigWebParams>,
     appdata: web::Data<Arc<AppShareData>>,
 ) -> impl Responder {
     if let Some(search) = web_param.search.as_ref() {
         if search == "blur" {
-            let query_param = web_param.0.build_like_search_param();
+            let mut query_param = web_param.0.build_like_search_param();
+            query_param.group_privilege = user_config_group_privilege!(req);
             return do_search_config(query_param, appdata).await;
         } else if search == "accurate" {
-            let query_param = web_param.0.build_search_param();
+            let mut query_param = web_param.0.build_search_param();
+            query_param.group_privilege = user_config_group_privilege!(req);
             return do_search_config(query_param, appdata).await;
         }
     };
     let param = web_param.to_confirmed_param();
     match param {
         Ok(p) => {
-            let cmd = ConfigCmd::GET(ConfigKey::new(&p.data_id, &p.group, &p.tenant));
+            if let Some(resp) = check_group_permission(&req, &p.group) {
+                return resp;
+            }
+            let cmd = ConfigCmd::GetByClient(
+                ConfigKey::new(&p.data_id, &p.group, &p.tenant),
+                build_config_client(&req),
+            );
             match appdata.config_addr.send(cmd).await {
                 Ok(res) => {
                     let r: ConfigResult = res.unwrap();
@@ -285,16 +435,23 @@ pub(crate) async fn get_config(
                             value: v,
                             md5,
                             config_type,
+                            gray,
                             ..
-                        } => HttpResponse::Ok()
-                            .content_type(
-                                config_type
-                                    .map(|v| ConfigType::new_by_value(&v))
-                                    .unwrap_or_default()
-                                    .get_media_type(),
-                            )
-                            .insert_header(("content-md5", md5.as_ref().to_string()))
-                            .body(v.as_ref().as_bytes().to_vec()),
+                        } => {
+                            let mut response = HttpResponse::Ok();
+                            response
+                                .content_type(
+                                    config_type
+                                        .map(|v| ConfigType::new_by_value(&v))
+                                        .unwrap_or_default()
+                                        .get_media_type(),
+                                )
+                                .insert_header(("content-md5", md5.as_ref().to_string()));
+                            if gray {
+                                response.insert_header(("isBeta", "true"));
+                            }
+                            response.body(v.as_ref().as_bytes().to_vec())
+                        }
                         _ => HttpResponse::NotFound().body("config data not exist"),
                     }
                 }
@@ -357,6 +514,103 @@ impl ListenerParams {
     }
 }
 
+///
+/// 按Long-Pulling-Timeout计算长轮询的超时时间点,未设置时返回0(立即返回)
+fn get_long_pulling_timeout(req: &HttpRequest) -> i64 {
+    req.headers()
+        .get("Long-Pulling-Timeout")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i64>().ok())
+        .map(|v| Local::now().timestamp_millis() + v.clamp(10000, 120000) - 500)
+        .unwrap_or(0)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FuzzyListenerParams {
+    #[serde(rename = "dataId")]
+    data_id: Option<String>,
+    group: Option<String>,
+    tenant: Option<String>,
+    #[serde(rename(serialize = "Listening-Configs", deserialize = "Listening-Configs"))]
+    configs: Option<String>,
+}
+
+impl FuzzyListenerParams {
+    pub fn select_option(&self, o: &Self) -> Self {
+        Self {
+            data_id: select_option_by_clone(&self.data_id, &o.data_id),
+            group: select_option_by_clone(&self.group, &o.group),
+            tenant: select_option_by_clone(&self.tenant, &o.tenant),
+            configs: select_option_by_clone(&self.configs, &o.configs),
+        }
+    }
+
+    pub fn to_pattern(&self) -> ConfigFuzzyPattern {
+        ConfigFuzzyPattern::new(
+            self.data_id.as_deref().unwrap_or_default(),
+            self.group.as_deref().unwrap_or_default(),
+            &ConfigUtils::default_tenant(self.tenant.clone().unwrap_or_default()),
+        )
+    }
+
+    pub fn to_items(&self) -> Vec<ListenerItem> {
+        ListenerItem::decode_listener_items(self.configs.as_deref().unwrap_or_default())
+    }
+}
+
+///
+/// 按dataId/group通配规则长轮询监听配置,返回新增、变更、删除的配置列表;
+/// Listening-Configs为客户端已知的配置及md5,格式与/listener一致
+pub(super) async fn fuzzy_listener_config(
+    req: HttpRequest,
+    a: web::Query<FuzzyListenerParams>,
+    payload: web::Payload,
+    config_addr: web::Data<Addr<ConfigActor>>,
+) -> impl Responder {
+    let body = match get_req_body(payload).await {
+        Ok(v) => v,
+        Err(err) => {
+            return HttpResponse::InternalServerError().body(err.to_string());
+        }
+    };
+    let b = match serde_urlencoded::from_bytes(&body) {
+        Ok(v) => v,
+        Err(err) => {
+            return HttpResponse::InternalServerError().body(err.to_string());
+        }
+    };
+    let param = a.select_option(&b);
+    let pattern = param.to_pattern();
+    if !user_namespace_privilege!(req).check_permission(&pattern.tenant) {
+        return HttpResponse::Forbidden().body(format!(
+            "user no such namespace permission: {}",
+            &pattern.tenant
+        ));
+    }
+    let watcher = ConfigFuzzyWatcher::new(pattern, user_config_group_privilege!(req));
+    if !watcher.check_group_permission() {
+        return HttpResponse::Forbidden().body(format!(
+            "user no such group permission: {}",
+            &watcher.pattern.group
+        ));
+    }
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let cmd = ConfigCmd::FuzzyListener(
+        Box::new(watcher),
+        param.to_items(),
+        tx,
+        get_long_pulling_timeout(&req),
+    );
+    if let Err(err) = config_addr.send(cmd).await {
+        return HttpResponse::InternalServerError().body(err.to_string());
+    }
+    let list: Vec<ConfigFuzzyChangeDto> = match rx.await {
+        Ok(ListenerResult::FuzzyData(list)) => list.iter().map(|e| e.to_dto()).collect(),
+        _ => vec![],
+    };
+    HttpResponse::Ok().json(list)
+}
+
 pub(super) async fn listener_config(
     _req: HttpRequest,
     a: web::Query<ListenerParams>,
@@ -383,20 +637,9 @@ pub(super) async fn listener_config(
             .body("error:listener empty");
     }
     let (tx, rx) = tokio::sync::oneshot::channel();
-    let current_time = Local::now().timestamp_millis();
-    let mut time_out = 0;
-    if let Some(_timeout) = _req.headers().get("Long-Pulling-Timeout") {
-        match _timeout.to_str().unwrap().parse::<i64>() {
-            Ok(v) => {
-                time_out = current_time + v.clamp(10000, 120000) - 500;
-            }
-            Err(_) => {
-                time_out = 0;
-            }
-        }
-    }
+    let time_out = get_long_pulling_timeout(&_req);
     //println!("timeout header:{:?},time_out:{}",_req.headers().get("Long-Pulling-Timeout") ,time_out);
-    let cmd = ConfigCmd::LISTENER(list, tx, time_out);
+    let cmd = ConfigCmd::LISTENER(list, tx, time_out, build_config_client(&_req));
     let _ = config_addr.send(cmd).await;
     let res = rx.await.unwrap();
     let v = match res {
@@ -411,9 +654,214 @@ pub(super) async fn listener_config(
             let t = serde_urlencoded::to_string(&tmp_param).unwrap();
             t[2..t.len()].to_owned() + "\n"
         }
-        ListenerResult::NULL => "".to_owned(),
+        ListenerResult::NULL | ListenerResult::FuzzyData(_) => "".to_owned(),
     };
     HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
         .body(v)
 }
+
+pub(crate) async fn get_gray_config(
+    req: HttpRequest,
+    web_param: web::Query<ConfigWebParams>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let p = match web_param.to_confirmed_param() {
+        Ok(p) => p,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    if let Some(resp) = check_group_permission(&req, &p.group) {
+        return resp;
+    }
+    let cmd = ConfigCmd::GetGray(ConfigKey::new(&p.data_id, &p.group, &p.tenant));
+    match appdata.config_addr.send(cmd).await {
+        Ok(Ok(ConfigResult::GrayInfo(info))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(*info)))
+        }
+        Ok(_) => HttpResponse::NotFound().body("gray config data not exist"),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+pub(crate) async fn add_gray_config(
+    req: HttpRequest,
+    a: web::Query<ConfigWebParams>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let selected_param = merge_web_param!(a.0, payload);
+    do_add_gray_config(&req, selected_param, appdata).await
+}
+
+async fn do_add_gray_config(
+    req: &HttpRequest,
+    selected_param: ConfigWebParams,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> HttpResponse {
+    if let Err(err) = param_utils::check_tenant(&selected_param.tenant) {
+        return HttpResponse::InternalServerError().body(err.to_string());
+    }
+    if let Err(err) = param_utils::check_param(
+        &selected_param.data_id,
+        &selected_param.group,
+        &Some(String::from("datumId")),
+        &selected_param.content,
+    ) {
+        return HttpResponse::InternalServerError().body(err.to_string());
+    }
+    let rule = ConfigGrayRule::from_web_param(
+        selected_param.beta_ips.as_deref(),
+        selected_param.labels.as_deref(),
+    );
+    match selected_param.to_confirmed_param() {
+        Ok(p) => {
+            if let Some(resp) = check_group_permission(req, &p.group) {
+                return resp;
+            }
+            let key = ConfigKey::new(&p.data_id, &p.group, &p.tenant);
+            let resource = AuditLogUtils::config_resource(&key);
+            let res = appdata
+                .config_route
+                .set_gray_config(
+                    key,
+                    Arc::new(p.content),
+                    rule,
+                    AuditLogUtils::get_op_user_option(req),
+                )
+                .await;
+            AuditLogUtils::record(
+                &appdata,
+                req,
+                AUDIT_MODULE_CONFIG,
+                AUDIT_ACTION_GRAY_PUBLISH,
+                resource,
+                res.is_ok(),
+            );
+            match res {
+                Ok(_) => HttpResponse::Ok()
+                    .content_type("text/html; charset=utf-8")
+                    .body("true"),
+                Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub(crate) async fn del_gray_config(
+    req: HttpRequest,
+    a: web::Query<ConfigWebParams>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let selected_param = merge_web_param!(a.0, payload);
+    match selected_param.to_confirmed_param() {
+        Ok(p) => {
+            if let Some(resp) = check_group_permission(&req, &p.group) {
+                return resp;
+            }
+            let key = ConfigKey::new(&p.data_id, &p.group, &p.tenant);
+            let resource = AuditLogUtils::config_resource(&key);
+            let res = appdata.config_route.remove_gray_config(key).await;
+            AuditLogUtils::record(
+                &appdata,
+                &req,
+                AUDIT_MODULE_CONFIG,
+                AUDIT_ACTION_GRAY_REMOVE,
+                resource,
+                res.is_ok(),
+            );
+            match res {
+                Ok(_) => HttpResponse::Ok()
+                    .content_type("text/html; charset=utf-8")
+                    .body("true"),
+                Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub(crate) async fn promote_gray_config(
+    req: HttpRequest,
+    a: web::Query<ConfigWebParams>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let selected_param = merge_web_param!(a.0, payload);
+    match selected_param.to_confirmed_param() {
+        Ok(p) => {
+            if let Some(resp) = check_group_permission(&req, &p.group) {
+                return resp;
+            }
+            let key = ConfigKey::new(&p.data_id, &p.group, &p.tenant);
+            let resource = AuditLogUtils::config_resource(&key);
+            let res = appdata
+                .config_route
+                .promote_gray_config(key, AuditLogUtils::get_op_user_option(&req))
+                .await;
+            AuditLogUtils::record(
+                &appdata,
+                &req,
+                AUDIT_MODULE_CONFIG,
+                AUDIT_ACTION_GRAY_PROMOTE,
+                resource,
+                res.is_ok(),
+            );
+            match res {
+                Ok(_) => HttpResponse::Ok()
+                    .content_type("text/html; charset=utf-8")
+                    .body("true"),
+                Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub(crate) async fn rollback_config(
+    req: HttpRequest,
+    a: web::Query<ConfigHistoryWebParams>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let selected_param = merge_web_param!(a.0, payload);
+    if let Err(err) = param_utils::check_tenant(&selected_param.tenant) {
+        return HttpResponse::InternalServerError().body(err.to_string());
+    }
+    let history_id = match selected_param.id {
+        Some(id) => id,
+        None => return HttpResponse::InternalServerError().body("history id is empty"),
+    };
+    let data_id = selected_param.data_id.unwrap_or_default();
+    if data_id.is_empty() {
+        return HttpResponse::InternalServerError().body("dataId is empty");
+    }
+    let group = selected_param
+        .group
+        .unwrap_or_else(|| "DEFAULT_GROUP".to_owned());
+    if let Some(resp) = check_group_permission(&req, &group) {
+        return resp;
+    }
+    let tenant = ConfigUtils::default_tenant(selected_param.tenant.unwrap_or_default());
+    let key = ConfigKey::new(&data_id, &group, &tenant);
+    let resource = AuditLogUtils::config_resource(&key);
+    let res = appdata
+        .config_route
+        .rollback_config(key, history_id, AuditLogUtils::get_op_user_option(&req))
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_CONFIG,
+        AUDIT_ACTION_ROLLBACK,
+        resource,
+        res.is_ok(),
+    );
+    match res {
+        Ok(_) => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body("true"),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
@@ -12,5 +12,7 @@ pub fn openapi_service(conf: RouteConf) -> Vec<Scope> {
 }
 
 pub fn openapi_v1_route(_conf: RouteConf) -> Scope {
-    web::scope(CONFIG_V1_BASE_PATH).service(api::service())
+    web::scope(CONFIG_V1_BASE_PATH)
+        .service(api::service())
+        .service(api::history_service())
 }
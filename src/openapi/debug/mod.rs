@@ -3,8 +3,13 @@ use actix_web::web;
 pub(crate) mod naming_api;
 
 pub fn debug_config(config: &mut web::ServiceConfig) {
-    config.service(
-        web::resource("/rnacos/debug/naming/common")
-            .route(web::get().to(naming_api::naming_debug_req)),
-    );
+    config
+        .service(
+            web::resource("/rnacos/debug/naming/common")
+                .route(web::get().to(naming_api::naming_debug_req)),
+        )
+        .service(
+            web::resource("/rnacos/debug/naming/event")
+                .route(web::get().to(naming_api::query_naming_event)),
+        );
 }
@@ -1,5 +1,8 @@
 use crate::common::appdata::AppShareData;
+use crate::naming::core::{NamingCmd, NamingResult};
+use crate::naming::model::ServiceKey;
 use crate::naming::naming_debug::NamingDebugCmd;
+use crate::naming::NamingUtils;
 use actix_web::{web, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -25,3 +28,36 @@ pub async fn naming_debug_req(
     }
     HttpResponse::Ok().body("ok")
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamingEventParam {
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: String,
+    pub limit: Option<usize>,
+}
+
+///
+/// 查询本节点记录的服务实例变更事件
+pub async fn query_naming_event(
+    app_share_data: web::Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<NamingEventParam>,
+) -> impl Responder {
+    let key = ServiceKey::new(
+        &NamingUtils::default_namespace(param.namespace_id.unwrap_or_default()),
+        &NamingUtils::default_group(param.group_name.unwrap_or_default()),
+        &param.service_name,
+    );
+    match app_share_data
+        .naming_addr
+        .send(NamingCmd::QueryEventHistory(
+            key,
+            param.limit.unwrap_or_default(),
+        ))
+        .await
+    {
+        Ok(Ok(NamingResult::EventHistory(list))) => HttpResponse::Ok().json(list),
+        _ => HttpResponse::InternalServerError().body("query naming event error"),
+    }
+}
@@ -7,6 +7,11 @@ use crate::metrics::metrics_key::MetricsKey;
 use crate::metrics::model::{MetricsItem, MetricsRecord, MetricsRequest};
 use crate::raft::cache::model::{CacheKey, CacheType, CacheValue};
 use crate::raft::cache::{CacheManager, CacheManagerReq, CacheManagerResult};
+use crate::user::access_key::{
+    check_access_key_session, check_access_key_signature, AccessKeySignature,
+};
+use crate::user::permission::UserRoleHelper;
+use crate::user::role::match_url_by_custom_roles;
 use actix::Addr;
 use actix_http::body::EitherBody;
 use actix_http::HttpMessage;
@@ -27,6 +32,10 @@ lazy_static::lazy_static! {
     pub static ref IGNORE_METRICS_PATH: Vec<&'static str> = vec![
         "/nacos/v1/cs/configs/listener"
     ];
+    /// 只读访问密钥可访问的模块
+    pub static ref ACCESS_KEY_READ_MODULES: Vec<&'static str> = vec![
+        "CONFIG_VISITOR", "NAMING_VISITOR", "NAMESPACE_VISITOR"
+    ];
     //pub static ref PARM_AUTH_TOKEN: Regex = Regex::new(r"accessToken=(\w*)").unwrap();
 }
 
@@ -108,18 +117,54 @@ where
             } else {
                 EMPTY_ARC_STRING.clone()
             };
+            let mut message = "unknown user!";
+            let session = if !enable_auth || !is_check_path {
+                None
+            } else if !token.is_empty() {
+                let session = get_user_session(
+                    cache_manager,
+                    CacheManagerReq::Get(CacheKey::new(CacheType::ApiTokenSession, token.clone())),
+                )
+                .await
+                .unwrap_or_default();
+                check_access_key_session(&app_share_data.access_key_manager, session).await
+            } else if let Some(sign) = AccessKeySignature::from_headers(|key| {
+                request
+                    .headers()
+                    .get(key)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|v| v.to_owned())
+            }) {
+                check_access_key_signature(&app_share_data.access_key_manager, sign).await
+            } else {
+                None
+            };
             let pass = if !enable_auth || !is_check_path {
                 true
-            } else if token.is_empty() {
-                false
-            } else if let Ok(Some(session)) = get_user_session(
-                cache_manager,
-                CacheManagerReq::Get(CacheKey::new(CacheType::ApiTokenSession, token.clone())),
-            )
-            .await
-            {
+            } else if let Some(session) = session {
+                let has_permission = if session.read_only || session.namespace_privilege.is_some() {
+                    //访问密钥按只读与命名空间范围校验
+                    check_access_key_scope(&session, &mut request).await
+                } else {
+                    //有内置角色的用户保持原有的全部openapi权限,只有自定义角色时按角色授权校验
+                    session.roles.is_empty()
+                        || session
+                            .roles
+                            .iter()
+                            .any(|e| UserRoleHelper::is_builtin_role(e))
+                        || match_url_by_custom_roles(
+                            &app_share_data.role_manager,
+                            &session.roles,
+                            request.path(),
+                            request.method().as_str(),
+                        )
+                        .await
+                };
+                if !has_permission {
+                    message = "no permission!";
+                }
                 request.extensions_mut().insert(session);
-                true
+                has_permission
             } else {
                 false
             };
@@ -142,8 +187,8 @@ where
                 })
             } else {
                 //没有登录
-                let body=format!("{{\"timestamp\":\"{}\",\"status\":403,\"error\":\"Forbidden\",\"message\":\"unknown user!\",\"path\":\"{}\"}}"
-                                 ,datetime_utils::get_now_timestamp_str(offset),request.path());
+                let body=format!("{{\"timestamp\":\"{}\",\"status\":403,\"error\":\"Forbidden\",\"message\":\"{}\",\"path\":\"{}\"}}"
+                                 ,datetime_utils::get_now_timestamp_str(offset),message,request.path());
                 let response = HttpResponse::Forbidden()
                     .insert_header(("Content-Type", "application/json;charset=UTF-8"))
                     .body(body)
@@ -168,22 +213,70 @@ pub struct AccessInfo<'a> {
     pub access_token: &'a str,
 }
 
-async fn peek_body_token(request: &mut ServiceRequest) -> Arc<String> {
-    let mut result = EMPTY_ARC_STRING.clone();
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceInfo {
+    pub tenant: Option<String>,
+    pub namespace_id: Option<String>,
+}
+
+impl NamespaceInfo {
+    fn get_namespace(self) -> Option<String> {
+        self.namespace_id.or(self.tenant)
+    }
+}
+
+async fn peek_body(request: &mut ServiceRequest) -> Option<web::Bytes> {
     if request.method().as_str() == "GET" {
-        return result;
+        return None;
     }
     if let Ok(p) = request.extract::<web::Payload>().await {
         if let Ok(v) = p.to_bytes().await {
-            //let body_str = String::from_utf8_lossy(v.as_ref());
-            //log::info!("body info: {}",body_str.as_ref());
-            if let Ok(info) = serde_urlencoded::from_bytes::<AccessInfo>(v.as_ref()) {
-                result = Arc::new(info.access_token.to_string())
-            }
-            request.set_payload(bytes_to_payload(v));
+            request.set_payload(bytes_to_payload(v.clone()));
+            return Some(v);
         }
     };
-    result
+    None
+}
+
+async fn peek_body_token(request: &mut ServiceRequest) -> Arc<String> {
+    if let Some(v) = peek_body(request).await {
+        //let body_str = String::from_utf8_lossy(v.as_ref());
+        //log::info!("body info: {}",body_str.as_ref());
+        if let Ok(info) = serde_urlencoded::from_bytes::<AccessInfo>(v.as_ref()) {
+            return Arc::new(info.access_token.to_string());
+        }
+    }
+    EMPTY_ARC_STRING.clone()
+}
+
+///
+/// 校验访问密钥的只读与命名空间范围
+async fn check_access_key_scope(session: &TokenSession, request: &mut ServiceRequest) -> bool {
+    let path = request.path();
+    let method = request.method().as_str();
+    if session.read_only
+        && !ACCESS_KEY_READ_MODULES
+            .iter()
+            .filter_map(|e| UserRoleHelper::get_module_resource(e))
+            .any(|e| e.match_url(path, method))
+    {
+        return false;
+    }
+    if session.namespace_privilege.is_none() || IGNORE_METRICS_PATH.contains(&path) {
+        return true;
+    }
+    let mut namespace = serde_urlencoded::from_str::<NamespaceInfo>(request.query_string())
+        .ok()
+        .and_then(NamespaceInfo::get_namespace);
+    if namespace.is_none() {
+        if let Some(v) = peek_body(request).await {
+            namespace = serde_urlencoded::from_bytes::<NamespaceInfo>(v.as_ref())
+                .ok()
+                .and_then(NamespaceInfo::get_namespace);
+        }
+    }
+    session.check_namespace_permission(&Arc::new(namespace.unwrap_or_default()))
 }
 
 fn bytes_to_payload(buf: web::Bytes) -> dev::Payload {
@@ -0,0 +1,59 @@
+use crate::audit::model::{AUDIT_ACTION_UPDATE, AUDIT_MODULE_NAMING};
+use crate::audit::AuditLogUtils;
+use crate::common::appdata::AppShareData;
+use crate::merge_web_param;
+use crate::naming::persistent_instance::NamingRaftReq;
+use crate::now_millis_i64;
+use crate::openapi::constant::EMPTY;
+use crate::openapi::naming::model::ClusterWebParams;
+use actix_web::{web, HttpRequest, HttpResponse, Responder, Scope};
+use std::sync::Arc;
+
+pub(super) fn service() -> Scope {
+    web::scope("/cluster").service(
+        web::resource(EMPTY)
+            .route(web::put().to(update_cluster))
+            .route(web::post().to(update_cluster)),
+    )
+}
+
+pub async fn update_cluster(
+    req: HttpRequest,
+    param: web::Query<ClusterWebParams>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = merge_web_param!(param.0, payload);
+    let mut cluster = match param.build_cluster_dto().and_then(|e| e.to_do()) {
+        Ok(v) => v,
+        Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
+    };
+    cluster.gmt_modified = now_millis_i64();
+    let resource = format!(
+        "{}/{}",
+        AuditLogUtils::service_resource(
+            &cluster.namespace_id,
+            &cluster.group_name,
+            &cluster.service_name
+        ),
+        &cluster.cluster_name
+    );
+    let res = appdata
+        .raft_request_route
+        .request_naming(NamingRaftReq::UpdateCluster {
+            cluster: Box::new(cluster),
+        })
+        .await;
+    AuditLogUtils::record(
+        &appdata,
+        &req,
+        AUDIT_MODULE_NAMING,
+        AUDIT_ACTION_UPDATE,
+        resource,
+        res.is_ok(),
+    );
+    match res {
+        Ok(_) => HttpResponse::Ok().body("ok"),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
@@ -5,22 +5,33 @@ use std::sync::Arc;
 
 use actix::prelude::*;
 use actix_web::dev::HttpServiceFactory;
-use actix_web::{get, http::header, put, web, HttpResponse, Responder, Scope};
+use actix_web::{
+    get, http::header, put, web, HttpMessage, HttpRequest, HttpResponse, Responder, Scope,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::audit::model::{AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_NAMING};
+use crate::audit::AuditLogUtils;
 use crate::common::appdata::AppShareData;
 use crate::common::web_utils::get_req_body;
-use crate::merge_web_param;
 use crate::naming::api_model::InstanceVO;
 use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
+use crate::naming::instance_drain::InstanceDrainDto;
 use crate::naming::model::{Instance, InstanceUpdateTag, ServiceKey};
+use crate::naming::persistent_instance::NamingRaftReq;
 use crate::naming::{
     NamingUtils, CLIENT_BEAT_INTERVAL_KEY, LIGHT_BEAT_ENABLED_KEY, RESPONSE_CODE_KEY,
     RESPONSE_CODE_OK,
 };
+use crate::now_millis_i64;
 use crate::openapi::constant::EMPTY;
-use crate::openapi::naming::model::{BeatRequest, InstanceWebParams, InstanceWebQueryListParams};
+use crate::openapi::naming::check_group_permission;
+use crate::openapi::naming::model::{
+    BeatRequest, InstanceDrainWebParams, InstanceWebParams, InstanceWebQueryListParams,
+    ServiceQueryListRequest,
+};
 use crate::utils::{get_bool_from_string, select_option_by_clone};
+use crate::{merge_web_param, user_service_group_privilege};
 
 pub(super) fn service() -> Scope {
     web::scope("/instance")
@@ -32,36 +43,172 @@ pub(super) fn service() -> Scope {
                 .route(web::patch().to(update_instance))
                 .route(web::delete().to(del_instance)),
         )
+        .service(
+            web::resource("/drain")
+                .route(web::get().to(query_drain_list))
+                .route(web::post().to(drain_instance))
+                .route(web::put().to(drain_instance))
+                .route(web::delete().to(restore_instance)),
+        )
         .service(beat_instance)
         .service(get_instance_list)
 }
 
+pub async fn query_drain_list(
+    req: HttpRequest,
+    param: web::Query<ServiceQueryListRequest>,
+    naming_addr: web::Data<Addr<NamingActor>>,
+) -> impl Responder {
+    let namespace_id =
+        NamingUtils::default_namespace(param.namespace_id.clone().unwrap_or_default());
+    let group_privilege = user_service_group_privilege!(req);
+    let service_name = param.service_name.clone().unwrap_or_default();
+    let service_key = if service_name.is_empty() {
+        None
+    } else {
+        Some(ServiceKey::new(
+            &namespace_id,
+            &NamingUtils::default_group(param.group_name.clone().unwrap_or_default()),
+            &service_name,
+        ))
+    };
+    match naming_addr
+        .send(NamingCmd::QueryDrainList(service_key))
+        .await
+    {
+        Ok(Ok(NamingResult::DrainList(list))) => {
+            let list: Vec<InstanceDrainDto> = list
+                .iter()
+                .filter(|e| {
+                    e.namespace_id == namespace_id
+                        && group_privilege.check_permission(&e.group_name)
+                })
+                .map(|e| e.as_ref().into())
+                .collect();
+            HttpResponse::Ok()
+                .insert_header(header::ContentType(mime::APPLICATION_JSON))
+                .body(serde_json::to_string(&list).unwrap_or_default())
+        }
+        Ok(Ok(_)) => HttpResponse::InternalServerError().body("error"),
+        Ok(Err(err)) => HttpResponse::InternalServerError().body(err.to_string()),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+async fn do_drain_request(
+    req: &HttpRequest,
+    param: InstanceDrainWebParams,
+    appdata: &web::Data<Arc<AppShareData>>,
+    is_restore: bool,
+) -> HttpResponse {
+    let drain = match param.build_drain_dto().and_then(|e| {
+        let operator = AuditLogUtils::get_operator(req);
+        if is_restore {
+            e.to_restore_do(operator, now_millis_i64())
+        } else {
+            e.to_do(operator, now_millis_i64())
+        }
+    }) {
+        Ok(v) => v,
+        Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
+    };
+    if let Some(resp) = check_group_permission(req, &drain.group_name) {
+        return resp;
+    }
+    let resource = format!(
+        "{}/{}",
+        AuditLogUtils::service_resource(
+            &drain.namespace_id,
+            &drain.group_name,
+            &drain.service_name
+        ),
+        drain.get_target()
+    );
+    let (req_data, action) = if is_restore {
+        (
+            NamingRaftReq::RemoveDrain {
+                drain: Box::new(drain),
+            },
+            AUDIT_ACTION_DELETE,
+        )
+    } else {
+        (
+            NamingRaftReq::UpdateDrain {
+                drain: Box::new(drain),
+            },
+            AUDIT_ACTION_UPDATE,
+        )
+    };
+    let res = appdata.raft_request_route.request_naming(req_data).await;
+    AuditLogUtils::record(
+        appdata,
+        req,
+        AUDIT_MODULE_NAMING,
+        action,
+        resource,
+        res.is_ok(),
+    );
+    match res {
+        Ok(_) => HttpResponse::Ok().body("ok"),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+///
+/// 实例摘流,按ip、ip+端口或metadata匹配,ttl秒后自动恢复
+pub async fn drain_instance(
+    req: HttpRequest,
+    param: web::Query<InstanceDrainWebParams>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = merge_web_param!(param.0, payload);
+    do_drain_request(&req, param, &appdata, false).await
+}
+
+pub async fn restore_instance(
+    req: HttpRequest,
+    param: web::Query<InstanceDrainWebParams>,
+    payload: web::Payload,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let param = merge_web_param!(param.0, payload);
+    do_drain_request(&req, param, &appdata, true).await
+}
+
 pub async fn get_instance(
+    req: HttpRequest,
     param: web::Query<InstanceWebParams>,
     naming_addr: web::Data<Addr<NamingActor>>,
 ) -> impl Responder {
     let instance = param.0.convert_to_instance();
     match instance {
-        Ok(instance) => match naming_addr.send(NamingCmd::Query(instance)).await {
-            Ok(res) => {
-                let result: NamingResult = res.unwrap();
-                match result {
-                    NamingResult::Instance(v) => {
-                        let vo = InstanceVO::from_instance(&v);
-                        HttpResponse::Ok()
-                            .insert_header(header::ContentType(mime::APPLICATION_JSON))
-                            .body(serde_json::to_string(&vo).unwrap())
+        Ok(instance) => {
+            if let Some(resp) = check_group_permission(&req, &instance.group_name) {
+                return resp;
+            }
+            match naming_addr.send(NamingCmd::Query(instance)).await {
+                Ok(res) => {
+                    let result: NamingResult = res.unwrap();
+                    match result {
+                        NamingResult::Instance(v) => {
+                            let vo = InstanceVO::from_instance(&v);
+                            HttpResponse::Ok()
+                                .insert_header(header::ContentType(mime::APPLICATION_JSON))
+                                .body(serde_json::to_string(&vo).unwrap())
+                        }
+                        _ => HttpResponse::InternalServerError().body("error"),
                     }
-                    _ => HttpResponse::InternalServerError().body("error"),
                 }
+                Err(_) => HttpResponse::InternalServerError().body("error"),
             }
-            Err(_) => HttpResponse::InternalServerError().body("error"),
-        },
+        }
         Err(e) => HttpResponse::InternalServerError().body(e),
     }
 }
 
 pub async fn update_instance(
+    req: HttpRequest,
     param: web::Query<InstanceWebParams>,
     payload: web::Payload,
     appdata: web::Data<Arc<AppShareData>>,
@@ -89,6 +236,9 @@ pub async fn update_instance(
     let instance = param.convert_to_instance();
     match instance {
         Ok(instance) => {
+            if let Some(resp) = check_group_permission(&req, &instance.group_name) {
+                return resp;
+            }
             if !instance.check_valid() {
                 HttpResponse::InternalServerError().body("instance check is invalid")
             } else {
@@ -107,6 +257,7 @@ pub async fn update_instance(
 }
 
 pub async fn del_instance(
+    req: HttpRequest,
     param: web::Query<InstanceWebParams>,
     payload: web::Payload,
     appdata: web::Data<Arc<AppShareData>>,
@@ -115,6 +266,9 @@ pub async fn del_instance(
     let instance = param.convert_to_instance();
     match instance {
         Ok(instance) => {
+            if let Some(resp) = check_group_permission(&req, &instance.group_name) {
+                return resp;
+            }
             if !instance.check_valid() {
                 HttpResponse::InternalServerError().body("instance check is invalid")
             } else {
@@ -130,6 +284,7 @@ pub async fn del_instance(
 
 #[put("/beat")]
 pub async fn beat_instance(
+    req: HttpRequest,
     param: web::Query<BeatRequest>,
     payload: web::Payload,
     appdata: web::Data<Arc<AppShareData>>,
@@ -140,6 +295,9 @@ pub async fn beat_instance(
     let instance = param.convert_to_instance();
     match instance {
         Ok(instance) => {
+            if let Some(resp) = check_group_permission(&req, &instance.group_name) {
+                return resp;
+            }
             if !instance.check_valid() {
                 HttpResponse::InternalServerError().body("instance check is invalid")
             } else {
@@ -175,6 +333,7 @@ pub async fn beat_instance(
 
 #[get("/list")]
 pub async fn get_instance_list(
+    req: HttpRequest,
     param: web::Query<InstanceWebQueryListParams>,
     naming_addr: web::Data<Addr<NamingActor>>,
 ) -> impl Responder {
@@ -182,12 +341,17 @@ pub async fn get_instance_list(
     let addr = param.get_addr();
     match param.to_clusters_key() {
         Ok((key, clusters)) => {
+            if let Some(resp) = check_group_permission(&req, &key.group_name) {
+                return resp;
+            }
             match naming_addr
                 .send(NamingCmd::QueryListString(
                     key.clone(),
                     clusters,
                     only_healthy,
                     addr,
+                    param.client_ip.clone(),
+                    param.get_order_param(),
                 ))
                 .await
             {
@@ -1,9 +1,11 @@
-use actix_web::{web, Scope};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Scope};
 
 use crate::openapi::constant::NAMING_V1_BASE_PATH;
 use crate::openapi::RouteConf;
+use crate::user_service_group_privilege;
 
 mod catalog;
+mod cluster;
 pub(crate) mod instance;
 pub mod model;
 mod operator;
@@ -20,4 +22,15 @@ pub fn openapi_v1_route(_conf: RouteConf) -> Scope {
         .service(service::service())
         .service(operator::service())
         .service(catalog::service())
+        .service(cluster::service())
+}
+
+///
+/// 校验openapi用户的服务分组数据权限,无权限时返回403
+pub(crate) fn check_group_permission(req: &HttpRequest, group: &str) -> Option<HttpResponse> {
+    if user_service_group_privilege!(req).check_permission(group) {
+        None
+    } else {
+        Some(HttpResponse::Forbidden().body(format!("user no such group permission: {}", group)))
+    }
 }
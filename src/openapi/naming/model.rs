@@ -1,7 +1,10 @@
 #![allow(unused_imports, unused_assignments, unused_variables)]
 use crate::common::option_utils::OptionUtils;
+use crate::naming::instance_drain::InstanceDrainDto;
 use crate::naming::model::{Instance, ServiceKey};
+use crate::naming::order::InstanceOrderParam;
 use crate::naming::service::SubscriberInfoDto;
+use crate::naming::service_cluster::ServiceClusterDto;
 use crate::naming::NamingUtils;
 use crate::utils::get_bool_from_string;
 use serde::{Deserialize, Serialize};
@@ -113,9 +116,16 @@ pub struct InstanceWebQueryListParams {
     #[serde(rename = "clientIP")]
     pub client_ip: Option<String>,
     pub udp_port: Option<String>,
+    /// 服务端排序方式: weight、zone,为空时使用服务端默认配置
+    pub order: Option<String>,
+    pub zone: Option<String>,
 }
 
 impl InstanceWebQueryListParams {
+    pub(crate) fn get_order_param(&self) -> InstanceOrderParam {
+        InstanceOrderParam::new(self.order.as_deref(), self.zone.clone())
+    }
+
     pub(crate) fn to_clusters_key(&self) -> Result<(ServiceKey, String), String> {
         let mut service_name = "".to_owned();
         let mut group_name = "".to_owned();
@@ -347,6 +357,114 @@ pub struct ServiceQueryListRequest {
     pub service_name: Option<String>,
 }
 
+///
+/// 实例摘流接口参数,ip为空时按metadata匹配整个服务的实例
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceDrainWebParams {
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: Option<String>,
+    pub ip: Option<String>,
+    pub port: Option<u32>,
+    pub metadata: Option<String>,
+    /// 自动恢复的秒数
+    pub ttl: Option<i64>,
+}
+
+impl InstanceDrainWebParams {
+    pub(crate) fn merge(self, o: Self) -> Self {
+        Self {
+            namespace_id: OptionUtils::select(self.namespace_id, o.namespace_id),
+            group_name: OptionUtils::select(self.group_name, o.group_name),
+            service_name: OptionUtils::select(self.service_name, o.service_name),
+            ip: OptionUtils::select(self.ip, o.ip),
+            port: OptionUtils::select(self.port, o.port),
+            metadata: OptionUtils::select(self.metadata, o.metadata),
+            ttl: OptionUtils::select(self.ttl, o.ttl),
+        }
+    }
+
+    pub(crate) fn build_drain_dto(self) -> anyhow::Result<InstanceDrainDto> {
+        let grouped_name = self.service_name.unwrap_or_default();
+        let (mut group_name, service_name) =
+            NamingUtils::split_group_and_service_name(&grouped_name)
+                .ok_or_else(|| anyhow::anyhow!("serviceName is invalid!"))?;
+        if let Some(v) = self.group_name.filter(|e| !e.is_empty()) {
+            group_name = v;
+        }
+        let metadata = match self.metadata.filter(|e| !e.is_empty()) {
+            Some(v) => Some(NamingUtils::parse_metadata(&v)?),
+            None => None,
+        };
+        Ok(InstanceDrainDto {
+            namespace_id: self.namespace_id,
+            group_name: Some(group_name),
+            service_name: Arc::new(service_name),
+            ip: self.ip,
+            port: self.port,
+            metadata,
+            ttl_seconds: self.ttl,
+            ..Default::default()
+        })
+    }
+}
+
+///
+/// 兼容nacos v1 /ns/cluster 接口参数
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterWebParams {
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    pub service_name: Option<String>,
+    pub cluster_name: Option<String>,
+    pub check_port: Option<u32>,
+    #[serde(rename = "useInstancePort4Check")]
+    pub use_instance_port: Option<String>,
+    pub metadata: Option<String>,
+}
+
+impl ClusterWebParams {
+    pub(crate) fn merge(self, o: Self) -> Self {
+        Self {
+            namespace_id: OptionUtils::select(self.namespace_id, o.namespace_id),
+            group_name: OptionUtils::select(self.group_name, o.group_name),
+            service_name: OptionUtils::select(self.service_name, o.service_name),
+            cluster_name: OptionUtils::select(self.cluster_name, o.cluster_name),
+            check_port: OptionUtils::select(self.check_port, o.check_port),
+            use_instance_port: OptionUtils::select(self.use_instance_port, o.use_instance_port),
+            metadata: OptionUtils::select(self.metadata, o.metadata),
+        }
+    }
+
+    pub(crate) fn build_cluster_dto(self) -> anyhow::Result<ServiceClusterDto> {
+        let grouped_name = self.service_name.unwrap_or_default();
+        let (mut group_name, service_name) =
+            NamingUtils::split_group_and_service_name(&grouped_name)
+                .ok_or_else(|| anyhow::anyhow!("serviceName is invalid!"))?;
+        if let Some(v) = self.group_name.filter(|e| !e.is_empty()) {
+            group_name = v;
+        }
+        let metadata = match self.metadata.filter(|e| !e.is_empty()) {
+            Some(v) => Some(NamingUtils::parse_metadata(&v)?),
+            None => None,
+        };
+        Ok(ServiceClusterDto {
+            namespace_id: self.namespace_id,
+            group_name: Some(group_name),
+            service_name: Arc::new(service_name),
+            cluster_name: Arc::new(self.cluster_name.unwrap_or_default()),
+            health_check_port: self.check_port,
+            use_instance_port: self
+                .use_instance_port
+                .map(|e| get_bool_from_string(&Some(e), true)),
+            metadata,
+            gmt_modified: None,
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct ServiceQueryListResponce {
     pub count: usize,
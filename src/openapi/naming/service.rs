@@ -1,15 +1,21 @@
+use crate::audit::model::{AUDIT_ACTION_DELETE, AUDIT_ACTION_UPDATE, AUDIT_MODULE_NAMING};
+use crate::audit::AuditLogUtils;
+use crate::common::appdata::AppShareData;
 use crate::merge_web_param;
 use crate::naming::api_model::ServiceInfoParam;
 use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
 use crate::naming::model::ServiceKey;
+use crate::naming::persistent_instance::NamingRaftReq;
 use crate::naming::NamingUtils;
 use crate::openapi::constant::EMPTY;
+use crate::openapi::naming::check_group_permission;
 use crate::openapi::naming::model::{
     ServiceQueryListRequest, ServiceQueryListResponce, ServiceQuerySubscribersListResponce,
 };
 use actix::Addr;
 use actix_web::http::header;
-use actix_web::{web, HttpResponse, Responder, Scope};
+use actix_web::{web, HttpRequest, HttpResponse, Responder, Scope};
+use std::sync::Arc;
 
 pub(super) fn service() -> Scope {
     web::scope("/service")
@@ -25,39 +31,118 @@ pub(super) fn service() -> Scope {
 }
 
 pub async fn query_service(
-    _param: web::Query<ServiceQueryListRequest>,
-    _naming_addr: web::Data<Addr<NamingActor>>,
+    req: HttpRequest,
+    param: web::Query<ServiceQueryListRequest>,
+    naming_addr: web::Data<Addr<NamingActor>>,
 ) -> impl Responder {
-    HttpResponse::InternalServerError().body("error,not support at present")
+    let service_name = param.service_name.clone().unwrap_or_default();
+    if service_name.is_empty() {
+        return HttpResponse::InternalServerError().body("service_name is empty");
+    }
+    let key = ServiceKey::new(
+        &NamingUtils::default_namespace(param.namespace_id.clone().unwrap_or_default()),
+        &NamingUtils::default_group(param.group_name.clone().unwrap_or_default()),
+        &service_name,
+    );
+    if let Some(resp) = check_group_permission(&req, &key.group_name) {
+        return resp;
+    }
+    match naming_addr.send(NamingCmd::QueryServiceDetail(key)).await {
+        Ok(Ok(NamingResult::ServiceDetail(Some(detail)))) => HttpResponse::Ok()
+            .insert_header(header::ContentType(mime::APPLICATION_JSON))
+            .body(serde_json::to_string(&detail).unwrap_or_default()),
+        Ok(Ok(_)) => HttpResponse::InternalServerError().body("service is not exist"),
+        Ok(Err(err)) => HttpResponse::InternalServerError().body(err.to_string()),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
 }
 
 pub async fn update_service(
+    req: HttpRequest,
     param: web::Query<ServiceInfoParam>,
     payload: web::Payload,
-    naming_addr: web::Data<Addr<NamingActor>>,
+    appdata: web::Data<Arc<AppShareData>>,
 ) -> impl Responder {
     let param = merge_web_param!(param.0, payload);
     match param.build_service_info() {
         Ok(service_info) => {
-            let _ = naming_addr
-                .send(NamingCmd::UpdateService(service_info))
+            if let Some(resp) = check_group_permission(&req, &service_info.group_name) {
+                return resp;
+            }
+            let resource = AuditLogUtils::service_resource(
+                &service_info.namespace_id,
+                &service_info.group_name,
+                &service_info.service_name,
+            );
+            let detail = Some(format!(
+                "metadata: {:?}, protect_threshold: {:?}",
+                &service_info.metadata, &service_info.protect_threshold
+            ));
+            let res = appdata
+                .raft_request_route
+                .request_naming(NamingRaftReq::UpdateService {
+                    service: Box::new(service_info),
+                })
                 .await;
-            HttpResponse::Ok().body("ok")
+            AuditLogUtils::record_detail(
+                &appdata,
+                &req,
+                AUDIT_MODULE_NAMING,
+                AUDIT_ACTION_UPDATE,
+                resource,
+                res.is_ok(),
+                detail,
+            );
+            match res {
+                Ok(_) => HttpResponse::Ok().body("ok"),
+                Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+            }
         }
         Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
     }
 }
 
 pub async fn remove_service(
+    req: HttpRequest,
     param: web::Query<ServiceInfoParam>,
     payload: web::Payload,
-    naming_addr: web::Data<Addr<NamingActor>>,
+    appdata: web::Data<Arc<AppShareData>>,
 ) -> impl Responder {
     let param = merge_web_param!(param.0, payload);
     match param.build_service_info() {
         Ok(service_info) => {
             let key = service_info.to_service_key();
-            match naming_addr.send(NamingCmd::RemoveService(key)).await {
+            if let Some(resp) = check_group_permission(&req, &key.group_name) {
+                return resp;
+            }
+            let resource = AuditLogUtils::service_resource(
+                &key.namespace_id,
+                &key.group_name,
+                &key.service_name,
+            );
+            let res = match appdata
+                .naming_addr
+                .send(NamingCmd::RemoveService(key))
+                .await
+            {
+                Ok(Ok(_)) => Ok(appdata
+                    .raft_request_route
+                    .request_naming(NamingRaftReq::RemoveService {
+                        service: Box::new(service_info),
+                    })
+                    .await
+                    .map(|_| NamingResult::NULL)),
+                res => res,
+            };
+            AuditLogUtils::record(
+                &appdata,
+                &req,
+                AUDIT_MODULE_NAMING,
+                AUDIT_ACTION_DELETE,
+                resource,
+                matches!(res, Ok(Ok(_))),
+            );
+            match res {
                 Ok(res) => {
                     let res: anyhow::Result<NamingResult> = res;
                     match res {
@@ -73,6 +158,7 @@ pub async fn remove_service(
 }
 
 pub async fn query_service_list(
+    req: HttpRequest,
     param: web::Query<ServiceQueryListRequest>,
     naming_addr: web::Data<Addr<NamingActor>>,
 ) -> impl Responder {
@@ -92,6 +178,9 @@ pub async fn query_service_list(
             .unwrap_or(&"".to_owned())
             .to_owned(),
     );
+    if let Some(resp) = check_group_permission(&req, &group) {
+        return resp;
+    }
     let key = ServiceKey::new(&namespace_id, &group, "");
     match naming_addr
         .send(NamingCmd::QueryServicePage(key, page_size, page_index))
@@ -114,6 +203,7 @@ pub async fn query_service_list(
 /// 控制台的接口应该走v2的接口,标记废弃
 /// #[deprecated]
 pub async fn query_subscribers_list(
+    req: HttpRequest,
     param: web::Query<ServiceQueryListRequest>,
     naming_addr: web::Data<Addr<NamingActor>>,
 ) -> impl Responder {
@@ -139,6 +229,9 @@ pub async fn query_subscribers_list(
         .unwrap_or(&"".to_owned())
         .to_owned();
 
+    if let Some(resp) = check_group_permission(&req, &group) {
+        return resp;
+    }
     let key = ServiceKey::new(&namespace_id, &group, &service);
     match naming_addr
         .send(NamingCmd::QueryServiceSubscribersPage(
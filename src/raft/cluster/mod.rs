@@ -5,6 +5,7 @@ use async_raft_ext::raft::ClientWriteRequest;
 use self::model::{RouterRequest, RouterResponse};
 use super::{db::table::TableManagerAsyncReq, join_node, store::ClientRequest};
 use crate::namespace::model::NamespaceRaftResult;
+use crate::naming::persistent_instance::NamingRaftResult;
 use crate::raft::store::ClientResponse;
 use crate::transfer::model::TransferImportRequest;
 use crate::{
@@ -27,6 +28,7 @@ pub async fn handle_route(
             op_user,
             config_type,
             desc,
+            tags,
             extend_info: _,
         } => {
             let config_key: ConfigKey = (&key as &str).into();
@@ -37,6 +39,7 @@ pub async fn handle_route(
                     op_user,
                     config_type,
                     desc,
+                    tags,
                 })
                 .await??;
         }
@@ -88,6 +91,48 @@ pub async fn handle_route(
                 });
             }
         }
+        RouterRequest::NamingReq { req } => {
+            let resp = app
+                .raft
+                .client_write(ClientWriteRequest::new(ClientRequest::NamingReq(req)))
+                .await?;
+            if let ClientResponse::Success = resp.data {
+                return Ok(RouterResponse::NamingResult {
+                    result: NamingRaftResult::None,
+                });
+            }
+        }
+        RouterRequest::ConfigGraySet {
+            key,
+            value,
+            rule,
+            op_user,
+        } => {
+            let config_key: ConfigKey = (&key as &str).into();
+            app.config_addr
+                .send(ConfigAsyncCmd::GraySet {
+                    key: config_key,
+                    value,
+                    rule,
+                    op_user,
+                })
+                .await??;
+        }
+        RouterRequest::ConfigGrayRemove { key } => {
+            let config_key: ConfigKey = (&key as &str).into();
+            app.config_addr
+                .send(ConfigAsyncCmd::GrayRemove(config_key))
+                .await??;
+        }
+        RouterRequest::ConfigGrayPromote { key, op_user } => {
+            let config_key: ConfigKey = (&key as &str).into();
+            app.config_addr
+                .send(ConfigAsyncCmd::GrayPromote {
+                    key: config_key,
+                    op_user,
+                })
+                .await??;
+        }
         RouterRequest::ImportData { data, param } => {
             let result = app
                 .transfer_import_manager
@@ -2,8 +2,10 @@ use std::{collections::HashMap, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::config_gray::ConfigGrayRule;
 use crate::config::config_type::ConfigType;
 use crate::namespace::model::{NamespaceRaftReq, NamespaceRaftResult};
+use crate::naming::persistent_instance::{NamingRaftReq, NamingRaftResult};
 use crate::transfer::model::{TransferImportParam, TransferImportResponse};
 use crate::{
     config::core::ConfigKey,
@@ -26,6 +28,9 @@ pub struct SetConfigReq {
     pub op_user: Option<Arc<String>>,
     pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    pub tags: Option<Arc<String>>,
+    /// 跳过按配置类型的内容校验
+    pub force: bool,
     //pub can_route_to_remote: bool,
     //pub extend_info: Option<HashMap<String,String>>,
 }
@@ -38,6 +43,8 @@ impl SetConfigReq {
             op_user: None,
             config_type: None,
             desc: None,
+            tags: None,
+            force: false,
         }
     }
 
@@ -52,6 +59,8 @@ impl SetConfigReq {
             op_user: Some(op_user),
             config_type: None,
             desc: None,
+            tags: None,
+            force: false,
         }
     }
 
@@ -89,6 +98,8 @@ pub enum RouterRequest {
         op_user: Option<Arc<String>>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        #[serde(default)]
+        tags: Option<Arc<String>>,
         extend_info: HashMap<String, String>,
     },
     ConfigDel {
@@ -111,10 +122,26 @@ pub enum RouterRequest {
     NamespaceReq {
         req: NamespaceRaftReq,
     },
+    NamingReq {
+        req: NamingRaftReq,
+    },
     ImportData {
         data: Vec<u8>,
         param: TransferImportParam,
     },
+    ConfigGraySet {
+        key: String,
+        value: Arc<String>,
+        rule: ConfigGrayRule,
+        op_user: Option<Arc<String>>,
+    },
+    ConfigGrayRemove {
+        key: String,
+    },
+    ConfigGrayPromote {
+        key: String,
+        op_user: Option<Arc<String>>,
+    },
 }
 
 impl From<SetConfigReq> for RouterRequest {
@@ -125,6 +152,7 @@ impl From<SetConfigReq> for RouterRequest {
             op_user: req.op_user,
             config_type: req.config_type,
             desc: req.desc,
+            tags: req.tags,
             extend_info: Default::default(),
         }
     }
@@ -151,11 +179,18 @@ impl From<NamespaceRaftReq> for RouterRequest {
     }
 }
 
+impl From<NamingRaftReq> for RouterRequest {
+    fn from(req: NamingRaftReq) -> Self {
+        Self::NamingReq { req }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum RouterResponse {
     None,
     TableManagerResult { result: TableManagerResult },
     CacheManagerResult { result: CacheManagerResult },
     NamespaceResult { result: NamespaceRaftResult },
+    NamingResult { result: NamingRaftResult },
     ImportResult { result: TransferImportResponse },
 }
@@ -1,14 +1,19 @@
 use std::{fmt::Debug, sync::Arc};
 
 use super::model::{DelConfigReq, RouteAddr, RouterRequest, RouterResponse, SetConfigReq};
+use crate::config::config_cipher::ConfigCipherUtils;
+use crate::config::config_gray::ConfigGrayRule;
+use crate::config::config_type::ConfigType;
+use crate::config::core::ConfigKey;
 use crate::grpc::handler::RAFT_ROUTE_REQUEST;
 use crate::namespace::model::{NamespaceRaftReq, NamespaceRaftResult};
+use crate::naming::persistent_instance::{NamingRaftReq, NamingRaftResult};
 use crate::raft::filestore::core::FileStore;
 use crate::raft::store::{ClientRequest, ClientResponse};
 use crate::transfer::model::{TransferImportParam, TransferImportRequest, TransferImportResponse};
 use crate::transfer::reader::TransferImportManager;
 use crate::{
-    config::core::{ConfigActor, ConfigAsyncCmd, ConfigCmd},
+    config::core::{ConfigActor, ConfigAsyncCmd, ConfigCmd, ConfigResult},
     grpc::PayloadUtils,
     raft::{network::factory::RaftClusterRequestSender, NacosRaft},
 };
@@ -78,7 +83,37 @@ impl ConfigRoute {
         anyhow::anyhow!("unknown the raft leader addr!")
     }
 
+    ///
+    /// 按配置类型校验内容格式;请求未指定类型时使用已存储配置的类型
+    async fn check_content(
+        &self,
+        config_key: &ConfigKey,
+        value: &str,
+        config_type: Option<Arc<String>>,
+    ) -> anyhow::Result<()> {
+        let config_type = match config_type {
+            Some(v) => Some(v),
+            None => match self
+                .config_addr
+                .send(ConfigCmd::GET(config_key.clone()))
+                .await??
+            {
+                ConfigResult::Data { config_type, .. } => config_type,
+                _ => None,
+            },
+        };
+        if let Some(config_type) = config_type {
+            ConfigType::new_by_value(&config_type).check_content(value)?;
+        }
+        Ok(())
+    }
+
     pub async fn set_config(&self, req: SetConfigReq) -> anyhow::Result<()> {
+        ConfigCipherUtils::check_enable(&req.config_key)?;
+        if !req.force {
+            self.check_content(&req.config_key, &req.value, req.config_type.clone())
+                .await?;
+        }
         match self.raft_addr_route.get_route_addr().await? {
             RouteAddr::Local => {
                 let cmd = ConfigAsyncCmd::Add {
@@ -87,6 +122,7 @@ impl ConfigRoute {
                     op_user: req.op_user,
                     config_type: req.config_type,
                     desc: req.desc,
+                    tags: req.tags,
                 };
                 self.config_addr.send(cmd).await?.ok();
             }
@@ -130,6 +166,129 @@ impl ConfigRoute {
         }
         Ok(())
     }
+
+    ///
+    /// 回滚配置到指定历史版本
+    /// 历史内容按正常发布流程重新写入,操作人记录为执行回滚的用户
+    pub async fn rollback_config(
+        &self,
+        config_key: ConfigKey,
+        history_id: u64,
+        op_user: Option<Arc<String>>,
+    ) -> anyhow::Result<()> {
+        let history = match self
+            .config_addr
+            .send(ConfigCmd::GetHistory(config_key.clone(), history_id))
+            .await??
+        {
+            ConfigResult::ConfigHistoryInfo(history) => history,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "the config history is not exist, id: {}",
+                    history_id
+                ))
+            }
+        };
+        let mut req = SetConfigReq::new(config_key, Arc::new(history.content.unwrap_or_default()));
+        req.config_type = history.config_type.map(Arc::new);
+        req.desc = history.desc.map(Arc::new);
+        req.op_user = op_user;
+        req.force = true;
+        self.set_config(req).await
+    }
+
+    pub async fn set_gray_config(
+        &self,
+        config_key: ConfigKey,
+        value: Arc<String>,
+        rule: ConfigGrayRule,
+        op_user: Option<Arc<String>>,
+    ) -> anyhow::Result<()> {
+        self.check_content(&config_key, &value, None).await?;
+        match self.raft_addr_route.get_route_addr().await? {
+            RouteAddr::Local => {
+                let cmd = ConfigAsyncCmd::GraySet {
+                    key: config_key,
+                    value,
+                    rule,
+                    op_user,
+                };
+                self.config_addr.send(cmd).await??;
+            }
+            RouteAddr::Remote(_, addr) => {
+                let req = RouterRequest::ConfigGraySet {
+                    key: config_key.build_key(),
+                    value,
+                    rule,
+                    op_user,
+                };
+                self.send_route_request(addr, req).await?;
+            }
+            RouteAddr::Unknown => {
+                return Err(self.unknown_err());
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn remove_gray_config(&self, config_key: ConfigKey) -> anyhow::Result<()> {
+        match self.raft_addr_route.get_route_addr().await? {
+            RouteAddr::Local => {
+                let cmd = ConfigAsyncCmd::GrayRemove(config_key);
+                self.config_addr.send(cmd).await??;
+            }
+            RouteAddr::Remote(_, addr) => {
+                let req = RouterRequest::ConfigGrayRemove {
+                    key: config_key.build_key(),
+                };
+                self.send_route_request(addr, req).await?;
+            }
+            RouteAddr::Unknown => {
+                return Err(self.unknown_err());
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn promote_gray_config(
+        &self,
+        config_key: ConfigKey,
+        op_user: Option<Arc<String>>,
+    ) -> anyhow::Result<()> {
+        match self.raft_addr_route.get_route_addr().await? {
+            RouteAddr::Local => {
+                let cmd = ConfigAsyncCmd::GrayPromote {
+                    key: config_key,
+                    op_user,
+                };
+                self.config_addr.send(cmd).await??;
+            }
+            RouteAddr::Remote(_, addr) => {
+                let req = RouterRequest::ConfigGrayPromote {
+                    key: config_key.build_key(),
+                    op_user,
+                };
+                self.send_route_request(addr, req).await?;
+            }
+            RouteAddr::Unknown => {
+                return Err(self.unknown_err());
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_route_request(
+        &self,
+        addr: Arc<String>,
+        req: RouterRequest,
+    ) -> anyhow::Result<RouterResponse> {
+        let request = serde_json::to_string(&req).unwrap_or_default();
+        let payload = PayloadUtils::build_payload(RAFT_ROUTE_REQUEST, request);
+        let resp_payload = self.cluster_sender.send_request(addr, payload).await?;
+        let body_vec = resp_payload.body.unwrap_or_default().value;
+        let resp: RouterResponse = serde_json::from_slice(&body_vec)?;
+        Ok(resp)
+    }
 }
 
 ///
@@ -144,6 +303,12 @@ pub struct RaftRequestRoute {
     import_reader: Addr<TransferImportManager>,
 }
 
+impl Debug for RaftRequestRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RaftRequestRoute").finish()
+    }
+}
+
 impl RaftRequestRoute {
     pub fn new(
         raft_addr_route: Arc<RaftAddrRouter>,
@@ -195,6 +360,35 @@ impl RaftRequestRoute {
         }
     }
 
+    pub async fn request_naming(&self, req: NamingRaftReq) -> anyhow::Result<NamingRaftResult> {
+        match self.raft_addr_route.get_route_addr().await? {
+            RouteAddr::Local => {
+                let resp = self
+                    .raft
+                    .client_write(ClientWriteRequest::new(ClientRequest::NamingReq(req)))
+                    .await?;
+                if let ClientResponse::Success = resp.data {
+                    Ok(NamingRaftResult::None)
+                } else {
+                    Err(anyhow::anyhow!("response type is error!"))
+                }
+            }
+            RouteAddr::Remote(_, addr) => {
+                let req: RouterRequest = req.into();
+                let request = serde_json::to_string(&req).unwrap_or_default();
+                let payload = PayloadUtils::build_payload(RAFT_ROUTE_REQUEST, request);
+                let resp_payload = self.cluster_sender.send_request(addr, payload).await?;
+                let body_vec = resp_payload.body.unwrap_or_default().value;
+                let resp: RouterResponse = serde_json::from_slice(&body_vec)?;
+                match resp {
+                    RouterResponse::NamingResult { result } => Ok(result),
+                    _ => Err(anyhow::anyhow!("response type is error!")),
+                }
+            }
+            RouteAddr::Unknown => Err(self.unknown_err()),
+        }
+    }
+
     pub async fn request_import(
         &self,
         data: Vec<u8>,
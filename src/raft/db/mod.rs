@@ -1,5 +1,6 @@
 pub mod kvapi;
 pub mod route;
 pub mod table;
+pub mod table_store;
 //raft actor sequence 暂时不启用
 //mod sequence;
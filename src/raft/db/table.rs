@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::ops::Bound;
 use std::{
     collections::HashMap,
     sync::{Arc, Weak},
@@ -10,7 +11,9 @@ use serde::{Deserialize, Serialize};
 
 use actix::prelude::*;
 
-use crate::common::constant::{CACHE_TREE_NAME, USER_TREE_NAME};
+use crate::common::constant::{
+    ACCESS_KEY_TREE_NAME, CACHE_TREE_NAME, ROLE_TREE_NAME, USER_TREE_NAME,
+};
 use crate::common::sequence_utils::SimpleSequence;
 use crate::raft::filestore::model::SnapshotRecordDto;
 use crate::raft::filestore::raftsnapshot::{SnapshotWriterActor, SnapshotWriterRequest};
@@ -32,6 +35,7 @@ use crate::{
 };
 
 type TableKV = (Vec<u8>, Vec<u8>);
+type TableValueFilter = Box<dyn Fn(&[u8]) -> bool + Send>;
 
 #[derive(Clone, prost::Message, Serialize, Deserialize)]
 pub struct TableDefinition {
@@ -212,6 +216,48 @@ impl TableManager {
         }
     }
 
+    pub(crate) fn query_filter_page_list(
+        &self,
+        param: &TableFilterQueryParam,
+    ) -> (usize, Vec<TableKV>) {
+        let table_info = match self.table_map.get(&param.table_name) {
+            Some(v) => v,
+            None => return (0, vec![]),
+        };
+        if let (Some(start_key), Some(end_key)) = (&param.start_key, &param.end_key) {
+            if start_key >= end_key {
+                return (0, vec![]);
+            }
+        }
+        let start = match &param.start_key {
+            Some(v) => Bound::Included(v),
+            None => Bound::Unbounded,
+        };
+        let end = match &param.end_key {
+            Some(v) => Bound::Excluded(v),
+            None => Bound::Unbounded,
+        };
+        let range = table_info.table_data.range::<Vec<u8>, _>((start, end));
+        let iter: Box<dyn Iterator<Item = (&Vec<u8>, &Vec<u8>)>> = if param.is_rev {
+            Box::new(range.rev())
+        } else {
+            Box::new(range)
+        };
+        let end_index = param.offset + param.limit;
+        let mut total = 0;
+        let mut ret = vec![];
+        for (k, v) in iter {
+            if !(param.filter)(v) {
+                continue;
+            }
+            if total >= param.offset && total < end_index {
+                ret.push((k.to_owned(), v.to_owned()));
+            }
+            total += 1;
+        }
+        (total, ret)
+    }
+
     pub(crate) fn query_list_count(&self, name: &Arc<String>, like_key: &Option<String>) -> usize {
         if let Some(table_info) = self.table_map.get(name) {
             if let Some(like_key) = like_key {
@@ -299,6 +345,19 @@ impl TableManager {
                     };
                     writer.do_send(TransferWriterRequest::AddRecord(record));
                 }
+            } else if param.user
+                && (key.as_str() == ROLE_TREE_NAME.as_str()
+                    || key.as_str() == ACCESS_KEY_TREE_NAME.as_str())
+            {
+                for (key, value) in &table_info.table_data {
+                    let record = TransferRecordDto {
+                        table_name: Some(table_info.name.clone()),
+                        key: key.to_owned(),
+                        value: value.to_owned(),
+                        table_id: 0,
+                    };
+                    writer.do_send(TransferWriterRequest::AddRecord(record));
+                }
             }
         }
         Ok(())
@@ -380,6 +439,21 @@ pub enum TableManagerReq {
 #[rtype(result = "anyhow::Result<TableManagerResult>")]
 pub enum TableManagerInnerReq {
     BuildSnapshot(Addr<SnapshotWriterActor>),
+    FilterPageList(Box<TableFilterQueryParam>),
+}
+
+///
+/// 本节点内按key范围及条件分页查询,过滤在表内完成,只复制命中页的数据
+pub struct TableFilterQueryParam {
+    pub table_name: Arc<String>,
+    /// 起始key(包含)
+    pub start_key: Option<Vec<u8>>,
+    /// 结束key(不包含)
+    pub end_key: Option<Vec<u8>>,
+    pub offset: usize,
+    pub limit: usize,
+    pub is_rev: bool,
+    pub filter: TableValueFilter,
 }
 
 impl From<TableManagerReq> for RouterRequest {
@@ -521,6 +595,10 @@ impl Handler<TableManagerInnerReq> for TableManager {
                 self.build_snapshot(writer).ok();
                 Ok(TableManagerResult::None)
             }
+            TableManagerInnerReq::FilterPageList(param) => {
+                let (size, list) = self.query_filter_page_list(&param);
+                Ok(TableManagerResult::PageListResult(size, list))
+            }
         }
     }
 }
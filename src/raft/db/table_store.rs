@@ -0,0 +1,143 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::prelude::*;
+
+use super::route::TableRoute;
+use super::table::{TableManager, TableManagerQueryReq, TableManagerReq, TableManagerResult};
+
+/// 各节点从本地表重新加载到内存的间隔
+pub const TABLE_RELOAD_INTERVAL: Duration = Duration::from_secs(3);
+
+///
+/// 存储在raft表中的值
+pub trait TableValue: Sized + 'static {
+    fn table_key(&self) -> String;
+    fn encode_value(&self) -> anyhow::Result<Vec<u8>>;
+    fn decode_value(v: &[u8]) -> anyhow::Result<Self>;
+}
+
+///
+/// 基于raft表的数据读写
+/// 写入通过raft在集群内同步,读取使用本节点的表;需要在内存中使用的数据由各actor定时调用reload加载
+pub struct RaftTableStore<T> {
+    table_name: Arc<String>,
+    raft_table_route: Option<Arc<TableRoute>>,
+    table_manager: Option<Addr<TableManager>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for RaftTableStore<T> {
+    fn clone(&self) -> Self {
+        Self {
+            table_name: self.table_name.clone(),
+            raft_table_route: self.raft_table_route.clone(),
+            table_manager: self.table_manager.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: TableValue> RaftTableStore<T> {
+    pub fn new(table_name: Arc<String>) -> Self {
+        Self {
+            table_name,
+            raft_table_route: None,
+            table_manager: None,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn inject(&mut self, factory_data: &bean_factory::FactoryData) {
+        self.raft_table_route = factory_data.get_bean();
+        self.table_manager = factory_data.get_actor();
+    }
+
+    pub fn table_manager(&self) -> Option<&Addr<TableManager>> {
+        self.table_manager.as_ref()
+    }
+
+    pub async fn query_list(&self) -> anyhow::Result<Vec<T>> {
+        let table_manager = match &self.table_manager {
+            Some(v) => v,
+            None => return Ok(vec![]),
+        };
+        let query_req = TableManagerQueryReq::QueryPageList {
+            table_name: self.table_name.clone(),
+            like_key: None,
+            offset: None,
+            limit: None,
+            is_rev: false,
+        };
+        let list = match table_manager.send(query_req).await?? {
+            TableManagerResult::PageListResult(_, list) => list,
+            _ => vec![],
+        };
+        let mut rlist = Vec::with_capacity(list.len());
+        for (_, v) in list {
+            match T::decode_value(&v) {
+                Ok(v) => rlist.push(v),
+                Err(err) => log::warn!("decode {} value error,{}", &self.table_name, err),
+            }
+        }
+        Ok(rlist)
+    }
+
+    pub async fn get(&self, key: String) -> anyhow::Result<Option<T>> {
+        let table_manager = match &self.table_manager {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        let query_req = TableManagerQueryReq::Get {
+            table_name: self.table_name.clone(),
+            key,
+        };
+        match table_manager.send(query_req).await?? {
+            TableManagerResult::Value(v) => Ok(Some(T::decode_value(&v)?)),
+            _ => Ok(None),
+        }
+    }
+
+    pub async fn save(&self, value: &T) -> anyhow::Result<()> {
+        let req = TableManagerReq::Set {
+            table_name: self.table_name.clone(),
+            key: value.table_key().into_bytes(),
+            value: value.encode_value()?,
+            last_seq_id: None,
+        };
+        if let Some(raft_table_route) = &self.raft_table_route {
+            raft_table_route.request(req).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn remove(&self, key: String) -> anyhow::Result<()> {
+        let req = TableManagerReq::Remove {
+            table_name: self.table_name.clone(),
+            key: key.into_bytes(),
+        };
+        if let Some(raft_table_route) = &self.raft_table_route {
+            raft_table_route.request(req).await?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// 异步加载全部数据,加载成功后在actor内回调
+    pub fn reload<A, F>(&self, act: &mut A, ctx: &mut Context<A>, apply: F)
+    where
+        A: Actor<Context = Context<A>>,
+        F: FnOnce(&mut A, Vec<T>) + 'static,
+    {
+        let store = self.clone();
+        let table_name = self.table_name.clone();
+        async move { store.query_list().await }
+            .into_actor(act)
+            .map(move |r, act, _ctx| match r {
+                Ok(list) => apply(act, list),
+                Err(err) => log::warn!("load {} error,{}", &table_name, err),
+            })
+            .spawn(ctx);
+    }
+}
@@ -13,8 +13,10 @@ use super::{
 };
 use crate::common::byte_utils::bin_to_id;
 use crate::common::constant::{
-    CACHE_TREE_NAME, CONFIG_TREE_NAME, NAMESPACE_TREE_NAME, SEQUENCE_TREE_NAME, SEQ_KEY_CONFIG,
-    USER_TREE_NAME,
+    ACCESS_KEY_TREE_NAME, CACHE_TREE_NAME, CONFIG_TREE_NAME, NAMESPACE_TREE_NAME,
+    NAMING_INSTANCE_DRAIN_TREE_NAME, NAMING_PERSISTENT_INSTANCE_TREE_NAME,
+    NAMING_SERVICE_CLUSTER_TREE_NAME, NAMING_SERVICE_TREE_NAME, ROLE_TREE_NAME, SEQUENCE_TREE_NAME,
+    SEQ_KEY_CONFIG, USER_TREE_NAME,
 };
 use crate::config::core::{ConfigCmd, ConfigKey, ConfigValue};
 use crate::config::model::{ConfigRaftCmd, ConfigValueDO};
@@ -70,6 +72,7 @@ impl LogRecordLoader for LogRecordLoaderInstance {
                     value,
                     config_type,
                     desc,
+                    tags,
                     history_id,
                     history_table_id,
                     op_time,
@@ -80,6 +83,7 @@ impl LogRecordLoader for LogRecordLoaderInstance {
                         value,
                         config_type,
                         desc,
+                        tags,
                         history_id,
                         history_table_id,
                         op_time,
@@ -113,6 +117,16 @@ impl LogRecordLoader for LogRecordLoaderInstance {
                 ClientRequest::NamespaceReq(req) => {
                     self.data_wrap.namespace.send(req).await.ok();
                 }
+                ClientRequest::NamingReq(req) => {
+                    self.data_wrap.naming.send(req).await.ok();
+                }
+                req @ (ClientRequest::ConfigGraySet { .. }
+                | ClientRequest::ConfigGrayRemove { .. }
+                | ClientRequest::ConfigGrayPromote { .. }) => {
+                    if let Some(cmd) = req.into_config_gray_cmd() {
+                        self.data_wrap.config.send(cmd).await.ok();
+                    }
+                }
             },
             _ => {}
         }
@@ -272,6 +286,26 @@ impl StateApplyManager {
                     last_seq_id: None,
                 };
                 data_wrap.table.send(req).await??;
+            } else if record.tree.as_str() == ROLE_TREE_NAME.as_str() {
+                let key = record.key;
+                let value = record.value;
+                let req = TableManagerReq::Set {
+                    table_name: ROLE_TREE_NAME.clone(),
+                    key,
+                    value,
+                    last_seq_id: None,
+                };
+                data_wrap.table.send(req).await??;
+            } else if record.tree.as_str() == ACCESS_KEY_TREE_NAME.as_str() {
+                let key = record.key;
+                let value = record.value;
+                let req = TableManagerReq::Set {
+                    table_name: ACCESS_KEY_TREE_NAME.clone(),
+                    key,
+                    value,
+                    last_seq_id: None,
+                };
+                data_wrap.table.send(req).await??;
             } else if record.tree.as_str() == CACHE_TREE_NAME.as_str() {
                 let key = record.key;
                 let value = record.value;
@@ -285,6 +319,13 @@ impl StateApplyManager {
             } else if record.tree.as_str() == NAMESPACE_TREE_NAME.as_str() {
                 let req = RaftApplyDataRequest::LoadSnapshotRecord(record);
                 data_wrap.namespace.send(req).await??;
+            } else if record.tree.as_str() == NAMING_PERSISTENT_INSTANCE_TREE_NAME.as_str()
+                || record.tree.as_str() == NAMING_SERVICE_CLUSTER_TREE_NAME.as_str()
+                || record.tree.as_str() == NAMING_SERVICE_TREE_NAME.as_str()
+                || record.tree.as_str() == NAMING_INSTANCE_DRAIN_TREE_NAME.as_str()
+            {
+                let req = RaftApplyDataRequest::LoadSnapshotRecord(record);
+                data_wrap.naming.send(req).await??;
             } else {
                 log::warn!(
                     "do_load_snapshot ignore data,table name:{}",
@@ -356,6 +397,7 @@ impl StateApplyManager {
                 value,
                 config_type,
                 desc,
+                tags,
                 history_id,
                 history_table_id,
                 op_time,
@@ -367,6 +409,7 @@ impl StateApplyManager {
                         value,
                         config_type,
                         desc,
+                        tags,
                         history_id,
                         history_table_id,
                         op_time,
@@ -409,6 +452,20 @@ impl StateApplyManager {
                     raft_data_wrap.namespace.do_send(req);
                 }
             }
+            ClientRequest::NamingReq(req) => {
+                if let Some(raft_data_wrap) = &self.data_wrap {
+                    raft_data_wrap.naming.do_send(req);
+                }
+            }
+            req @ (ClientRequest::ConfigGraySet { .. }
+            | ClientRequest::ConfigGrayRemove { .. }
+            | ClientRequest::ConfigGrayPromote { .. }) => {
+                if let (Some(raft_data_wrap), Some(cmd)) =
+                    (&self.data_wrap, req.into_config_gray_cmd())
+                {
+                    raft_data_wrap.config.do_send(cmd);
+                }
+            }
         };
         Ok(())
     }
@@ -437,6 +494,7 @@ impl StateApplyManager {
                 value,
                 config_type,
                 desc,
+                tags,
                 history_id,
                 history_table_id,
                 op_time,
@@ -447,6 +505,7 @@ impl StateApplyManager {
                     value,
                     config_type,
                     desc,
+                    tags,
                     history_id,
                     history_table_id,
                     op_time,
@@ -485,6 +544,18 @@ impl StateApplyManager {
                 raft_data_wrap.namespace.send(req).await??;
                 Ok(ClientResponse::Success)
             }
+            ClientRequest::NamingReq(req) => {
+                raft_data_wrap.naming.send(req).await??;
+                Ok(ClientResponse::Success)
+            }
+            req @ (ClientRequest::ConfigGraySet { .. }
+            | ClientRequest::ConfigGrayRemove { .. }
+            | ClientRequest::ConfigGrayPromote { .. }) => {
+                if let Some(cmd) = req.into_config_gray_cmd() {
+                    raft_data_wrap.config.send(cmd).await??;
+                }
+                Ok(ClientResponse::Success)
+            }
         };
         index_manager.do_send(RaftIndexRequest::SaveLastAppliedLog(last_applied_log));
         r
@@ -554,6 +625,10 @@ impl StateApplyManager {
             .namespace
             .send(RaftApplyDataRequest::BuildSnapshot(writer.clone()))
             .await??;
+        data_wrap
+            .naming
+            .send(RaftApplyDataRequest::BuildSnapshot(writer.clone()))
+            .await??;
 
         //5. flush to file
         writer
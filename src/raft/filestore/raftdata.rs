@@ -1,5 +1,6 @@
 use crate::config::core::ConfigActor;
 use crate::namespace::NamespaceActor;
+use crate::naming::core::NamingActor;
 use crate::raft::cache::CacheManager;
 use crate::raft::db::table::TableManager;
 use actix::prelude::*;
@@ -9,6 +10,7 @@ pub struct RaftDataWrap {
     pub(crate) config: Addr<ConfigActor>,
     pub(crate) table: Addr<TableManager>,
     pub(crate) namespace: Addr<NamespaceActor>,
+    pub(crate) naming: Addr<NamingActor>,
     //pub(crate) cache: Addr<CacheManager>,
 }
 
@@ -17,12 +19,14 @@ impl RaftDataWrap {
         config: Addr<ConfigActor>,
         table: Addr<TableManager>,
         namespace: Addr<NamespaceActor>,
+        naming: Addr<NamingActor>,
         _cache: Addr<CacheManager>,
     ) -> Self {
         Self {
             config,
             table,
             namespace,
+            naming,
             //cache,
         }
     }
@@ -1,10 +1,17 @@
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::Poll;
 
+use crate::common::tls_utils::{ClusterTlsConnector, LazyTlsChannel};
 use crate::common::AppSysConfig;
 use crate::grpc::handler::CLUSTER_TOKEN;
 use actix::prelude::*;
 use inner_mem_cache::MemCache;
+use tonic::body::BoxBody;
+use tonic::codegen::http;
 use tonic::transport::Channel;
+use tower::Service;
 
 use crate::grpc::nacos_proto::{request_client::RequestClient, Payload};
 
@@ -22,7 +29,7 @@ impl RaftClusterRequestSender {
         }
     }
 
-    pub async fn get_node_channel(&self, addr: Arc<String>) -> anyhow::Result<Arc<Channel>> {
+    pub async fn get_node_channel(&self, addr: Arc<String>) -> anyhow::Result<Arc<ClusterChannel>> {
         let res: RaftConnResponse = self
             .conn_factory
             .send(RaftConnRequest::GetChannel(addr))
@@ -74,29 +81,69 @@ impl RaftClusterRequestSender {
     }
 }
 
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+type ChannelResponse = <Channel as Service<http::Request<BoxBody>>>::Response;
+
+///
+/// 集群节点间的通道,两种方式都在首次请求时才建立连接
+#[derive(Clone)]
+pub enum ClusterChannel {
+    Plain(Channel),
+    Tls(LazyTlsChannel),
+}
+
+impl Service<http::Request<BoxBody>> for ClusterChannel {
+    type Response = ChannelResponse;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self {
+            ClusterChannel::Plain(channel) => channel.poll_ready(cx).map_err(Into::into),
+            ClusterChannel::Tls(channel) => channel.poll_ready(cx),
+        }
+    }
+
+    fn call(&mut self, req: http::Request<BoxBody>) -> Self::Future {
+        match self {
+            ClusterChannel::Plain(channel) => {
+                let fut = channel.call(req);
+                Box::pin(async move { fut.await.map_err(Into::into) })
+            }
+            ClusterChannel::Tls(channel) => channel.call(req),
+        }
+    }
+}
+
 pub struct RaftConnectionFactory {
-    channel_cache: MemCache<Arc<String>, Arc<Channel>>,
+    channel_cache: MemCache<Arc<String>, Arc<ClusterChannel>>,
     cache_ses: i32,
+    tls_connector: Option<Arc<ClusterTlsConnector>>,
 }
 
 impl RaftConnectionFactory {
-    pub fn new(cache_ses: i32) -> Self {
+    pub fn new(cache_ses: i32, tls_connector: Option<Arc<ClusterTlsConnector>>) -> Self {
         Self {
-            channel_cache: MemCache::<Arc<String>, Arc<Channel>>::new(),
+            channel_cache: MemCache::<Arc<String>, Arc<ClusterChannel>>::new(),
             cache_ses,
+            tls_connector,
         }
     }
 
-    fn build_channel(&mut self, key: Arc<String>) -> anyhow::Result<Arc<Channel>> {
+    fn build_channel(&mut self, key: Arc<String>) -> anyhow::Result<RaftConnResponse> {
         self.channel_cache.clear_time_out();
         if let Ok(channel) = self.channel_cache.get(&key) {
-            Ok(channel)
+            return Ok(RaftConnResponse::Channel(channel));
+        }
+        let channel = if let Some(connector) = &self.tls_connector {
+            ClusterChannel::Tls(connector.connect_lazy(key.clone()))
         } else {
             let addr = format!("http://{}", &key);
-            let channel = Arc::new(Channel::from_shared(addr)?.connect_lazy()?);
-            self.channel_cache.set(key, channel.clone(), self.cache_ses);
-            Ok(channel)
-        }
+            ClusterChannel::Plain(Channel::from_shared(addr)?.connect_lazy()?)
+        };
+        let channel = Arc::new(channel);
+        self.channel_cache.set(key, channel.clone(), self.cache_ses);
+        Ok(RaftConnResponse::Channel(channel))
     }
 
     fn update_channel_status(&mut self, key: Arc<String>, is_active: bool) {
@@ -129,7 +176,7 @@ pub enum RaftConnRequest {
 }
 
 pub enum RaftConnResponse {
-    Channel(Arc<Channel>),
+    Channel(Arc<ClusterChannel>),
     None,
 }
 
@@ -138,10 +185,7 @@ impl Handler<RaftConnRequest> for RaftConnectionFactory {
 
     fn handle(&mut self, msg: RaftConnRequest, _ctx: &mut Self::Context) -> Self::Result {
         match msg {
-            RaftConnRequest::GetChannel(key) => {
-                let c = self.build_channel(key)?;
-                Ok(RaftConnResponse::Channel(c))
-            }
+            RaftConnRequest::GetChannel(key) => self.build_channel(key),
             RaftConnRequest::UpdateChannel { key, is_active } => {
                 self.update_channel_status(key, is_active);
                 Ok(RaftConnResponse::None)
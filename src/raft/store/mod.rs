@@ -4,7 +4,10 @@
 use std::sync::Arc;
 
 use super::db::table::TableManagerReq;
+use crate::config::config_gray::ConfigGrayRule;
+use crate::config::model::ConfigRaftCmd;
 use crate::namespace::model::NamespaceRaftReq;
+use crate::naming::persistent_instance::NamingRaftReq;
 use async_raft_ext::AppData;
 use async_raft_ext::AppDataResponse;
 use serde::Deserialize;
@@ -25,6 +28,8 @@ pub enum ClientRequest {
         value: Arc<String>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        #[serde(default)]
+        tags: Option<Arc<String>>,
         history_id: u64,
         history_table_id: Option<u64>,
         op_time: i64,
@@ -40,6 +45,61 @@ pub enum ClientRequest {
     },
     TableManagerReq(TableManagerReq),
     NamespaceReq(NamespaceRaftReq),
+    NamingReq(NamingRaftReq),
+    ConfigGraySet {
+        key: String,
+        value: Arc<String>,
+        rule: ConfigGrayRule,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+    },
+    ConfigGrayRemove {
+        key: String,
+    },
+    ConfigGrayPromote {
+        key: String,
+        history_id: u64,
+        history_table_id: Option<u64>,
+        op_time: i64,
+        op_user: Option<Arc<String>>,
+    },
+}
+
+impl ClientRequest {
+    ///
+    /// 配置灰度请求转换为配置中心raft命令
+    pub fn into_config_gray_cmd(self) -> Option<ConfigRaftCmd> {
+        match self {
+            ClientRequest::ConfigGraySet {
+                key,
+                value,
+                rule,
+                op_time,
+                op_user,
+            } => Some(ConfigRaftCmd::GraySet {
+                key,
+                value,
+                rule,
+                op_time,
+                op_user,
+            }),
+            ClientRequest::ConfigGrayRemove { key } => Some(ConfigRaftCmd::GrayRemove { key }),
+            ClientRequest::ConfigGrayPromote {
+                key,
+                history_id,
+                history_table_id,
+                op_time,
+                op_user,
+            } => Some(ConfigRaftCmd::GrayPromote {
+                key,
+                history_id,
+                history_table_id,
+                op_time,
+                op_user,
+            }),
+            _ => None,
+        }
+    }
 }
 
 impl AppData for ClientRequest {}
@@ -1,11 +1,16 @@
 use std::{collections::HashSet, sync::Arc, time::Duration};
 
+use crate::audit::AuditManager;
 use crate::common::actor_utils::{create_actor_at_thread, create_actor_at_thread2};
+use crate::common::tls_utils::TlsUtils;
+use crate::config::webhook::ConfigWebhookManager;
 use crate::grpc::handler::RAFT_ROUTE_REQUEST;
 use crate::health::core::HealthManager;
 use crate::ldap::core::LdapManager;
 use crate::metrics::core::MetricsManager;
 use crate::namespace::NamespaceActor;
+use crate::naming::health_check::NamingHealthCheckManager;
+use crate::oidc::core::OidcManager;
 use crate::raft::cluster::route::RaftRequestRoute;
 use crate::raft::filestore::core::FileStore;
 use crate::raft::filestore::raftapply::StateApplyManager;
@@ -44,7 +49,7 @@ use crate::{
             store::ClientRequest,
         },
     },
-    user::UserManager,
+    user::{access_key::AccessKeyManager, role::RoleManager, UserManager},
 };
 use actix::prelude::*;
 use async_raft_ext::{raft::ClientWriteRequest, Config, Raft, RaftStorage};
@@ -71,7 +76,8 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
     ));
 
     //raft
-    let conn_factory = RaftConnectionFactory::new(60).start();
+    let cluster_tls_connector = TlsUtils::build_cluster_tls_connector(&sys_config)?;
+    let conn_factory = RaftConnectionFactory::new(60, cluster_tls_connector).start();
     factory.register(BeanDefinition::actor_from_obj(conn_factory.clone()));
     let cluster_sender = Arc::new(RaftClusterRequestSender::new(
         conn_factory,
@@ -139,13 +145,6 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
     ));
     let naming_node_manage = Arc::new(NodeManage::new(naming_inner_node_manage_addr.clone()));
     factory.register(BeanDefinition::from_obj(naming_node_manage.clone()));
-    let naming_route = Arc::new(NamingRoute::new(
-        sys_config.raft_node_id,
-        naming_addr.clone(),
-        naming_node_manage.clone(),
-        cluster_sender.clone(),
-    ));
-    factory.register(BeanDefinition::from_obj(naming_route.clone()));
     let naming_cluster_delay_notify_addr = ClusterInstanceDelayNotifyActor::new().start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(
         naming_cluster_delay_notify_addr.clone(),
@@ -156,7 +155,7 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
         bistream_manage_addr.clone(),
     ));
 
-    let user_manager = UserManager::new().start();
+    let user_manager = UserManager::new(sys_config.get_password_policy()).start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(user_manager));
     let cache_manager = CacheManager::new().start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(
@@ -182,11 +181,20 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
         raft.clone(),
         transfer_import_addr.clone(),
     ));
-    factory.register(BeanDefinition::from_obj(raft_request_route));
+    factory.register(BeanDefinition::from_obj(raft_request_route.clone()));
+    let naming_route = Arc::new(NamingRoute::new(
+        sys_config.raft_node_id,
+        naming_addr.clone(),
+        naming_node_manage.clone(),
+        cluster_sender.clone(),
+        raft_request_route,
+    ));
+    factory.register(BeanDefinition::from_obj(naming_route));
     let raft_data_wrap = Arc::new(RaftDataWrap {
         config: config_addr.clone(),
         table: table_manage.clone(),
         namespace: namespace_addr.clone(),
+        naming: naming_addr.clone(),
         //cache: cache_manager.clone(),
     });
     factory.register(BeanDefinition::from_obj(raft_data_wrap));
@@ -201,6 +209,29 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
     let ldap_manager =
         LdapManager::new(sys_config.get_ldap_config(), sys_config.ldap_enable).start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(ldap_manager));
+    let oidc_manager =
+        OidcManager::new(sys_config.get_oidc_config(), sys_config.oidc_enable).start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(oidc_manager));
+    let audit_manager = AuditManager::new(
+        sys_config.audit_log_keep_days,
+        sys_config.audit_log_max_count,
+    )
+    .start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(audit_manager));
+    let config_webhook_manager = ConfigWebhookManager::new().start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        config_webhook_manager,
+    ));
+    let naming_health_check_manager = NamingHealthCheckManager::new().start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        naming_health_check_manager,
+    ));
+    let role_manager = RoleManager::new().start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(role_manager));
+    let access_key_manager = AccessKeyManager::new().start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        access_key_manager,
+    ));
     Ok(factory.init().await)
 }
 
@@ -237,6 +268,12 @@ pub fn build_share_data(factory_data: FactoryData) -> anyhow::Result<Arc<AppShar
         transfer_import_manager: factory_data.get_actor().unwrap(),
         health_manager: factory_data.get_actor().unwrap(),
         ldap_manager: factory_data.get_actor().unwrap(),
+        oidc_manager: factory_data.get_actor().unwrap(),
+        audit_manager: factory_data.get_actor().unwrap(),
+        config_webhook_manager: factory_data.get_actor().unwrap(),
+        naming_health_check_manager: factory_data.get_actor().unwrap(),
+        role_manager: factory_data.get_actor().unwrap(),
+        access_key_manager: factory_data.get_actor().unwrap(),
         factory_data,
     });
     Ok(app_data)
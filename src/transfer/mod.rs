@@ -1,6 +1,9 @@
 use crate::common::actor_utils::create_actor_at_thread;
 use crate::common::constant::{
-    CACHE_TREE_NAME, CONFIG_TREE_NAME, NAMESPACE_TREE_NAME, SEQUENCE_TREE_NAME, USER_TREE_NAME,
+    ACCESS_KEY_TREE_NAME, CACHE_TREE_NAME, CONFIG_TREE_NAME, NAMESPACE_TREE_NAME,
+    NAMING_INSTANCE_DRAIN_TREE_NAME, NAMING_PERSISTENT_INSTANCE_TREE_NAME,
+    NAMING_SERVICE_CLUSTER_TREE_NAME, NAMING_SERVICE_TREE_NAME, ROLE_TREE_NAME, SEQUENCE_TREE_NAME,
+    USER_TREE_NAME,
 };
 use crate::transfer::model::TransferWriterRequest;
 use crate::transfer::writer::TransferWriterActor;
@@ -33,6 +36,24 @@ pub(crate) fn init_writer_actor(data_file: &str) -> Addr<TransferWriterActor> {
     writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
         CACHE_TREE_NAME.clone(),
     ));
+    writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+        NAMING_PERSISTENT_INSTANCE_TREE_NAME.clone(),
+    ));
+    writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+        NAMING_SERVICE_CLUSTER_TREE_NAME.clone(),
+    ));
+    writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+        NAMING_SERVICE_TREE_NAME.clone(),
+    ));
+    writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+        NAMING_INSTANCE_DRAIN_TREE_NAME.clone(),
+    ));
+    writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+        ROLE_TREE_NAME.clone(),
+    ));
+    writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+        ACCESS_KEY_TREE_NAME.clone(),
+    ));
     writer_actor.do_send(TransferWriterRequest::InitHeader);
     writer_actor
 }
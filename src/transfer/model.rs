@@ -214,6 +214,7 @@ pub struct TransferBackupParam {
     pub config: bool,
     pub user: bool,
     pub cache: bool,
+    pub naming: bool,
 }
 
 impl TransferBackupParam {
@@ -222,6 +223,7 @@ impl TransferBackupParam {
             config: true,
             user: true,
             cache: true,
+            naming: true,
         }
     }
 }
@@ -241,6 +243,9 @@ pub struct TransferImportParam {
     pub config: bool,
     pub user: bool,
     pub cache: bool,
+    /// 持久化服务实例
+    #[serde(default)]
+    pub naming: bool,
 }
 
 impl TransferImportParam {
@@ -249,6 +254,7 @@ impl TransferImportParam {
             config: true,
             user: true,
             cache: true,
+            naming: true,
         }
     }
 }
@@ -21,6 +21,7 @@ pub struct ConfigInfoDO {
     pub r#type: Option<String>,
     pub c_schema: Option<String>,
     pub encrypted_data_key: Option<String>,
+    pub config_tags: Option<String>,
 }
 
 #[derive(Debug, Default)]
@@ -44,7 +45,9 @@ impl ConfigInfoSql {
         B::prepare(
             B::new_sql("select id, data_id, group_id, content, md5, UNIX_TIMESTAMP(gmt_modified) \
             as gmt_modified_timestamp, src_user, src_ip, app_name, tenant_id, c_desc, c_use, effect, \
-            type, c_schema, encrypted_data_key from config_info")
+            type, c_schema, encrypted_data_key, \
+            (select group_concat(tag_name) from config_tags_relation r where r.id = config_info.id) \
+            as config_tags from config_info")
                 .push_build(&mut self.conditions(param))
                 .push_fn(||{
                     let mut b= B::new();
@@ -2,6 +2,7 @@ use crate::common::constant::{
     CONFIG_TREE_NAME, EMPTY_ARC_STRING, EMPTY_STR, NAMESPACE_TREE_NAME, USER_TREE_NAME,
 };
 use crate::common::sqlx_utils::MySqlExecutor;
+use crate::config::config_cipher::ConfigCipherUtils;
 use crate::config::core::{ConfigKey, ConfigValue};
 use crate::config::model::ConfigValueDO;
 use crate::config::ConfigUtils;
@@ -101,6 +102,11 @@ fn build_config_record(
 ) -> anyhow::Result<TransferRecordDto> {
     let current_content = config_do.content.unwrap_or_default();
     let mut config_value = ConfigValue::new(Arc::new(current_content.clone()));
+    config_value.tags = config_do
+        .config_tags
+        .as_ref()
+        .map(|v| ConfigUtils::format_tags(v))
+        .filter(|v| !v.is_empty());
     let mut last_content = None;
     let mut use_histories = vec![];
     for item in histories {
@@ -149,7 +155,8 @@ fn build_config_record(
             None,
         );
     }
-    let value_do: ConfigValueDO = config_value.into();
+    let value_do: ConfigValueDO =
+        ConfigCipherUtils::encrypt_value_or_keep(&key, config_value).into();
     let record = TransferRecordDto {
         table_name: Some(CONFIG_TREE_NAME.clone()),
         key: key.build_key().as_bytes().to_vec(),
@@ -1,4 +1,5 @@
 use crate::common::constant::{CONFIG_TREE_NAME, EMPTY_STR, NAMESPACE_TREE_NAME};
+use crate::config::config_cipher::ConfigCipherUtils;
 use crate::config::core::{ConfigKey, ConfigValue};
 use crate::config::model::ConfigValueDO;
 use crate::namespace::model::{NamespaceDO, FROM_USER_VALUE};
@@ -136,7 +137,8 @@ fn build_config_record(
         None,
         None,
     );
-    let value_do: ConfigValueDO = config_value.into();
+    let value_do: ConfigValueDO =
+        ConfigCipherUtils::encrypt_value_or_keep(&key, config_value).into();
     let record = TransferRecordDto {
         table_name: Some(CONFIG_TREE_NAME.clone()),
         key: key.build_key().as_bytes().to_vec(),
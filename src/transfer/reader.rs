@@ -1,14 +1,21 @@
 use crate::common::constant::{
-    CACHE_TREE_NAME, CONFIG_TREE_NAME, EMPTY_ARC_STRING, NAMESPACE_TREE_NAME, USER_TREE_NAME,
+    ACCESS_KEY_TREE_NAME, CACHE_TREE_NAME, CONFIG_TREE_NAME, EMPTY_ARC_STRING, NAMESPACE_TREE_NAME,
+    NAMING_INSTANCE_DRAIN_TREE_NAME, NAMING_PERSISTENT_INSTANCE_TREE_NAME,
+    NAMING_SERVICE_CLUSTER_TREE_NAME, NAMING_SERVICE_TREE_NAME, ROLE_TREE_NAME, USER_TREE_NAME,
 };
 use crate::common::pb::transfer::{TransferHeader, TransferItem};
 use crate::common::protobuf_utils::{FileMessageReader, MessageBufReader};
 use crate::common::sequence_utils::CacheSequence;
-use crate::config::core::{ConfigActor, ConfigCmd, ConfigResult, ConfigValue};
+use crate::config::config_cipher::ConfigCipherUtils;
+use crate::config::core::{ConfigActor, ConfigCmd, ConfigKey, ConfigResult, ConfigValue};
 use crate::config::model::ConfigValueDO;
 use crate::namespace::model::{
     Namespace, NamespaceDO, NamespaceFromFlags, NamespaceParam, NamespaceRaftReq,
 };
+use crate::naming::instance_drain::InstanceDrainDo;
+use crate::naming::persistent_instance::{NamingRaftReq, PersistentInstanceDo};
+use crate::naming::service_cluster::ServiceClusterDo;
+use crate::naming::service_definition::ServiceDefinitionDo;
 use crate::raft::db::table::TableManagerReq;
 use crate::raft::filestore::raftdata::RaftDataWrap;
 use crate::raft::store::ClientRequest;
@@ -41,6 +48,18 @@ pub(crate) fn reader_transfer_record<'a>(
             CACHE_TREE_NAME.clone()
         } else if NAMESPACE_TREE_NAME.as_str() == record_do.table_name.as_ref() {
             NAMESPACE_TREE_NAME.clone()
+        } else if NAMING_PERSISTENT_INSTANCE_TREE_NAME.as_str() == record_do.table_name.as_ref() {
+            NAMING_PERSISTENT_INSTANCE_TREE_NAME.clone()
+        } else if NAMING_SERVICE_CLUSTER_TREE_NAME.as_str() == record_do.table_name.as_ref() {
+            NAMING_SERVICE_CLUSTER_TREE_NAME.clone()
+        } else if NAMING_SERVICE_TREE_NAME.as_str() == record_do.table_name.as_ref() {
+            NAMING_SERVICE_TREE_NAME.clone()
+        } else if NAMING_INSTANCE_DRAIN_TREE_NAME.as_str() == record_do.table_name.as_ref() {
+            NAMING_INSTANCE_DRAIN_TREE_NAME.clone()
+        } else if ROLE_TREE_NAME.as_str() == record_do.table_name.as_ref() {
+            ROLE_TREE_NAME.clone()
+        } else if ACCESS_KEY_TREE_NAME.as_str() == record_do.table_name.as_ref() {
+            ACCESS_KEY_TREE_NAME.clone()
         } else {
             //ignore
             EMPTY_ARC_STRING.clone()
@@ -194,7 +213,26 @@ impl TransferImportManager {
                 } else if param.config && record.table_name.as_str() == NAMESPACE_TREE_NAME.as_str()
                 {
                     Self::apply_namespace(raft, record).await?;
-                } else if (param.user && record.table_name.as_str() == USER_TREE_NAME.as_str())
+                } else if param.naming
+                    && record.table_name.as_str() == NAMING_PERSISTENT_INSTANCE_TREE_NAME.as_str()
+                {
+                    Self::apply_naming_instance(raft, record).await?;
+                } else if param.naming
+                    && record.table_name.as_str() == NAMING_SERVICE_CLUSTER_TREE_NAME.as_str()
+                {
+                    Self::apply_naming_cluster(raft, record).await?;
+                } else if param.naming
+                    && record.table_name.as_str() == NAMING_SERVICE_TREE_NAME.as_str()
+                {
+                    Self::apply_naming_service(raft, record).await?;
+                } else if param.naming
+                    && record.table_name.as_str() == NAMING_INSTANCE_DRAIN_TREE_NAME.as_str()
+                {
+                    Self::apply_naming_drain(raft, record).await?;
+                } else if (param.user
+                    && (record.table_name.as_str() == USER_TREE_NAME.as_str()
+                        || record.table_name.as_str() == ROLE_TREE_NAME.as_str()
+                        || record.table_name.as_str() == ACCESS_KEY_TREE_NAME.as_str()))
                     || (param.cache && record.table_name.as_str() == CACHE_TREE_NAME.as_str())
                 {
                     Self::apply_table(raft, record).await?;
@@ -238,6 +276,55 @@ impl TransferImportManager {
         Ok(())
     }
 
+    async fn apply_naming_instance(
+        raft: &Arc<NacosRaft>,
+        record: TransferRecordRef<'_>,
+    ) -> anyhow::Result<()> {
+        let instance = PersistentInstanceDo::from_bytes(&record.value)?;
+        let req = ClientRequest::NamingReq(NamingRaftReq::Update {
+            instance: Box::new(instance),
+            tag: None,
+        });
+        Self::send_raft_request(raft, req).await?;
+        Ok(())
+    }
+
+    async fn apply_naming_service(
+        raft: &Arc<NacosRaft>,
+        record: TransferRecordRef<'_>,
+    ) -> anyhow::Result<()> {
+        let service = ServiceDefinitionDo::from_bytes(&record.value)?;
+        let req = ClientRequest::NamingReq(NamingRaftReq::UpdateService {
+            service: Box::new(service.into()),
+        });
+        Self::send_raft_request(raft, req).await?;
+        Ok(())
+    }
+
+    async fn apply_naming_drain(
+        raft: &Arc<NacosRaft>,
+        record: TransferRecordRef<'_>,
+    ) -> anyhow::Result<()> {
+        let drain = InstanceDrainDo::from_bytes(&record.value)?;
+        let req = ClientRequest::NamingReq(NamingRaftReq::UpdateDrain {
+            drain: Box::new(drain),
+        });
+        Self::send_raft_request(raft, req).await?;
+        Ok(())
+    }
+
+    async fn apply_naming_cluster(
+        raft: &Arc<NacosRaft>,
+        record: TransferRecordRef<'_>,
+    ) -> anyhow::Result<()> {
+        let cluster = ServiceClusterDo::from_bytes(&record.value)?;
+        let req = ClientRequest::NamingReq(NamingRaftReq::UpdateCluster {
+            cluster: Box::new(cluster),
+        });
+        Self::send_raft_request(raft, req).await?;
+        Ok(())
+    }
+
     async fn apply_config(
         raft: &Arc<NacosRaft>,
         config_seq: &mut ConfigCacheSequence,
@@ -253,7 +340,9 @@ impl TransferImportManager {
             }
             item.id = id;
         }
-        let save_do: ConfigValueDO = config_value.into();
+        let config_key = ConfigKey::from(&String::from_utf8_lossy(&record.key) as &str);
+        let save_do: ConfigValueDO =
+            ConfigCipherUtils::encrypt_value_or_keep(&config_key, config_value).into();
         let req = ClientRequest::ConfigFullValue {
             key: record.key.into_owned(),
             value: save_do.to_bytes()?,
@@ -82,6 +82,16 @@ impl From<UserDO> for UserDo {
             namespace_white_list: Default::default(),
             namespace_black_list: Default::default(),
             source: None,
+            config_group_privilege_flags: None,
+            config_group_white_list: Default::default(),
+            config_group_black_list: Default::default(),
+            service_group_privilege_flags: None,
+            service_group_white_list: Default::default(),
+            service_group_black_list: Default::default(),
+            password_modified: None,
+            password_history: Default::default(),
+            need_change_password: false,
+            lock_until: 0,
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::common::constant::{
     CONFIG_TREE_NAME, EMPTY_ARC_STRING, EMPTY_STR, NAMESPACE_TREE_NAME, USER_TREE_NAME,
 };
+use crate::config::config_cipher::ConfigCipherUtils;
 use crate::config::core::{ConfigKey, ConfigValue};
 use crate::config::model::ConfigValueDO;
 use crate::config::ConfigUtils;
@@ -112,7 +113,8 @@ fn build_config_record(
             None,
         );
     }
-    let value_do: ConfigValueDO = config_value.into();
+    let value_do: ConfigValueDO =
+        ConfigCipherUtils::encrypt_value_or_keep(&key, config_value).into();
     let record = TransferRecordDto {
         table_name: Some(CONFIG_TREE_NAME.clone()),
         key: key.build_key().as_bytes().to_vec(),
@@ -1,6 +1,8 @@
 #![allow(clippy::suspicious_open_options)]
 use crate::common::constant::{
-    CACHE_TREE_NAME, CONFIG_TREE_NAME, EMPTY_STR, NAMESPACE_TREE_NAME, SEQUENCE_TREE_NAME,
+    ACCESS_KEY_TREE_NAME, CACHE_TREE_NAME, CONFIG_TREE_NAME, EMPTY_STR, NAMESPACE_TREE_NAME,
+    NAMING_INSTANCE_DRAIN_TREE_NAME, NAMING_PERSISTENT_INSTANCE_TREE_NAME,
+    NAMING_SERVICE_CLUSTER_TREE_NAME, NAMING_SERVICE_TREE_NAME, ROLE_TREE_NAME, SEQUENCE_TREE_NAME,
     USER_TREE_NAME,
 };
 use crate::common::tempfile::TempFile;
@@ -212,6 +214,24 @@ impl TransferWriterManager {
         writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
             CACHE_TREE_NAME.clone(),
         ));
+        writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+            NAMING_PERSISTENT_INSTANCE_TREE_NAME.clone(),
+        ));
+        writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+            NAMING_SERVICE_CLUSTER_TREE_NAME.clone(),
+        ));
+        writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+            NAMING_SERVICE_TREE_NAME.clone(),
+        ));
+        writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+            NAMING_INSTANCE_DRAIN_TREE_NAME.clone(),
+        ));
+        writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+            ROLE_TREE_NAME.clone(),
+        ));
+        writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+            ACCESS_KEY_TREE_NAME.clone(),
+        ));
         writer_actor.do_send(TransferWriterRequest::InitHeader);
         writer_actor
     }
@@ -243,6 +263,13 @@ impl TransferWriterManager {
                     backup_param.clone(),
                 ))
                 .await??;
+            data_wrap
+                .naming
+                .send(TransferDataRequest::Backup(
+                    writer_actor.clone(),
+                    backup_param.clone(),
+                ))
+                .await??;
         } else {
             return Err(anyhow::anyhow!("data_wrap is empty"));
         }
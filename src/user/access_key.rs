@@ -0,0 +1,543 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use actix::prelude::*;
+use bean_factory::{bean, Inject};
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha1::Sha1;
+use serde::{Deserialize, Serialize};
+
+use crate::common::constant::{
+    ACCESS_KEY_HEADER, ACCESS_KEY_TREE_NAME, DEFAULT_NAMESPACE_ARC_STRING, SIGNATURE_DATA_HEADER,
+    SIGNATURE_HEADER, SPAS_ACCESS_KEY_HEADER, SPAS_SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use crate::common::crypto_utils::encode_base64;
+use crate::common::model::privilege::{PrivilegeGroup, PrivilegeGroupFlags};
+use crate::common::model::TokenSession;
+use crate::namespace::is_default_namespace;
+use crate::now_millis_i64;
+use crate::raft::db::table_store::{RaftTableStore, TableValue, TABLE_RELOAD_INTERVAL};
+
+const ACCESS_KEY_ID_PREFIX: &str = "AK";
+/// 签名时间戳与服务端时间允许的偏差,单位毫秒
+const SIGNATURE_TIME_WINDOW: i64 = 15 * 60 * 1000;
+pub const ACCESS_KEY_NAME_INFO_KEY: &str = "accessKeyName";
+
+///
+/// 访问密钥
+/// namespaces为空时不限制命名空间;expire_time为0时不过期
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+pub struct AccessKeyDo {
+    #[prost(string, tag = "1")]
+    pub key_id: String,
+    #[prost(string, tag = "2")]
+    pub secret: String,
+    #[prost(string, tag = "3")]
+    pub name: String,
+    #[prost(string, tag = "4")]
+    pub description: String,
+    #[prost(string, repeated, tag = "5")]
+    pub namespaces: Vec<String>,
+    #[prost(bool, tag = "6")]
+    pub writable: bool,
+    #[prost(int64, tag = "7")]
+    pub expire_time: i64,
+    #[prost(bool, tag = "8")]
+    pub enable: bool,
+    #[prost(string, tag = "9")]
+    pub creator: String,
+    #[prost(int64, tag = "10")]
+    pub gmt_create: i64,
+    #[prost(int64, tag = "11")]
+    pub gmt_modified: i64,
+}
+
+impl AccessKeyDo {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::new();
+        prost::Message::encode(self, &mut v).unwrap_or_default();
+        v
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(prost::Message::decode(v)?)
+    }
+
+    pub fn is_valid(&self, now: i64) -> bool {
+        self.enable && (self.expire_time == 0 || self.expire_time > now)
+    }
+
+    ///
+    /// 访问密钥对应的openapi会话,用户名为key id
+    pub fn build_session(&self) -> TokenSession {
+        let namespace_privilege = if self.namespaces.is_empty() {
+            None
+        } else {
+            let whitelist: HashSet<Arc<String>> = self
+                .namespaces
+                .iter()
+                .map(|e| {
+                    if is_default_namespace(e) {
+                        DEFAULT_NAMESPACE_ARC_STRING.clone()
+                    } else {
+                        Arc::new(e.to_owned())
+                    }
+                })
+                .collect();
+            Some(PrivilegeGroup::new(
+                PrivilegeGroupFlags::ENABLE.bits(),
+                Some(Arc::new(whitelist)),
+                None,
+            ))
+        };
+        let mut extend_infos = HashMap::new();
+        extend_infos.insert(ACCESS_KEY_NAME_INFO_KEY.to_owned(), self.name.clone());
+        TokenSession {
+            username: Arc::new(self.key_id.clone()),
+            roles: vec![],
+            extend_infos,
+            namespace_privilege,
+            read_only: !self.writable,
+            access_key_id: Some(Arc::new(self.key_id.clone())),
+            ..Default::default()
+        }
+    }
+
+    fn check_secret(&self, secret: &str) -> bool {
+        crypto::util::fixed_time_eq(self.secret.as_bytes(), secret.as_bytes())
+    }
+
+    ///
+    /// 兼容nacos客户端ak/sk签名: signature = base64(hmac_sha1(secret, data)),
+    /// data以毫秒时间戳开头(如`timestamp`或`timestamp@@serviceName`)
+    fn check_signature(&self, data: &str, signature: &str, now: i64) -> bool {
+        let timestamp: i64 = match data.split("@@").next().unwrap_or_default().parse() {
+            Ok(v) => v,
+            Err(_) => return false,
+        };
+        if (now - timestamp).abs() > SIGNATURE_TIME_WINDOW {
+            return false;
+        }
+        let expected = Self::sign(&self.secret, data);
+        crypto::util::fixed_time_eq(expected.as_bytes(), signature.as_bytes())
+    }
+
+    pub fn sign(secret: &str, data: &str) -> String {
+        let mut hmac = Hmac::new(Sha1::new(), secret.as_bytes());
+        hmac.input(data.as_bytes());
+        encode_base64(hmac.result().code())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessKeyDto {
+    pub key_id: Option<Arc<String>>,
+    /// 只在创建时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub namespaces: Option<Vec<String>>,
+    pub writable: Option<bool>,
+    pub expire_time: Option<i64>,
+    pub enable: Option<bool>,
+    pub creator: Option<String>,
+    pub gmt_create: Option<i64>,
+    pub gmt_modified: Option<i64>,
+}
+
+impl From<AccessKeyDo> for AccessKeyDto {
+    fn from(v: AccessKeyDo) -> Self {
+        Self {
+            key_id: Some(Arc::new(v.key_id)),
+            secret: None,
+            name: Some(v.name),
+            description: Some(v.description),
+            namespaces: Some(v.namespaces),
+            writable: Some(v.writable),
+            expire_time: Some(v.expire_time),
+            enable: Some(v.enable),
+            creator: Some(v.creator),
+            gmt_create: Some(v.gmt_create),
+            gmt_modified: Some(v.gmt_modified),
+        }
+    }
+}
+
+///
+/// 请求头中的ak签名信息
+#[derive(Debug, Clone)]
+pub struct AccessKeySignature {
+    pub key_id: Arc<String>,
+    pub data: String,
+    pub signature: String,
+}
+
+impl AccessKeySignature {
+    ///
+    /// 支持`ak`/`data`/`signature`与`Spas-AccessKey`/`Timestamp`/`Spas-Signature`两组请求头
+    pub fn from_headers<F>(get_header: F) -> Option<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let key_id =
+            get_header(ACCESS_KEY_HEADER).or_else(|| get_header(SPAS_ACCESS_KEY_HEADER))?;
+        let signature =
+            get_header(SIGNATURE_HEADER).or_else(|| get_header(SPAS_SIGNATURE_HEADER))?;
+        let data = get_header(SIGNATURE_DATA_HEADER).or_else(|| get_header(TIMESTAMP_HEADER))?;
+        if key_id.is_empty() || signature.is_empty() || data.is_empty() {
+            return None;
+        }
+        Some(Self {
+            key_id: Arc::new(key_id),
+            data,
+            signature,
+        })
+    }
+}
+
+impl TableValue for AccessKeyDo {
+    fn table_key(&self) -> String {
+        self.key_id.clone()
+    }
+
+    fn encode_value(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.to_bytes())
+    }
+
+    fn decode_value(v: &[u8]) -> anyhow::Result<Self> {
+        Self::from_bytes(v)
+    }
+}
+
+///
+/// 访问密钥管理
+/// 密钥存储在raft表T_ACCESS_KEY中,鉴权使用内存中定时加载的数据
+#[bean(inject)]
+pub struct AccessKeyManager {
+    store: RaftTableStore<AccessKeyDo>,
+    key_map: HashMap<Arc<String>, Arc<AccessKeyDo>>,
+}
+
+impl Default for AccessKeyManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AccessKeyManager {
+    pub fn new() -> Self {
+        Self {
+            store: RaftTableStore::new(ACCESS_KEY_TREE_NAME.clone()),
+            key_map: HashMap::new(),
+        }
+    }
+
+    fn fill_value(value: &mut AccessKeyDo, dto: AccessKeyDto) {
+        if let Some(v) = dto.name {
+            value.name = v;
+        }
+        if let Some(v) = dto.description {
+            value.description = v;
+        }
+        if let Some(v) = dto.namespaces {
+            value.namespaces = v
+                .into_iter()
+                .map(|e| e.trim().to_owned())
+                .filter(|e| !e.is_empty())
+                .collect();
+        }
+        if let Some(v) = dto.writable {
+            value.writable = v;
+        }
+        if let Some(v) = dto.expire_time {
+            value.expire_time = v.max(0);
+        }
+        if let Some(v) = dto.enable {
+            value.enable = v;
+        }
+    }
+
+    async fn create(
+        store: RaftTableStore<AccessKeyDo>,
+        dto: AccessKeyDto,
+    ) -> anyhow::Result<AccessKeyResult> {
+        let now = now_millis_i64();
+        let key_id = format!(
+            "{}{}",
+            ACCESS_KEY_ID_PREFIX,
+            &uuid::Uuid::new_v4().simple().to_string()[..16].to_uppercase()
+        );
+        let secret =
+            uuid::Uuid::new_v4().simple().to_string() + &uuid::Uuid::new_v4().simple().to_string();
+        let mut value = AccessKeyDo {
+            key_id,
+            secret,
+            creator: dto.creator.clone().unwrap_or_default(),
+            enable: true,
+            gmt_create: now,
+            gmt_modified: now,
+            ..Default::default()
+        };
+        Self::fill_value(&mut value, dto);
+        if value.name.is_empty() {
+            value.name = value.key_id.clone();
+        }
+        store.save(&value).await?;
+        let secret = value.secret.clone();
+        let mut rdto: AccessKeyDto = value.into();
+        rdto.secret = Some(secret);
+        Ok(AccessKeyResult::AccessKey(Box::new(rdto)))
+    }
+
+    async fn update(
+        store: RaftTableStore<AccessKeyDo>,
+        dto: AccessKeyDto,
+    ) -> anyhow::Result<AccessKeyResult> {
+        let key_id = dto.key_id.clone().unwrap_or_default();
+        let mut value = match store.get(key_id.as_ref().to_owned()).await? {
+            Some(v) => v,
+            None => return Err(anyhow::anyhow!("access key {} is not exist", &key_id)),
+        };
+        Self::fill_value(&mut value, dto);
+        value.gmt_modified = now_millis_i64();
+        store.save(&value).await?;
+        Ok(AccessKeyResult::AccessKey(Box::new(value.into())))
+    }
+
+    async fn remove(
+        store: RaftTableStore<AccessKeyDo>,
+        key_id: Arc<String>,
+    ) -> anyhow::Result<AccessKeyResult> {
+        store.remove(key_id.as_ref().to_owned()).await?;
+        Ok(AccessKeyResult::None)
+    }
+
+    fn reload(&mut self, ctx: &mut Context<Self>) {
+        self.store
+            .clone()
+            .reload(self, ctx, |act, list| act.set_access_keys(list));
+    }
+
+    fn set_access_keys(&mut self, list: Vec<AccessKeyDo>) {
+        self.key_map = list
+            .into_iter()
+            .map(|e| (Arc::new(e.key_id.clone()), Arc::new(e)))
+            .collect();
+    }
+
+    fn get_valid_key(&self, key_id: &Arc<String>) -> Option<&Arc<AccessKeyDo>> {
+        self.key_map
+            .get(key_id)
+            .filter(|e| e.is_valid(now_millis_i64()))
+    }
+
+    pub fn check_secret(&self, key_id: &Arc<String>, secret: &str) -> Option<Arc<AccessKeyDo>> {
+        self.get_valid_key(key_id)
+            .filter(|e| e.check_secret(secret))
+            .cloned()
+    }
+
+    pub fn check_signature(&self, sign: &AccessKeySignature) -> Option<Arc<AccessKeyDo>> {
+        self.get_valid_key(&sign.key_id)
+            .filter(|e| e.check_signature(&sign.data, &sign.signature, now_millis_i64()))
+            .cloned()
+    }
+}
+
+impl Actor for AccessKeyManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("AccessKeyManager started")
+    }
+}
+
+impl Inject for AccessKeyManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        ctx: &mut Self::Context,
+    ) {
+        self.store.inject(&factory_data);
+        self.reload(ctx);
+        ctx.run_interval(TABLE_RELOAD_INTERVAL, |act, ctx| act.reload(ctx));
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<AccessKeyResult>")]
+pub enum AccessKeyReq {
+    Create(Box<AccessKeyDto>),
+    Update(Box<AccessKeyDto>),
+    Remove(Arc<String>),
+    QueryList,
+    /// 按key id与secret校验,用于openapi登录
+    CheckSecret {
+        key_id: Arc<String>,
+        secret: String,
+    },
+    /// 按请求头签名校验
+    CheckSignature(AccessKeySignature),
+    /// 校验已发放token对应的密钥是否仍然有效
+    CheckKey(Arc<String>),
+}
+
+pub enum AccessKeyResult {
+    None,
+    AccessKey(Box<AccessKeyDto>),
+    AccessKeyList(Vec<AccessKeyDto>),
+    /// 校验通过的密钥
+    Valid(Option<Arc<AccessKeyDo>>),
+}
+
+impl Handler<AccessKeyReq> for AccessKeyManager {
+    type Result = ResponseActFuture<Self, anyhow::Result<AccessKeyResult>>;
+
+    fn handle(&mut self, msg: AccessKeyReq, _ctx: &mut Self::Context) -> Self::Result {
+        let store = self.store.clone();
+        match msg {
+            AccessKeyReq::CheckSecret { key_id, secret } => {
+                let r = self.check_secret(&key_id, &secret);
+                Box::pin(actix::fut::ready(Ok(AccessKeyResult::Valid(r))))
+            }
+            AccessKeyReq::CheckSignature(sign) => {
+                let r = self.check_signature(&sign);
+                Box::pin(actix::fut::ready(Ok(AccessKeyResult::Valid(r))))
+            }
+            AccessKeyReq::CheckKey(key_id) => {
+                let r = self.get_valid_key(&key_id).cloned();
+                Box::pin(actix::fut::ready(Ok(AccessKeyResult::Valid(r))))
+            }
+            AccessKeyReq::Create(dto) => Box::pin(Self::create(store, *dto).into_actor(self).map(
+                |r, act, ctx| {
+                    act.reload(ctx);
+                    r
+                },
+            )),
+            AccessKeyReq::Update(dto) => Box::pin(Self::update(store, *dto).into_actor(self).map(
+                |r, act, ctx| {
+                    act.reload(ctx);
+                    r
+                },
+            )),
+            AccessKeyReq::Remove(key_id) => Box::pin(
+                Self::remove(store, key_id)
+                    .into_actor(self)
+                    .map(|r, act, ctx| {
+                        act.reload(ctx);
+                        r
+                    }),
+            ),
+            AccessKeyReq::QueryList => Box::pin(
+                async move {
+                    let list = store
+                        .query_list()
+                        .await?
+                        .into_iter()
+                        .map(AccessKeyDto::from)
+                        .collect();
+                    Ok(AccessKeyResult::AccessKeyList(list))
+                }
+                .into_actor(self),
+            ),
+        }
+    }
+}
+
+///
+/// 校验请求头中的ak签名,通过时返回对应的openapi会话
+pub async fn check_access_key_signature(
+    access_key_manager: &Addr<AccessKeyManager>,
+    sign: AccessKeySignature,
+) -> Option<Arc<TokenSession>> {
+    match access_key_manager
+        .send(AccessKeyReq::CheckSignature(sign))
+        .await
+    {
+        Ok(Ok(AccessKeyResult::Valid(Some(v)))) => Some(Arc::new(v.build_session())),
+        _ => None,
+    }
+}
+
+///
+/// 访问密钥登录发放的token在使用时重新校验密钥,密钥删除、禁用或过期后token随之失效
+pub async fn check_access_key_session(
+    access_key_manager: &Addr<AccessKeyManager>,
+    session: Option<Arc<TokenSession>>,
+) -> Option<Arc<TokenSession>> {
+    let key_id = match session.as_ref().and_then(|e| e.access_key_id.clone()) {
+        Some(v) => v,
+        None => return session,
+    };
+    match access_key_manager
+        .send(AccessKeyReq::CheckKey(key_id))
+        .await
+    {
+        Ok(Ok(AccessKeyResult::Valid(Some(_)))) => session,
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::model::privilege::NamespacePrivilegeGroup;
+
+    #[test]
+    fn access_key_check() {
+        let now = now_millis_i64();
+        let key = AccessKeyDo {
+            key_id: "AK01".to_owned(),
+            secret: "s3cret".to_owned(),
+            namespaces: vec!["public".to_owned(), "dev".to_owned()],
+            enable: true,
+            ..Default::default()
+        };
+        let mut manager = AccessKeyManager::new();
+        manager.set_access_keys(vec![AccessKeyDo::from_bytes(&key.to_bytes()).unwrap()]);
+        let key_id = Arc::new("AK01".to_owned());
+        assert!(manager.check_secret(&key_id, "s3cret").is_some());
+        assert!(manager.check_secret(&key_id, "other").is_none());
+
+        let data = format!("{}@@DEFAULT_GROUP@@demo", now);
+        let sign = AccessKeySignature::from_headers(|k| match k {
+            "Spas-AccessKey" => Some("AK01".to_owned()),
+            "Spas-Signature" => Some(AccessKeyDo::sign("s3cret", &data)),
+            "data" => Some(data.clone()),
+            _ => None,
+        })
+        .unwrap();
+        assert!(manager.check_signature(&sign).is_some());
+        let expired = AccessKeySignature {
+            data: (now - SIGNATURE_TIME_WINDOW - 1000).to_string(),
+            ..sign.clone()
+        };
+        assert!(manager.check_signature(&expired).is_none());
+
+        let session = key.build_session();
+        assert!(session.read_only);
+        let privilege = NamespacePrivilegeGroup::new(session.namespace_privilege.unwrap());
+        assert!(privilege.check_permission(&Arc::new("public".to_owned())));
+        assert!(!privilege.check_permission(&Arc::new("prod".to_owned())));
+        assert_eq!(session.access_key_id, Some(key_id.clone()));
+
+        let expired_key = AccessKeyDo {
+            expire_time: now - 1,
+            ..key.clone()
+        };
+        assert!(!expired_key.is_valid(now));
+
+        //密钥禁用后,已发放的token重新校验时失效
+        manager.set_access_keys(vec![AccessKeyDo {
+            enable: false,
+            ..key
+        }]);
+        assert!(manager.get_valid_key(&key_id).is_none());
+    }
+}
@@ -64,6 +64,7 @@ pub async fn add_user(
             ..Default::default()
         },
         namespace_privilege_param: None,
+        group_privilege_param: Default::default(),
     };
     app.user_manager.send(msg).await.ok();
     Ok("{\"ok\":1}")
@@ -81,6 +82,7 @@ pub async fn update_user(
             ..Default::default()
         },
         namespace_privilege_param: None,
+        group_privilege_param: Default::default(),
     };
     app.user_manager.send(msg).await.ok();
     Ok("{\"ok\":1}")
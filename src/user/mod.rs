@@ -2,11 +2,11 @@ use crate::common::AppSysConfig;
 use actix::prelude::*;
 use anyhow::Error;
 use bean_factory::{bean, Inject};
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 //use inner_mem_cache::MemCache;
 
 use self::{
-    model::{UserDo, UserDto},
+    model::{UserDo, UserDto, UserGroupPrivilegeParam},
     permission::USER_ROLE_MANAGER,
 };
 use crate::common::constant::USER_TREE_NAME;
@@ -14,6 +14,7 @@ use crate::common::model::privilege::{PrivilegeGroup, PrivilegeGroupOptionParam}
 use crate::common::string_utils::StringUtils;
 use crate::raft::cache::{CacheManager, CacheUserChangeReq};
 use crate::user::model::UserSourceType;
+use crate::user::password_policy::PasswordPolicy;
 use crate::user::permission::UserRole;
 use crate::{
     now_millis,
@@ -26,9 +27,12 @@ use crate::{
     },
 };
 
+pub mod access_key;
 pub mod api;
 pub mod model;
+pub mod password_policy;
 pub mod permission;
+pub mod role;
 
 pub(crate) fn build_password_hash(password: &str) -> anyhow::Result<String> {
     Ok(bcrypt::hash(password, 10u32)?)
@@ -56,16 +60,28 @@ pub struct UserManager {
     raft_table_route: Option<Arc<TableRoute>>,
     table_manager: Option<Addr<TableManager>>,
     cache_manager: Option<Addr<CacheManager>>,
+    password_policy: Arc<PasswordPolicy>,
+    ///
+    /// 登录失败次数只在处理登录的节点内存中计数,达到上限后才通过raft写入锁定时间
+    login_failed: HashMap<Arc<String>, LoginFailedInfo>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct LoginFailedInfo {
+    count: u32,
+    last_time: u32,
 }
 
 impl UserManager {
-    pub fn new() -> Self {
+    pub fn new(password_policy: Arc<PasswordPolicy>) -> Self {
         Self {
             //cache: MemCache::new(),
             //cache_sec: 1200,
             raft_table_route: Default::default(),
             table_manager: Default::default(),
             cache_manager: Default::default(),
+            password_policy,
+            login_failed: Default::default(),
         }
     }
 
@@ -93,11 +109,14 @@ impl UserManager {
                         nickname: Some(sys_config.init_admin_username.to_owned()),
                         password: Some(sys_config.init_admin_password.to_owned()),
                         roles: Some(vec![USER_ROLE_MANAGER.clone()]),
+                        need_change_password: Some(sys_config.password_init_admin_force_change),
                         ..Default::default()
                     };
-                    let user_manager_req = UserManagerReq::AddUser {
+                    //初始化账号不校验密码策略
+                    let user_manager_req = UserManagerReq::InitUser {
                         user,
                         namespace_privilege_param: None,
+                        group_privilege_param: Default::default(),
                     };
                     self_addr.do_send(user_manager_req);
                 }
@@ -111,6 +130,7 @@ impl UserManager {
         cache_manager: Option<Addr<CacheManager>>,
         user: UserDto,
         namespace_privilege_param: Option<PrivilegeGroupOptionParam<Arc<String>>>,
+        group_privilege_param: UserGroupPrivilegeParam,
     ) -> anyhow::Result<UserManagerInnerCtx> {
         let now = (now_millis() / 1000) as u32;
         let password_hash = if let Some(password) = &user.password {
@@ -118,18 +138,7 @@ impl UserManager {
         } else {
             None
         };
-        let mut namespace_privilege = PrivilegeGroup::all();
-        if let Some(namespace_privilege_param) = namespace_privilege_param {
-            namespace_privilege.blacklist = namespace_privilege_param.blacklist;
-            namespace_privilege.whitelist = namespace_privilege_param.whitelist;
-            if let Some(value) = namespace_privilege_param.whitelist_is_all {
-                namespace_privilege.whitelist_is_all = value;
-            }
-            if let Some(value) = namespace_privilege_param.blacklist_is_all {
-                namespace_privilege.blacklist_is_all = value;
-            }
-        }
-        let user_do = UserDo {
+        let mut user_do = UserDo {
             username: user.username.as_ref().to_owned(),
             // 新版本不存储原密码,启用后新版数据不支持降级回去使用
             password: String::new(),
@@ -145,21 +154,17 @@ impl UserManager {
                 .collect(),
             enable: true,
             extend_info: user.extend_info.unwrap_or_default(),
-            namespace_privilege_flags: Some(namespace_privilege.get_flags() as u32),
-            namespace_white_list: namespace_privilege
-                .whitelist
-                .unwrap_or_default()
-                .iter()
-                .map(|e| e.as_ref().to_owned())
-                .collect(),
-            namespace_black_list: namespace_privilege
-                .blacklist
-                .unwrap_or_default()
-                .iter()
-                .map(|e| e.as_ref().to_owned())
-                .collect(),
             source: user.source,
+            password_modified: Some(now),
+            need_change_password: user.need_change_password.unwrap_or_default(),
+            ..Default::default()
         };
+        if let Some(namespace_privilege_param) = namespace_privilege_param {
+            let mut namespace_privilege = PrivilegeGroup::all();
+            namespace_privilege_param.apply_to(&mut namespace_privilege);
+            user_do.set_namespace_privilege(namespace_privilege);
+        }
+        Self::apply_group_privilege_param(&mut user_do, group_privilege_param);
         let user_data = user_do.to_bytes();
         let req = TableManagerReq::Set {
             table_name: USER_TREE_NAME.clone(),
@@ -190,6 +195,7 @@ impl UserManager {
         cache_manager: Option<Addr<CacheManager>>,
         user: UserDto,
         namespace_privilege_param: Option<PrivilegeGroupOptionParam<Arc<String>>>,
+        group_privilege_param: UserGroupPrivilegeParam,
     ) -> anyhow::Result<UserManagerInnerCtx> {
         if let Some(raft_table_route) = &raft_table_route {
             let query_req = TableManagerQueryReq::GetByArcKey {
@@ -212,15 +218,64 @@ impl UserManager {
             cache_manager,
             user,
             namespace_privilege_param,
+            group_privilege_param,
         )
         .await
     }
 
+    fn apply_group_privilege_param(user_do: &mut UserDo, param: UserGroupPrivilegeParam) {
+        if let Some(config_group_param) = param.config_group {
+            let mut privilege = user_do.build_config_group_privilege();
+            config_group_param.apply_to(&mut privilege);
+            user_do.set_config_group_privilege(privilege);
+        }
+        if let Some(service_group_param) = param.service_group {
+            let mut privilege = user_do.build_service_group_privilege();
+            service_group_param.apply_to(&mut privilege);
+            user_do.set_service_group_privilege(privilege);
+        }
+    }
+
+    fn check_add_user_password(
+        password_policy: &PasswordPolicy,
+        user: &UserDto,
+    ) -> anyhow::Result<()> {
+        let source = user
+            .source
+            .as_ref()
+            .map(|s| UserSourceType::from_name(s).unwrap_or_default())
+            .unwrap_or_default();
+        if let Some(password) = &user.password {
+            if source.is_inner() {
+                password_policy.check_password(password)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn save_user(
+        raft_table_route: &Option<Arc<TableRoute>>,
+        user: &UserDo,
+    ) -> anyhow::Result<()> {
+        let req = TableManagerReq::Set {
+            table_name: USER_TREE_NAME.clone(),
+            key: user.username.as_bytes().to_owned(),
+            value: user.to_bytes(),
+            last_seq_id: None,
+        };
+        if let Some(raft_table_route) = raft_table_route {
+            raft_table_route.request(req).await?;
+        }
+        Ok(())
+    }
+
     async fn update_user(
         raft_table_route: &Option<Arc<TableRoute>>,
         cache_manager: &Option<Addr<CacheManager>>,
+        password_policy: &PasswordPolicy,
         user: UserDto,
         namespace_privilege_param: Option<PrivilegeGroupOptionParam<Arc<String>>>,
+        group_privilege_param: UserGroupPrivilegeParam,
     ) -> Result<UserManagerInnerCtx, Error> {
         let mut last_user = if let Some(raft_table_route) = &raft_table_route {
             let query_req = TableManagerQueryReq::GetByArcKey {
@@ -248,9 +303,30 @@ impl UserManager {
         }
         if let Some(password) = user.password {
             if source.is_inner() && !password.is_empty() {
+                password_policy.check_password(&password)?;
+                let mut used_hashes: Vec<&str> = vec![];
+                if let Some(password_hash) = &last_user.password_hash {
+                    used_hashes.push(password_hash);
+                }
+                used_hashes.extend(last_user.password_history.iter().map(|e| e.as_str()));
+                password_policy.check_history(&password, &used_hashes)?;
+                // 当前密码也计入历史数量,只需保留之前的 history_count-1 个
+                if let Some(old_hash) = last_user.password_hash.take() {
+                    last_user.password_history.insert(0, old_hash);
+                }
+                last_user
+                    .password_history
+                    .truncate(password_policy.history_count.saturating_sub(1));
                 last_user.password_hash = build_password_hash(&password).ok();
                 // 新版本不存储原密码
                 last_user.password = String::new();
+                last_user.password_modified = Some(now);
+                last_user.need_change_password = false;
+            }
+        }
+        if let Some(need_change_password) = user.need_change_password {
+            if source.is_inner() {
+                last_user.need_change_password = need_change_password;
             }
         }
         if let Some(enable) = user.enable {
@@ -268,44 +344,12 @@ impl UserManager {
         }
         if let Some(namespace_privilege_param) = namespace_privilege_param {
             let mut namespace_privilege = last_user.build_namespace_privilege();
-            namespace_privilege.enabled = true;
-            if let Some(value) = namespace_privilege_param.blacklist {
-                namespace_privilege.blacklist = Some(value);
-            }
-            if let Some(value) = namespace_privilege_param.whitelist {
-                namespace_privilege.whitelist = Some(value);
-            }
-            if let Some(value) = namespace_privilege_param.whitelist_is_all {
-                namespace_privilege.whitelist_is_all = value;
-            }
-            if let Some(value) = namespace_privilege_param.blacklist_is_all {
-                namespace_privilege.blacklist_is_all = value;
-            }
-            last_user.namespace_privilege_flags = Some(namespace_privilege.get_flags() as u32);
-            last_user.namespace_white_list = namespace_privilege
-                .whitelist
-                .unwrap_or_default()
-                .iter()
-                .map(|e| e.as_ref().to_owned())
-                .collect();
-            last_user.namespace_black_list = namespace_privilege
-                .blacklist
-                .unwrap_or_default()
-                .iter()
-                .map(|e| e.as_ref().to_owned())
-                .collect();
+            namespace_privilege_param.apply_to(&mut namespace_privilege);
+            last_user.set_namespace_privilege(namespace_privilege);
         }
+        Self::apply_group_privilege_param(&mut last_user, group_privilege_param);
         last_user.gmt_modified = now;
-        let user_data = last_user.to_bytes();
-        let req = TableManagerReq::Set {
-            table_name: USER_TREE_NAME.clone(),
-            key: user.username.as_bytes().to_owned(),
-            value: user_data,
-            last_seq_id: None,
-        };
-        if let Some(raft_table_route) = raft_table_route {
-            raft_table_route.request(req).await.ok();
-        }
+        Self::save_user(raft_table_route, &last_user).await.ok();
         if let Some(cache_manager) = &cache_manager {
             cache_manager
                 .send(CacheUserChangeReq::UserPrivilegeChange {
@@ -323,13 +367,14 @@ impl UserManager {
 
     async fn check_user(
         raft_table_route: &Option<Arc<TableRoute>>,
+        password_policy: &PasswordPolicy,
         name: Arc<String>,
         password: &String,
     ) -> Result<UserManagerInnerCtx, Error> {
         if name.is_empty() || password.is_empty() {
             return Err(anyhow::anyhow!("args is empty"));
         }
-        let last_user = if let Some(raft_table_route) = &raft_table_route {
+        let mut last_user = if let Some(raft_table_route) = &raft_table_route {
             let query_req = TableManagerQueryReq::GetByArcKey {
                 table_name: USER_TREE_NAME.clone(),
                 key: name.clone(),
@@ -347,6 +392,10 @@ impl UserManager {
             .as_ref()
             .map(|s| UserSourceType::from_name(s).unwrap_or_default())
             .unwrap_or_default();
+        let now = (now_millis() / 1000) as u32;
+        if password_policy.lock_enabled() && last_user.is_locked(now) {
+            return Ok(UserManagerInnerCtx::CheckUserResult(name, false, last_user));
+        }
         let mut check_success = last_user.enable && source.is_inner();
         if !StringUtils::is_option_empty(&last_user.password_hash) {
             check_success = check_success
@@ -363,6 +412,23 @@ impl UserManager {
             //兼容老版本数据比较,以支持平滑从老版本升级到新版本
             check_success = check_success && &last_user.password == password;
         }
+        //登录成功时只在状态变化时写入,失败次数由actor在内存中统计
+        if check_success {
+            let mut changed = false;
+            if last_user.lock_until > 0 {
+                last_user.lock_until = 0;
+                changed = true;
+            }
+            if !last_user.need_change_password
+                && password_policy.is_expired(last_user.get_password_modified(), now)
+            {
+                last_user.need_change_password = true;
+                changed = true;
+            }
+            if changed {
+                Self::save_user(raft_table_route, &last_user).await?;
+            }
+        }
         Ok(UserManagerInnerCtx::CheckUserResult(
             name,
             check_success,
@@ -370,6 +436,109 @@ impl UserManager {
         ))
     }
 
+    async fn unlock(
+        raft_table_route: &Option<Arc<TableRoute>>,
+        username: Arc<String>,
+    ) -> Result<UserManagerInnerCtx, Error> {
+        let mut last_user = if let Some(raft_table_route) = &raft_table_route {
+            let query_req = TableManagerQueryReq::GetByArcKey {
+                table_name: USER_TREE_NAME.clone(),
+                key: username.clone(),
+            };
+            match raft_table_route.get_leader_data(query_req).await? {
+                TableManagerResult::Value(old_value) => UserDo::from_bytes(&old_value)?,
+                _ => return Err(anyhow::anyhow!("not found user {}", &username)),
+            }
+        } else {
+            return Err(anyhow::anyhow!("raft_table_route is none "));
+        };
+        last_user.lock_until = 0;
+        Self::save_user(raft_table_route, &last_user).await?;
+        Ok(UserManagerInnerCtx::UpdateUser {
+            key: username,
+            value: last_user,
+        })
+    }
+
+    ///
+    /// 写入时重新读取最新用户信息,只修改锁定时间
+    async fn lock_user(
+        raft_table_route: Option<Arc<TableRoute>>,
+        username: Arc<String>,
+        lock_until: u32,
+    ) -> anyhow::Result<()> {
+        let raft_table_route = match raft_table_route {
+            Some(v) => v,
+            None => return Err(anyhow::anyhow!("raft_table_route is none ")),
+        };
+        let query_req = TableManagerQueryReq::GetByArcKey {
+            table_name: USER_TREE_NAME.clone(),
+            key: username.clone(),
+        };
+        if let TableManagerResult::Value(old_value) =
+            raft_table_route.get_leader_data(query_req).await?
+        {
+            let mut last_user = UserDo::from_bytes(&old_value)?;
+            last_user.lock_until = lock_until;
+            Self::save_user(&Some(raft_table_route), &last_user).await?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// 统计登录失败次数,超过锁定时长未再失败的计数重新开始;达到上限时返回锁定截止时间
+    fn record_login_failed(&mut self, username: &Arc<String>, now: u32) -> Option<u32> {
+        let policy = &self.password_policy;
+        let info = self.login_failed.entry(username.clone()).or_default();
+        if info.last_time + policy.lock_second <= now {
+            info.count = 0;
+        }
+        info.count += 1;
+        info.last_time = now;
+        if info.count >= policy.lock_failed_times {
+            self.login_failed.remove(username);
+            Some(now + policy.lock_second)
+        } else {
+            None
+        }
+    }
+
+    fn handle_check_result(
+        &mut self,
+        ctx: &mut Context<Self>,
+        username: Arc<String>,
+        check_success: bool,
+        mut user: UserDo,
+    ) -> UserManagerResult {
+        let now = (now_millis() / 1000) as u32;
+        let source = user
+            .source
+            .as_ref()
+            .map(|s| UserSourceType::from_name(s).unwrap_or_default())
+            .unwrap_or_default();
+        if check_success {
+            self.login_failed.remove(&username);
+        } else if self.password_policy.lock_enabled()
+            && user.enable
+            && source.is_inner()
+            && !user.is_locked(now)
+        {
+            if let Some(lock_until) = self.record_login_failed(&username, now) {
+                user.lock_until = lock_until;
+                let raft_table_route = self.raft_table_route.clone();
+                async move {
+                    if let Err(err) = Self::lock_user(raft_table_route, username, lock_until).await
+                    {
+                        log::warn!("lock user error,{}", err);
+                    }
+                }
+                .into_actor(self)
+                .spawn(ctx);
+            }
+        }
+        UserManagerResult::CheckUserResult(check_success, user.into())
+    }
+
     async fn remove(
         raft_table_route: Option<Arc<TableRoute>>,
         table_manager: &Option<Addr<TableManager>>,
@@ -494,12 +663,6 @@ impl UserManager {
     }
 }
 
-impl Default for UserManager {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl Inject for UserManager {
     type Context = Context<Self>;
 
@@ -558,19 +721,27 @@ pub enum UserManagerReq {
     AddUser {
         user: UserDto,
         namespace_privilege_param: Option<PrivilegeGroupOptionParam<Arc<String>>>,
+        group_privilege_param: UserGroupPrivilegeParam,
     },
     InitUser {
         user: UserDto,
         namespace_privilege_param: Option<PrivilegeGroupOptionParam<Arc<String>>>,
+        group_privilege_param: UserGroupPrivilegeParam,
     },
     UpdateUser {
         user: UserDto,
         namespace_privilege_param: Option<PrivilegeGroupOptionParam<Arc<String>>>,
+        group_privilege_param: UserGroupPrivilegeParam,
     },
     CheckUser {
         name: Arc<String>,
         password: String,
     },
+    ///
+    /// 解除登录失败导致的账号锁定
+    Unlock {
+        username: Arc<String>,
+    },
     Remove {
         username: Arc<String>,
     },
@@ -607,6 +778,10 @@ impl Handler<UserManagerReq> for UserManager {
         let raft_table_route = self.raft_table_route.clone();
         let table_manager = self.table_manager.clone();
         let cache_manager = self.cache_manager.clone();
+        let password_policy = self.password_policy.clone();
+        if let UserManagerReq::Unlock { username } = &msg {
+            self.login_failed.remove(username);
+        }
         //let query_info_at_cache = match &msg {
         //    UserManagerReq::Query { name } => self.cache.get(name).ok().is_some(),
         //    _ => false,
@@ -617,41 +792,52 @@ impl Handler<UserManagerReq> for UserManager {
                 UserManagerReq::AddUser {
                     user,
                     namespace_privilege_param,
+                    group_privilege_param,
                 } => {
+                    Self::check_add_user_password(&password_policy, &user)?;
                     Self::add_user(
                         raft_table_route,
                         cache_manager,
                         user,
                         namespace_privilege_param,
+                        group_privilege_param,
                     )
                     .await
                 }
                 UserManagerReq::InitUser {
                     user,
                     namespace_privilege_param,
+                    group_privilege_param,
                 } => {
                     Self::init_user(
                         raft_table_route,
                         cache_manager,
                         user,
                         namespace_privilege_param,
+                        group_privilege_param,
                     )
                     .await
                 }
                 UserManagerReq::UpdateUser {
                     user,
                     namespace_privilege_param,
+                    group_privilege_param,
                 } => {
                     Self::update_user(
                         &raft_table_route,
                         &cache_manager,
+                        &password_policy,
                         user,
                         namespace_privilege_param,
+                        group_privilege_param,
                     )
                     .await
                 }
                 UserManagerReq::CheckUser { name, password } => {
-                    Self::check_user(&raft_table_route, name, &password).await
+                    Self::check_user(&raft_table_route, &password_policy, name, &password).await
+                }
+                UserManagerReq::Unlock { username } => {
+                    Self::unlock(&raft_table_route, username).await
                 }
                 UserManagerReq::Remove { username } => {
                     Self::remove(raft_table_route, &table_manager, &cache_manager, username).await
@@ -672,17 +858,14 @@ impl Handler<UserManagerReq> for UserManager {
         }
         .into_actor(self)
         .map(
-            |res: anyhow::Result<UserManagerInnerCtx>, _act, _ctx| match res? {
+            |res: anyhow::Result<UserManagerInnerCtx>, act, ctx| match res? {
                 UserManagerInnerCtx::None => Ok(UserManagerResult::None),
                 UserManagerInnerCtx::UpdateUser { key: _, value: _ } => {
                     //act.cache.set(key, Arc::new(value), act.cache_sec);
                     Ok(UserManagerResult::None)
                 }
-                UserManagerInnerCtx::CheckUserResult(_key, v, user) => {
-                    //if v {
-                    //    act.update_timeout(&key);
-                    //}
-                    Ok(UserManagerResult::CheckUserResult(v, user.into()))
+                UserManagerInnerCtx::CheckUserResult(key, v, user) => {
+                    Ok(act.handle_check_result(ctx, key, v, user))
                 }
                 UserManagerInnerCtx::QueryUser(_key, user) => match user {
                     Some(user) => Ok(UserManagerResult::QueryUser(Some(user.into()))),
@@ -696,3 +879,26 @@ impl Handler<UserManagerReq> for UserManager {
         Box::pin(fut)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_login_failed() {
+        let mut manager = UserManager::new(Arc::new(PasswordPolicy {
+            lock_failed_times: 3,
+            lock_second: 60,
+            ..Default::default()
+        }));
+        let username = Arc::new("u1".to_owned());
+        assert_eq!(manager.record_login_failed(&username, 100), None);
+        assert_eq!(manager.record_login_failed(&username, 110), None);
+        assert_eq!(manager.record_login_failed(&username, 120), Some(180));
+        assert!(manager.login_failed.is_empty());
+        //超过锁定时长未再失败,重新计数
+        assert_eq!(manager.record_login_failed(&username, 200), None);
+        assert_eq!(manager.record_login_failed(&username, 300), None);
+        assert_eq!(manager.login_failed.get(&username).unwrap().count, 1);
+    }
+}
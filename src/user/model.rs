@@ -1,4 +1,7 @@
-use crate::common::model::privilege::{PrivilegeGroup, PrivilegeGroupFlags};
+use crate::common::model::privilege::{
+    PrivilegeGroup, PrivilegeGroupFlags, PrivilegeGroupOptionParam,
+};
+use crate::now_millis;
 use crate::user::permission::UserRoleHelper;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -9,6 +12,7 @@ use std::{collections::HashMap, sync::Arc};
 pub enum UserSourceType {
     Inner,
     Ldap,
+    Oidc,
 }
 
 impl Default for UserSourceType {
@@ -27,6 +31,7 @@ impl UserSourceType {
             "" => Some(UserSourceType::Inner),
             "INNER" => Some(UserSourceType::Inner),
             "LDAP" => Some(UserSourceType::Ldap),
+            "OIDC" => Some(UserSourceType::Oidc),
             _ => None,
         }
     }
@@ -35,6 +40,7 @@ impl UserSourceType {
         match self {
             UserSourceType::Inner => "INNER",
             UserSourceType::Ldap => "LDAP",
+            UserSourceType::Oidc => "OIDC",
         }
     }
 }
@@ -68,6 +74,29 @@ pub struct UserDo {
     pub namespace_black_list: ::prost::alloc::vec::Vec<String>,
     #[prost(string, optional, tag = "13")]
     pub source: Option<String>,
+    #[prost(uint32, optional, tag = "14")]
+    pub config_group_privilege_flags: Option<u32>,
+    #[prost(string, repeated, tag = "15")]
+    pub config_group_white_list: ::prost::alloc::vec::Vec<String>,
+    #[prost(string, repeated, tag = "16")]
+    pub config_group_black_list: ::prost::alloc::vec::Vec<String>,
+    #[prost(uint32, optional, tag = "17")]
+    pub service_group_privilege_flags: Option<u32>,
+    #[prost(string, repeated, tag = "18")]
+    pub service_group_white_list: ::prost::alloc::vec::Vec<String>,
+    #[prost(string, repeated, tag = "19")]
+    pub service_group_black_list: ::prost::alloc::vec::Vec<String>,
+    /// 密码修改时间(秒),为空时按创建时间计算
+    #[prost(uint32, optional, tag = "20")]
+    pub password_modified: Option<u32>,
+    /// 历史密码hash,最近的在前
+    #[prost(string, repeated, tag = "21")]
+    pub password_history: ::prost::alloc::vec::Vec<String>,
+    #[prost(bool, tag = "22")]
+    pub need_change_password: bool,
+    /// 账号锁定截止时间(秒)
+    #[prost(uint32, tag = "24")]
+    pub lock_until: u32,
 }
 
 impl UserDo {
@@ -81,26 +110,98 @@ impl UserDo {
         Ok(prost::Message::decode(v)?)
     }
 
+    pub fn get_password_modified(&self) -> u32 {
+        self.password_modified.unwrap_or(self.gmt_create)
+    }
+
+    pub fn is_locked(&self, now: u32) -> bool {
+        self.lock_until > now
+    }
+
     pub fn build_namespace_privilege(&self) -> PrivilegeGroup<Arc<String>> {
-        let namespace_privilege_flags = self.namespace_privilege_flags.unwrap_or_default() as u8;
-        if namespace_privilege_flags & PrivilegeGroupFlags::ENABLE.bits() > 0 {
-            let mut namespace_whitelist = HashSet::new();
-            for item in &self.namespace_white_list {
-                namespace_whitelist.insert(Arc::new(item.clone()));
-            }
-            let mut namespace_black_list = HashSet::new();
-            for item in &self.namespace_black_list {
-                namespace_black_list.insert(Arc::new(item.clone()));
-            }
-            PrivilegeGroup::new(
-                namespace_privilege_flags,
-                Some(Arc::new(namespace_whitelist)),
-                Some(Arc::new(namespace_black_list)),
-            )
+        Self::build_privilege(
+            self.namespace_privilege_flags,
+            &self.namespace_white_list,
+            &self.namespace_black_list,
+        )
+    }
+
+    ///
+    /// 配置分组数据权限,名单值支持`*`通配符
+    pub fn build_config_group_privilege(&self) -> PrivilegeGroup<Arc<String>> {
+        Self::build_privilege(
+            self.config_group_privilege_flags,
+            &self.config_group_white_list,
+            &self.config_group_black_list,
+        )
+    }
+
+    ///
+    /// 服务分组数据权限,名单值支持`*`通配符
+    pub fn build_service_group_privilege(&self) -> PrivilegeGroup<Arc<String>> {
+        Self::build_privilege(
+            self.service_group_privilege_flags,
+            &self.service_group_white_list,
+            &self.service_group_black_list,
+        )
+    }
+
+    pub fn set_namespace_privilege(&mut self, privilege: PrivilegeGroup<Arc<String>>) {
+        let (flags, white_list, black_list) = Self::split_privilege(privilege);
+        self.namespace_privilege_flags = flags;
+        self.namespace_white_list = white_list;
+        self.namespace_black_list = black_list;
+    }
+
+    pub fn set_config_group_privilege(&mut self, privilege: PrivilegeGroup<Arc<String>>) {
+        let (flags, white_list, black_list) = Self::split_privilege(privilege);
+        self.config_group_privilege_flags = flags;
+        self.config_group_white_list = white_list;
+        self.config_group_black_list = black_list;
+    }
+
+    pub fn set_service_group_privilege(&mut self, privilege: PrivilegeGroup<Arc<String>>) {
+        let (flags, white_list, black_list) = Self::split_privilege(privilege);
+        self.service_group_privilege_flags = flags;
+        self.service_group_white_list = white_list;
+        self.service_group_black_list = black_list;
+    }
+
+    fn build_privilege(
+        flags: Option<u32>,
+        white_list: &[String],
+        black_list: &[String],
+    ) -> PrivilegeGroup<Arc<String>> {
+        let flags = flags.unwrap_or_default() as u8;
+        if flags & PrivilegeGroupFlags::ENABLE.bits() > 0 {
+            let whitelist: HashSet<Arc<String>> =
+                white_list.iter().map(|e| Arc::new(e.clone())).collect();
+            let blacklist: HashSet<Arc<String>> =
+                black_list.iter().map(|e| Arc::new(e.clone())).collect();
+            PrivilegeGroup::new(flags, Some(Arc::new(whitelist)), Some(Arc::new(blacklist)))
         } else {
             PrivilegeGroup::all()
         }
     }
+
+    fn split_privilege(
+        privilege: PrivilegeGroup<Arc<String>>,
+    ) -> (Option<u32>, Vec<String>, Vec<String>) {
+        let flags = Some(privilege.get_flags() as u32);
+        let white_list = privilege
+            .whitelist
+            .unwrap_or_default()
+            .iter()
+            .map(|e| e.as_ref().to_owned())
+            .collect();
+        let black_list = privilege
+            .blacklist
+            .unwrap_or_default()
+            .iter()
+            .map(|e| e.as_ref().to_owned())
+            .collect();
+        (flags, white_list, black_list)
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Default)]
@@ -116,7 +217,12 @@ pub struct UserDto {
     pub roles: Option<Vec<Arc<String>>>,
     pub extend_info: Option<HashMap<String, String>>,
     pub namespace_privilege: Option<PrivilegeGroup<Arc<String>>>,
+    pub config_group_privilege: Option<PrivilegeGroup<Arc<String>>>,
+    pub service_group_privilege: Option<PrivilegeGroup<Arc<String>>>,
     pub source: Option<String>,
+    pub need_change_password: Option<bool>,
+    pub locked: Option<bool>,
+    pub lock_until: Option<i64>,
 }
 
 impl From<UserDo> for UserDto {
@@ -126,6 +232,9 @@ impl From<UserDo> for UserDto {
             roles.push(UserRoleHelper::get_role(role));
         }
         let namespace_privilege = Some(value.build_namespace_privilege());
+        let config_group_privilege = Some(value.build_config_group_privilege());
+        let service_group_privilege = Some(value.build_service_group_privilege());
+        let locked = value.is_locked((now_millis() / 1000) as u32);
         Self {
             username: Arc::new(value.username),
             nickname: Some(value.nickname),
@@ -139,7 +248,24 @@ impl From<UserDo> for UserDto {
             roles: Some(roles),
             extend_info: Some(value.extend_info),
             namespace_privilege,
+            config_group_privilege,
+            service_group_privilege,
             source: value.source,
+            need_change_password: Some(value.need_change_password),
+            locked: Some(locked),
+            lock_until: if locked {
+                Some(value.lock_until as i64 * 1000)
+            } else {
+                None
+            },
         }
     }
 }
+
+///
+/// 用户分组数据权限更新参数
+#[derive(Clone, Debug, Default)]
+pub struct UserGroupPrivilegeParam {
+    pub config_group: Option<PrivilegeGroupOptionParam<Arc<String>>>,
+    pub service_group: Option<PrivilegeGroupOptionParam<Arc<String>>>,
+}
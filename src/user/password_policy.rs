@@ -0,0 +1,147 @@
+use crate::user::verify_password_hash;
+use std::fmt::{Display, Formatter};
+
+///
+/// 密码策略校验不通过的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordPolicyError {
+    TooShort(usize),
+    CharTypesNotEnough(usize),
+    RecentlyUsed(usize),
+}
+
+impl Display for PasswordPolicyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PasswordPolicyError::TooShort(v) => {
+                write!(f, "password length must be at least {}", v)
+            }
+            PasswordPolicyError::CharTypesNotEnough(v) => write!(
+                f,
+                "password must contain at least {} of lowercase letters, uppercase letters, digits and symbols",
+                v
+            ),
+            PasswordPolicyError::RecentlyUsed(v) => {
+                write!(f, "password can't be the same as the last {} passwords", v)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PasswordPolicyError {}
+
+///
+/// 内部用户密码策略与登录锁定策略,各项值为0时表示不启用
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub min_char_types: usize,
+    pub expire_days: u32,
+    pub history_count: usize,
+    pub init_admin_force_change: bool,
+    pub lock_failed_times: u32,
+    pub lock_second: u32,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 0,
+            min_char_types: 0,
+            expire_days: 0,
+            history_count: 0,
+            init_admin_force_change: false,
+            lock_failed_times: 0,
+            lock_second: 1800,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn check_password(&self, password: &str) -> anyhow::Result<()> {
+        if password.chars().count() < self.min_length {
+            return Err(PasswordPolicyError::TooShort(self.min_length).into());
+        }
+        if self.min_char_types > 0 && Self::char_types(password) < self.min_char_types {
+            return Err(PasswordPolicyError::CharTypesNotEnough(self.min_char_types).into());
+        }
+        Ok(())
+    }
+
+    ///
+    /// 新密码不能与当前密码及最近使用过的密码相同
+    pub fn check_history(&self, password: &str, history_hashes: &[&str]) -> anyhow::Result<()> {
+        if self.history_count == 0 {
+            return Ok(());
+        }
+        for hash in history_hashes.iter().take(self.history_count) {
+            if verify_password_hash(password, hash).unwrap_or(false) {
+                return Err(PasswordPolicyError::RecentlyUsed(self.history_count).into());
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// 密码修改时间(秒)超过有效期后视为过期
+    pub fn is_expired(&self, password_modified: u32, now: u32) -> bool {
+        self.expire_days > 0 && password_modified + self.expire_days * 86400 <= now
+    }
+
+    pub fn lock_enabled(&self) -> bool {
+        self.lock_failed_times > 0
+    }
+
+    fn char_types(password: &str) -> usize {
+        let (mut lower, mut upper, mut digit, mut other) = (false, false, false, false);
+        for c in password.chars() {
+            if c.is_ascii_lowercase() {
+                lower = true;
+            } else if c.is_ascii_uppercase() {
+                upper = true;
+            } else if c.is_ascii_digit() {
+                digit = true;
+            } else {
+                other = true;
+            }
+        }
+        [lower, upper, digit, other].iter().filter(|v| **v).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::build_password_hash;
+
+    #[test]
+    fn check_password_policy() {
+        let policy = PasswordPolicy {
+            min_length: 8,
+            min_char_types: 3,
+            history_count: 2,
+            expire_days: 1,
+            ..Default::default()
+        };
+        let err = policy.check_password("Ab1").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PasswordPolicyError>(),
+            Some(&PasswordPolicyError::TooShort(8))
+        );
+        assert!(policy.check_password("abcdefgh1").is_err());
+        assert!(policy.check_password("abcdefG1").is_ok());
+        assert!(policy.check_password("abcdefg#1").is_ok());
+
+        let h1 = build_password_hash("Passw0rd1").unwrap();
+        let h2 = build_password_hash("Passw0rd2").unwrap();
+        let h3 = build_password_hash("Passw0rd3").unwrap();
+        let history = vec![h1.as_str(), h2.as_str(), h3.as_str()];
+        assert!(policy.check_history("Passw0rd2", &history).is_err());
+        // 超出历史数量的密码可以复用
+        assert!(policy.check_history("Passw0rd3", &history).is_ok());
+
+        assert!(!policy.is_expired(1000, 1000 + 86399));
+        assert!(policy.is_expired(1000, 1000 + 86400));
+        assert!(!PasswordPolicy::default().is_expired(0, u32::MAX));
+    }
+}
@@ -10,18 +10,23 @@ use crate::common::constant::{EMPTY_STR, HTTP_METHOD_ALL, HTTP_METHOD_GET, HTTP_
 pub enum Resource {
     WebResource(&'static str),
     Path(&'static str, &'static str),
+    /// 按路径前缀匹配
+    PathPrefix(&'static str, &'static str),
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct PathResource {
     pub path: &'static str,
     pub method: &'static str,
+    pub prefix: bool,
 }
 
 impl PathResource {
     pub fn match_url(&self, path: &str, method: &str) -> bool {
         let match_method = self.is_match_all_method() || self.method == method;
-        if path.is_empty() {
+        if self.prefix {
+            match_method && path.starts_with(self.path)
+        } else if path.is_empty() {
             match_method && (self.is_match_all_path() || self.path == "/")
         } else {
             match_method && (self.is_match_all_path() || self.path == path)
@@ -50,7 +55,18 @@ impl ModuleResource {
                     web_resources.insert(r);
                 }
                 Resource::Path(path, method) => {
-                    path_resources.insert(PathResource { path, method });
+                    path_resources.insert(PathResource {
+                        path,
+                        method,
+                        prefix: false,
+                    });
+                }
+                Resource::PathPrefix(path, method) => {
+                    path_resources.insert(PathResource {
+                        path,
+                        method,
+                        prefix: true,
+                    });
                 }
             }
         }
@@ -171,6 +187,11 @@ lazy_static::lazy_static! {
         //path
         R::Path("/rnacos/manage/namespace",HTTP_METHOD_GET),
         //R::Path("/rnacos/api/console/namespaces",HTTP_METHOD_GET),
+
+        //openapi
+        R::Path("/nacos/v1/console/namespaces",HTTP_METHOD_GET),
+        R::Path("/nacos/v2/console/namespace/list",HTTP_METHOD_GET),
+        R::Path("/nacos/v2/console/namespace",HTTP_METHOD_GET),
     ]);
 
     static ref M_NAMESPACE_MANAGE: ModuleResource = ModuleResource::new(vec![
@@ -185,6 +206,11 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/namespaces/add",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/namespaces/update",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/namespaces/remove",HTTP_METHOD_ALL),
+
+        //openapi
+        R::Path("/nacos/v1/console/namespaces",HTTP_METHOD_ALL),
+        R::Path("/nacos/v2/console/namespace/list",HTTP_METHOD_GET),
+        R::Path("/nacos/v2/console/namespace",HTTP_METHOD_ALL),
     ]);
 
     static ref M_USER_MANAGE: ModuleResource = ModuleResource::new(vec![
@@ -205,6 +231,22 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/user/add",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/user/update",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/user/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/user/unlock",HTTP_METHOD_ALL),
+
+        R::Path("/rnacos/api/console/v2/role/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/role/modules",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/role/add",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/role/update",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/role/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/access_key/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/access_key/add",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/access_key/update",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/access_key/remove",HTTP_METHOD_ALL),
+    ]);
+
+    static ref M_AUDIT_MANAGE: ModuleResource = ModuleResource::new(vec![
+        //path
+        R::Path("/rnacos/api/console/v2/audit/list",HTTP_METHOD_GET),
     ]);
 
     static ref M_CONFIG_VISITOR: ModuleResource = ModuleResource::new(vec![
@@ -226,6 +268,11 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/config/download",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/config/info",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/config/history",HTTP_METHOD_GET),
+
+        //openapi
+        R::PathPrefix("/nacos/v1/cs/",HTTP_METHOD_GET),
+        R::Path("/nacos/v1/cs/configs/listener",HTTP_METHOD_POST),
+        R::Path("/nacos/v1/cs/configs/fuzzy-listener",HTTP_METHOD_POST),
     ]);
 
     static ref M_CONFIG_MANAGE: ModuleResource = ModuleResource::new(vec![
@@ -254,6 +301,14 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/config/add",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/config/update",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/config/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/webhook/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/webhook/add",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/webhook/update",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/webhook/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/webhook/delivery/list",HTTP_METHOD_GET),
+
+        //openapi
+        R::PathPrefix("/nacos/v1/cs/",HTTP_METHOD_ALL),
     ]);
 
     static ref M_NAMING_VISITOR: ModuleResource = ModuleResource::new(vec![
@@ -276,10 +331,18 @@ lazy_static::lazy_static! {
 
         R::Path("/rnacos/api/console/v2/service/list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/service/subscriber/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/service/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/service/cluster/list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/instance/list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/instance/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/service/event/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/service/subscriber/push/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/instance/drain/list",HTTP_METHOD_GET),
         R::Path("/rnacos/manage/subscriber", HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/cluster/cluster_node_list",HTTP_METHOD_GET),
+
+        //openapi
+        R::PathPrefix("/nacos/v1/ns/",HTTP_METHOD_GET),
     ]);
 
     static ref M_NAMING_MANAGE: ModuleResource = ModuleResource::new(vec![
@@ -304,14 +367,29 @@ lazy_static::lazy_static! {
 
         R::Path("/rnacos/api/console/v2/service/list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/service/subscriber/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/service/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/service/cluster/list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/service/add",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/service/update",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/service/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/service/healthcheck/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/service/healthcheck/update",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/service/healthcheck/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/service/cluster/update",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/service/cluster/remove",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/instance/list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/instance/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/service/event/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/service/subscriber/push/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/instance/drain/list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/instance/add",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/instance/update",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/instance/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/instance/drain/add",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/instance/drain/remove",HTTP_METHOD_ALL),
+
+        //openapi
+        R::PathPrefix("/nacos/v1/ns/",HTTP_METHOD_ALL),
     ]);
 
     static ref M_METRICS_VISITOR: ModuleResource = ModuleResource::new(vec![
@@ -361,10 +439,26 @@ lazy_static::lazy_static! {
         &M_CONFIG_MANAGE,
         &M_NAMING_MANAGE,
         &M_USER_MANAGE,
+        &M_AUDIT_MANAGE,
         &M_METRICS_VISITOR,
         &M_TRASFER_DATE_MANAGE,
     ]));
 
+    /// 自定义角色可授权的模块
+    static ref MODULE_RESOURCES: Vec<(&'static str, &'static ModuleResource)> = vec![
+        ("CLUSTER_VISITOR", &*M_CLUSTER_VISITOR),
+        ("NAMESPACE_VISITOR", &*M_NAMESPACE_VISITOR),
+        ("NAMESPACE_MANAGE", &*M_NAMESPACE_MANAGE),
+        ("CONFIG_VISITOR", &*M_CONFIG_VISITOR),
+        ("CONFIG_MANAGE", &*M_CONFIG_MANAGE),
+        ("NAMING_VISITOR", &*M_NAMING_VISITOR),
+        ("NAMING_MANAGE", &*M_NAMING_MANAGE),
+        ("METRICS_VISITOR", &*M_METRICS_VISITOR),
+        ("USER_MANAGE", &*M_USER_MANAGE),
+        ("AUDIT_MANAGE", &*M_AUDIT_MANAGE),
+        ("TRANSFER_DATA_MANAGE", &*M_TRASFER_DATE_MANAGE),
+    ];
+
 }
 
 #[derive(Debug)]
@@ -483,6 +577,27 @@ impl UserRoleHelper {
         Arc::new(role_value.to_owned())
     }
 
+    pub fn is_builtin_role(role_value: &str) -> bool {
+        ALL_ROLES.iter().any(|e| e.as_str() == role_value)
+    }
+
+    pub fn get_module_names() -> Vec<&'static str> {
+        MODULE_RESOURCES.iter().map(|(name, _)| *name).collect()
+    }
+
+    pub fn get_module_resource(name: &str) -> Option<&'static ModuleResource> {
+        MODULE_RESOURCES
+            .iter()
+            .find(|(module_name, _)| *module_name == name)
+            .map(|(_, module)| *module)
+    }
+
+    ///
+    /// 所有登录用户都有的基础资源
+    pub fn get_base_module_resource() -> &'static ModuleResource {
+        &M_BASE
+    }
+
     pub fn get_role_by_name(role_name: &str, default: Arc<String>) -> Arc<String> {
         match role_name {
             "VISITOR" => USER_ROLE_VISITOR.clone(),
@@ -0,0 +1,539 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use actix::prelude::*;
+use bean_factory::{bean, Inject};
+use serde::{Deserialize, Serialize};
+
+use super::model::UserDo;
+use super::permission::{ModuleResource, UserRoleHelper};
+use crate::common::constant::{HTTP_METHOD_GET, ROLE_TREE_NAME, USER_TREE_NAME};
+use crate::now_millis_i64;
+use crate::raft::db::table::{TableManagerQueryReq, TableManagerResult};
+use crate::raft::db::table_store::{RaftTableStore, TableValue, TABLE_RELOAD_INTERVAL};
+
+const HTTP_METHODS: [&str; 6] = ["GET", "HEAD", "POST", "PUT", "DELETE", "PATCH"];
+const ROLE_CODE_MAX_LEN: usize = 64;
+
+///
+/// 角色对单个功能模块的授权,methods为空时允许模块内全部请求方法
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+pub struct RoleGrantDo {
+    #[prost(string, tag = "1")]
+    pub module: String,
+    #[prost(string, repeated, tag = "2")]
+    #[serde(default)]
+    pub methods: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+pub struct RoleDo {
+    #[prost(string, tag = "1")]
+    pub code: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub description: String,
+    #[prost(message, repeated, tag = "4")]
+    pub grants: Vec<RoleGrantDo>,
+    #[prost(int64, tag = "5")]
+    pub gmt_create: i64,
+    #[prost(int64, tag = "6")]
+    pub gmt_modified: i64,
+}
+
+impl RoleDo {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::new();
+        prost::Message::encode(self, &mut v).unwrap_or_default();
+        v
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(prost::Message::decode(v)?)
+    }
+}
+
+///
+/// 自定义角色
+/// code即用户roles中保存的角色值,不能与内置角色(0,1,2)重复
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleDto {
+    pub code: Arc<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub grants: Option<Vec<RoleGrantDo>>,
+    pub builtin: Option<bool>,
+    pub gmt_create: Option<i64>,
+    pub gmt_modified: Option<i64>,
+}
+
+impl RoleDto {
+    fn new_builtin(code: Arc<String>, name: &str) -> Self {
+        Self {
+            code,
+            name: Some(name.to_owned()),
+            builtin: Some(true),
+            ..Default::default()
+        }
+    }
+
+    pub fn check_code(code: &str) -> anyhow::Result<()> {
+        if code.is_empty() || code.len() > ROLE_CODE_MAX_LEN {
+            return Err(anyhow::anyhow!("role code is invalid"));
+        }
+        if UserRoleHelper::is_builtin_role(code) {
+            return Err(anyhow::anyhow!("role code {} is builtin", code));
+        }
+        if !code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(anyhow::anyhow!("role code {} is invalid", code));
+        }
+        Ok(())
+    }
+
+    ///
+    /// 校验并规整授权,方法统一大写,包含`*`时表示全部方法
+    pub fn check_grants(grants: Vec<RoleGrantDo>) -> anyhow::Result<Vec<RoleGrantDo>> {
+        let mut rlist: Vec<RoleGrantDo> = Vec::with_capacity(grants.len());
+        for grant in grants {
+            if UserRoleHelper::get_module_resource(&grant.module).is_none() {
+                return Err(anyhow::anyhow!("module {} is not exist", &grant.module));
+            }
+            if rlist.iter().any(|e| e.module == grant.module) {
+                return Err(anyhow::anyhow!("module {} is repeated", &grant.module));
+            }
+            let mut methods = vec![];
+            for method in grant.methods {
+                let method = method.trim().to_uppercase();
+                if method == "*" {
+                    methods.clear();
+                    break;
+                }
+                if !HTTP_METHODS.contains(&method.as_str()) {
+                    return Err(anyhow::anyhow!("http method {} is invalid", &method));
+                }
+                if !methods.contains(&method) {
+                    methods.push(method);
+                }
+            }
+            rlist.push(RoleGrantDo {
+                module: grant.module,
+                methods,
+            });
+        }
+        Ok(rlist)
+    }
+}
+
+impl From<RoleDo> for RoleDto {
+    fn from(v: RoleDo) -> Self {
+        Self {
+            code: Arc::new(v.code),
+            name: Some(v.name),
+            description: Some(v.description),
+            grants: Some(v.grants),
+            builtin: Some(false),
+            gmt_create: Some(v.gmt_create),
+            gmt_modified: Some(v.gmt_modified),
+        }
+    }
+}
+
+///
+/// 自定义角色编译后的权限资源
+pub struct CustomRoleResource {
+    grants: Vec<(&'static ModuleResource, HashSet<String>)>,
+}
+
+impl CustomRoleResource {
+    pub fn new(role: &RoleDo) -> Self {
+        let mut grants = Vec::with_capacity(role.grants.len());
+        for grant in &role.grants {
+            if let Some(module) = UserRoleHelper::get_module_resource(&grant.module) {
+                grants.push((module, grant.methods.iter().cloned().collect()));
+            }
+        }
+        Self { grants }
+    }
+
+    pub fn match_url(&self, path: &str, method: &str) -> bool {
+        self.grants.iter().any(|(module, methods)| {
+            (methods.is_empty() || methods.contains(method)) && module.match_url(path, method)
+        })
+    }
+
+    ///
+    /// 只读授权(仅GET/HEAD)不开放页面上的更新功能
+    pub fn fill_web_resources(&self, set: &mut HashSet<&'static str>) {
+        for (module, methods) in &self.grants {
+            let read_only = !methods.is_empty()
+                && methods
+                    .iter()
+                    .all(|e| e.as_str() == HTTP_METHOD_GET || e.as_str() == "HEAD");
+            for item in &module.web_resources {
+                if read_only && item.ends_with("_UPDATE") {
+                    continue;
+                }
+                set.insert(*item);
+            }
+        }
+    }
+}
+
+impl TableValue for RoleDo {
+    fn table_key(&self) -> String {
+        self.code.clone()
+    }
+
+    fn encode_value(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.to_bytes())
+    }
+
+    fn decode_value(v: &[u8]) -> anyhow::Result<Self> {
+        Self::from_bytes(v)
+    }
+}
+
+///
+/// 自定义角色管理
+/// 角色定义存储在raft表T_ROLE中,鉴权使用内存中定时加载的数据
+#[bean(inject)]
+pub struct RoleManager {
+    store: RaftTableStore<RoleDo>,
+    role_map: HashMap<Arc<String>, Arc<CustomRoleResource>>,
+}
+
+impl Default for RoleManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RoleManager {
+    pub fn new() -> Self {
+        Self {
+            store: RaftTableStore::new(ROLE_TREE_NAME.clone()),
+            role_map: HashMap::new(),
+        }
+    }
+
+    async fn set_role(
+        store: RaftTableStore<RoleDo>,
+        dto: RoleDto,
+        is_add: bool,
+    ) -> anyhow::Result<RoleResult> {
+        RoleDto::check_code(&dto.code)?;
+        let now = now_millis_i64();
+        let mut value = match store.get(dto.code.as_ref().to_owned()).await? {
+            Some(_) if is_add => {
+                return Err(anyhow::anyhow!("role {} is exist", &dto.code));
+            }
+            Some(v) => v,
+            None if is_add => RoleDo {
+                code: dto.code.as_ref().to_owned(),
+                gmt_create: now,
+                ..Default::default()
+            },
+            None => return Err(anyhow::anyhow!("role {} is not exist", &dto.code)),
+        };
+        if let Some(v) = dto.name {
+            value.name = v;
+        }
+        if let Some(v) = dto.description {
+            value.description = v;
+        }
+        if let Some(v) = dto.grants {
+            value.grants = RoleDto::check_grants(v)?;
+        }
+        if value.name.is_empty() {
+            value.name = value.code.clone();
+        }
+        value.gmt_modified = now;
+        store.save(&value).await?;
+        Ok(RoleResult::Role(Box::new(value.into())))
+    }
+
+    ///
+    /// 仍被用户引用的角色不能删除
+    async fn remove_role(
+        store: RaftTableStore<RoleDo>,
+        code: Arc<String>,
+    ) -> anyhow::Result<RoleResult> {
+        if let Some(table_manager) = store.table_manager() {
+            let query_req = TableManagerQueryReq::QueryPageList {
+                table_name: USER_TREE_NAME.clone(),
+                like_key: None,
+                offset: None,
+                limit: None,
+                is_rev: false,
+            };
+            if let TableManagerResult::PageListResult(_, list) =
+                table_manager.send(query_req).await??
+            {
+                for (_, v) in list {
+                    let user = UserDo::from_bytes(&v)?;
+                    if user.roles.iter().any(|e| e == code.as_str()) {
+                        return Err(anyhow::anyhow!(
+                            "role {} is used by user {}",
+                            &code,
+                            &user.username
+                        ));
+                    }
+                }
+            }
+        }
+        store.remove(code.as_ref().to_owned()).await?;
+        Ok(RoleResult::None)
+    }
+
+    async fn check_roles(
+        store: RaftTableStore<RoleDo>,
+        roles: Vec<Arc<String>>,
+    ) -> anyhow::Result<RoleResult> {
+        for role in roles {
+            if UserRoleHelper::is_builtin_role(&role) {
+                continue;
+            }
+            if store.get(role.as_ref().to_owned()).await?.is_none() {
+                return Err(anyhow::anyhow!("role {} is not exist", &role));
+            }
+        }
+        Ok(RoleResult::None)
+    }
+
+    fn reload(&mut self, ctx: &mut Context<Self>) {
+        self.store
+            .clone()
+            .reload(self, ctx, |act, list| act.set_roles(list));
+    }
+
+    fn set_roles(&mut self, list: Vec<RoleDo>) {
+        self.role_map = list
+            .iter()
+            .map(|e| {
+                (
+                    Arc::new(e.code.clone()),
+                    Arc::new(CustomRoleResource::new(e)),
+                )
+            })
+            .collect();
+    }
+
+    pub fn match_url(&self, roles: &[Arc<String>], path: &str, method: &str) -> bool {
+        let mut has_custom_role = false;
+        for role in roles {
+            if let Some(resource) = self.role_map.get(role) {
+                has_custom_role = true;
+                if resource.match_url(path, method) {
+                    return true;
+                }
+            }
+        }
+        has_custom_role && UserRoleHelper::get_base_module_resource().match_url(path, method)
+    }
+
+    pub fn get_web_resources(&self, roles: &[Arc<String>]) -> Vec<&'static str> {
+        let mut set = HashSet::new();
+        for role in roles {
+            if let Some(resource) = self.role_map.get(role) {
+                resource.fill_web_resources(&mut set);
+            }
+        }
+        if !set.is_empty() {
+            for item in &UserRoleHelper::get_base_module_resource().web_resources {
+                set.insert(*item);
+            }
+        }
+        set.into_iter().collect()
+    }
+
+    fn builtin_roles() -> Vec<RoleDto> {
+        UserRoleHelper::get_all_roles()
+            .into_iter()
+            .map(|code| {
+                let name = match code.as_str() {
+                    "0" => "MANAGER",
+                    "1" => "DEVELOPER",
+                    _ => "VISITOR",
+                };
+                RoleDto::new_builtin(code, name)
+            })
+            .collect()
+    }
+}
+
+impl Actor for RoleManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("RoleManager started")
+    }
+}
+
+impl Inject for RoleManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: bean_factory::FactoryData,
+        _factory: bean_factory::BeanFactory,
+        ctx: &mut Self::Context,
+    ) {
+        self.store.inject(&factory_data);
+        self.reload(ctx);
+        ctx.run_interval(TABLE_RELOAD_INTERVAL, |act, ctx| act.reload(ctx));
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<RoleResult>")]
+pub enum RoleReq {
+    Add(Box<RoleDto>),
+    Update(Box<RoleDto>),
+    Remove(Arc<String>),
+    QueryList,
+    /// 校验角色值是否都存在
+    CheckRoles(Vec<Arc<String>>),
+    MatchUrl {
+        roles: Vec<Arc<String>>,
+        path: String,
+        method: String,
+    },
+    QueryWebResources(Vec<Arc<String>>),
+}
+
+pub enum RoleResult {
+    None,
+    Match(bool),
+    Role(Box<RoleDto>),
+    RoleList(Vec<RoleDto>),
+    WebResources(Vec<&'static str>),
+}
+
+impl Handler<RoleReq> for RoleManager {
+    type Result = ResponseActFuture<Self, anyhow::Result<RoleResult>>;
+
+    fn handle(&mut self, msg: RoleReq, _ctx: &mut Self::Context) -> Self::Result {
+        let store = self.store.clone();
+        match msg {
+            RoleReq::MatchUrl {
+                roles,
+                path,
+                method,
+            } => {
+                let r = self.match_url(&roles, &path, &method);
+                Box::pin(actix::fut::ready(Ok(RoleResult::Match(r))))
+            }
+            RoleReq::QueryWebResources(roles) => {
+                let r = self.get_web_resources(&roles);
+                Box::pin(actix::fut::ready(Ok(RoleResult::WebResources(r))))
+            }
+            RoleReq::CheckRoles(roles) => {
+                Box::pin(Self::check_roles(store, roles).into_actor(self))
+            }
+            RoleReq::Add(dto) => Box::pin(Self::set_role(store, *dto, true).into_actor(self).map(
+                |r, act, ctx| {
+                    act.reload(ctx);
+                    r
+                },
+            )),
+            RoleReq::Update(dto) => Box::pin(
+                Self::set_role(store, *dto, false)
+                    .into_actor(self)
+                    .map(|r, act, ctx| {
+                        act.reload(ctx);
+                        r
+                    }),
+            ),
+            RoleReq::Remove(code) => Box::pin(Self::remove_role(store, code).into_actor(self).map(
+                |r, act, ctx| {
+                    act.reload(ctx);
+                    r
+                },
+            )),
+            RoleReq::QueryList => Box::pin(
+                async move {
+                    let mut rlist = Self::builtin_roles();
+                    for item in store.query_list().await? {
+                        rlist.push(item.into());
+                    }
+                    Ok(RoleResult::RoleList(rlist))
+                }
+                .into_actor(self),
+            ),
+        }
+    }
+}
+
+///
+/// 按自定义角色判断是否有请求权限;只有内置角色时直接返回false
+pub async fn match_url_by_custom_roles(
+    role_manager: &Addr<RoleManager>,
+    roles: &[Arc<String>],
+    path: &str,
+    method: &str,
+) -> bool {
+    if roles.iter().all(|e| UserRoleHelper::is_builtin_role(e)) {
+        return false;
+    }
+    let req = RoleReq::MatchUrl {
+        roles: roles.to_vec(),
+        path: path.to_owned(),
+        method: method.to_owned(),
+    };
+    matches!(
+        role_manager.send(req).await,
+        Ok(Ok(RoleResult::Match(true)))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_role_match() {
+        let grants = RoleDto::check_grants(vec![
+            RoleGrantDo {
+                module: "CONFIG_VISITOR".to_owned(),
+                methods: vec![],
+            },
+            RoleGrantDo {
+                module: "NAMING_MANAGE".to_owned(),
+                methods: vec!["get".to_owned()],
+            },
+        ])
+        .unwrap();
+        assert_eq!(grants[1].methods, vec!["GET".to_owned()]);
+        assert!(RoleDto::check_grants(vec![RoleGrantDo {
+            module: "NOT_EXIST".to_owned(),
+            methods: vec![],
+        }])
+        .is_err());
+        assert!(RoleDto::check_code("0").is_err());
+        assert!(RoleDto::check_code("ops team").is_err());
+        assert!(RoleDto::check_code("ops_team").is_ok());
+
+        let role = RoleDo {
+            code: "ops".to_owned(),
+            grants,
+            ..Default::default()
+        };
+        let mut manager = RoleManager::new();
+        manager.set_roles(vec![RoleDo::from_bytes(&role.to_bytes()).unwrap()]);
+        let roles = vec![Arc::new("ops".to_owned())];
+        assert!(manager.match_url(&roles, "/nacos/v1/cs/configs", "GET"));
+        assert!(!manager.match_url(&roles, "/nacos/v1/cs/configs", "POST"));
+        assert!(manager.match_url(&roles, "/nacos/v1/ns/instance/list", "GET"));
+        assert!(!manager.match_url(&roles, "/nacos/v1/ns/instance", "POST"));
+        assert!(!manager.match_url(&[Arc::new("dev".to_owned())], "/", "GET"));
+
+        let web_resources = manager.get_web_resources(&roles);
+        assert!(web_resources.contains(&"/manage/about"));
+        assert!(!web_resources.contains(&"SERVICE_UPDATE"));
+    }
+}